@@ -0,0 +1,272 @@
+// Measures the algorithm crossovers on the host and prints a threshold table.
+//
+//   cargo run --release --features _bench_internals --bin tune -- [--quick]
+//       [--table PATH] [--emit PATH]
+//
+// `--table` writes the `key = value` table that `Thresholds::from_str` reads
+// back at runtime, `--emit` writes a replacement for src/utils/thresholds.rs.
+//
+// The active table is fixed once arithmetic starts, so every candidate cutoff
+// is timed in a fresh child process (`tune --measure KEY TABLE SIZES..`) which
+// installs the candidate table and reports ns per call for each size.
+use big_bits::*;
+use rand::Rng;
+use std::hint::black_box;
+use std::process::Command;
+use std::time::Instant;
+
+struct Param {
+    key: &'static str,
+    candidates: &'static [usize],
+    sizes: &'static [usize],
+}
+
+// tuned in dependency order: short products and Burnikel-Ziegler both fall
+// back on the Karatsuba routines, so those are settled first
+const PARAMS: [Param; 5] = [
+    Param {
+        key: "karatsuba",
+        candidates: &[8, 12, 16, 20, 24, 28, 32, 40, 48, 56, 64],
+        sizes: &[16, 24, 32, 48, 64, 96, 128, 192, 256],
+    },
+    Param {
+        key: "karatsuba_sqr",
+        candidates: &[8, 12, 16, 20, 24, 28, 32, 40, 48, 56, 64],
+        sizes: &[16, 24, 32, 48, 64, 96, 128, 192, 256],
+    },
+    Param {
+        key: "short",
+        candidates: &[8, 16, 24, 32, 40, 48, 64, 80, 96],
+        sizes: &[8, 16, 24, 32, 48, 64, 96, 128],
+    },
+    Param {
+        key: "short_sqr",
+        candidates: &[8, 16, 24, 32, 40, 48, 64, 80, 96],
+        sizes: &[8, 16, 24, 32, 48, 64, 96, 128],
+    },
+    Param {
+        key: "bz",
+        candidates: &[16, 24, 32, 48, 64, 80, 96, 128, 160, 192],
+        sizes: &[32, 48, 64, 96, 128, 192, 256, 384],
+    },
+];
+
+struct Opts {
+    quick: bool,
+    table: Option<String>,
+    emit: Option<String>,
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("--measure") {
+        measure(&args[1..]);
+        return;
+    }
+
+    let opts = parse_opts(&args);
+    let mut best = Thresholds::DEFAULT;
+    for param in &PARAMS {
+        let pick = tune_param(param, &best, opts.quick);
+        set(&mut best, param.key, pick);
+        eprintln!("{} = {pick}", param.key);
+    }
+
+    print!("{best}");
+    if let Some(path) = &opts.table {
+        std::fs::write(path, best.to_string()).expect("failed to write table");
+    }
+    if let Some(path) = &opts.emit {
+        std::fs::write(path, thresholds_rs(&best)).expect("failed to write thresholds.rs");
+    }
+}
+
+fn parse_opts(args: &[String]) -> Opts {
+    let mut opts = Opts {
+        quick: false,
+        table: None,
+        emit: None,
+    };
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--quick" => opts.quick = true,
+            "--table" => opts.table = it.next().cloned(),
+            "--emit" => opts.emit = it.next().cloned(),
+            _ => {
+                eprintln!("usage: tune [--quick] [--table PATH] [--emit PATH]");
+                std::process::exit(2);
+            }
+        }
+    }
+    opts
+}
+
+fn set(table: &mut Thresholds, key: &str, val: usize) {
+    match key {
+        "karatsuba" => table.karatsuba = val,
+        "karatsuba_sqr" => table.karatsuba_sqr = val,
+        "short" => table.short = val,
+        "short_sqr" => table.short_sqr = val,
+        "bz" => table.bz = val,
+        _ => unreachable!(),
+    }
+}
+
+// times every candidate over every size and keeps the one with the lowest
+// total slowdown relative to the fastest candidate at each size
+fn tune_param(param: &Param, base: &Thresholds, quick: bool) -> usize {
+    let exe = std::env::current_exe().expect("cannot locate tune binary");
+    let sizes: Vec<String> = param.sizes.iter().map(usize::to_string).collect();
+
+    let mut times: Vec<Vec<f64>> = Vec::with_capacity(param.candidates.len());
+    for &cand in param.candidates {
+        let mut table = *base;
+        set(&mut table, param.key, cand);
+        let mut cmd = Command::new(&exe);
+        cmd.arg("--measure").arg(param.key).arg(table.to_string());
+        if quick {
+            cmd.arg("--quick");
+        }
+        let out = cmd
+            .args(&sizes)
+            .output()
+            .expect("failed to run measurement");
+        if !out.status.success() {
+            panic!(
+                "measurement failed: {}",
+                String::from_utf8_lossy(&out.stderr)
+            );
+        }
+        let row: Vec<f64> = String::from_utf8_lossy(&out.stdout)
+            .split_whitespace()
+            .map(|t| t.parse().expect("bad measurement output"))
+            .collect();
+        assert_eq!(row.len(), sizes.len());
+        times.push(row);
+    }
+
+    let mut scores = vec![0.0; times.len()];
+    for s in 0..param.sizes.len() {
+        let fastest = times.iter().map(|row| row[s]).fold(f64::INFINITY, f64::min);
+        for (score, row) in scores.iter_mut().zip(&times) {
+            *score += row[s] / fastest;
+        }
+    }
+
+    let mut pick = 0;
+    for (i, &score) in scores.iter().enumerate() {
+        if score < scores[pick] {
+            pick = i;
+        }
+    }
+    param.candidates[pick]
+}
+
+fn measure(args: &[String]) {
+    let key = args[0].as_str();
+    let table: Thresholds = args[1].parse().expect("bad threshold table");
+    table.install().expect("thresholds already in use");
+
+    let mut rest = &args[2..];
+    let quick = rest.first().map(String::as_str) == Some("--quick");
+    if quick {
+        rest = &rest[1..];
+    }
+    let batch_ns = if quick { 200_000 } else { 2_000_000 };
+
+    let times: Vec<String> = rest
+        .iter()
+        .map(|s| {
+            let n: usize = s.parse().expect("bad size");
+            format!("{:.1}", time_op(key, n, batch_ns))
+        })
+        .collect();
+    println!("{}", times.join(" "));
+}
+
+fn random_limbs(n: usize) -> Vec<u64> {
+    let mut rng = rand::thread_rng();
+    let mut out: Vec<u64> = (0..n).map(|_| rng.gen()).collect();
+    out[n - 1] |= 1 << 63;
+    out
+}
+
+fn time_op(key: &str, n: usize, batch_ns: u128) -> f64 {
+    match key {
+        "karatsuba" => {
+            let (a, b) = (random_limbs(n), random_limbs(n));
+            time_ns(batch_ns, || {
+                black_box(mul_vec(black_box(&a), black_box(&b)));
+            })
+        }
+        "karatsuba_sqr" => {
+            let a = random_limbs(n);
+            time_ns(batch_ns, || {
+                black_box(sqr_vec(black_box(&a)));
+            })
+        }
+        "short" => {
+            let (a, b) = (random_limbs(n), random_limbs(n));
+            time_ns(batch_ns, || {
+                black_box(short_mul_vec(black_box(&a), black_box(&b), n));
+            })
+        }
+        "short_sqr" => {
+            let a = random_limbs(n);
+            time_ns(batch_ns, || {
+                black_box(short_sqr_vec(black_box(&a), n));
+            })
+        }
+        "bz" => {
+            let (num, den) = (random_limbs(3 * n), random_limbs(n));
+            let (mut nb, mut db) = (num.clone(), den.clone());
+            time_ns(batch_ns, || {
+                nb.copy_from_slice(&num);
+                db.copy_from_slice(&den);
+                black_box(div_vec(black_box(&mut nb), black_box(&mut db)));
+            })
+        }
+        _ => panic!("unknown threshold {key}"),
+    }
+}
+
+// best of several batches, each long enough to drown out timer overhead
+fn time_ns(batch_ns: u128, mut f: impl FnMut()) -> f64 {
+    let mut iters = 1_u64;
+    loop {
+        let start = Instant::now();
+        for _ in 0..iters {
+            f();
+        }
+        if start.elapsed().as_nanos() >= batch_ns {
+            break;
+        }
+        iters *= 2;
+    }
+
+    let mut best = f64::INFINITY;
+    for _ in 0..5 {
+        let start = Instant::now();
+        for _ in 0..iters {
+            f();
+        }
+        best = best.min(start.elapsed().as_nanos() as f64 / iters as f64);
+    }
+    best
+}
+
+fn thresholds_rs(t: &Thresholds) -> String {
+    format!(
+        "// Generated by `cargo run --release --features _bench_internals --bin tune -- --emit src/utils/thresholds.rs`.
+// Default algorithm cutoffs in limbs; rerun the tuner to regenerate them for the host.
+
+pub(crate) const KARATSUBA_CUTOFF: usize = {};
+pub(crate) const KARATSUBA_SQR_CUTOFF: usize = {};
+pub(crate) const SHORT_CUTOFF: usize = {};
+pub(crate) const SHORT_SQR_CUTOFF: usize = {};
+pub(crate) const BZ_CUTOFF: usize = {};
+",
+        t.karatsuba, t.karatsuba_sqr, t.short, t.short_sqr, t.bz
+    )
+}
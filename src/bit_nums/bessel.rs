@@ -0,0 +1,306 @@
+use super::bitfloat::{
+    add_sub_bf, div_bf, invalid, mul_bf, raise, rel_err, ziv_loop, BitFloat, Flags,
+};
+use super::constants::{euler_gamma_approx, pi_approx};
+use super::exp_log::{bit_len, ln_kernel};
+use super::roots::sqrt_bf;
+use super::trig::sin_cos_approx;
+
+// floor(log2|x|), or far below any error for zero
+fn mag(x: &BitFloat) -> i128 {
+    if x.is_zero() {
+        i128::MIN / 4
+    } else {
+        x.bit_exp()
+    }
+}
+
+// (x/2)^n at w limbs and the bits of relative error lost
+fn half_pow(x: &BitFloat, n: u32, w: usize) -> (BitFloat, i128) {
+    let h = x >> 1_usize;
+    let mut y = BitFloat::one();
+    for i in (0..u32::BITS - n.leading_zeros()).rev() {
+        y = mul_bf(&y, &y, w);
+        if n >> i & 1 == 1 {
+            y = mul_bf(&y, &h, w);
+        }
+    }
+    (y, 2 * bit_len(n as u128) + 1)
+}
+
+// The power series of J_n and Y_n at x > 0 in w limbs, with
+// u_k = (x^2/4)^k / (k! (k + n)!):
+// S_J = sum_k (-1)^k u_k and, with harmonic, S_H = sum_k (-1)^k (H_k + H_(k+n)) u_k / 2.
+// Returns both sums and an error exponent for either.
+fn bessel_sums(n: u32, x: &BitFloat, w: usize, harmonic: bool) -> (BitFloat, BitFloat, i128) {
+    let one = BitFloat::one();
+    let q = &mul_bf(x, x, w) >> 2_usize;
+    let mut fact = BitFloat::one();
+    let mut hn = BitFloat::zero();
+    for i in 1..=n {
+        fact = mul_bf(&fact, &BitFloat::from(i), usize::MAX);
+        hn = add_sub_bf(&hn, &div_bf(&one, &BitFloat::from(i), w), false, w);
+    }
+    let mut u = div_bf(&one, &fact, w);
+    let mut sj = u.clone();
+    let (mut hk, mut hkn) = (BitFloat::zero(), hn);
+    let mut sh = if harmonic {
+        &mul_bf(&hkn, &u, w) >> 1_usize
+    } else {
+        BitFloat::zero()
+    };
+
+    let mut umax = u.bit_exp();
+    let mut k = 0_u64;
+    loop {
+        k += 1;
+        let kn = k + n as u64;
+        u = div_bf(&mul_bf(&u, &q, w), &BitFloat::from(k * kn), w);
+        umax = umax.max(u.bit_exp());
+        let sub = k % 2 == 1;
+        sj = add_sub_bf(&sj, &u, sub, w);
+        if harmonic {
+            hk = add_sub_bf(&hk, &div_bf(&one, &BitFloat::from(k), w), false, w);
+            hkn = add_sub_bf(&hkn, &div_bf(&one, &BitFloat::from(kn), w), false, w);
+            let h = &add_sub_bf(&hk, &hkn, false, w) >> 1_usize;
+            sh = add_sub_bf(&sh, &mul_bf(&h, &u, w), sub, w);
+        }
+        // past x^2/4 <= (k + 1)(k + 1 + n)/2 the terms at least halve, and
+        // the harmonic factors grow by less than a factor 2
+        if u.bit_exp() < umax - 64 * w as i128 - 8 && q <= ((k + 1) * (kn + 1) / 2) {
+            break;
+        }
+    }
+    // each term is within 3k + 2n + 8 roundings of the largest, with harmonic
+    // factors below k + n
+    let kn = (k + n as u64) as u128;
+    let err = umax + 2 * bit_len(kn) + 8 - 64 * (w as i128 - 1);
+    (sj, sh, err)
+}
+
+// limbs making up the cancellation of the power series, whose terms reach
+// about e^x
+fn series_limbs(x: &BitFloat) -> usize {
+    let xf = f64::try_from(x).unwrap_or(f64::MAX);
+    (xf * 1.45 / 64.0) as usize + 1
+}
+
+// Hankel's expansion of J_n and Y_n for large x > 0 at w limbs, with the
+// error exponent of both:
+// J_n = (P C - Q S) / √(πx) and Y_n = (P S + Q C) / √(πx), where
+// C = √2 cos(x - (2n + 1)π/4), S = √2 sin(x - (2n + 1)π/4),
+// P = sum_k (-1)^k a_2k and Q = sum_k (-1)^k a_(2k+1) with
+// a_k = a_(k-1) (4n^2 - (2k - 1)^2) / (8kx). Past k = n both remainders are
+// below the first term left out, so the sums stop once the terms pass below
+// 2^(-64w - 8); None if the terms start to grow again before then.
+fn bessel_asympt(n: u32, x: &BitFloat, w: usize) -> Option<(BitFloat, BitFloat, i128)> {
+    let wp = w + 1;
+    let mu = 4 * (n as i128).pow(2);
+    let x8 = x << 3_usize;
+    let stop = -64 * w as i128 - 8;
+    let mut a = BitFloat::one();
+    let mut p = BitFloat::one();
+    let mut q = BitFloat::zero();
+    let mut k = 0_i128;
+    let next = |a: &BitFloat, k: i128| {
+        let f = mu - (2 * k - 1).pow(2);
+        div_bf(
+            &mul_bf(a, &BitFloat::from(f), wp),
+            &mul_bf(&x8, &BitFloat::from(k), wp),
+            wp,
+        )
+    };
+    loop {
+        k += 1;
+        let b = next(&a, k);
+        if k > n as i128 && b.abs() > a.abs() {
+            return None;
+        }
+        a = b;
+        match k % 4 {
+            1 => q = add_sub_bf(&q, &a, false, wp),
+            2 => p = add_sub_bf(&p, &a, true, wp),
+            3 => q = add_sub_bf(&q, &a, true, wp),
+            _ => p = add_sub_bf(&p, &a, false, wp),
+        }
+        if k > n as i128 + 1 && mag(&a) < stop {
+            break;
+        }
+    }
+    // the first terms left out of P and Q
+    let a1 = next(&a, k + 1);
+    let a2 = next(&a1, k + 2);
+    let trunc = mag(&a1).max(mag(&a2)) + 1;
+
+    let (sn, cs, lost) = sin_cos_approx(x, wp);
+    let (c, s) = match n % 4 {
+        0 => (
+            add_sub_bf(&cs, &sn, false, wp),
+            add_sub_bf(&sn, &cs, true, wp),
+        ),
+        1 => (
+            add_sub_bf(&sn, &cs, true, wp),
+            -&add_sub_bf(&sn, &cs, false, wp),
+        ),
+        2 => (
+            -&add_sub_bf(&cs, &sn, false, wp),
+            add_sub_bf(&cs, &sn, true, wp),
+        ),
+        _ => (
+            add_sub_bf(&cs, &sn, true, wp),
+            add_sub_bf(&sn, &cs, false, wp),
+        ),
+    };
+    let sq = sqrt_bf(&mul_bf(&pi_approx(wp), x, wp), 64 * wp);
+    let j = add_sub_bf(&mul_bf(&p, &c, wp), &mul_bf(&q, &s, wp), true, wp);
+    let y = add_sub_bf(&mul_bf(&p, &s, wp), &mul_bf(&q, &c, wp), false, wp);
+    let j = div_bf(&j, &sq, w);
+    let y = div_bf(&y, &sq, w);
+    // C, S, P and Q are all below 2 in size
+    let err = (lost + 6 - 64 * w as i128).max(trunc + 2) - sq.bit_exp() + 1;
+    let err = err.max(rel_err(&j, 1, w)).max(rel_err(&y, 1, w));
+    Some((j, y, err))
+}
+
+// whether Hankel's expansion can reach w limbs at x, its smallest term being
+// about e^(-2x)
+fn asympt_reach(x: &BitFloat, w: usize) -> bool {
+    x.bit_exp() >= 62 || f64::try_from(x).unwrap() * 2.885 > (64 * w + 16) as f64
+}
+
+// J_n(x) for finite x > 0 at w limbs and its error exponent
+fn j_approx(n: u32, x: &BitFloat, w: usize) -> (BitFloat, i128) {
+    if asympt_reach(x, w) {
+        if let Some((j, _, err)) = bessel_asympt(n, x, w) {
+            return (j, err);
+        }
+    }
+    let wp = w + 1 + series_limbs(x);
+    let (sj, _, err_s) = bessel_sums(n, x, wp, false);
+    let (pre, lost) = half_pow(x, n, wp);
+    let y = mul_bf(&pre, &sj, w);
+    let err = pre.bit_exp() + 1 + err_s.max(mag(&sj) + lost + 1 - 64 * (wp as i128 - 1));
+    let err = err.max(rel_err(&y, 1, w));
+    (y, err)
+}
+
+// Y_n(x) for n = 0, 1 and finite x > 0 at w limbs and its error exponent,
+// from the series
+// Y_n = (2/π) (x/2)^n ((ln(x/2) + γ) S_J - S_H) - [n = 1] 2/(πx)
+fn y_approx(n: u32, x: &BitFloat, w: usize) -> (BitFloat, i128) {
+    if asympt_reach(x, w) {
+        if let Some((_, y, err)) = bessel_asympt(n, x, w) {
+            return (y, err);
+        }
+    }
+    let wp = w + 1 + series_limbs(x);
+    let (sj, sh, err_s) = bessel_sums(n, x, wp, true);
+
+    let h = x >> 1_usize;
+    let (lx, err_lx) = if h == 1 {
+        (BitFloat::zero(), i128::MIN / 4)
+    } else {
+        let (lx, lost) = ln_kernel(&h, wp);
+        let err = rel_err(&lx, lost, wp);
+        (lx, err)
+    };
+    let l = add_sub_bf(&lx, &euler_gamma_approx(wp), false, wp);
+    let err_l = err_lx.max(rel_err(&l, 2, wp)) + 1;
+    let ls = mul_bf(&l, &sj, wp);
+    let inner = add_sub_bf(&ls, &sh, true, wp);
+    let err_in = (mag(&l) + 1 + err_s)
+        .max(mag(&sj) + 1 + err_l)
+        .max(err_s)
+        .max(rel_err(&ls, 1, wp))
+        .max(rel_err(&inner, 1, wp))
+        + 2;
+
+    let (pre, lost) = half_pow(x, n, wp);
+    let c = div_bf(&BitFloat::from(2), &pi_approx(wp + 1), wp);
+    let cp = mul_bf(&c, &pre, wp);
+    let mut y = mul_bf(&cp, &inner, wp);
+    let mut err = cp.bit_exp() + 1 + err_in.max(mag(&inner) + lost + 3 - 64 * (wp as i128 - 1));
+    if n == 1 {
+        let v = div_bf(&c, x, wp);
+        y = add_sub_bf(&y, &v, true, wp);
+        err = err.max(rel_err(&v, 2, wp)).max(rel_err(&y, 1, wp)) + 1;
+    }
+    (y, err)
+}
+
+// NaN for a NaN argument, or for one below zero raising the invalid flag
+fn y_domain(x: &BitFloat) -> Option<BitFloat> {
+    if x.is_nan() {
+        return Some(BitFloat::nan());
+    }
+    (x.get_s() && !x.is_zero()).then(invalid)
+}
+
+impl BitFloat {
+    /// Bessel function of the first kind J_0, correctly rounded to `prec`
+    /// bits.
+    pub fn j0(&self, prec: usize) -> BitFloat {
+        self.jn(0, prec)
+    }
+
+    /// Bessel function of the first kind J_1, correctly rounded to `prec`
+    /// bits.
+    pub fn j1(&self, prec: usize) -> BitFloat {
+        self.jn(1, prec)
+    }
+
+    /// Bessel function of the first kind J_n of integer order, correctly
+    /// rounded to `prec` bits.
+    pub fn jn(&self, n: i32, prec: usize) -> BitFloat {
+        if self.is_nan() {
+            return BitFloat::nan();
+        }
+        if self.is_inf() {
+            return BitFloat::zero();
+        }
+        let m = n.unsigned_abs();
+        if self.is_zero() && m == 0 {
+            return BitFloat::one();
+        }
+
+        // J_-n = (-1)^n J_n and J_n(-x) = (-1)^n J_n(x)
+        let flip = m % 2 == 1 && ((n < 0) != self.get_s());
+        let mut out = if self.is_zero() {
+            BitFloat::zero()
+        } else {
+            let x = self.abs();
+            ziv_loop(prec, |w| j_approx(m, &x, w))
+        };
+        if flip {
+            out.neg_mut();
+        }
+        out
+    }
+
+    /// Bessel function of the second kind Y_0, correctly rounded to `prec`
+    /// bits. -∞ at zero and NaN below it.
+    pub fn y0(&self, prec: usize) -> BitFloat {
+        self.yn_small(0, prec)
+    }
+
+    /// Bessel function of the second kind Y_1, correctly rounded to `prec`
+    /// bits. -∞ at zero and NaN below it.
+    pub fn y1(&self, prec: usize) -> BitFloat {
+        self.yn_small(1, prec)
+    }
+
+    // Y_0 or Y_1
+    fn yn_small(&self, n: u32, prec: usize) -> BitFloat {
+        if let Some(nan) = y_domain(self) {
+            return nan;
+        }
+        if self.is_zero() {
+            raise(Flags::DIV_BY_ZERO);
+            return BitFloat::neg_inf();
+        }
+        if self.is_inf() {
+            return BitFloat::zero();
+        }
+        ziv_loop(prec, |w| y_approx(n, self, w))
+    }
+}
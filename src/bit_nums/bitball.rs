@@ -0,0 +1,358 @@
+use super::bitfloat::{op_prec, with_inexact, BitFloat, RoundingMode};
+use super::bitinterval::{exp_bound, BitInterval};
+use std::ops::*;
+
+const DOWN: RoundingMode = RoundingMode::TowardNegative;
+const UP: RoundingMode = RoundingMode::TowardPositive;
+const NEAR: RoundingMode = RoundingMode::NearestEven;
+
+/// Bits kept in a radius, which only needs to bound the error.
+const RAD_BITS: usize = 30;
+
+/// Real number as a ball: a [`BitFloat`] midpoint and a radius bounding the
+/// distance to the value it stands for, in the style of Arb.
+///
+/// The midpoint carries the working precision, while the radius is kept to
+/// a few bits and always rounded up. Every operation adds the error it
+/// propagates from its operands to the rounding error of its own midpoint,
+/// so the ball holds the exact result whenever the operands held theirs.
+/// Operations with no finite enclosure, such as division by a ball around
+/// zero, give an indeterminate ball with a NaN midpoint and infinite radius.
+///
+/// The operators round the midpoint at the larger of the operands'
+/// precisions and the thread's default precision, like those of `BitFloat`,
+/// while the elementary functions take a precision in bits. [`evaluate_to`]
+/// reruns a computation at rising precision until its ball is tight enough.
+#[derive(Debug, Clone)]
+pub struct BitBall {
+    mid: BitFloat,
+    rad: BitFloat,
+}
+
+// f's result, rounded to p bits, with a bound on its rounding error: zero
+// when exact, else an ulp
+fn rounded(p: usize, f: impl FnOnce() -> BitFloat) -> (BitFloat, BitFloat) {
+    let (y, inexact) = with_inexact(f);
+    if !inexact || !y.is_finite() {
+        return (y, BitFloat::zero());
+    }
+    if y.is_zero() {
+        // underflow leaves no bound on the value lost
+        return (BitFloat::nan(), BitFloat::zero());
+    }
+    let err = BitFloat::pow2(y.bit_exp() + 1 - p.max(1) as i128);
+    (y, err)
+}
+
+fn rad_add(a: &BitFloat, b: &BitFloat) -> BitFloat {
+    a.add_round(b, RAD_BITS, UP)
+}
+
+// a * b rounded up, taking 0 * inf as 0
+fn rad_mul(a: &BitFloat, b: &BitFloat) -> BitFloat {
+    if a.is_zero() || b.is_zero() {
+        return BitFloat::zero();
+    }
+    a.mul_round(b, RAD_BITS, UP)
+}
+
+impl BitBall {
+    /// The ball of midpoint `mid` and radius `rad`, or `None` unless `mid` is
+    /// finite and `rad` is at least zero. The radius is rounded up to a few
+    /// bits.
+    pub fn new(mid: BitFloat, rad: BitFloat) -> Option<Self> {
+        (mid.is_finite() && rad >= 0).then(|| BitBall::ball(mid, rad))
+    }
+
+    // the ball around mid, indeterminate for a non-finite mid or NaN radius
+    fn ball(mid: BitFloat, rad: BitFloat) -> Self {
+        if !mid.is_finite() || rad.is_nan() {
+            return BitBall::indeterminate();
+        }
+        BitBall {
+            mid,
+            rad: rad.abs().to_prec_round(RAD_BITS, UP),
+        }
+    }
+
+    /// The ball of no information, a NaN midpoint with an infinite radius.
+    pub fn indeterminate() -> Self {
+        BitBall {
+            mid: BitFloat::nan(),
+            rad: BitFloat::inf(),
+        }
+    }
+
+    /// The midpoint, NaN for the indeterminate ball.
+    pub fn mid(&self) -> &BitFloat {
+        &self.mid
+    }
+
+    /// The radius, rounded up to a few bits.
+    pub fn rad(&self) -> &BitFloat {
+        &self.rad
+    }
+
+    /// Whether the midpoint and radius are both finite.
+    pub fn is_finite(&self) -> bool {
+        self.mid.is_finite() && self.rad.is_finite()
+    }
+
+    /// Whether the radius is zero, so the ball is its midpoint alone.
+    pub fn is_exact(&self) -> bool {
+        self.rad.is_zero() && self.mid.is_finite()
+    }
+
+    /// Whether `x` lies within the ball. Never true of NaN.
+    pub fn contains(&self, x: &BitFloat) -> bool {
+        if !self.is_finite() {
+            return !x.is_nan();
+        }
+        x.is_finite() && x.sub_round(&self.mid, usize::MAX, NEAR).abs() <= self.rad
+    }
+
+    /// floor(log2|mid|) - floor(log2(rad)), about the number of bits of the
+    /// midpoint the radius leaves certain. `i128::MAX` for exact balls and
+    /// `i128::MIN` for indeterminate ones or a zero midpoint with a non-zero
+    /// radius.
+    pub fn rel_accuracy_bits(&self) -> i128 {
+        if self.is_exact() {
+            return i128::MAX;
+        }
+        if !self.is_finite() || self.mid.is_zero() {
+            return i128::MIN;
+        }
+        self.mid.bit_exp() - self.rad.bit_exp()
+    }
+
+    /// The interval `[mid - rad, mid + rad]`, rounded outward at the
+    /// midpoint's precision.
+    pub fn to_interval(&self) -> BitInterval {
+        if !self.is_finite() {
+            return BitInterval::entire();
+        }
+        let p = self.mid.prec().max(RAD_BITS);
+        let lo = self.mid.sub_round(&self.rad, p, DOWN);
+        let hi = self.mid.add_round(&self.rad, p, UP);
+        BitInterval::new(lo, hi).unwrap()
+    }
+
+    /// Square root, indeterminate if the ball reaches below zero.
+    pub fn sqrt(&self, prec: usize) -> BitBall {
+        let lo = self.mid.sub_round(&self.rad, RAD_BITS, DOWN);
+        if !self.is_finite() || lo < 0 {
+            return BitBall::indeterminate();
+        }
+        let (y, err) = rounded(prec, || self.mid.sqrt(prec));
+        if self.rad.is_zero() {
+            return BitBall::ball(y, err);
+        }
+        // |√x - √m| <= r / √m over the ball
+        let root = self.mid.sqrt_round(RAD_BITS, DOWN);
+        let prop = self.rad.div_round(&root, RAD_BITS, UP);
+        BitBall::ball(y, rad_add(&prop, &err))
+    }
+
+    /// e^x over the ball.
+    pub fn exp(&self, prec: usize) -> BitBall {
+        if !self.is_finite() {
+            return BitBall::indeterminate();
+        }
+        let (y, err) = rounded(prec, || self.mid.exp(prec));
+        if self.rad.is_zero() {
+            return BitBall::ball(y, err);
+        }
+        // |e^x - e^m| <= r e^(m + r) over the ball
+        let top = exp_bound(&self.mid.add_round(&self.rad, RAD_BITS, UP), RAD_BITS, true);
+        let prop = rad_mul(&self.rad, &top);
+        BitBall::ball(y, rad_add(&prop, &err))
+    }
+
+    /// Natural logarithm, indeterminate unless the ball lies above zero.
+    pub fn ln(&self, prec: usize) -> BitBall {
+        let lo = self.mid.sub_round(&self.rad, RAD_BITS, DOWN);
+        if !self.is_finite() || lo <= 0 {
+            return BitBall::indeterminate();
+        }
+        let (y, err) = rounded(prec, || self.mid.ln(prec));
+        // |ln x - ln m| <= r / (m - r) over the ball
+        let prop = self.rad.div_round(&lo, RAD_BITS, UP);
+        BitBall::ball(y, rad_add(&prop, &err))
+    }
+
+    /// Sine over the ball.
+    pub fn sin(&self, prec: usize) -> BitBall {
+        if !self.is_finite() {
+            return BitBall::indeterminate();
+        }
+        // sine and cosine move no faster than their argument
+        let (y, err) = rounded(prec, || self.mid.sin(prec));
+        BitBall::ball(y, rad_add(&self.rad, &err))
+    }
+
+    /// Cosine over the ball.
+    pub fn cos(&self, prec: usize) -> BitBall {
+        if !self.is_finite() {
+            return BitBall::indeterminate();
+        }
+        let (y, err) = rounded(prec, || self.mid.cos(prec));
+        BitBall::ball(y, rad_add(&self.rad, &err))
+    }
+}
+
+/// Runs `f` at rising working precision until the ball it returns has at
+/// least `bits` bits of relative accuracy, and returns that ball. `f` gets
+/// the working precision in bits, which is also the thread's default
+/// precision while it runs so the operators follow it. `None` if the
+/// accuracy is out of reach, as for a value that is exactly zero but not
+/// computed as an exact ball.
+pub fn evaluate_to(mut f: impl FnMut(usize) -> BitBall, bits: usize) -> Option<BitBall> {
+    let _restore = RestorePrec(BitFloat::default_prec());
+    let cap = 16 * bits.max(64) + 4096;
+    let mut prec = bits + 32;
+    loop {
+        BitFloat::set_default_prec(prec);
+        let y = f(prec);
+        if y.rel_accuracy_bits() >= bits as i128 {
+            return Some(y);
+        }
+        if prec >= cap {
+            return None;
+        }
+        prec += prec / 2;
+    }
+}
+
+// Sets the thread's default precision back when dropped, so a panic in
+// evaluate_to's closure does not leave the working precision behind.
+struct RestorePrec(usize);
+
+impl Drop for RestorePrec {
+    fn drop(&mut self) {
+        BitFloat::set_default_prec(self.0);
+    }
+}
+
+impl From<BitFloat> for BitBall {
+    /// The exact ball around `x`, or the indeterminate one for NaN and the
+    /// infinities.
+    fn from(x: BitFloat) -> Self {
+        BitBall::ball(x, BitFloat::zero())
+    }
+}
+
+fn add_sub_ball(lhs: &BitBall, rhs: &BitBall, sub: bool) -> BitBall {
+    if !lhs.is_finite() || !rhs.is_finite() {
+        return BitBall::indeterminate();
+    }
+    let p = op_prec(&lhs.mid, &rhs.mid);
+    let (y, err) = rounded(p, || {
+        if sub {
+            lhs.mid.sub_round(&rhs.mid, p, NEAR)
+        } else {
+            lhs.mid.add_round(&rhs.mid, p, NEAR)
+        }
+    });
+    let rad = rad_add(&rad_add(&lhs.rad, &rhs.rad), &err);
+    BitBall::ball(y, rad)
+}
+
+fn add_ball(lhs: &BitBall, rhs: &BitBall) -> BitBall {
+    add_sub_ball(lhs, rhs, false)
+}
+
+fn sub_ball(lhs: &BitBall, rhs: &BitBall) -> BitBall {
+    add_sub_ball(lhs, rhs, true)
+}
+
+fn mul_ball(lhs: &BitBall, rhs: &BitBall) -> BitBall {
+    if !lhs.is_finite() || !rhs.is_finite() {
+        return BitBall::indeterminate();
+    }
+    let p = op_prec(&lhs.mid, &rhs.mid);
+    let (y, err) = rounded(p, || lhs.mid.mul_round(&rhs.mid, p, NEAR));
+    // |xy - ab| <= |a| s + |b| r + r s
+    let rad = rad_add(
+        &rad_add(
+            &rad_mul(&lhs.mid.abs(), &rhs.rad),
+            &rad_mul(&rhs.mid.abs(), &lhs.rad),
+        ),
+        &rad_add(&rad_mul(&lhs.rad, &rhs.rad), &err),
+    );
+    BitBall::ball(y, rad)
+}
+
+fn div_ball(lhs: &BitBall, rhs: &BitBall) -> BitBall {
+    if !lhs.is_finite() || !rhs.is_finite() {
+        return BitBall::indeterminate();
+    }
+    let b = rhs.mid.abs();
+    let gap = b.sub_round(&rhs.rad, RAD_BITS, DOWN);
+    if gap <= 0 {
+        return BitBall::indeterminate();
+    }
+    let p = op_prec(&lhs.mid, &rhs.mid);
+    let (y, err) = rounded(p, || lhs.mid.div_round(&rhs.mid, p, NEAR));
+    if lhs.rad.is_zero() && rhs.rad.is_zero() {
+        return BitBall::ball(y, err);
+    }
+    // |x/y - a/b| <= (|b| r + |a| s) / (|b| (|b| - s))
+    let num = rad_add(&rad_mul(&b, &lhs.rad), &rad_mul(&lhs.mid.abs(), &rhs.rad));
+    let den = b.mul_round(&gap, RAD_BITS, DOWN);
+    let prop = num.div_round(&den, RAD_BITS, UP);
+    BitBall::ball(y, rad_add(&prop, &err))
+}
+
+macro_rules! impl_op_ball {
+    ($($tr:ident, $f:ident, $op:ident);*) => {$(
+        impl $tr for BitBall {
+            type Output = BitBall;
+            fn $f(self, rhs: Self) -> Self::Output {
+                $op(&self, &rhs)
+            }
+        }
+
+        impl $tr for &BitBall {
+            type Output = BitBall;
+            fn $f(self, rhs: Self) -> Self::Output {
+                $op(self, rhs)
+            }
+        }
+
+        impl $tr<&BitBall> for BitBall {
+            type Output = BitBall;
+            fn $f(self, rhs: &BitBall) -> Self::Output {
+                $op(&self, rhs)
+            }
+        }
+
+        impl $tr<BitBall> for &BitBall {
+            type Output = BitBall;
+            fn $f(self, rhs: BitBall) -> Self::Output {
+                $op(self, &rhs)
+            }
+        }
+    )*};
+}
+
+impl_op_ball!(Add, add, add_ball; Sub, sub, sub_ball; Mul, mul, mul_ball; Div, div, div_ball);
+
+impl Neg for BitBall {
+    type Output = BitBall;
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
+
+impl Neg for &BitBall {
+    type Output = BitBall;
+    fn neg(self) -> Self::Output {
+        if !self.is_finite() {
+            return BitBall::indeterminate();
+        }
+        BitBall {
+            mid: -&self.mid,
+            rad: self.rad.clone(),
+        }
+    }
+}
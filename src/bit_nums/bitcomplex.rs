@@ -0,0 +1,601 @@
+use super::bitfloat::{
+    add_sub_bf, div_bf_mode, invalid, mul_bf, op_prec, rel_err, underflow, ziv_loop_n, BitFloat,
+    RoundingMode::NearestEven,
+};
+use super::exp_log::{exp_kernel, ln1p_kernel, ln_kernel, EXP_MAX_BITS};
+use super::hyperbolic::sinh_cosh_approx;
+use super::roots::sqrt_bf;
+use super::trig::sin_cos_approx;
+use std::ops::*;
+
+/// Arbitrary precision complex number with [`BitFloat`] real and imaginary
+/// parts.
+///
+/// The operators round each part to nearest at the largest precision among
+/// the operands' parts and the thread's default precision, like those of
+/// `BitFloat`, and give the correctly rounded parts of the exact result.
+/// The elementary functions take a precision in bits and round each part
+/// correctly for finite arguments, on the principal branch. Their branch
+/// cuts lie along the negative real axis for `ln`, `sqrt` and `pow`, where
+/// the sign of a zero imaginary part picks the side, so `ln(-1 + 0i) = πi`
+/// while `ln(-1 - 0i) = -πi`.
+#[derive(Debug, Clone)]
+pub struct BitComplex {
+    re: BitFloat,
+    im: BitFloat,
+}
+
+// floor(log2|x|), or far below any exponent for zero
+fn mag(x: &BitFloat) -> i128 {
+    if x.is_zero() {
+        i128::MIN / 4
+    } else {
+        x.bit_exp()
+    }
+}
+
+// the zero of sign s
+fn signed_zero(s: bool) -> BitFloat {
+    BitFloat::make(s, 0, &[])
+}
+
+fn with_sign(mut y: BitFloat, neg: bool) -> BitFloat {
+    if y.get_s() != neg {
+        y.neg_mut();
+    }
+    y
+}
+
+// ln|x + iy| for finite x + iy other than zero at w limbs, and the bits of
+// relative error lost, zero when |x + iy| = 1. Near |x + iy| = 1 the log is
+// ln1p of x^2 + y^2 - 1, found from exact squares so it cancels nothing.
+fn ln_abs(x: &BitFloat, y: &BitFloat, w: usize) -> (BitFloat, i128) {
+    if x.is_zero() || y.is_zero() {
+        let a = if x.is_zero() { y.abs() } else { x.abs() };
+        if a == 1 {
+            return (BitFloat::zero(), 0);
+        }
+        return ln_kernel(&a, w);
+    }
+    let one = BitFloat::one();
+    let x2 = mul_bf(x, x, usize::MAX);
+    let y2 = mul_bf(y, y, usize::MAX);
+    let (a2, b2) = if x2 >= y2 { (x2, y2) } else { (y2, x2) };
+    if (-3..=1).contains(&a2.bit_exp()) {
+        let a1 = add_sub_bf(&a2, &one, true, usize::MAX);
+        let t = add_sub_bf(&a1, &b2, false, w + 1);
+        // x^2 + y^2 = 1 has no solution in binary fractions off the axes
+        if mag(&t) < -2 {
+            let (l, lost) = ln1p_kernel(&t, w);
+            return (l >> 1_usize, lost + 2);
+        }
+    }
+    let s = add_sub_bf(&a2, &b2, false, w + 1);
+    let (l, lost) = ln_kernel(&s, w);
+    (l >> 1_usize, lost + 2)
+}
+
+// z1 z2 exactly
+fn mul_exact(z1: &BitComplex, z2: &BitComplex) -> BitComplex {
+    let prod = |a, b| mul_bf(a, b, usize::MAX);
+    BitComplex {
+        re: add_sub_bf(
+            &prod(&z1.re, &z2.re),
+            &prod(&z1.im, &z2.im),
+            true,
+            usize::MAX,
+        ),
+        im: add_sub_bf(
+            &prod(&z1.re, &z2.im),
+            &prod(&z1.im, &z2.re),
+            false,
+            usize::MAX,
+        ),
+    }
+}
+
+// z1 z2 with each part rounded once to p bits from exact products
+fn mul_c(z1: &BitComplex, z2: &BitComplex, p: usize) -> BitComplex {
+    let prod = |a, b| mul_bf(a, b, usize::MAX);
+    BitComplex {
+        re: prod(&z1.re, &z2.re).sub_round(&prod(&z1.im, &z2.im), p, NearestEven),
+        im: prod(&z1.re, &z2.im).add_round(&prod(&z1.im, &z2.re), p, NearestEven),
+    }
+}
+
+// z1 / z2 = z1 conj(z2) / |z2|^2, with the numerator and denominator exact
+// so that each part is rounded once to p bits
+fn div_c(z1: &BitComplex, z2: &BitComplex, p: usize) -> BitComplex {
+    let num = mul_exact(z1, &z2.conj());
+    let den = add_sub_bf(
+        &mul_bf(&z2.re, &z2.re, usize::MAX),
+        &mul_bf(&z2.im, &z2.im, usize::MAX),
+        false,
+        usize::MAX,
+    );
+    BitComplex {
+        re: div_bf_mode(&num.re, &den, p, NearestEven),
+        im: div_bf_mode(&num.im, &den, p, NearestEven),
+    }
+}
+
+impl BitComplex {
+    /// The complex number `re + i im`.
+    pub fn new(re: BitFloat, im: BitFloat) -> Self {
+        BitComplex { re, im }
+    }
+
+    /// The imaginary unit.
+    pub fn i() -> Self {
+        BitComplex::new(BitFloat::zero(), BitFloat::one())
+    }
+
+    /// The real part.
+    pub fn re(&self) -> &BitFloat {
+        &self.re
+    }
+
+    /// The imaginary part.
+    pub fn im(&self) -> &BitFloat {
+        &self.im
+    }
+
+    /// Whether either part is NaN.
+    pub fn is_nan(&self) -> bool {
+        self.re.is_nan() || self.im.is_nan()
+    }
+
+    /// Whether both parts are finite.
+    pub fn is_finite(&self) -> bool {
+        self.re.is_finite() && self.im.is_finite()
+    }
+
+    /// Whether both parts are zero, of either sign.
+    pub fn is_zero(&self) -> bool {
+        self.re.is_zero() && self.im.is_zero()
+    }
+
+    fn nan() -> Self {
+        BitComplex::new(BitFloat::nan(), BitFloat::nan())
+    }
+
+    /// The complex conjugate.
+    pub fn conj(&self) -> Self {
+        BitComplex::new(self.re.clone(), -&self.im)
+    }
+
+    /// |self|, correctly rounded to `prec` bits.
+    pub fn abs(&self, prec: usize) -> BitFloat {
+        self.re.hypot(&self.im, prec)
+    }
+
+    /// The argument in [-π, π], correctly rounded to `prec` bits. The sign of
+    /// a zero imaginary part gives the sign of π on the negative real axis.
+    pub fn arg(&self, prec: usize) -> BitFloat {
+        self.im.atan2(&self.re, prec)
+    }
+
+    /// `self * rhs` by Gauss's three multiplications, `c (a + b)`,
+    /// `a (d - c)` and `b (c + d)`, taken at `prec + 64` bits. This saves a
+    /// quarter of the work at large precisions, but unlike the operator the
+    /// parts are only good to within the rounding of the intermediate
+    /// products, which cancellation lifts above an ulp when one part is
+    /// much smaller than the other.
+    pub fn mul_gauss(&self, rhs: &BitComplex, prec: usize) -> BitComplex {
+        let wp = prec.saturating_add(64);
+        let (a, b, c, d) = (&self.re, &self.im, &rhs.re, &rhs.im);
+        let k1 = c.mul_round(&a.add_round(b, wp, NearestEven), wp, NearestEven);
+        let k2 = a.mul_round(&d.sub_round(c, wp, NearestEven), wp, NearestEven);
+        let k3 = b.mul_round(&c.add_round(d, wp, NearestEven), wp, NearestEven);
+        BitComplex {
+            re: k1.sub_round(&k3, prec, NearestEven),
+            im: k1.add_round(&k2, prec, NearestEven),
+        }
+    }
+
+    /// e^self.
+    pub fn exp(&self, prec: usize) -> BitComplex {
+        let (x, y) = (&self.re, &self.im);
+        if self.is_nan() {
+            return BitComplex::nan();
+        }
+        if y.is_zero() {
+            return BitComplex::new(x.exp(prec), y.clone());
+        }
+        if x.is_zero() {
+            let (s, c) = y.sin_cos(prec);
+            return BitComplex::new(c, s);
+        }
+        if !self.is_finite() || x.bit_exp() >= EXP_MAX_BITS {
+            let e = x.exp(prec);
+            let (s, c) = y.sin_cos(prec);
+            return BitComplex::new(
+                e.mul_round(&c, prec, NearestEven),
+                e.mul_round(&s, prec, NearestEven),
+            );
+        }
+
+        let [re, im] = ziv_loop_n(prec, NearestEven, |w| {
+            let (e, lost_e) = exp_kernel(x, w);
+            let (s, c, lost_t) = sin_cos_approx(y, w);
+            let lost = lost_e.max(lost_t) + 2;
+            let re = mul_bf(&e, &c, w);
+            let im = mul_bf(&e, &s, w);
+            let (err_re, err_im) = (rel_err(&re, lost, w), rel_err(&im, lost, w));
+            [(re, err_re), (im, err_im)]
+        });
+        BitComplex::new(re, im)
+    }
+
+    /// Natural logarithm on the principal branch, `ln|self| + i arg(self)`.
+    /// `ln(0) = -∞`, raising the divide by zero flag.
+    pub fn ln(&self, prec: usize) -> BitComplex {
+        let (x, y) = (&self.re, &self.im);
+        if self.is_nan() {
+            return BitComplex::nan();
+        }
+        let im = self.arg(prec);
+        if !self.is_finite() {
+            return BitComplex::new(BitFloat::inf(), im);
+        }
+        if x.is_zero() || y.is_zero() {
+            let a = if x.is_zero() { y.abs() } else { x.abs() };
+            return BitComplex::new(a.ln(prec), im);
+        }
+
+        let [re] = ziv_loop_n(prec, NearestEven, |w| {
+            let (l, lost) = ln_abs(x, y, w);
+            let err = rel_err(&l, lost, w);
+            [(l, err)]
+        });
+        BitComplex::new(re, im)
+    }
+
+    /// Square root on the principal branch, with a real part at or above
+    /// zero and an imaginary part of the sign of `self`'s.
+    pub fn sqrt(&self, prec: usize) -> BitComplex {
+        let (x, y) = (&self.re, &self.im);
+        if y.is_inf() {
+            return BitComplex::new(BitFloat::inf(), y.clone());
+        }
+        if self.is_nan() {
+            return BitComplex::nan();
+        }
+        if y.is_zero() {
+            if x.is_zero() {
+                return BitComplex::new(BitFloat::zero(), y.clone());
+            }
+            let r = x.abs().sqrt(prec);
+            return if x.get_s() {
+                BitComplex::new(BitFloat::zero(), with_sign(r, y.get_s()))
+            } else {
+                BitComplex::new(r, y.clone())
+            };
+        }
+        if x.is_inf() {
+            return if x.get_s() {
+                BitComplex::new(BitFloat::zero(), with_sign(BitFloat::inf(), y.get_s()))
+            } else {
+                BitComplex::new(BitFloat::inf(), signed_zero(y.get_s()))
+            };
+        }
+        if x.is_zero() {
+            // √(iy) = √(|y| / 2) (1 ± i)
+            let r = (y.abs() >> 1_usize).sqrt(prec);
+            return BitComplex::new(r.clone(), with_sign(r, y.get_s()));
+        }
+
+        // t = √((|x| + |z|) / 2) and |y| / 2t, with no cancellation
+        let [re, im] = ziv_loop_n(prec, NearestEven, |w| {
+            let s = add_sub_bf(
+                &mul_bf(x, x, usize::MAX),
+                &mul_bf(y, y, usize::MAX),
+                false,
+                w + 1,
+            );
+            let r = sqrt_bf(&s, 64 * (w + 1));
+            let h = add_sub_bf(&x.abs(), &r, false, w + 1) >> 1_usize;
+            let t = sqrt_bf(&h, 64 * w);
+            let u = div_bf_mode(&y.abs(), &(&t << 1_usize), 64 * w, NearestEven);
+            let (a, b) = if x.get_s() { (u, t) } else { (t, u) };
+            let b = with_sign(b, y.get_s());
+            let (err_a, err_b) = (rel_err(&a, 6, w), rel_err(&b, 6, w));
+            [(a, err_a), (b, err_b)]
+        });
+        BitComplex::new(re, im)
+    }
+
+    /// self^w = e^(w ln(self)) on the principal branch. `self^0 = 1`, while
+    /// zero to a power with a real part above zero is zero, and to any
+    /// other power NaN, raising the invalid flag. Integer powers are exact
+    /// before their rounding, and positive reals to real powers are real.
+    pub fn pow(&self, w: &BitComplex, prec: usize) -> BitComplex {
+        if w.is_zero() {
+            return BitComplex::from(BitFloat::one());
+        }
+        if self.is_nan() || w.is_nan() {
+            return BitComplex::nan();
+        }
+        if self.is_zero() {
+            if w.re > 0 {
+                return BitComplex::from(BitFloat::zero());
+            }
+            return BitComplex::new(invalid(), BitFloat::nan());
+        }
+        if w.im.is_zero() && self.im.is_zero() && !self.re.get_s() {
+            return BitComplex::from(self.re.powf(&w.re, prec));
+        }
+        if let Some(z) = self.powi_exact(w, prec) {
+            return z;
+        }
+        // past exp's reach, or for infinite parts, e^(w ln(self)) as it comes
+        let wp = prec.saturating_add(64);
+        let far = |u: &BitComplex| !u.is_finite() || mag(&u.re) >= EXP_MAX_BITS - 1;
+        let u0 = mul_c(w, &self.ln(64), 64);
+        if !self.is_finite() || !w.is_finite() || far(&u0) {
+            return mul_c(w, &self.ln(wp), wp).exp(prec);
+        }
+
+        // the error in u = w ln(self) is that of ln(self) scaled up by |w|,
+        // so ln takes enough extra limbs to keep it below 2^(-64 wl + 64 w)
+        let (wr, wi) = (&w.re, &w.im);
+        let top_w = mag(wr).max(mag(wi)) + 1;
+        let extra = ((top_w + mag(&u0.re).max(mag(&u0.im)) + 72).max(0) / 64) as usize + 1;
+        let (x, y) = (&self.re, &self.im);
+        let [re, im] = ziv_loop_n(prec, NearestEven, |wk| {
+            let wl = wk + extra;
+            let (lr, lost_r) = ln_abs(x, y, wl);
+            let li = y.atan2(x, 64 * wl);
+            let err_l = (mag(&lr) + lost_r).max(mag(&li) + 1) - 64 * (wl as i128 - 1);
+            let l = BitComplex::new(lr, li);
+            let u = mul_c(w, &l, 64 * wl);
+            let err_u = (top_w + err_l + 2).max(mag(&u.re).max(mag(&u.im)) + 2 - 64 * wl as i128);
+
+            let (e, lost_e) = if u.re.is_zero() {
+                (BitFloat::one(), 0)
+            } else {
+                exp_kernel(&u.re, wk)
+            };
+            let (s, c, lost_t) = if u.im.is_zero() {
+                (BitFloat::zero(), BitFloat::one(), 0)
+            } else {
+                sin_cos_approx(&u.im, wk)
+            };
+            let re = mul_bf(&e, &c, wk);
+            let im = mul_bf(&e, &s, wk);
+            // an error in u moves e^u by as much relative to |e^u|
+            let kernel = lost_e.max(lost_t) + 2 - 64 * (wk as i128 - 1);
+            let err = mag(&e) + 2 + (err_u + 1).max(kernel);
+            [(re, err), (im, err)]
+        });
+        BitComplex::new(re, im)
+    }
+
+    // self^n for a real integer w = n whose exact power stays a modest size,
+    // by repeated squaring and a single rounding
+    fn powi_exact(&self, w: &BitComplex, prec: usize) -> Option<BitComplex> {
+        if !w.im.is_zero() || !w.re.is_integer() || !self.is_finite() || mag(&w.re) >= 32 {
+            return None;
+        }
+        let n = i128::try_from(&w.re).ok()?.unsigned_abs();
+        let bits = self.re.prec().max(self.im.prec()).max(1) as u128;
+        if n * bits > 16 * prec as u128 + 4096 {
+            return None;
+        }
+        let mut acc = BitComplex::from(BitFloat::one());
+        let mut base = self.clone();
+        let mut k = n;
+        while k > 0 {
+            if k & 1 == 1 {
+                acc = mul_exact(&acc, &base);
+            }
+            k >>= 1;
+            if k > 0 {
+                base = mul_exact(&base, &base);
+            }
+        }
+        let one = BitComplex::from(BitFloat::one());
+        Some(if w.re.get_s() {
+            div_c(&one, &acc, prec)
+        } else {
+            BitComplex::new(acc.re.to_prec(prec), acc.im.to_prec(prec))
+        })
+    }
+
+    /// Sine, `sin(x) cosh(y) + i cos(x) sinh(y)`.
+    pub fn sin(&self, prec: usize) -> BitComplex {
+        let (x, y) = (&self.re, &self.im);
+        if self.is_nan() {
+            return BitComplex::nan();
+        }
+        if y.is_zero() && x.is_finite() {
+            let c = x.cos(8);
+            return BitComplex::new(x.sin(prec), signed_zero(y.get_s() ^ c.get_s()));
+        }
+        if x.is_zero() {
+            return BitComplex::new(x.clone(), y.sinh(prec));
+        }
+        if !self.is_finite() || y.bit_exp() >= EXP_MAX_BITS {
+            let (s, c) = x.sin_cos(prec);
+            return BitComplex::new(
+                s.mul_round(&y.cosh(prec), prec, NearestEven),
+                c.mul_round(&y.sinh(prec), prec, NearestEven),
+            );
+        }
+
+        let [re, im] = ziv_loop_n(prec, NearestEven, |w| {
+            let (s, c, lost_t) = sin_cos_approx(x, w);
+            let (sh, ch, lost_h) = sinh_cosh_approx(y, w);
+            let lost = lost_t.max(lost_h) + 2;
+            let re = mul_bf(&s, &ch, w);
+            let im = mul_bf(&c, &sh, w);
+            let (err_re, err_im) = (rel_err(&re, lost, w), rel_err(&im, lost, w));
+            [(re, err_re), (im, err_im)]
+        });
+        BitComplex::new(re, im)
+    }
+
+    /// Cosine, `cos(x) cosh(y) - i sin(x) sinh(y)`.
+    pub fn cos(&self, prec: usize) -> BitComplex {
+        let (x, y) = (&self.re, &self.im);
+        if self.is_nan() {
+            return BitComplex::nan();
+        }
+        if y.is_zero() && x.is_finite() {
+            let s = x.sin(8);
+            return BitComplex::new(x.cos(prec), signed_zero(!(y.get_s() ^ s.get_s())));
+        }
+        if x.is_zero() {
+            return BitComplex::new(y.cosh(prec), signed_zero(!(x.get_s() ^ y.get_s())));
+        }
+        if !self.is_finite() || y.bit_exp() >= EXP_MAX_BITS {
+            let (s, c) = x.sin_cos(prec);
+            return BitComplex::new(
+                c.mul_round(&y.cosh(prec), prec, NearestEven),
+                -s.mul_round(&y.sinh(prec), prec, NearestEven),
+            );
+        }
+
+        let [re, im] = ziv_loop_n(prec, NearestEven, |w| {
+            let (s, c, lost_t) = sin_cos_approx(x, w);
+            let (sh, ch, lost_h) = sinh_cosh_approx(y, w);
+            let lost = lost_t.max(lost_h) + 2;
+            let re = mul_bf(&c, &ch, w);
+            let im = -mul_bf(&s, &sh, w);
+            let (err_re, err_im) = (rel_err(&re, lost, w), rel_err(&im, lost, w));
+            [(re, err_re), (im, err_im)]
+        });
+        BitComplex::new(re, im)
+    }
+
+    /// Tangent, as `(sin(x) cos(x) + i sinh(y) cosh(y)) / (cos(x)^2 +
+    /// sinh(y)^2)`, whose denominator cancels nothing near the poles.
+    pub fn tan(&self, prec: usize) -> BitComplex {
+        let (x, y) = (&self.re, &self.im);
+        if self.is_nan() {
+            return BitComplex::nan();
+        }
+        if y.is_zero() {
+            return BitComplex::new(x.tan(prec), y.clone());
+        }
+        if x.is_zero() {
+            return BitComplex::new(x.clone(), y.tanh(prec));
+        }
+        if !x.is_finite() {
+            return BitComplex::nan();
+        }
+        // tan(z) = ±i within 2^(2 - 2|y|) past any precision we can reach
+        if y.is_inf() || y.bit_exp() >= EXP_MAX_BITS {
+            let (s, c) = x.sin_cos(8);
+            let one = with_sign(BitFloat::one(), y.get_s());
+            return BitComplex::new(underflow(s.get_s() ^ c.get_s()), one);
+        }
+
+        let [re, im] = ziv_loop_n(prec, NearestEven, |w| {
+            let (s, c, lost_t) = sin_cos_approx(x, w + 1);
+            let (sh, ch, lost_h) = sinh_cosh_approx(y, w + 1);
+            let den = add_sub_bf(
+                &mul_bf(&c, &c, w + 1),
+                &mul_bf(&sh, &sh, w + 1),
+                false,
+                w + 1,
+            );
+            let re = div_bf_mode(&mul_bf(&s, &c, w + 1), &den, 64 * w, NearestEven);
+            let im = div_bf_mode(&mul_bf(&sh, &ch, w + 1), &den, 64 * w, NearestEven);
+            let lost = 2 * lost_t.max(lost_h) + 4;
+            let (err_re, err_im) = (rel_err(&re, lost, w), rel_err(&im, lost, w));
+            [(re, err_re), (im, err_im)]
+        });
+        BitComplex::new(re, im)
+    }
+}
+
+impl From<BitFloat> for BitComplex {
+    /// The complex number `x + 0i`.
+    fn from(x: BitFloat) -> Self {
+        BitComplex::new(x, BitFloat::zero())
+    }
+}
+
+impl PartialEq for BitComplex {
+    fn eq(&self, other: &Self) -> bool {
+        self.re == other.re && self.im == other.im
+    }
+}
+
+// precision of an operator result in bits
+fn c_prec(lhs: &BitComplex, rhs: &BitComplex) -> usize {
+    op_prec(&lhs.re, &lhs.im).max(op_prec(&rhs.re, &rhs.im))
+}
+
+fn add_c_op(lhs: &BitComplex, rhs: &BitComplex) -> BitComplex {
+    let p = c_prec(lhs, rhs);
+    BitComplex {
+        re: lhs.re.add_round(&rhs.re, p, NearestEven),
+        im: lhs.im.add_round(&rhs.im, p, NearestEven),
+    }
+}
+
+fn sub_c_op(lhs: &BitComplex, rhs: &BitComplex) -> BitComplex {
+    let p = c_prec(lhs, rhs);
+    BitComplex {
+        re: lhs.re.sub_round(&rhs.re, p, NearestEven),
+        im: lhs.im.sub_round(&rhs.im, p, NearestEven),
+    }
+}
+
+fn mul_c_op(lhs: &BitComplex, rhs: &BitComplex) -> BitComplex {
+    mul_c(lhs, rhs, c_prec(lhs, rhs))
+}
+
+fn div_c_op(lhs: &BitComplex, rhs: &BitComplex) -> BitComplex {
+    div_c(lhs, rhs, c_prec(lhs, rhs))
+}
+
+macro_rules! impl_op_c {
+    ($($tr:ident, $f:ident, $op:ident);*) => {$(
+        impl $tr for BitComplex {
+            type Output = BitComplex;
+            fn $f(self, rhs: Self) -> Self::Output {
+                $op(&self, &rhs)
+            }
+        }
+
+        impl $tr for &BitComplex {
+            type Output = BitComplex;
+            fn $f(self, rhs: Self) -> Self::Output {
+                $op(self, rhs)
+            }
+        }
+
+        impl $tr<&BitComplex> for BitComplex {
+            type Output = BitComplex;
+            fn $f(self, rhs: &BitComplex) -> Self::Output {
+                $op(&self, rhs)
+            }
+        }
+
+        impl $tr<BitComplex> for &BitComplex {
+            type Output = BitComplex;
+            fn $f(self, rhs: BitComplex) -> Self::Output {
+                $op(self, &rhs)
+            }
+        }
+    )*};
+}
+
+impl_op_c!(Add, add, add_c_op; Sub, sub, sub_c_op; Mul, mul, mul_c_op; Div, div, div_c_op);
+
+impl Neg for BitComplex {
+    type Output = BitComplex;
+    fn neg(self) -> Self::Output {
+        BitComplex::new(-self.re, -self.im)
+    }
+}
+
+impl Neg for &BitComplex {
+    type Output = BitComplex;
+    fn neg(self) -> Self::Output {
+        BitComplex::new(-&self.re, -&self.im)
+    }
+}
@@ -0,0 +1,371 @@
+use super::bitfloat::RoundingMode;
+use super::bitint::BitInt;
+use super::traits::{DivRem, FromStrErr, PowI, I};
+use super::ubitint::UBitInt;
+use core::fmt;
+use std::cmp::Ordering;
+use std::num::IntErrorKind;
+use std::ops::*;
+use std::str::FromStr;
+
+/// Arbitrary precision decimal number `c * 10^e`, with a [`BitInt`]
+/// coefficient `c`, for exact base-10 arithmetic such as currency amounts.
+///
+/// Addition, subtraction and multiplication are exact. Division and
+/// [`BitDecimal::round_to_scale`] round to a given number of digits after the
+/// point in a [`RoundingMode`], `NearestEven` being banker's rounding.
+///
+/// The exponent is kept as written, so `1.50` keeps its two digits after the
+/// point and prints as such, while comparisons are by value: `1.50 == 1.5`.
+#[derive(Debug, Clone)]
+pub struct BitDecimal {
+    c: BitInt,
+    e: i128,
+}
+
+// 10^k, or None for a k past usize, whose power would not fit in memory
+fn pow10(k: u128) -> Option<UBitInt> {
+    let k = usize::try_from(k).ok()?;
+    Some(UBitInt::from(10_u64).powi(k))
+}
+
+// pow10 for the operators, which have no error to give
+fn pow10_or_panic(k: u128) -> UBitInt {
+    pow10(k).expect("BitDecimal exponents too far apart")
+}
+
+fn magnitude(c: &BitInt) -> UBitInt {
+    UBitInt::make(c.get_data().to_vec())
+}
+
+// m with the given sign, trimmed, and never a negative zero
+fn with_sign(m: UBitInt, neg: bool) -> BitInt {
+    let mut data = m.get_data().to_vec();
+    while data.last() == Some(&0) {
+        data.pop();
+    }
+    let neg = neg && !data.is_empty();
+    BitInt::make(data, neg)
+}
+
+fn tidy(c: BitInt) -> BitInt {
+    with_sign(magnitude(&c), c.get_sign())
+}
+
+// n / d rounded to an integer in the given mode, negated when neg is set
+fn div_int(n: &UBitInt, d: &UBitInt, neg: bool, mode: RoundingMode) -> BitInt {
+    assert!(!d.is_zero(), "attempt to divide by zero");
+    let (mut q, r) = n.div_rem(d);
+    let twice = &r << 1;
+    let half = twice >= *d;
+    let rest = !r.is_zero() && twice != *d;
+    let odd = q.get_data().first().is_some_and(|&x| x & 1 == 1);
+    if mode.away(neg, half, rest, odd) {
+        q += 1_u64;
+    }
+    with_sign(q, neg)
+}
+
+// the coefficients of x and y over the lesser of their exponents, and that
+// exponent
+fn aligned(x: &BitDecimal, y: &BitDecimal) -> (BitInt, BitInt, i128) {
+    let e = x.e.min(y.e);
+    let up = |d: &BitDecimal| {
+        if d.e == e {
+            d.c.clone()
+        } else {
+            with_sign(
+                magnitude(&d.c) * pow10_or_panic(d.e.abs_diff(e)),
+                d.c.get_sign(),
+            )
+        }
+    };
+    (up(x), up(y), e)
+}
+
+impl BitDecimal {
+    /// `c * 10^e`.
+    pub fn new(c: BitInt, e: i128) -> Self {
+        BitDecimal { c: tidy(c), e }
+    }
+
+    pub fn get_coeff(&self) -> &BitInt {
+        &self.c
+    }
+
+    pub fn get_exp(&self) -> i128 {
+        self.e
+    }
+
+    pub fn zero() -> Self {
+        BitDecimal {
+            c: BitInt::zero(),
+            e: 0,
+        }
+    }
+
+    pub fn one() -> Self {
+        BitDecimal {
+            c: BitInt::one(),
+            e: 0,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.c.is_zero()
+    }
+
+    /// Digits after the point, `-e`, saturating at `i128::MAX` for the
+    /// lowest exponent.
+    pub fn scale(&self) -> i128 {
+        self.e.saturating_neg()
+    }
+
+    pub fn abs(&self) -> Self {
+        BitDecimal {
+            c: with_sign(magnitude(&self.c), false),
+            e: self.e,
+        }
+    }
+
+    /// `self` with `scale` digits after the point, rounded in the given mode
+    /// when digits are dropped and padded with zeros otherwise. `None` if
+    /// `-scale` is out of range or too far from the exponent of `self`.
+    pub fn round_to_scale(&self, scale: i128, mode: RoundingMode) -> Option<Self> {
+        let e = scale.checked_neg()?;
+        let sh = pow10(self.e.abs_diff(e))?;
+        let c = match self.e.cmp(&e) {
+            Ordering::Equal => self.c.clone(),
+            Ordering::Greater => with_sign(magnitude(&self.c) * sh, self.c.get_sign()),
+            Ordering::Less => div_int(&magnitude(&self.c), &sh, self.c.get_sign(), mode),
+        };
+        Some(BitDecimal { c, e })
+    }
+
+    /// `self / rhs` with `scale` digits after the point, rounded in the
+    /// given mode. `None` if `-scale` is out of range or too far from the
+    /// exponents of the operands. Panics if `rhs` is zero.
+    pub fn div_to_scale(&self, rhs: &BitDecimal, scale: i128, mode: RoundingMode) -> Option<Self> {
+        // c1 10^e1 / (c2 10^e2) = (c1 10^k / c2) 10^-scale
+        let e = scale.checked_neg()?;
+        let k = self.e.checked_sub(rhs.e)?.checked_sub(e)?;
+        let sh = pow10(k.unsigned_abs())?;
+        let (mut n, mut d) = (magnitude(&self.c), magnitude(&rhs.c));
+        if k >= 0 {
+            n *= sh;
+        } else {
+            d *= sh;
+        }
+        let neg = self.c.get_sign() ^ rhs.c.get_sign();
+        Some(BitDecimal {
+            c: div_int(&n, &d, neg, mode),
+            e,
+        })
+    }
+}
+
+impl From<BitInt> for BitDecimal {
+    fn from(value: BitInt) -> Self {
+        BitDecimal {
+            c: tidy(value),
+            e: 0,
+        }
+    }
+}
+
+impl<T: I> From<T> for BitDecimal {
+    fn from(value: T) -> Self {
+        BitDecimal {
+            c: BitInt::from(value),
+            e: 0,
+        }
+    }
+}
+
+/// Plain notation with `-e` digits after the point, such as `-12.50`, or
+/// `1250e3` for a positive exponent, so the exponent reads back as written.
+/// An exponent below `-usize::MAX` is written out as well.
+impl fmt::Display for BitDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.c.get_sign() { "-" } else { "" };
+        let mut digits = magnitude(&self.c).to_string();
+        if self.e > 0 {
+            return f.pad(&format!("{sign}{digits}e{}", self.e));
+        }
+        // a scale past usize could never be padded out, so it keeps the
+        // exponent, which reads back the same
+        let Some(scale) = self.e.checked_neg().and_then(|e| usize::try_from(e).ok()) else {
+            return f.pad(&format!("{sign}{digits}e{}", self.e));
+        };
+        if scale > 0 {
+            if digits.len() <= scale {
+                digits.insert_str(0, &"0".repeat(scale - digits.len() + 1));
+            }
+            digits.insert(digits.len() - scale, '.');
+        }
+        f.pad(&format!("{sign}{digits}"))
+    }
+}
+
+/// Reads `[+-]digits[.digits][(e|E)[+-]digits]`, keeping every digit
+/// written: `1.50` has coefficient 150 and exponent -2.
+impl FromStr for BitDecimal {
+    type Err = FromStrErr;
+    fn from_str(s: &str) -> Result<Self, FromStrErr> {
+        if s.is_empty() {
+            return Err(FromStrErr::Empty);
+        }
+        if s.trim() != s {
+            return Err(FromStrErr::Whitespace);
+        }
+        let (neg, rest) = match s.as_bytes()[0] {
+            b'-' => (true, &s[1..]),
+            b'+' => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (digits, exp) = match rest.split_once(['e', 'E']) {
+            Some((digits, exp)) => {
+                let exp = exp.parse::<i128>().map_err(|err| match err.kind() {
+                    IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => FromStrErr::Overflow,
+                    _ => FromStrErr::MalformedExpression,
+                })?;
+                (digits, exp)
+            }
+            None => (rest, 0),
+        };
+        let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+        if int.is_empty() && frac.is_empty()
+            || !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit())
+        {
+            return Err(FromStrErr::MalformedExpression);
+        }
+        let m = UBitInt::from_str(&format!("{int}{frac}"))?;
+        let e = exp
+            .checked_sub(frac.len() as i128)
+            .ok_or(FromStrErr::Overflow)?;
+        Ok(BitDecimal {
+            c: with_sign(m, neg),
+            e,
+        })
+    }
+}
+
+impl PartialEq for BitDecimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for BitDecimal {}
+
+impl PartialOrd for BitDecimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// -1, 0 or 1 as x is negative, zero or positive
+fn signum(x: &BitDecimal) -> i8 {
+    match (x.is_zero(), x.c.get_sign()) {
+        (true, _) => 0,
+        (false, true) => -1,
+        (false, false) => 1,
+    }
+}
+
+// floor(log10 |x|) for a non-zero x, give or take one, from the bit length
+// of its coefficient
+fn magnitude_order(x: &BitDecimal) -> i128 {
+    let data = x.c.get_data();
+    let bits = 64 * data.len() as u64 - data[data.len() - 1].leading_zeros() as u64;
+    x.e.saturating_add((bits as f64 * std::f64::consts::LOG10_2) as i128)
+}
+
+impl Ord for BitDecimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (sa, sb) = (signum(self), signum(other));
+        if sa != sb || sa == 0 {
+            return sa.cmp(&sb);
+        }
+        // values orders of magnitude apart compare without aligning, which
+        // could need far too large a power of ten
+        let (oa, ob) = (magnitude_order(self), magnitude_order(other));
+        if oa.abs_diff(ob) > 2 {
+            let by_size = oa.cmp(&ob);
+            return if sa < 0 { by_size.reverse() } else { by_size };
+        }
+        let (a, b, _) = aligned(self, other);
+        a.cmp(&b)
+    }
+}
+
+fn add_dec(lhs: &BitDecimal, rhs: &BitDecimal) -> BitDecimal {
+    let (a, b, e) = aligned(lhs, rhs);
+    BitDecimal { c: tidy(a + b), e }
+}
+
+fn sub_dec(lhs: &BitDecimal, rhs: &BitDecimal) -> BitDecimal {
+    let (a, b, e) = aligned(lhs, rhs);
+    BitDecimal { c: tidy(a - b), e }
+}
+
+fn mul_dec(lhs: &BitDecimal, rhs: &BitDecimal) -> BitDecimal {
+    BitDecimal {
+        c: tidy(&lhs.c * &rhs.c),
+        e: lhs
+            .e
+            .checked_add(rhs.e)
+            .expect("BitDecimal exponent out of range"),
+    }
+}
+
+macro_rules! impl_op_dec {
+    ($($tr:ident, $f:ident, $op:ident);*) => {$(
+        impl $tr for BitDecimal {
+            type Output = BitDecimal;
+            fn $f(self, rhs: Self) -> Self::Output {
+                $op(&self, &rhs)
+            }
+        }
+
+        impl $tr for &BitDecimal {
+            type Output = BitDecimal;
+            fn $f(self, rhs: Self) -> Self::Output {
+                $op(self, rhs)
+            }
+        }
+
+        impl $tr<&BitDecimal> for BitDecimal {
+            type Output = BitDecimal;
+            fn $f(self, rhs: &BitDecimal) -> Self::Output {
+                $op(&self, rhs)
+            }
+        }
+
+        impl $tr<BitDecimal> for &BitDecimal {
+            type Output = BitDecimal;
+            fn $f(self, rhs: BitDecimal) -> Self::Output {
+                $op(self, &rhs)
+            }
+        }
+    )*};
+}
+
+impl_op_dec!(Add, add, add_dec; Sub, sub, sub_dec; Mul, mul, mul_dec);
+
+impl Neg for BitDecimal {
+    type Output = BitDecimal;
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
+
+impl Neg for &BitDecimal {
+    type Output = BitDecimal;
+    fn neg(self) -> Self::Output {
+        BitDecimal {
+            c: with_sign(magnitude(&self.c), !self.c.get_sign()),
+            e: self.e,
+        }
+    }
+}
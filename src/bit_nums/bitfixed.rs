@@ -0,0 +1,464 @@
+use super::bitfloat::{BitFloat, RoundingMode};
+use super::bitint_static::BitIntStatic;
+use super::traits::{DivRem, FromErr};
+use super::ubitint::UBitInt;
+use super::ubitint_static::UBitIntStatic;
+use crate::utils::mul::mul_buf;
+use crate::utils::utils::{acc, cmp_buf, inc, scmp};
+use std::cmp::Ordering;
+use std::ops::*;
+use std::slice;
+
+/// Signed fixed-point number `±(int + frac / 2^(64 F))` with `I` limbs of
+/// integer part and `F` limbs of fraction, stored inline.
+///
+/// The integer part is a [`BitIntStatic`] carrying the sign, so the range
+/// is symmetric, `|x| < 2^(64 I)`, in steps of `2^(-64 F)`. Addition and
+/// subtraction are exact, while multiplication, division and `sqrt` round
+/// the bits they drop, to nearest with ties to even unless given a
+/// [`RoundingMode`]. Addition, subtraction, multiplication and comparison
+/// work on the inline limbs and never allocate.
+///
+/// Out of range, the operators wrap the magnitude, checking in debug builds
+/// like `BitIntStatic`, and the `checked_*`, `wrapping_*` and
+/// `saturating_*` methods make the choice explicit.
+#[derive(Debug, Clone, Copy)]
+pub struct BitFixed<const I: usize, const F: usize> {
+    int: BitIntStatic<I>,
+    frac: UBitIntStatic<F>,
+}
+
+fn trimmed(limbs: &[u64]) -> UBitInt {
+    let len = limbs.iter().rposition(|&x| x != 0).map_or(0, |i| i + 1);
+    UBitInt::make(limbs[..len].to_vec())
+}
+
+// A magnitude scaled by 2^(64 F), its limbs least significant first and
+// back to back, so the slice kernels run on it without leaving the stack
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Limbs<const I: usize, const F: usize> {
+    frac: [u64; F],
+    int: [u64; I],
+}
+
+impl<const I: usize, const F: usize> Limbs<I, F> {
+    const ZERO: Self = Limbs {
+        frac: [0; F],
+        int: [0; I],
+    };
+
+    const MAX: Self = Limbs {
+        frac: [u64::MAX; F],
+        int: [u64::MAX; I],
+    };
+
+    fn as_slice(&self) -> &[u64] {
+        // SAFETY: repr(C) lays the two u64 arrays out in order, and arrays of
+        // u64 leave no padding, so self is I + F initialized u64s
+        unsafe { slice::from_raw_parts(self as *const Self as *const u64, I + F) }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u64] {
+        // SAFETY: as for as_slice
+        unsafe { slice::from_raw_parts_mut(self as *mut Self as *mut u64, I + F) }
+    }
+}
+
+// the 2 (I + F) limbs of a product of two magnitudes
+fn wide_mut<const I: usize, const F: usize>(w: &mut [Limbs<I, F>; 2]) -> &mut [u64] {
+    // SAFETY: array elements sit back to back, each I + F u64s as in
+    // Limbs::as_slice
+    unsafe { slice::from_raw_parts_mut(w as *mut _ as *mut u64, 2 * (I + F)) }
+}
+
+// A result's sign, the low I + F limbs of its magnitude and whether the
+// magnitude had more
+type Raw<const I: usize, const F: usize> = (bool, Limbs<I, F>, bool);
+
+// q rounded up a step in the given mode when the remainder is inexact,
+// vs_half comparing the remainder with half a step
+fn rounded(
+    mut q: UBitInt,
+    vs_half: Ordering,
+    exact: bool,
+    neg: bool,
+    mode: RoundingMode,
+) -> UBitInt {
+    let half = !exact && vs_half != Ordering::Less;
+    let rest = !exact && vs_half != Ordering::Equal;
+    let odd = q.get_data().first().is_some_and(|&x| x & 1 == 1);
+    if mode.away(neg, half, rest, odd) {
+        q += 1_u64;
+    }
+    q
+}
+
+impl<const I: usize, const F: usize> BitFixed<I, F> {
+    const FRAC_BITS: usize = 64 * F;
+
+    pub fn make(int: BitIntStatic<I>, frac: UBitIntStatic<F>) -> Self {
+        BitFixed { int, frac }
+    }
+
+    /// The integer part, truncated toward zero, with the sign.
+    pub fn get_int(&self) -> BitIntStatic<I> {
+        self.int
+    }
+
+    /// The fraction bits of the magnitude.
+    pub fn get_frac(&self) -> UBitIntStatic<F> {
+        self.frac
+    }
+
+    pub fn zero() -> Self {
+        Self::from_parts(false, Limbs::ZERO)
+    }
+
+    /// The largest value, `2^(64 I) - 2^(-64 F)`.
+    pub fn max_value() -> Self {
+        Self::from_parts(false, Limbs::MAX)
+    }
+
+    /// The least value, `-max_value()`.
+    pub fn min_value() -> Self {
+        Self::from_parts(true, Limbs::MAX)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.int.is_zero() && self.frac.get_data().iter().all(|&x| x == 0)
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.int.get_sign() && !self.is_zero()
+    }
+
+    pub fn abs(&self) -> Self {
+        let mut out = *self;
+        out.int.mut_abs();
+        out
+    }
+
+    fn limbs(&self) -> Limbs<I, F> {
+        Limbs {
+            frac: self.frac.get_data(),
+            int: self.int.get_data(),
+        }
+    }
+
+    // sign and magnitude scaled by 2^(64 F)
+    fn raw(&self) -> (bool, UBitInt) {
+        (self.is_negative(), trimmed(self.limbs().as_slice()))
+    }
+
+    fn from_parts(neg: bool, m: Limbs<I, F>) -> Self {
+        let nonzero = m.as_slice().iter().any(|&x| x != 0);
+        BitFixed {
+            int: BitIntStatic::make(m.int, neg && nonzero),
+            frac: UBitIntStatic::make(m.frac),
+        }
+    }
+
+    // a raw magnitude of any length cut to I + F limbs
+    fn fit(neg: bool, m: &UBitInt) -> Raw<I, F> {
+        let (data, mut out) = (m.get_data(), Limbs::ZERO);
+        let len = data.len().min(I + F);
+        out.as_mut_slice()[..len].copy_from_slice(&data[..len]);
+        (neg, out, data.len() > I + F)
+    }
+
+    fn checked((neg, m, of): Raw<I, F>) -> Option<Self> {
+        (!of).then(|| Self::from_parts(neg, m))
+    }
+
+    fn saturated((neg, m, of): Raw<I, F>) -> Self {
+        match (of, neg) {
+            (false, _) => Self::from_parts(neg, m),
+            (true, false) => Self::max_value(),
+            (true, true) => Self::min_value(),
+        }
+    }
+
+    // the operators' result: wrapped, and checked in debug builds
+    fn wrapped((neg, m, of): Raw<I, F>, what: &str) -> Self {
+        debug_assert!(!of, "attempt to {what} with overflow");
+        Self::from_parts(neg, m)
+    }
+
+    fn add_sub_raw(&self, rhs: &Self, sub: bool) -> Raw<I, F> {
+        let (mut a, mut b) = (self.limbs(), rhs.limbs());
+        let (sa, sb) = (self.is_negative(), rhs.is_negative() ^ sub);
+        if sa == sb {
+            let of = acc(a.as_mut_slice(), b.as_slice(), 0);
+            return (sa, a, of);
+        }
+        // the lesser magnitude comes off the greater, whose sign is kept
+        let mut neg = sa;
+        if cmp_buf(a.as_slice(), b.as_slice()) == Ordering::Less {
+            (a, b, neg) = (b, a, sb);
+        }
+        acc(a.as_mut_slice(), b.as_slice(), 1);
+        (neg, a, false)
+    }
+
+    fn mul_raw(&self, rhs: &Self, mode: RoundingMode) -> Raw<I, F> {
+        let neg = self.is_negative() ^ rhs.is_negative();
+        let mut wide = [Limbs::<I, F>::ZERO; 2];
+        let out = wide_mut(&mut wide);
+        mul_buf(self.limbs().as_slice(), rhs.limbs().as_slice(), out);
+
+        // the F limbs under the result round it, the I above it overflow
+        let (low, high) = out.split_at(F);
+        let (half, rest) = match low.split_last() {
+            Some((&top, below)) => (
+                top >> 63 == 1,
+                top << 1 != 0 || below.iter().any(|&x| x != 0),
+            ),
+            None => (false, false),
+        };
+        let mut m = Limbs::ZERO;
+        m.as_mut_slice().copy_from_slice(&high[..I + F]);
+        let mut of = high[I + F..].iter().any(|&x| x != 0);
+        let odd = m.as_slice().first().is_some_and(|&x| x & 1 == 1);
+        if mode.away(neg, half, rest, odd) {
+            of |= inc(m.as_mut_slice());
+        }
+        (neg, m, of)
+    }
+
+    fn div_raw(&self, rhs: &Self, mode: RoundingMode) -> Raw<I, F> {
+        let (sa, ma) = self.raw();
+        let (sb, mb) = rhs.raw();
+        assert!(!mb.is_zero(), "attempt to divide by zero");
+        let neg = sa ^ sb;
+        let (q, r) = (ma << Self::FRAC_BITS).div_rem(&mb);
+        let vs_half = (&r << 1).cmp(&mb);
+        Self::fit(neg, &rounded(q, vs_half, r.is_zero(), neg, mode))
+    }
+
+    /// `self + rhs`, or `None` out of range.
+    pub fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Self::checked(self.add_sub_raw(rhs, false))
+    }
+
+    /// `self - rhs`, or `None` out of range.
+    pub fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        Self::checked(self.add_sub_raw(rhs, true))
+    }
+
+    /// `self * rhs` rounded in the given mode, or `None` out of range.
+    pub fn mul_round(&self, rhs: &Self, mode: RoundingMode) -> Option<Self> {
+        Self::checked(self.mul_raw(rhs, mode))
+    }
+
+    /// `self / rhs` rounded in the given mode, or `None` out of range.
+    /// Panics if `rhs` is zero.
+    pub fn div_round(&self, rhs: &Self, mode: RoundingMode) -> Option<Self> {
+        Self::checked(self.div_raw(rhs, mode))
+    }
+
+    /// `self + rhs`, the magnitude wrapping modulo `2^(64 I)`.
+    pub fn wrapping_add(&self, rhs: &Self) -> Self {
+        let (neg, m, _) = self.add_sub_raw(rhs, false);
+        Self::from_parts(neg, m)
+    }
+
+    /// `self - rhs`, the magnitude wrapping modulo `2^(64 I)`.
+    pub fn wrapping_sub(&self, rhs: &Self) -> Self {
+        let (neg, m, _) = self.add_sub_raw(rhs, true);
+        Self::from_parts(neg, m)
+    }
+
+    /// `self * rhs` to nearest, the magnitude wrapping modulo `2^(64 I)`.
+    pub fn wrapping_mul(&self, rhs: &Self) -> Self {
+        let (neg, m, _) = self.mul_raw(rhs, RoundingMode::NearestEven);
+        Self::from_parts(neg, m)
+    }
+
+    /// `self / rhs` to nearest, the magnitude wrapping modulo `2^(64 I)`.
+    /// Panics if `rhs` is zero.
+    pub fn wrapping_div(&self, rhs: &Self) -> Self {
+        let (neg, m, _) = self.div_raw(rhs, RoundingMode::NearestEven);
+        Self::from_parts(neg, m)
+    }
+
+    /// `self + rhs`, clamped to the range.
+    pub fn saturating_add(&self, rhs: &Self) -> Self {
+        Self::saturated(self.add_sub_raw(rhs, false))
+    }
+
+    /// `self - rhs`, clamped to the range.
+    pub fn saturating_sub(&self, rhs: &Self) -> Self {
+        Self::saturated(self.add_sub_raw(rhs, true))
+    }
+
+    /// `self * rhs` to nearest, clamped to the range.
+    pub fn saturating_mul(&self, rhs: &Self) -> Self {
+        Self::saturated(self.mul_raw(rhs, RoundingMode::NearestEven))
+    }
+
+    /// `self / rhs` to nearest, clamped to the range. Panics if `rhs` is
+    /// zero.
+    pub fn saturating_div(&self, rhs: &Self) -> Self {
+        Self::saturated(self.div_raw(rhs, RoundingMode::NearestEven))
+    }
+
+    /// The square root rounded in the given mode, or `None` for negative
+    /// values.
+    pub fn sqrt_round(&self, mode: RoundingMode) -> Option<Self> {
+        let (neg, m) = self.raw();
+        if neg {
+            return None;
+        }
+        if m.is_zero() {
+            return Some(Self::zero());
+        }
+        // floor(sqrt(n)) from a root rounded down with room for its integer
+        // part, then the remainder n - q^2 places sqrt(n) against q + 1/2
+        let n = m << Self::FRAC_BITS;
+        let bits = 64 * n.get_data().len() / 2 + 1;
+        let root = BitFloat::from(n.clone()).sqrt_round(bits, RoundingMode::TowardZero);
+        let q = UBitInt::make(
+            root.to_bitint(RoundingMode::TowardZero)
+                .ok()?
+                .get_data()
+                .to_vec(),
+        );
+        let r = n - &q * &q;
+        // the remainder is never exactly q + 1/4, so the root is never a tie
+        let vs_half = if r > q {
+            Ordering::Greater
+        } else {
+            Ordering::Less
+        };
+        let q = rounded(q, vs_half, r.is_zero(), false, mode);
+        Self::checked(Self::fit(false, &q))
+    }
+
+    /// The square root to nearest, or `None` for negative values.
+    pub fn sqrt(&self) -> Option<Self> {
+        self.sqrt_round(RoundingMode::NearestEven)
+    }
+
+    /// `x` rounded to a multiple of `2^(-64 F)` in the given mode, or an
+    /// error for NaN and values out of range.
+    pub fn from_bitfloat(x: &BitFloat, mode: RoundingMode) -> Result<Self, FromErr> {
+        let scaled = x.ldexp(Self::FRAC_BITS as i128).to_bitint(mode)?;
+        let m = trimmed(scaled.get_data());
+        Self::checked(Self::fit(scaled.get_sign(), &m)).ok_or(FromErr::Overflow)
+    }
+
+    /// The `f64` nearest to `self` in the given mode, or an error where it
+    /// rounds to infinity.
+    pub fn to_f64_round(&self, mode: RoundingMode) -> Result<f64, FromErr> {
+        BitFloat::from(*self).to_f64_round(mode)
+    }
+}
+
+impl<const I: usize, const F: usize> From<BitIntStatic<I>> for BitFixed<I, F> {
+    fn from(value: BitIntStatic<I>) -> Self {
+        BitFixed {
+            int: value,
+            frac: UBitIntStatic::make([0; F]),
+        }
+    }
+}
+
+/// Exact, as every fixed-point value is a dyadic rational.
+impl<const I: usize, const F: usize> From<BitFixed<I, F>> for BitFloat {
+    fn from(value: BitFixed<I, F>) -> Self {
+        let (neg, m) = value.raw();
+        let mut out = BitFloat::from(m).ldexp(-(BitFixed::<I, F>::FRAC_BITS as i128));
+        if neg {
+            out.neg_mut();
+        }
+        out
+    }
+}
+
+impl<const I: usize, const F: usize> TryFrom<&BitFloat> for BitFixed<I, F> {
+    type Error = FromErr;
+    fn try_from(value: &BitFloat) -> Result<Self, Self::Error> {
+        BitFixed::from_bitfloat(value, RoundingMode::NearestEven)
+    }
+}
+
+impl<const I: usize, const F: usize> TryFrom<f64> for BitFixed<I, F> {
+    type Error = FromErr;
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        BitFixed::from_bitfloat(&BitFloat::from(value), RoundingMode::NearestEven)
+    }
+}
+
+impl<const I: usize, const F: usize> TryFrom<BitFixed<I, F>> for f64 {
+    type Error = FromErr;
+    fn try_from(value: BitFixed<I, F>) -> Result<Self, Self::Error> {
+        value.to_f64_round(RoundingMode::NearestEven)
+    }
+}
+
+impl<const I: usize, const F: usize> PartialEq for BitFixed<I, F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<const I: usize, const F: usize> Eq for BitFixed<I, F> {}
+
+impl<const I: usize, const F: usize> PartialOrd for BitFixed<I, F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const I: usize, const F: usize> Ord for BitFixed<I, F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let neg = self.is_negative();
+        if neg != other.is_negative() {
+            return other.is_negative().cmp(&neg);
+        }
+        scmp(
+            neg,
+            cmp_buf(self.limbs().as_slice(), other.limbs().as_slice()),
+        )
+    }
+}
+
+impl<const I: usize, const F: usize> Add for BitFixed<I, F> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::wrapped(self.add_sub_raw(&rhs, false), "add")
+    }
+}
+
+impl<const I: usize, const F: usize> Sub for BitFixed<I, F> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::wrapped(self.add_sub_raw(&rhs, true), "subtract")
+    }
+}
+
+impl<const I: usize, const F: usize> Mul for BitFixed<I, F> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::wrapped(self.mul_raw(&rhs, RoundingMode::NearestEven), "multiply")
+    }
+}
+
+impl<const I: usize, const F: usize> Div for BitFixed<I, F> {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self::Output {
+        Self::wrapped(self.div_raw(&rhs, RoundingMode::NearestEven), "divide")
+    }
+}
+
+impl<const I: usize, const F: usize> Neg for BitFixed<I, F> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        let mut out = self;
+        if !self.is_zero() {
+            out.int.mut_neg();
+        }
+        out
+    }
+}
@@ -1,756 +1,1149 @@
-use crate::{
-    impl_commutative, impl_commutative_peq_pord,
-    traits::{
-        DivVariants, FromErr, FromStrErr, Int, MulVariants, Prim, Recipricol, Rounding, SmallBuf,
-        Sqr, SqrVariants, SEM,
-    },
-    utils::*,
-};
-use std::{fmt, ops::*, str::FromStr};
-
+use super::bitint::BitInt;
+use super::traits::{DivVariants, FromErr, MulVariants, Rounding, Sqr, SqrVariants, SEM};
+use super::ubitint::UBitInt;
+use super::ubitint_static::UBitIntStatic;
+use crate::utils::{div::*, mul::*, utils::*};
+use crate::{impl_commutative, impl_commutative_peq_pord};
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::ops::*;
+
+/// Arbitrary precision binary floating point number.
+///
+/// The value is `m * 2^(64 * (e + 1 - m.len()))`, where `m` holds the
+/// significand limbs without zero limbs at either end and `e` is the exponent
+/// of the top limb, so one is stored as `m = [1], e = 0`. Zero and the
+/// infinities have an empty mantissa with `e` set to `i128::MIN` and
+/// `i128::MAX`, and zero keeps its sign. NaN has `e = i128::MAX` with a
+/// mantissa holding its payload, `[1]` for the plain NaN, see
+/// [`BitFloat::nan_payload`].
+///
+/// Comparisons follow IEEE 754: NaN is unordered and unequal to everything,
+/// itself included, and the two zeros compare equal. Invalid operations such
+/// as `0 / 0` or `inf - inf` give NaN and raise the invalid flag, see
+/// [`Flags`].
+///
+/// Precision is counted in bits of the significand, from its top set bit
+/// down, and a precision of zero rounds to one bit. The arithmetic operators round to nearest, ties to even, at the
+/// larger of the operands' precisions and the thread's default precision.
+///
+/// The functions said to be correctly rounded raise their working precision
+/// until the result rounds unambiguously, but stop at about sixteen times
+/// the target, `16 * ceil(prec / 64) + 64` limbs. A result still undecided
+/// there, which is exact, a tie, or within an error of that size from one,
+/// is rounded from the last approximation and raises the inexact flag.
 #[derive(Debug, Clone)]
-struct Mantissa {
-    buf: Vec<u64>,
-    start: usize,
-    prev_sz: usize,
-}
+pub struct BitFloat {
+    s: bool,
+    e: i128,
+    m: Vec<u64>,
+}
+
+/// How a result that does not fit the target precision is rounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingMode {
+    /// To the nearest value, ties to the even one. Used by the operators.
+    #[default]
+    NearestEven,
+    /// To the nearest value, ties away from zero.
+    NearestAway,
+    /// Toward zero, truncating the dropped bits.
+    TowardZero,
+    /// Toward positive infinity.
+    TowardPositive,
+    /// Toward negative infinity.
+    TowardNegative,
+    /// To either neighbour of the exact result, keeping exact results. This
+    /// only promises an error under one ulp and is the cheapest mode.
+    Faithful,
+}
+
+impl RoundingMode {
+    // Whether a magnitude with sign s steps away from zero, given the first
+    // dropped bit, whether any bit below it is set and the parity of the last
+    // bit kept.
+    pub(crate) fn away(self, s: bool, half: bool, rest: bool, odd: bool) -> bool {
+        match self {
+            RoundingMode::NearestEven => half && (rest || odd),
+            RoundingMode::NearestAway => half,
+            RoundingMode::TowardZero | RoundingMode::Faithful => false,
+            RoundingMode::TowardPositive => !s && (half || rest),
+            RoundingMode::TowardNegative => s && (half || rest),
+        }
+    }
+}
+
+/// Sticky IEEE 754 exception flags. Operations on the current thread raise
+/// them, and they stay raised until [`BitFloat::clear_flags`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Flags {
+    /// An operation had no defined result and gave NaN.
+    pub invalid: bool,
+    /// An exact infinity came from finite operands, as in `1 / 0` or `ln 0`.
+    pub div_by_zero: bool,
+    /// A finite result was too large and became infinite.
+    pub overflow: bool,
+    /// A non-zero result was too small and became zero or subnormal.
+    pub underflow: bool,
+    /// A result was rounded.
+    pub inexact: bool,
+}
+
+impl Flags {
+    pub(crate) const NONE: Flags = Flags {
+        invalid: false,
+        div_by_zero: false,
+        overflow: false,
+        underflow: false,
+        inexact: false,
+    };
+    pub(crate) const INVALID: Flags = Flags {
+        invalid: true,
+        ..Flags::NONE
+    };
+    pub(crate) const DIV_BY_ZERO: Flags = Flags {
+        div_by_zero: true,
+        ..Flags::NONE
+    };
+    pub(crate) const OVERFLOW: Flags = Flags {
+        overflow: true,
+        inexact: true,
+        ..Flags::NONE
+    };
+    pub(crate) const UNDERFLOW: Flags = Flags {
+        underflow: true,
+        inexact: true,
+        ..Flags::NONE
+    };
+    pub(crate) const INEXACT: Flags = Flags {
+        inexact: true,
+        ..Flags::NONE
+    };
 
-impl Mantissa {
-    fn find_fib(n: usize) -> (usize, usize) {
-        if n <= 2 {
-            return (2, 1);
-        }
-        const SQRT_5: f64 = 5.0.sqrt();
-        const PHI: f64 = (1.0 + SQRT_5) / 2.0;
-        let i = ((n as f64) * SQRT_5).log(PHI) as i32;
-        let mut f0 = (PHI.powi(i) / SQRT_5 + 0.5) as usize;
-        let mut f1 = (f0 * PHI + 0.5) as usize;
-        if f1 <= n {
-            f1 += f0;
-            f0 = f1 - f0;
+    fn union(self, other: Flags) -> Flags {
+        Flags {
+            invalid: self.invalid || other.invalid,
+            div_by_zero: self.div_by_zero || other.div_by_zero,
+            overflow: self.overflow || other.overflow,
+            underflow: self.underflow || other.underflow,
+            inexact: self.inexact || other.inexact,
         }
-        (f1, f0)
     }
+}
 
-    fn new() -> Self {
-        Mantissa {
-            buf: vec![0; 2],
-            start: 2,
-            prev_sz: 1,
-        }
+thread_local! {
+    static DEFAULT_PREC: Cell<usize> = const { Cell::new(64) };
+    static FLAGS: Cell<Flags> = const { Cell::new(Flags::NONE) };
+}
+
+// raises flags on the current thread
+pub(crate) fn raise(flags: Flags) {
+    FLAGS.with(|f| f.set(f.get().union(flags)));
+}
+
+// Runs f, dropping the flags it raises. Internal steps round freely, so a
+// function raises its flags for the result alone.
+pub(crate) fn quietly<T>(f: impl FnOnce() -> T) -> T {
+    let saved = BitFloat::flags();
+    let out = f();
+    FLAGS.with(|f| f.set(saved));
+    out
+}
+
+// Runs f quietly, reporting whether it raised the inexact flag
+pub(crate) fn with_inexact<T>(f: impl FnOnce() -> T) -> (T, bool) {
+    quietly(|| {
+        BitFloat::clear_flags();
+        let out = f();
+        (out, BitFloat::flags().inexact)
+    })
+}
+
+// NaN, raising the invalid flag
+pub(crate) fn invalid() -> BitFloat {
+    raise(Flags::INVALID);
+    BitFloat::nan()
+}
+
+// the infinity of sign s for a finite result past any exponent we can reach
+pub(crate) fn overflow(s: bool) -> BitFloat {
+    raise(Flags::OVERFLOW);
+    BitFloat {
+        s,
+        ..BitFloat::inf()
     }
+}
 
-    fn make_take(mut buf: Vec<u64>) -> Self {
-        let len = buf.len();
-        let (sz, prev_sz) = Self::find_fib(len);
-        let start = sz - len;
-        buf.resize(sz, 0);
-        buf.copy_within(..len, start);
-        Mantissa {
-            buf: buf,
-            start,
-            prev_sz,
-        }
+// the zero of sign s for a non-zero result below any exponent we can reach
+pub(crate) fn underflow(s: bool) -> BitFloat {
+    raise(Flags::UNDERFLOW);
+    BitFloat {
+        s,
+        ..BitFloat::zero()
     }
+}
 
-    fn make_ref(buf: &[u64]) -> Self {
-        let len = buf.len();
-        let (sz, prev_sz) = Self::find_fib(len);
-        let start = sz - len;
-        let mut new_buf = vec![0; sz];
-        new_buf[sz..].copy_from_slice(buf);
-        Mantissa {
-            buf: new_buf,
-            start,
-            prev_sz,
-        }
+impl BitFloat {
+    /// The precision in bits the operators round to at least, for the
+    /// current thread. It starts at 64.
+    pub fn default_prec() -> usize {
+        DEFAULT_PREC.with(Cell::get)
     }
 
-    fn len(&self) -> usize {
-        self.buf.len() - self.start
+    /// Sets the precision in bits the operators round to at least, for the
+    /// current thread.
+    pub fn set_default_prec(prec: usize) {
+        DEFAULT_PREC.with(|p| p.set(prec.max(1)));
     }
 
-    fn is_empty(&self) -> bool {
-        return self.start == self.buf.len();
+    /// The exception flags raised on the current thread since they were last
+    /// cleared.
+    pub fn flags() -> Flags {
+        FLAGS.with(Cell::get)
     }
 
-    fn push(&mut self, val: u64) {
-        self.buf.push(val);
+    /// Clears the exception flags of the current thread.
+    pub fn clear_flags() {
+        FLAGS.with(|f| f.set(Flags::NONE));
     }
 
-    fn push_slice(&mut self, vals: &[u64]) {
-        self.buf.extend_from_slice(vals);
+    pub fn get_m(&self) -> &[u64] {
+        &self.m
+    }
+
+    pub fn get_e(&self) -> i128 {
+        self.e
     }
 
-    fn push_zeros(&mut self, amt: usize) {
-        self.buf.resize(self.buf.len() + amt, 0);
+    pub fn get_s(&self) -> bool {
+        self.s
     }
 
-    fn realloc(&mut self) {
-        let len = self.buf.len();
-        let new_start = self.start + self.prev_sz;
-        self.buf.resize(len + self.prev_sz, 0);
-        self.buf.copy_within(self.start..len, new_start);
-        self.prev_sz = len;
-        self.start = new_start;
+    /// Builds `(-1)^s * m * 2^(64 * (e + 1 - m.len()))`, dropping zero limbs
+    /// at either end of `m`.
+    pub fn make(s: bool, e: i128, m: &[u64]) -> Self {
+        let lo = e + 1 - m.len() as i128;
+        round_bf(s, m.to_vec(), lo, false, usize::MAX)
     }
 
-    fn put(&mut self, val: u64) {
-        if self.start == 0 {
-            self.realloc();
+    pub fn zero() -> Self {
+        BitFloat {
+            s: false,
+            e: i128::MIN,
+            m: Vec::new(),
         }
-        self.start -= 1;
-        self.buf[self.start] = val;
     }
 
-    fn put_slice(&mut self, vals: &[u64]) {
-        if self.start < vals.len() {
-            self.realloc();
-            while self.start < vals.len() {
-                self.realloc();
-            }
+    pub fn neg_zero() -> Self {
+        BitFloat {
+            s: true,
+            e: i128::MIN,
+            m: Vec::new(),
         }
-        self.start -= vals.len();
-        self.buf[self.start..self.start + vals.len()].copy_from_slice(vals);
     }
 
-    fn put_zeros(&mut self, amt: usize) {
-        if amt == 0 {
-            return;
+    pub fn one() -> Self {
+        BitFloat {
+            s: false,
+            e: 0,
+            m: vec![1],
         }
-        if self.start < amt {
-            self.realloc();
-            while self.start < amt {
-                self.realloc();
-            }
+    }
+
+    pub fn neg_one() -> Self {
+        BitFloat {
+            s: true,
+            e: 0,
+            m: vec![1],
         }
-        self.start -= amt;
-        self.buf[self.start..self.start + amt].fill(0);
     }
 
-    fn drain(&mut self, amt: usize) {
-        self.start = (self.start + amt).min(self.len());
+    pub fn inf() -> Self {
+        BitFloat {
+            s: false,
+            e: i128::MAX,
+            m: Vec::new(),
+        }
     }
 
-    fn trunc(&mut self, amt: usize) {
-        self.buf.truncate(self.buf.len().saturating_sub(amt));
+    pub fn neg_inf() -> Self {
+        BitFloat {
+            s: true,
+            e: i128::MAX,
+            m: Vec::new(),
+        }
     }
 
-    fn pop(&mut self) -> u64 {
-        self.buf.pop().unwrap()
+    pub fn nan() -> Self {
+        BitFloat {
+            s: false,
+            e: i128::MAX,
+            m: vec![1],
+        }
     }
 
-    fn pull(&mut self) -> u64 {
-        self.start += 1;
-        self.buf[self.start - 1]
+    /// Whether `self` is either zero.
+    pub fn is_zero(&self) -> bool {
+        self.e == i128::MIN
     }
 
-    fn trim_zeros(&mut self) -> Option<usize> {
-        let mut fnt_idx = self.start;
-        let mut end_idx = self.buf.len() - 1;
-        loop {
-            if fnt_idx >= end_idx {
-                self.start = self.buf.len();
-                return None;
-            }
-            let fnt_zero = self.buf[fnt_idx] == 0;
-            let end_zero = self.buf[end_idx] == 0;
-            if !(fnt_zero || end_zero) {
-                break;
-            }
-            if fnt_zero {
-                fnt_idx += 1;
-            }
-            if end_zero {
-                end_idx -= 1;
-            }
+    /// Whether `self` is either infinity.
+    pub fn is_inf(&self) -> bool {
+        self.e == i128::MAX && self.m.is_empty()
+    }
+
+    pub fn is_nan(&self) -> bool {
+        self.e == i128::MAX && !self.m.is_empty()
+    }
+
+    /// The payload of a NaN, the fraction bits below the quiet bit of the
+    /// interchange encoding it was read from, or `None` for numbers. The
+    /// NaNs the arithmetic produces have payload zero.
+    pub fn nan_payload(&self) -> Option<UBitInt> {
+        if !self.is_nan() {
+            return None;
         }
-        self.start = fnt_idx;
-        let end_truc_len = self.buf.len() - end_idx - 1;
-        self.buf.truncate(end_idx + 1);
-        return Some(end_truc_len);
+        let mut m = self.m.clone();
+        shr_buf(&mut m, 2);
+        trim_lz(&mut m);
+        Some(UBitInt::make(m))
     }
 
-    fn take(&self, n: usize) -> &[u64] {
-        let idx = self.buf.len().saturating_sub(n.max(1)).max(self.start);
-        &self.buf[idx..]
+    /// Whether `self` is a signaling NaN, read from an interchange encoding
+    /// with the quiet bit clear. Signaling NaNs do not trap here: the bit is
+    /// kept only so the encoding can be written back.
+    pub fn is_signaling_nan(&self) -> bool {
+        self.is_nan() && self.m[0] & 2 != 0
     }
 
-    fn top(&self) -> u64 {
-        *self.last().unwrap()
+    /// Whether `self` is neither infinite nor NaN.
+    pub fn is_finite(&self) -> bool {
+        self.e != i128::MAX
     }
-}
 
-impl Deref for Mantissa {
-    type Target = [u64];
-    fn deref(&self) -> &[u64] {
-        &self.buf[self.start..]
+    /// Whether `self` is a finite integer, either zero included.
+    pub fn is_integer(&self) -> bool {
+        self.is_zero() || (self.is_finite() && self.lo() >= 0)
     }
-}
 
-impl DerefMut for Mantissa {
-    fn deref_mut(&mut self) -> &mut [u64] {
-        &mut self.buf[self.start..]
+    pub fn neg_mut(&mut self) {
+        self.s ^= true;
     }
-}
 
-impl PartialEq for Mantissa {
-    fn eq(&self, other: &Self) -> bool {
-        self.buf[self.start..] == other.buf[other.start..]
+    /// IEEE 754 total order: -NaN < -inf < ... < -0 < +0 < ... < inf < NaN.
+    pub fn total_cmp(&self, other: &Self) -> Ordering {
+        if self.s != other.s {
+            return scmp(self.s, Ordering::Greater);
+        }
+        let ord = match (self.is_nan(), other.is_nan()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => cmp_abs(self, other),
+        };
+        scmp(self.s, ord)
     }
-}
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct BitFloat {
-    s: bool,
-    e: i128,
-    m: Mantissa,
-}
+    pub fn abs(&self) -> Self {
+        let mut out = self.clone();
+        out.s = false;
+        out
+    }
 
-impl BitFloat {
-    pub fn get_m(&self) -> &[u64] {
-        &self.m
+    pub fn abs_mut(&mut self) {
+        self.s = false;
     }
 
-    pub fn get_e(&self) -> i128 {
-        self.e
+    /// Number of bits in the significand, from the top set bit to the lowest
+    /// one. Zero, the infinities and NaN have none.
+    pub fn prec(&self) -> usize {
+        if self.is_zero() || !self.is_finite() {
+            return 0;
+        }
+        (self.bit_exp() - 64 * self.lo() - self.m[0].trailing_zeros() as i128 + 1) as usize
     }
 
-    pub fn get_s(&self) -> bool {
-        self.s
+    /// `self` rounded to `prec` bits, to nearest with ties to even.
+    pub fn to_prec(&self, prec: usize) -> Self {
+        self.to_prec_round(prec, RoundingMode::NearestEven)
     }
 
-    pub fn make(s: bool, e: i128, m: &[u64]) -> Self {
-        BitFloat {
-            s,
-            e,
-            m: Mantissa::make_ref(m),
+    /// `self` rounded to `prec` bits in the given mode.
+    pub fn to_prec_round(&self, prec: usize, mode: RoundingMode) -> Self {
+        if self.prec() <= prec {
+            return self.clone();
         }
+        round_bf_mode(self.s, self.m.to_vec(), self.lo(), false, prec, mode)
     }
 
-    pub const ZERO: Self = BitFloat {
-        s: false,
-        e: i128::MIN,
-        m: Mantissa::new(),
-    };
+    /// `self + rhs` rounded to `prec` bits in the given mode.
+    pub fn add_round(&self, rhs: &BitFloat, prec: usize, mode: RoundingMode) -> Self {
+        add_sub_bf_mode(self, rhs, false, prec, mode)
+    }
 
-    pub const ONE: Self = BitFloat {
-        s: false,
-        e: 0,
-        m: Mantissa::make_ref(&[1]),
-    };
+    /// `self - rhs` rounded to `prec` bits in the given mode.
+    pub fn sub_round(&self, rhs: &BitFloat, prec: usize, mode: RoundingMode) -> Self {
+        add_sub_bf_mode(self, rhs, true, prec, mode)
+    }
 
-    pub const NEG_ONE: Self = BitFloat {
-        s: true,
-        e: 0,
-        m: Mantissa::make_ref(&[1]),
-    };
+    /// `self * rhs` rounded to `prec` bits in the given mode.
+    pub fn mul_round(&self, rhs: &BitFloat, prec: usize, mode: RoundingMode) -> Self {
+        mul_bf_mode(self, rhs, prec, mode)
+    }
 
-    pub const INF: Self = BitFloat {
-        s: false,
-        e: i128::MAX,
-        m: Mantissa::new(),
-    };
+    /// `self / rhs` rounded to `prec` bits in the given mode. With `prec`
+    /// at `usize::MAX`, a quotient that terminates in binary comes out
+    /// exactly and any other is rounded at the bits of both operands.
+    pub fn div_round(&self, rhs: &BitFloat, prec: usize, mode: RoundingMode) -> Self {
+        div_bf_mode(self, rhs, prec, mode)
+    }
 
-    pub const NEG_INF: Self = BitFloat {
-        s: true,
-        e: i128::MAX,
-        m: Mantissa::new(),
-    };
+    /// `self` rounded to an integer in the given mode. `Faithful` truncates.
+    pub fn round_to_int(&self, mode: RoundingMode) -> Self {
+        round_int(self, mode)
+    }
 
-    pub fn is_zero(&self) -> bool {
-        self.e == i128::MIN
+    /// `self` rounded to an integer in the given mode, as a `BitInt`. An
+    /// error for the infinities and NaN.
+    pub fn to_bitint(&self, mode: RoundingMode) -> Result<BitInt, FromErr> {
+        if self.is_nan() {
+            return Err(FromErr::NaN);
+        }
+        if self.is_inf() {
+            return Err(FromErr::Overflow);
+        }
+        let r = round_int(self, mode);
+        Ok(BitInt::make(int_limbs(&r), r.s && !r.is_zero()))
     }
 
-    pub fn neg_mut(&mut self) {
-        self.s ^= true;
+    /// The `f64` nearest to `self` in the given mode, or an error where it
+    /// rounds to infinity or for a NaN payload too wide for it.
+    pub fn to_f64_round(&self, mode: RoundingMode) -> Result<f64, FromErr> {
+        let (s, bits) = to_ieee(self, 52, 1023, mode)?;
+        Ok(f64::from_bits(((s as u64) << 63) | bits as u64))
     }
 
-    pub fn abs(&self) -> Self {
-        let mut out = self.clone();
-        out.s = true;
-        return out;
+    /// The `f32` nearest to `self` in the given mode, or an error where it
+    /// rounds to infinity or for a NaN payload too wide for it.
+    pub fn to_f32_round(&self, mode: RoundingMode) -> Result<f32, FromErr> {
+        let (s, bits) = to_ieee(self, 23, 127, mode)?;
+        Ok(f32::from_bits(((s as u32) << 31) | bits as u32))
     }
 
-    pub fn abs_mut(&mut self) {
-        self.s = true;
+    // 2^n
+    pub(crate) fn pow2(n: i128) -> Self {
+        BitFloat {
+            s: false,
+            e: n.div_euclid(64),
+            m: vec![1 << n.rem_euclid(64)],
+        }
+    }
+
+    // floor(log2|self|) for finite non-zero values
+    pub(crate) fn bit_exp(&self) -> i128 {
+        64 * self.e + self.m[self.m.len() - 1].ilog2() as i128
+    }
+
+    // exponent of the lowest limb
+    pub(crate) fn lo(&self) -> i128 {
+        self.e + 1 - self.m.len() as i128
+    }
+
+    // whether the integer part is odd, for integral values
+    pub(crate) fn is_odd(&self) -> bool {
+        self.lo() == 0 && self.m[0] & 1 == 1
+    }
+
+    // power of two exponent k if |self| = 2^k
+    pub(crate) fn pow2_exp(&self) -> Option<i128> {
+        (self.is_finite() && !self.is_zero() && self.m.len() == 1 && self.m[0].is_power_of_two())
+            .then(|| self.bit_exp())
     }
 }
 
-impl Rounding for BitFloat {
-    fn floor(&self) -> Self {
-        if self.e < 0 {
-            return if self.s { Self::NEG_ONE } else { Self::ZERO };
-        }
+// Rounds buf * 2^(64 * lo) to p bits, to nearest with ties to even. `sticky`
+// marks non-zero bits below buf, which then needs more than p bits.
+pub(crate) fn round_bf(s: bool, buf: Vec<u64>, lo: i128, sticky: bool, p: usize) -> BitFloat {
+    round_bf_mode(s, buf, lo, sticky, p, RoundingMode::NearestEven)
+}
 
-        let mut out = self.clone();
-        let amt = self
-            .m
-            .len()
-            .saturating_sub(usize::try_from(self.e).unwrap_or(self.m.len()));
-        out.m.drain(amt);
-        if out.s {
-            if dec(&mut out.m) {
-                out.m.pop();
-                out.e -= 1;
+// round_bf in any rounding mode
+pub(crate) fn round_bf_mode(
+    s: bool,
+    mut buf: Vec<u64>,
+    mut lo: i128,
+    sticky: bool,
+    p: usize,
+    mode: RoundingMode,
+) -> BitFloat {
+    trim_lz(&mut buf);
+    if buf.is_empty() {
+        return BitFloat {
+            s,
+            ..BitFloat::zero()
+        };
+    }
+    // no precision keeps fewer bits than one
+    let p = p.max(1);
+    let len = 64 * buf.len() - buf[buf.len() - 1].leading_zeros() as usize;
+    debug_assert!(!sticky || len > p);
+
+    if len > p {
+        // the lowest bit kept is bit `cut` of buf, with the guard bit below it
+        let cut = len - p;
+        let (gl, gb) = ((cut - 1) / 64, (cut - 1) % 64);
+        let half = (buf[gl] >> gb) & 1 == 1;
+        let rest = sticky || buf[gl] & ((1 << gb) - 1) != 0 || buf[..gl].iter().any(|&x| x != 0);
+        if half || rest {
+            raise(Flags::INEXACT);
+        }
+        let (kl, kb) = (cut / 64, cut % 64);
+        buf.drain(..kl);
+        lo += kl as i128;
+        buf[0] &= u64::MAX << kb;
+        if mode.away(s, half, rest, (buf[0] >> kb) & 1 == 1) {
+            let (sum, carry) = buf[0].overflowing_add(1 << kb);
+            buf[0] = sum;
+            if carry && inc(&mut buf[1..]) {
+                buf.push(1);
             }
         }
+    }
 
-        out
+    let tz = buf.iter().position(|&x| x != 0).unwrap();
+    buf.drain(..tz);
+    lo += tz as i128;
+    BitFloat {
+        s,
+        e: lo + buf.len() as i128 - 1,
+        m: buf,
+    }
+}
+
+// Ziv's rounding test: the correctly rounded value at p bits, if every value
+// within 2^err of y rounds to it.
+pub(crate) fn ziv_round(y: &BitFloat, err: i128, p: usize, mode: RoundingMode) -> Option<BitFloat> {
+    if y.is_zero() || !y.is_finite() {
+        return None;
     }
+    // an error far below the last bit kept acts like one just below it
+    let err = BitFloat::pow2(err.max(y.bit_exp() - p as i128 - 128));
+    let lo = add_sub_bf(y, &err, true, usize::MAX).to_prec_round(p, mode);
+    let hi = add_sub_bf(y, &err, false, usize::MAX).to_prec_round(p, mode);
+    (lo == hi).then_some(lo)
+}
 
-    fn floor_mut(&mut self) {
-        if self.e < 0 {
-            if self.s {
-                *self = Self::NEG_ONE;
-            } else {
-                *self = Self::ZERO;
-            };
-            return;
-        }
+// The working precision in limbs past which the Ziv loops stop raising it
+pub(crate) fn ziv_cap(prec: usize) -> usize {
+    16 * prec.max(1).div_ceil(64) + 64
+}
 
-        let amt = self
-            .m
-            .len()
-            .saturating_sub(usize::try_from(self.e).unwrap_or(self.m.len()));
-        self.m.drain(amt);
-        if self.s {
-            if dec(&mut self.m) {
-                self.m.pop();
-                self.e -= 1;
-            }
+// Runs approx(w) -> (y, err), with |y - f| < 2^err at a working precision of
+// w limbs, raising w until y rounds unambiguously to prec bits. Past a cap
+// the value is most likely exact or a midpoint, so y is rounded as it stands
+// and raises the inexact flag, as it may still be off by the last bit.
+pub(crate) fn ziv_loop(prec: usize, approx: impl FnMut(usize) -> (BitFloat, i128)) -> BitFloat {
+    ziv_loop_round(prec, RoundingMode::NearestEven, approx)
+}
+
+// ziv_loop in any rounding mode
+pub(crate) fn ziv_loop_round(
+    prec: usize,
+    mode: RoundingMode,
+    mut approx: impl FnMut(usize) -> (BitFloat, i128),
+) -> BitFloat {
+    let [out] = ziv_loop_n(prec, mode, |w| [approx(w)]);
+    out
+}
+
+// ziv_loop for several results sharing the work of one approximation
+pub(crate) fn ziv_loop_n<const N: usize>(
+    prec: usize,
+    mode: RoundingMode,
+    mut approx: impl FnMut(usize) -> [(BitFloat, i128); N],
+) -> [BitFloat; N] {
+    let prec = prec.max(1);
+    let cap = ziv_cap(prec);
+    let mut w = prec.div_ceil(64) + 2;
+    loop {
+        let (ys, rounded) = quietly(|| {
+            let ys = approx(w);
+            let rounded: Vec<_> = ys
+                .iter()
+                .map(|(y, err)| ziv_round(y, *err, prec, mode))
+                .collect();
+            (ys, rounded)
+        });
+        if rounded.iter().all(Option::is_some) {
+            raise(Flags::INEXACT);
+            return rounded
+                .into_iter()
+                .map(Option::unwrap)
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap();
+        }
+        if w >= cap {
+            raise(Flags::INEXACT);
+            return ys.map(|(y, _)| y.to_prec_round(prec, mode));
         }
+        w += w / 2;
     }
+}
 
-    fn ceil(&self) -> Self {
-        if self.e < 0 {
-            return if self.s { Self::ZERO } else { Self::ONE };
-        }
+// Error exponent for y carrying `lost` bits of error at w limbs. A rounding
+// to w limbs is relative to the top limb, which may hold a single bit, so
+// only w - 1 limbs are counted.
+pub(crate) fn rel_err(y: &BitFloat, lost: i128, w: usize) -> i128 {
+    if y.is_zero() {
+        return i128::MAX / 2;
+    }
+    y.bit_exp() + lost - 64 * (w as i128 - 1)
+}
 
-        let mut out = self.clone();
-        let amt = self
-            .m
-            .len()
-            .saturating_sub(usize::try_from(self.e).unwrap_or(self.m.len()));
-        out.m.drain(amt);
-        if !out.s {
-            if inc(&mut out.m) {
-                out.m.push(1);
-                out.e += 1;
-            }
+// compares |lhs| and |rhs|
+fn cmp_abs(lhs: &BitFloat, rhs: &BitFloat) -> Ordering {
+    let ord = lhs.e.cmp(&rhs.e);
+    if !ord.is_eq() {
+        return ord;
+    }
+    for (l, r) in lhs.m.iter().rev().zip(rhs.m.iter().rev()) {
+        let ord = l.cmp(r);
+        if !ord.is_eq() {
+            return ord;
         }
+    }
+    lhs.m.len().cmp(&rhs.m.len())
+}
 
-        out
+// precision of an operator result in bits
+pub(crate) fn op_prec(lhs: &BitFloat, rhs: &BitFloat) -> usize {
+    lhs.prec().max(rhs.prec()).max(BitFloat::default_prec())
+}
+
+// the operators' lhs + rhs, or lhs - rhs when sub
+fn add_sub_op(lhs: &BitFloat, rhs: &BitFloat, sub: bool) -> BitFloat {
+    add_sub_bf_mode(lhs, rhs, sub, op_prec(lhs, rhs), RoundingMode::NearestEven)
+}
+
+fn mul_op(lhs: &BitFloat, rhs: &BitFloat) -> BitFloat {
+    mul_bf_mode(lhs, rhs, op_prec(lhs, rhs), RoundingMode::NearestEven)
+}
+
+fn div_op(lhs: &BitFloat, rhs: &BitFloat) -> BitFloat {
+    div_bf_mode(lhs, rhs, op_prec(lhs, rhs), RoundingMode::NearestEven)
+}
+
+// bits in p limbs, the working precision of the internal helpers
+fn limb_bits(p: usize) -> usize {
+    p.saturating_mul(64)
+}
+
+impl Rounding for BitFloat {
+    fn floor(&self) -> Self {
+        round_int(self, RoundingMode::TowardNegative)
     }
 
-    fn ceil_mut(&mut self) {
-        if self.e < 0 {
-            if self.s {
-                *self = Self::ZERO;
-            } else {
-                *self = Self::ONE;
-            };
-            return;
-        }
+    fn floor_mut(&mut self) {
+        *self = self.floor();
+    }
 
-        let amt = self
-            .m
-            .len()
-            .saturating_sub(usize::try_from(self.e).unwrap_or(self.m.len()));
-        self.m.drain(amt);
-        if !self.s {
-            if inc(&mut self.m) {
-                self.m.push(1);
-                self.e += 1;
-            }
-        }
+    fn ceil(&self) -> Self {
+        round_int(self, RoundingMode::TowardPositive)
+    }
+
+    fn ceil_mut(&mut self) {
+        *self = self.ceil();
     }
 
     fn round(&self) -> Self {
-        if self.e < -1 {
-            return Self::ZERO;
-        }
-        if self.e < 0 {
-            return if self.m.top() >> 63 > 0 {
-                if self.s {
-                    Self::NEG_ONE
-                } else {
-                    Self::ONE
-                }
-            } else {
-                Self::ZERO
-            };
-        }
+        round_int(self, RoundingMode::NearestAway)
+    }
 
-        let mut out = self.clone();
-        let amt = self
-            .m
-            .len()
-            .saturating_sub(usize::try_from(self.e - 1).unwrap_or(self.m.len()));
-        out.m.drain(amt);
-
-        if out.m.pull() >> 63 > 0 {
-            if out.s {
-                if dec(&mut out.m) {
-                    out.m.pop();
-                    out.e -= 1;
-                }
-            } else {
-                if inc(&mut out.m) {
-                    out.m.push(1);
-                    out.e += 1;
-                }
-            }
-        }
+    fn round_mut(&mut self) {
+        *self = self.round();
+    }
 
-        out
+    fn trunc(&self) -> Self {
+        round_int(self, RoundingMode::TowardZero)
     }
 
-    fn round_mut(&mut self) {
-        if self.e < -1 {
-            *self = Self::ZERO;
-            return;
+    fn trunc_mut(&mut self) {
+        *self = self.trunc();
+    }
+
+    fn fract(&self) -> Self {
+        if self.is_nan() {
+            return BitFloat::nan();
+        }
+        if self.is_inf() {
+            return invalid();
+        }
+        if self.is_integer() {
+            return BitFloat::zero();
         }
         if self.e < 0 {
-            if self.m.top() >> 63 > 0 {
-                if self.s {
-                    *self = Self::NEG_ONE;
-                } else {
-                    *self = Self::ONE;
-                }
-            } else {
-                *self = Self::ZERO;
-            };
-            return;
+            return self.clone();
         }
+        let len = (-self.lo()) as usize;
+        BitFloat::make(self.s, -1, &self.m[..len])
+    }
 
-        let amt = self
-            .m
-            .len()
-            .saturating_sub(usize::try_from(self.e - 1).unwrap_or(self.m.len()));
-        self.m.drain(amt);
-
-        if self.m.pull() >> 63 > 0 {
-            if self.s {
-                if dec(&mut self.m) {
-                    self.m.pop();
-                    self.e -= 1;
-                }
-            } else {
-                if inc(&mut self.m) {
-                    self.m.push(1);
-                    self.e += 1;
-                }
+    fn fract_mut(&mut self) {
+        *self = self.fract();
+    }
+}
+
+// bf rounded to an integer in the given mode
+fn round_int(bf: &BitFloat, mode: RoundingMode) -> BitFloat {
+    if bf.is_integer() || !bf.is_finite() {
+        return bf.clone();
+    }
+
+    // a zero result keeps the sign of bf
+    let frac_len = (-bf.lo()) as usize;
+    let (half, rest, mut out) = if frac_len > bf.m.len() {
+        (false, true, BitFloat::make(bf.s, 0, &[]))
+    } else {
+        let top = bf.m[frac_len - 1];
+        let rest = top << 1 != 0 || bf.m[..frac_len - 1].iter().any(|&x| x != 0);
+        let out = BitFloat::make(bf.s, bf.e, &bf.m[frac_len..]);
+        (top >> 63 == 1, rest, out)
+    };
+
+    if mode.away(bf.s, half, rest, out.is_odd()) {
+        let one = if bf.s {
+            BitFloat::neg_one()
+        } else {
+            BitFloat::one()
+        };
+        out = add_sub_bf(&out, &one, false, usize::MAX);
+    }
+    out
+}
+
+impl<T: Into<SEM>> From<T> for BitFloat {
+    fn from(value: T) -> Self {
+        let sem = value.into();
+        if sem.e == i128::MIN {
+            return BitFloat::make(sem.s, 0, &[]);
+        }
+        if sem.e == i128::MAX {
+            if !sem.m.is_empty() {
+                // a NaN, its sign, payload and quiet bit kept
+                return BitFloat {
+                    s: sem.s,
+                    e: i128::MAX,
+                    m: sem.m.to_vec(),
+                };
             }
+            return if sem.s {
+                BitFloat::neg_inf()
+            } else {
+                BitFloat::inf()
+            };
         }
+        BitFloat::make(sem.s, sem.e, &sem.m)
     }
+}
 
-    fn trunc(&self) -> Self {
-        if self.e < 0 {
-            return Self::ZERO;
-        }
+// limbs of |x| for integral x, least significant first
+pub(crate) fn int_limbs(x: &BitFloat) -> Vec<u64> {
+    if x.is_zero() {
+        return Vec::new();
+    }
+    let mut limbs = vec![0; x.lo() as usize];
+    limbs.extend_from_slice(&x.m);
+    limbs
+}
 
-        let mut out = self.clone();
-        let amt = self
-            .m
-            .len()
-            .saturating_sub(usize::try_from(self.e).unwrap_or(self.m.len()));
-        out.m.drain(amt);
+// the integer with the given limbs, least significant first, exactly
+fn from_limbs(s: bool, limbs: &[u64]) -> BitFloat {
+    BitFloat::make(s, limbs.len() as i128 - 1, limbs)
+}
 
-        return out;
+impl From<UBitInt> for BitFloat {
+    fn from(value: UBitInt) -> Self {
+        from_limbs(false, value.get_data())
     }
+}
 
-    fn trunc_mut(&mut self) {
-        if self.e < 0 {
-            *self = Self::ZERO;
-            return;
-        }
+impl From<BitInt> for BitFloat {
+    fn from(value: BitInt) -> Self {
+        from_limbs(value.get_sign(), value.get_data())
+    }
+}
 
-        let amt = self
-            .m
-            .len()
-            .saturating_sub(usize::try_from(self.e).unwrap_or(self.m.len()));
-        self.m.drain(amt);
+impl<const N: usize> From<UBitIntStatic<N>> for BitFloat {
+    fn from(value: UBitIntStatic<N>) -> Self {
+        from_limbs(false, &value.get_data())
     }
+}
 
-    fn fract(&self) -> Self {
-        if self.e < 0 {
-            return self.clone();
-        }
+// NaN of sign s with an IEEE payload, held in the mantissa as
+// 4 payload + 2 signaling + 1 so the plain NaN stays [1]. A signaling NaN
+// needs a non-zero payload to be encoded, so one without is quiet.
+pub(crate) fn nan_with(s: bool, signaling: bool, payload: &UBitInt) -> BitFloat {
+    let mut m = payload.get_data().to_vec();
+    m.push(0);
+    shl_buf(&mut m, 2);
+    m[0] |= 2 * (signaling && !payload.is_zero()) as u64 + 1;
+    trim_lz(&mut m);
+    BitFloat { s, e: i128::MAX, m }
+}
 
-        let Ok(amt) = usize::try_from(self.e + 1) else {
-            return Self::ZERO;
-        };
+// The fraction field, least significant limb first, of NaN bf in an
+// encoding with `frac` fraction bits: the quiet bit unless signaling, over
+// the payload, or an error when the payload does not fit below the quiet bit
+pub(crate) fn nan_field(bf: &BitFloat, frac: u32) -> Result<Vec<u64>, FromErr> {
+    let quiet = frac as usize - 1;
+    let mut field = bf.nan_payload().unwrap().get_data().to_vec();
+    let bits = field
+        .last()
+        .map_or(0, |&x| 64 * field.len() - x.leading_zeros() as usize);
+    if bits > quiet {
+        return Err(FromErr::NaN);
+    }
+    if !bf.is_signaling_nan() {
+        field.resize(field.len().max(quiet / 64 + 1), 0);
+        field[quiet / 64] |= 1 << (quiet % 64);
+    }
+    Ok(field)
+}
 
-        if amt > self.m.len() {
-            return Self::ZERO;
-        }
+// Whether a result of sign s past the largest finite value rounds to
+// infinity in the given mode. The modes rounding toward zero at that sign
+// saturate instead, raising the overflow flag.
+pub(crate) fn overflows(s: bool, mode: RoundingMode) -> bool {
+    if mode.away(s, true, true, true) {
+        return true;
+    }
+    raise(Flags::OVERFLOW);
+    false
+}
 
-        let mut out = self.clone();
-        out.m.trunc(amt);
-        return out;
+// Sign and bits (without the sign) of the IEEE binary value with `frac`
+// fraction bits and exponent bias `bias` that bf rounds to in the given mode,
+// or an error when it overflows to infinity.
+pub(crate) fn to_ieee(
+    bf: &BitFloat,
+    frac: u32,
+    bias: i128,
+    mode: RoundingMode,
+) -> Result<(bool, u128), FromErr> {
+    let max_biased = 2 * bias + 1;
+    if bf.is_zero() {
+        return Ok((bf.s, 0));
+    }
+    if bf.is_nan() {
+        let field = nan_field(bf, frac)?
+            .iter()
+            .rev()
+            .fold(0, |acc, &x| acc << 64 | x as u128);
+        return Ok((bf.s, (max_biased as u128) << frac | field));
+    }
+    if bf.is_inf() {
+        return Ok((bf.s, (max_biased as u128) << frac));
     }
 
-    fn fract_mut(&mut self) {
-        if self.e < 0 {
-            return;
+    let len = bf.m.len();
+    let lo = if len > 1 { bf.m[len - 2] } else { 0 };
+    let third = if len > 2 { bf.m[len - 3] } else { 0 };
+    let mut sticky = len > 3 && bf.m[..len - 3].iter().any(|&x| x != 0);
+    let mut top = combine_u64(lo, bf.m[bf.m.len() - 1]);
+    let lz = top.leading_zeros();
+    // the top limb may hold a single bit, so the third fills in below
+    top = top << lz | (third as u128) << lz >> 64;
+    sticky |= third << lz != 0;
+
+    let max_finite = ((max_biased as u128) << frac) - 1;
+    let exp = bf.bit_exp();
+    if exp > bias {
+        if overflows(bf.s, mode) {
+            return Err(FromErr::Overflow);
         }
+        return Ok((bf.s, max_finite));
+    }
+    let min_exp = 1 - bias;
+    let sh = (127 - frac) as i128 + (min_exp - exp).max(0);
+    if sh > 128 {
+        // below half the smallest subnormal
+        raise(Flags::UNDERFLOW);
+        return Ok((bf.s, mode.away(bf.s, false, true, false) as u128));
+    }
+
+    let sh = sh as u32;
+    let (mut q, half) = if sh == 128 {
+        (0, top >> 127 == 1)
+    } else {
+        (top >> sh, (top >> (sh - 1)) & 1 == 1)
+    };
+    // sh is at least 127 - frac, so the shift below stays in range
+    sticky |= top << (129 - sh) != 0;
+    if half || sticky {
+        raise(if exp < min_exp {
+            Flags::UNDERFLOW
+        } else {
+            Flags::INEXACT
+        });
+    }
+    if mode.away(bf.s, half, sticky, q & 1 == 1) {
+        q += 1;
+    }
 
-        let Ok(amt) = usize::try_from(self.e + 1) else {
-            *self = Self::ZERO;
-            return;
-        };
-        if amt > self.m.len() {
-            *self = Self::ZERO;
-            return;
+    // a carry out of the significand bumps the exponent field on its own
+    let bits = (((exp.max(min_exp) + bias - 1) as u128) << frac) + q;
+    if bits > max_finite {
+        if overflows(bf.s, mode) {
+            return Err(FromErr::Overflow);
         }
+        return Ok((bf.s, max_finite));
+    }
+    Ok((bf.s, bits))
+}
 
-        self.m.trunc(amt);
+impl TryFrom<&BitFloat> for f64 {
+    type Error = FromErr;
+    fn try_from(value: &BitFloat) -> Result<Self, Self::Error> {
+        value.to_f64_round(RoundingMode::NearestEven)
     }
 }
 
-impl<T: Into<SEM>> From<T> for BitFloat {
-    fn from(value: T) -> Self {
-        let sem = value.into();
-        return BitFloat {
-            s: sem.s,
-            e: sem.e,
-            m: Mantissa::make_ref(&sem.m),
-        };
+impl TryFrom<&BitFloat> for f32 {
+    type Error = FromErr;
+    fn try_from(value: &BitFloat) -> Result<Self, Self::Error> {
+        value.to_f32_round(RoundingMode::NearestEven)
     }
 }
 
+// sign and magnitude of the integer part
+fn to_int_parts(bf: &BitFloat) -> Result<(bool, u128), FromErr> {
+    if bf.is_nan() {
+        return Err(FromErr::NaN);
+    }
+    if bf.is_zero() || bf.e < 0 {
+        return Ok((false, 0));
+    }
+    if bf.is_inf() || bf.e > 1 {
+        return Err(FromErr::Overflow);
+    }
+    let mut mag = 0;
+    for (i, &limb) in bf.m.iter().enumerate() {
+        let pos = bf.lo() + i as i128;
+        if pos >= 0 {
+            mag |= (limb as u128) << (64 * pos);
+        }
+    }
+    Ok((bf.s && mag != 0, mag))
+}
+
 macro_rules! impl_try_from_bf {
     ($($t:ty), +) => {
         $(
-        impl TryFrom<BitFloat> for $t{
+        impl TryFrom<&BitFloat> for $t {
             type Error = FromErr;
-            fn try_from(value: BitFloat) -> Result<$t, Self::Error>{
-                let start = value.m.len().saturating_sub(2);
-                let sem = SEM{
-                    s: value.s,
-                    e: value.e,
-                    m: SmallBuf::try_from(&value.m[start..])?,
-                };
-                sem.try_into()
+            fn try_from(value: &BitFloat) -> Result<$t, Self::Error> {
+                let (s, mag) = to_int_parts(value)?;
+                if s {
+                    let neg = 0_i128
+                        .checked_sub_unsigned(mag)
+                        .ok_or(FromErr::Overflow)?;
+                    <$t>::try_from(neg).map_err(|_| FromErr::Underflow)
+                } else {
+                    <$t>::try_from(mag).map_err(|_| FromErr::Overflow)
+                }
             }
         }
+        )+
+    };
+}
+impl_try_from_bf!(i128, u128, i64, u64, i32, u32, i16, u16, i8, u8, isize, usize);
 
-        impl TryFrom<&BitFloat> for $t{
+macro_rules! impl_try_from_bf_owned {
+    ($($t:ty), +) => {
+        $(
+        impl TryFrom<BitFloat> for $t {
             type Error = FromErr;
-            fn try_from(value: &BitFloat) -> Result<$t, Self::Error>{
-                let start = value.m.len().saturating_sub(2);
-                let sem = SEM{
-                    s: value.s,
-                    e: value.e,
-                    m: SmallBuf::try_from(&value.m[start..])?,
-                };
-                sem.try_into()
+            fn try_from(value: BitFloat) -> Result<$t, Self::Error> {
+                <$t>::try_from(&value)
             }
         }
         )+
     };
 }
-impl_try_from_bf!(f64, f32, i128, u128, i64, u64, i32, u32, i16, u16, i8, u8);
+impl_try_from_bf_owned!(f64, f32, i128, u128, i64, u64, i32, u32, i16, u16, i8, u8, isize, usize);
 
-impl FromStr for BitFloat {
-    type Err = FromStrErr;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        todo!()
-    }
-}
-
-impl fmt::Display for BitFloat {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        todo!()
-    }
-}
-
-impl<T: Into<SEM>> PartialEq<T> for BitFloat {
-    fn eq(&self, other: &T) -> bool {
-        let sem = other.into();
-        if self.s ^ sem.s || self.e != sem.e {
-            return false;
-        }
-        self.m[..] == sem.m[..]
+impl PartialEq for BitFloat {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
     }
 }
 
 impl PartialOrd for BitFloat {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        use std::cmp::Ordering::*;
-        if self.s ^ other.s {
-            return Some(scmp(self.s, Greater));
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.is_nan() || other.is_nan() {
+            return None;
         }
-        let exp_ord = self.e.cmp(&other.e);
-        if !exp_ord.is_eq() {
-            return Some(scmp(self.s, exp_ord));
+        if self.is_zero() && other.is_zero() {
+            return Some(Ordering::Equal);
         }
-
-        return Some(scmp(self.s, cmp_buf(&self.m, &other.m)));
+        if self.s != other.s {
+            return Some(scmp(self.s, Ordering::Greater));
+        }
+        Some(scmp(self.s, cmp_abs(self, other)))
     }
 }
 
-impl<T: Into<SEM>> PartialOrd<T> for BitFloat {
-    fn partial_cmp(&self, other: &T) -> Option<std::cmp::Ordering> {
-        use std::cmp::Ordering::*;
-        let sem = other.into();
-        if self.s ^ sem.s {
-            return Some(scmp(self.s, Greater));
+macro_rules! impl_peq_pord_bf {
+    ($($t:ty), +) => {
+        $(
+        impl PartialEq<$t> for BitFloat {
+            fn eq(&self, other: &$t) -> bool {
+                self.eq(&BitFloat::from(*other))
+            }
         }
 
-        let exp_ord = self.e.cmp(&sem.e);
-        if !exp_ord.is_eq() {
-            return Some(scmp(self.s, exp_ord));
+        impl PartialOrd<$t> for BitFloat {
+            fn partial_cmp(&self, other: &$t) -> Option<Ordering> {
+                self.partial_cmp(&BitFloat::from(*other))
+            }
         }
-
-        return Some(scmp(self.s, cmp_buf(&self.m, &other.m)));
-    }
+        )+
+    };
 }
-
+impl_peq_pord_bf!(f64, f32, i128, u128, i64, u64, i32, u32, i16, u16, i8, u8);
 impl_commutative_peq_pord!(BitFloat, f64, f32, i128, u128, i64, u64, i32, u32, i16, u16, i8, u8);
 
-impl Eq for BitFloat {}
-
-impl Ord for BitFloat {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap()
+// lhs + rhs, or lhs - rhs when sub, rounded to the bits of p limbs
+pub(crate) fn add_sub_bf(lhs: &BitFloat, rhs: &BitFloat, sub: bool, p: usize) -> BitFloat {
+    add_sub_bf_mode(lhs, rhs, sub, limb_bits(p), RoundingMode::NearestEven)
+}
+
+// lhs + rhs, or lhs - rhs when sub, rounded to p bits in the given mode
+pub(crate) fn add_sub_bf_mode(
+    lhs: &BitFloat,
+    rhs: &BitFloat,
+    sub: bool,
+    p: usize,
+    mode: RoundingMode,
+) -> BitFloat {
+    let rs = rhs.s ^ sub;
+    if lhs.is_nan() || rhs.is_nan() {
+        return BitFloat::nan();
+    }
+    if lhs.is_inf() || rhs.is_inf() {
+        if lhs.is_inf() && rhs.is_inf() && lhs.s != rs {
+            return invalid();
+        }
+        let mut out = if lhs.is_inf() {
+            lhs.clone()
+        } else {
+            rhs.clone()
+        };
+        out.s = if lhs.is_inf() { lhs.s } else { rs };
+        return out;
     }
-}
-
-fn add_sub_bf(bf: &mut BitFloat, e: i128, m: &[u64], sub: bool) {
-    if m.is_empty() {
-        return;
+    // an exact zero sum is -0 only from two negative zeros, or when rounding
+    // toward negative infinity
+    let neg_zero = mode == RoundingMode::TowardNegative;
+    if lhs.is_zero() && rhs.is_zero() {
+        let mut out = BitFloat::zero();
+        out.s = if neg_zero { lhs.s || rs } else { lhs.s && rs };
+        return out;
     }
-    if bf.m.is_empty() {
-        bf.e = e;
-        bf.m = Mantissa::make(m);
-        bf.s = sub;
-        return;
+    if rhs.is_zero() {
+        return lhs.to_prec_round(p, mode);
+    }
+    if lhs.is_zero() {
+        let mut out = rhs.clone();
+        out.s = rs;
+        return out.to_prec_round(p, mode);
     }
 
-    let del_m = (bf.e - bf.m.len() as i128) - (e - m.len() as i128);
-    let del_p = e - bf.e;
+    let (a, a_s, b, b_s) = if cmp_abs(lhs, rhs).is_ge() {
+        (lhs, lhs.s, rhs, rs)
+    } else {
+        (rhs, rs, lhs, lhs.s)
+    };
+    let comp = a_s != b_s;
 
-    if del_m > 0 {
-        bf.m.put_zeros(del_m as usize);
-    }
-    if del_p > 0 {
-        bf.m.push_zeros(del_p as usize);
-        bf.e = e;
+    // Limbs of b more than q + 2 below the top of a, with q limbs covering
+    // the p bits, and below every limb of a, only matter as a sticky bit,
+    // unless a subtraction can cancel the top limbs of a. The dropped part
+    // then lies wholly below the lowest limb kept.
+    let mut b_m: &[u64] = &b.m;
+    let mut b_lo = b.lo();
+    let mut sticky = false;
+    let window = (a.e - p.div_ceil(64) as i128 - 3).min(a.lo());
+    if (!comp || b.e < a.e - 1) && b_lo < window {
+        let cut = ((window - b_lo) as usize).min(b_m.len());
+        sticky = b_m[..cut].iter().any(|&x| x != 0);
+        b_m = &b_m[cut..];
+        b_lo += cut as i128;
     }
 
-    let start = (-del_m).max(0) as usize;
-    let comp = bf.s ^ sub;
-    if acc(&mut bf.m[start..], m, comp as u8) {
-        if comp {
-            twos_comp(&mut bf.m);
-        } else {
-            bf.m.push(1);
-            bf.e += 1
-        }
-        bf.s ^= comp;
+    let mut lo = a.lo();
+    if !b_m.is_empty() {
+        lo = lo.min(b_lo);
     }
-    if comp {
-        if let Some(val) = bf.m.trim_zeros() {
-            bf.e -= val as i128;
-        } else {
-            bf.e = i128::MIN;
-        }
+    if sticky {
+        lo = lo.min(window);
+    }
+    let mut buf = vec![0; (a.e - lo) as usize + 2];
+    let a_off = (a.lo() - lo) as usize;
+    buf[a_off..a_off + a.m.len()].copy_from_slice(&a.m);
+    if !b_m.is_empty() {
+        acc(&mut buf[(b_lo - lo) as usize..], b_m, comp as u8);
     }
+    if sticky && comp {
+        // a - (b + d) = (a - b - 1) + (1 - d) for the dropped part 0 < d < 1
+        dec(&mut buf);
+    }
+
+    let mut out = round_bf_mode(a_s, buf, lo, sticky, p, mode);
+    if out.is_zero() {
+        out.s = neg_zero;
+    }
+    out
 }
 
 impl Add for BitFloat {
     type Output = BitFloat;
     fn add(self, rhs: Self) -> Self::Output {
-        let mut lhs = self;
-        add_sub_bf(&mut lhs, rhs.e, &rhs.m, rhs.s);
-        return lhs;
+        add_sub_op(&self, &rhs, false)
     }
 }
 
 impl Add for &BitFloat {
     type Output = BitFloat;
     fn add(self, rhs: Self) -> Self::Output {
-        let mut lhs = self.clone();
-        add_sub_bf(&mut lhs, rhs.e, &rhs.m, rhs.s);
-        return lhs;
+        add_sub_op(self, rhs, false)
     }
 }
 
 impl Add<&BitFloat> for BitFloat {
     type Output = BitFloat;
     fn add(self, rhs: &BitFloat) -> Self::Output {
-        let mut lhs = self;
-        add_sub_bf(&mut lhs, rhs.e, &rhs.m, rhs.s);
-        return lhs;
+        add_sub_op(&self, rhs, false)
     }
 }
 
 impl Add<BitFloat> for &BitFloat {
     type Output = BitFloat;
     fn add(self, rhs: BitFloat) -> Self::Output {
-        let mut lhs = self.clone();
-        add_sub_bf(&mut lhs, rhs.e, &rhs.m, rhs.s);
-        return lhs;
-    }
-}
-
-impl<T: Into<SEM>> Add<T> for BitFloat {
-    type Output = BitFloat;
-    fn add(self, rhs: T) -> Self::Output {
-        let sem = rhs.into();
-        let mut lhs = self;
-        add_sub_bf(&mut lhs, sem.e, &sem.m, sem.s);
-        return lhs;
-    }
-}
-
-impl<T: Into<SEM>> Add<T> for &BitFloat {
-    type Output = BitFloat;
-    fn add(self, rhs: T) -> Self::Output {
-        let sem = rhs.into();
-        let mut lhs = self.clone();
-        add_sub_bf(&mut lhs, sem.e, &sem.m, sem.s);
-        return lhs;
+        add_sub_op(self, &rhs, false)
     }
 }
 
-impl_commutative!(
-    Add,
-    add,
-    BitFloat,
-    |x| x,
-    f64,
-    f32,
-    u128,
-    i128,
-    u64,
-    i64,
-    u32,
-    i32,
-    u16,
-    i16,
-    u8,
-    i8
-);
-
 impl AddAssign for BitFloat {
     fn add_assign(&mut self, rhs: Self) {
-        add_sub_bf(self, rhs.e, &rhs.m, rhs.s);
+        *self = add_sub_op(self, &rhs, false);
     }
 }
 
 impl AddAssign<&BitFloat> for BitFloat {
     fn add_assign(&mut self, rhs: &BitFloat) {
-        add_sub_bf(self, rhs.e, &rhs.m, rhs.s);
-    }
-}
-
-impl<T: Into<SEM>> AddAssign<T> for BitFloat {
-    fn add_assign(&mut self, rhs: T) {
-        let sem = rhs.into();
-        add_sub_bf(self, rhs.e, &rhs.m, rhs.s);
+        *self = add_sub_op(self, rhs, false);
     }
 }
 
@@ -759,7 +1152,7 @@ impl Neg for BitFloat {
     fn neg(self) -> Self::Output {
         let mut out = self;
         out.neg_mut();
-        return out;
+        out
     }
 }
 
@@ -768,748 +1161,355 @@ impl Neg for &BitFloat {
     fn neg(self) -> Self::Output {
         let mut out = self.clone();
         out.neg_mut();
-        return out;
+        out
     }
 }
 
 impl Sub for BitFloat {
     type Output = BitFloat;
     fn sub(self, rhs: Self) -> Self::Output {
-        let mut lhs = self;
-        add_sub_bf(&mut lhs, rhs.e, &rhs.m, !rhs.s);
-        return lhs;
+        add_sub_op(&self, &rhs, true)
     }
 }
 
 impl Sub for &BitFloat {
     type Output = BitFloat;
     fn sub(self, rhs: Self) -> Self::Output {
-        let mut lhs = self.clone();
-        add_sub_bf(&mut lhs, rhs.e, &rhs.m, !rhs.s);
-        return lhs;
+        add_sub_op(self, rhs, true)
     }
 }
 
 impl Sub<&BitFloat> for BitFloat {
     type Output = BitFloat;
     fn sub(self, rhs: &BitFloat) -> Self::Output {
-        let mut lhs = self;
-        add_sub_bf(&mut lhs, rhs.e, &rhs.m, !rhs.s);
-        return lhs;
+        add_sub_op(&self, rhs, true)
     }
 }
 
 impl Sub<BitFloat> for &BitFloat {
     type Output = BitFloat;
     fn sub(self, rhs: BitFloat) -> Self::Output {
-        let mut lhs = self.clone();
-        add_sub_bf(&mut lhs, rhs.e, &rhs.m, !rhs.s);
-        return lhs;
-    }
-}
-
-impl<T: Into<SEM>> Sub<T> for BitFloat {
-    type Output = BitFloat;
-    fn sub(self, rhs: T) -> Self::Output {
-        let sem = rhs.into();
-        let mut lhs = self;
-        add_sub_bf(&mut lhs, sem.e, &sem.m, !sem.m);
-        return lhs;
-    }
-}
-
-impl<T: Into<SEM>> Sub<T> for &BitFloat {
-    type Output = BitFloat;
-    fn sub(self, rhs: T) -> Self::Output {
-        let sem = rhs.into();
-        let mut lhs = self.clone();
-        add_sub_bf(&mut lhs, sem.e, &sem.m, !sem.m);
-        return lhs;
-    }
-}
-
-impl_commutative!(
-    Sub,
-    sub,
-    BitFloat,
-    |x| -x,
-    f64,
-    f32,
-    i128,
-    u128,
-    i64,
-    u64,
-    i32,
-    u32,
-    i16,
-    u16,
-    i8,
-    u8
-);
-
-fn shl_shr_bf(bf: &mut BitFloat, sh: u128, s: bool) {
-    let div = (sh / 64) as i128;
-    let rem = (sh % 64) as u8;
-    if s {
-        bf.e -= div;
-        let c = shr_buf(&mut bf.m, rem);
-        if c > 0 {
-            bf.m.put(c);
-        }
-        if bf.m[bf.m.len() - 1] == 0 {
-            bf.m.pop();
-            bf.e -= 1;
-        }
-    } else {
-        bf.e += div;
-        let c = shl_buf(&mut bf.m, rem);
-        if c > 0 {
-            bf.m.push(c);
-            bf.m.e += 1
-        }
-        if bf.m[0] == 0 {
-            bf.m.pull();
-        }
+        add_sub_op(self, &rhs, true)
     }
 }
 
-impl<T: Int> Shl<T> for BitFloat
-where
-    T::U: Into<u128>,
-{
-    type Output = BitFloat;
-    fn shl(self, rhs: T) -> Self::Output {
-        let mut lhs = self;
-        shl_shr_bf(&mut lhs, rhs.unsigned().into(), rhs.sign());
-        return lhs;
+impl SubAssign for BitFloat {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = add_sub_op(self, &rhs, true);
     }
 }
 
-impl<T: Int> Shl<T> for &BitFloat
-where
-    T::U: Into<u128>,
-{
-    type Output = BitFloat;
-    fn shl(self, rhs: T) -> Self::Output {
-        let mut lhs = self.clone();
-        shl_shr_bf(&mut lhs, rhs.unsigned().into(), rhs.sign());
-        return lhs;
+impl SubAssign<&BitFloat> for BitFloat {
+    fn sub_assign(&mut self, rhs: &BitFloat) {
+        *self = add_sub_op(self, rhs, true);
     }
 }
 
-impl<T: Int> ShlAssign<T> for BitFloat
-where
-    T::U: Into<u128>,
-{
-    fn shl_assign(&mut self, rhs: T) {
-        shl_shr_bf(self, rhs.unsigned().into(), rhs.sign());
-    }
-}
-
-impl<T: Int> Shr<T> for BitFloat
-where
-    T::U: Into<u128>,
-{
-    type Output = BitFloat;
-    fn shr(self, rhs: T) -> Self::Output {
-        let mut lhs = self;
-        shl_shr_bf(&mut lhs, rhs.unsigned().into(), !rhs.sign());
-        return lhs;
-    }
-}
-
-impl<T: Int> Shr<T> for &BitFloat
-where
-    T::U: Into<u128>,
-{
-    type Output = BitFloat;
-    fn shr(self, rhs: T) -> Self::Output {
-        let mut lhs = self.clone();
-        shl_shr_bf(&mut lhs, rhs.unsigned().into(), !rhs.sign());
-        return lhs;
-    }
-}
-
-impl<T: Int> ShrAssign<T> for BitFloat
-where
-    T::U: Into<u128>,
-{
-    fn shr_assign(&mut self, rhs: T) {
-        shl_shr_bf(self, rhs.unsigned().into(), !rhs.sign());
+// multiplies bf by 2^sh
+pub(crate) fn shl_shr_bf(bf: &mut BitFloat, sh: i128) {
+    if bf.is_zero() || !bf.is_finite() {
+        return;
     }
-}
-
-fn mul_bf(lhs: &BitFloat, rhs: &BitFloat) -> BitFloat {
-    let mut e = lhs.e + rhs.e;
-    let p = lhs.m.len().min(rhs.m.len());
-    let (buf, c) = short_mul_vec(&lhs.m, &rhs.m, p + 1);
-    let mut m = Mantissa::make_take(buf);
-    let mut gaurd = m.pull();
+    let mut buf = bf.m.to_vec();
+    let c = shl_buf(&mut buf, sh.rem_euclid(64) as u8);
     if c > 0 {
-        m.push(c);
-        e += 1;
-        if c >> 32 > 0 {
-            gaurd = m.pull()
-        }
-    }
-    if gaurd >> 63 == 1 {
-        if inc(&mut m) {
-            m.push(1);
-            e += 1
-        }
-    }
-    BitFloat {
-        s: lhs.s ^ rhs.s,
-        e,
-        m,
-    }
-}
-
-impl Mul for BitFloat {
-    type Output = BitFloat;
-    fn mul(self, rhs: Self) -> Self::Output {
-        mul_bf(&self, &rhs)
-    }
-}
-
-impl Mul for &BitFloat {
-    type Output = BitFloat;
-    fn mul(self, rhs: Self) -> Self::Output {
-        mul_bf(self, rhs)
+        buf.push(c);
     }
+    *bf = round_bf(bf.s, buf, bf.lo() + sh.div_euclid(64), false, usize::MAX);
 }
 
-impl Mul<&BitFloat> for BitFloat {
-    type Output = BitFloat;
-    fn mul(self, rhs: &BitFloat) -> Self::Output {
-        mul_bf(&self, rhs)
-    }
-}
-
-impl Mul<BitFloat> for &BitFloat {
-    type Output = BitFloat;
-    fn mul(self, rhs: BitFloat) -> Self::Output {
-        mul_bf(self, &rhs)
-    }
-}
-
-fn mul_prim2_bf(bf: &mut BitFloat, sem: SEM) {
-    bf.s ^= sem.s;
-    bf.e += sem.e;
-    let c = mul_prim2(&mut bf.m, u128::from(sem.m));
-    if c > 0 {
-        bf.m.push(c as u64);
-        let mut gaurd = bf.m.pull();
-        let of = (c >> 64) as u64;
-        if of > 0 {
-            bf.m.push(of);
-            bf.e += 1;
-            if of >> 32 > 0 {
-                gaurd = bf.m.pull();
+macro_rules! impl_shl_shr_bf {
+    ($($t:ty), +) => {
+        $(
+        impl Shl<$t> for BitFloat {
+            type Output = BitFloat;
+            fn shl(self, rhs: $t) -> Self::Output {
+                let mut lhs = self;
+                shl_shr_bf(&mut lhs, rhs as i128);
+                lhs
             }
         }
-        if gaurd >> 63 > 0 {
-            if inc(&mut bf.m) {
-                bf.m.push(1);
-                bf.e += 1
+
+        impl Shl<$t> for &BitFloat {
+            type Output = BitFloat;
+            fn shl(self, rhs: $t) -> Self::Output {
+                let mut lhs = self.clone();
+                shl_shr_bf(&mut lhs, rhs as i128);
+                lhs
             }
         }
-    }
-}
 
-impl Mul<f64> for BitFloat {
-    type Output = BitFloat;
-    fn mul(self, rhs: f64) -> Self::Output {
-        let mut lhs = self;
-        let sem = SEM::from(rhs);
-        mul_prim2_bf(&mut lhs, sem);
-        return lhs;
-    }
-}
+        impl ShlAssign<$t> for BitFloat {
+            fn shl_assign(&mut self, rhs: $t) {
+                shl_shr_bf(self, rhs as i128);
+            }
+        }
 
-impl Mul<f64> for &BitFloat {
-    type Output = BitFloat;
-    fn mul(self, rhs: f64) -> Self::Output {
-        let mut lhs = self.clone();
-        let sem = SEM::from(rhs);
-        mul_prim2_bf(&mut lhs, sem);
-        return lhs;
-    }
-}
+        impl Shr<$t> for BitFloat {
+            type Output = BitFloat;
+            fn shr(self, rhs: $t) -> Self::Output {
+                let mut lhs = self;
+                shl_shr_bf(&mut lhs, -(rhs as i128));
+                lhs
+            }
+        }
 
-impl Mul<f32> for BitFloat {
-    type Output = BitFloat;
-    fn mul(self, rhs: f32) -> Self::Output {
-        let mut lhs = self;
-        let sem = SEM::from(rhs);
-        mul_prim2_bf(&mut lhs, sem);
-        return lhs;
-    }
-}
+        impl Shr<$t> for &BitFloat {
+            type Output = BitFloat;
+            fn shr(self, rhs: $t) -> Self::Output {
+                let mut lhs = self.clone();
+                shl_shr_bf(&mut lhs, -(rhs as i128));
+                lhs
+            }
+        }
 
-impl Mul<f32> for &BitFloat {
-    type Output = BitFloat;
-    fn mul(self, rhs: f32) -> Self::Output {
-        let mut lhs = self.clone();
-        let sem = SEM::from(rhs);
-        mul_prim2_bf(&mut lhs, sem);
-        return lhs;
-    }
+        impl ShrAssign<$t> for BitFloat {
+            fn shr_assign(&mut self, rhs: $t) {
+                shl_shr_bf(self, -(rhs as i128));
+            }
+        }
+        )+
+    };
 }
-
-impl Mul<i128> for BitFloat {
-    type Output = BitFloat;
-    fn mul(self, rhs: i128) -> Self::Output {
-        let mut lhs = self;
-        let sem = SEM::from(rhs);
-        mul_prim2_bf(&mut lhs, sem);
-        return lhs;
-    }
+impl_shl_shr_bf!(usize, i128, i64, u64, i32, u32);
+
+// lhs * rhs rounded to the bits of p limbs
+pub(crate) fn mul_bf(lhs: &BitFloat, rhs: &BitFloat, p: usize) -> BitFloat {
+    mul_bf_mode(lhs, rhs, limb_bits(p), RoundingMode::NearestEven)
 }
 
-impl Mul<i128> for &BitFloat {
-    type Output = BitFloat;
-    fn mul(self, rhs: i128) -> Self::Output {
-        let mut lhs = self.clone();
-        let sem = SEM::from(rhs);
-        mul_prim2_bf(&mut lhs, sem);
-        return lhs;
+// lhs * rhs rounded to p bits in the given mode
+pub(crate) fn mul_bf_mode(
+    lhs: &BitFloat,
+    rhs: &BitFloat,
+    p: usize,
+    mode: RoundingMode,
+) -> BitFloat {
+    let s = lhs.s ^ rhs.s;
+    if lhs.is_nan() || rhs.is_nan() {
+        return BitFloat::nan();
+    }
+    if lhs.is_inf() || rhs.is_inf() {
+        if lhs.is_zero() || rhs.is_zero() {
+            return invalid();
+        }
+        return if s {
+            BitFloat::neg_inf()
+        } else {
+            BitFloat::inf()
+        };
+    }
+    if lhs.is_zero() || rhs.is_zero() {
+        let mut out = BitFloat::zero();
+        out.s = s;
+        return out;
     }
+
+    let (mut buf, c) = mul_vec(&lhs.m, &rhs.m);
+    buf.push(c);
+    round_bf_mode(s, buf, lhs.lo() + rhs.lo(), false, p, mode)
 }
 
-impl Mul<u128> for BitFloat {
+impl Mul for BitFloat {
     type Output = BitFloat;
-    fn mul(self, rhs: u128) -> Self::Output {
-        let mut lhs = self;
-        let sem = SEM::from(rhs);
-        mul_prim2_bf(&mut lhs, sem);
-        return lhs;
+    fn mul(self, rhs: Self) -> Self::Output {
+        mul_op(&self, &rhs)
     }
 }
 
-impl Mul<u128> for &BitFloat {
+impl Mul for &BitFloat {
     type Output = BitFloat;
-    fn mul(self, rhs: u128) -> Self::Output {
-        let mut lhs = self.clone();
-        let sem = SEM::from(rhs);
-        mul_prim2_bf(&mut lhs, sem);
-        return lhs;
+    fn mul(self, rhs: Self) -> Self::Output {
+        mul_op(self, rhs)
     }
 }
 
-impl<T: Prim> Mul<T> for BitFloat {
+impl Mul<&BitFloat> for BitFloat {
     type Output = BitFloat;
-    fn mul(self, rhs: T) -> Self::Output {
-        let mut lhs = self;
-        let c = mul_prim(&mut lhs.m, rhs.unsigned().into());
-        if c > 0 {
-            lhs.m.push(c);
-            lhs.e += 1;
-            if c >> 32 > 0 {
-                if lhs.m.pull() >> 63 > 0 {
-                    if inc(&mut lhs.m) {
-                        lhs.m.push(1);
-                        lhs.e += 1;
-                    }
-                }
-            }
-        }
-        lhs.s ^= rhs.sign();
-        return lhs;
+    fn mul(self, rhs: &BitFloat) -> Self::Output {
+        mul_op(&self, rhs)
     }
 }
 
-impl<T: Prim> Mul<T> for &BitFloat {
+impl Mul<BitFloat> for &BitFloat {
     type Output = BitFloat;
-    fn mul(self, rhs: T) -> Self::Output {
-        let mut lhs = self.clone();
-        let c = mul_prim(&mut lhs.m, rhs.unsigned().into());
-        if c > 0 {
-            lhs.m.push(c);
-            lhs.e += 1;
-        }
-        lhs.s ^= rhs.sign();
-        return lhs;
+    fn mul(self, rhs: BitFloat) -> Self::Output {
+        mul_op(self, &rhs)
     }
 }
 
-impl_commutative!(
-    Mul,
-    mul,
-    BitFloat,
-    |x| x,
-    f64,
-    f32,
-    u128,
-    i128,
-    u64,
-    i64,
-    u32,
-    i32,
-    u16,
-    i16,
-    u8,
-    i8
-);
-
 impl MulAssign for BitFloat {
     fn mul_assign(&mut self, rhs: Self) {
-        *self = self.mul(rhs)
+        *self = mul_op(self, &rhs);
     }
 }
 
 impl MulAssign<&BitFloat> for BitFloat {
     fn mul_assign(&mut self, rhs: &BitFloat) {
-        *self = self.mul(rhs)
-    }
-}
-
-impl MulAssign<f64> for BitFloat {
-    fn mul_assign(&mut self, rhs: f64) {
-        let sem = SEM::from(rhs);
-        mul_prim2_bf(self, sem);
-    }
-}
-
-impl MulAssign<f32> for BitFloat {
-    fn mul_assign(&mut self, rhs: f32) {
-        let sem = SEM::from(rhs);
-        mul_prim2_bf(self, sem);
-    }
-}
-
-impl MulAssign<i128> for BitFloat {
-    fn mul_assign(&mut self, rhs: i128) {
-        let sem = SEM::from(rhs);
-        mul_prim2_bf(self, sem);
-    }
-}
-
-impl MulAssign<u128> for BitFloat {
-    fn mul_assign(&mut self, rhs: u128) {
-        let sem = SEM::from(rhs);
-        mul_prim2_bf(self, sem);
+        *self = mul_op(self, rhs);
     }
 }
 
-impl<T: Prim> MulAssign<T> for BitFloat {
-    fn mul_assign(&mut self, rhs: T) {
-        let c = mul_prim(&mut self.m, rhs.unsigned().into());
-        if c > 0 {
-            self.m.push(c);
-            self.e += 1;
-        }
-        self.s ^= rhs.sign();
-    }
+// x truncated to its top n bits, at least one
+fn take(x: &BitFloat, n: Option<usize>) -> BitFloat {
+    x.to_prec_round(n.unwrap_or(usize::MAX).max(1), RoundingMode::TowardZero)
 }
 
 impl MulVariants for BitFloat {
     fn full_mul(&self, rhs: &Self) -> Self {
-        let mut e = self.e + rhs.e;
-        let (mut vec, c) = mul_vec(&self.m, &rhs.m);
-        if c > 0 {
-            vec.push(c);
-            e += 1;
-        }
-        let m = Mantissa::make_take(vec);
-        BitFloat {
-            s: self.s ^ rhs.s,
-            e,
-            m,
-        }
+        mul_bf(self, rhs, usize::MAX)
     }
 
+    // Short product of the top l and r bits, rounded to prec bits. The
+    // skipped partial products can leave the last bit slightly off.
     fn man_mul(&self, rhs: &Self, l: Option<usize>, r: Option<usize>, prec: Option<usize>) -> Self {
-        let mut e = self.e + rhs.e;
-        let l = l.unwrap_or(self.m.len());
-        let r = r.unwrap_or(rhs.m.len());
-        let (mut vec, c) =
-            short_mul_vec(self.m.take(l), rhs.m.take(r), prec.unwrap_or(l + r - 1) + 1);
-        let mut m = Mantissa::make_take(vec);
-        let mut gaurd = m.pull();
-        if c > 0 {
-            m.push(c);
-            e += 1;
-            if c >> 32 > 0 {
-                gaurd = m.pull();
-            }
-        }
-        if gaurd >> 63 > 0 {
-            if inc(&mut m) {
-                m.push(1);
-                m.pull();
-                e += 1;
-            }
-        }
-        BitFloat {
-            s: self.s ^ rhs.s,
-            e,
-            m,
+        if self.is_zero() || rhs.is_zero() || !self.is_finite() || !rhs.is_finite() {
+            return mul_bf(self, rhs, 1);
         }
+        let (a, b) = (take(self, l), take(rhs, r));
+        let full = a.m.len() + b.m.len() - 1;
+        let prec = prec.unwrap_or(a.prec() + b.prec());
+        let (mut buf, c) = short_mul_vec(&a.m, &b.m, prec.div_ceil(64) + 2);
+        let skipped = (full - buf.len()) as i128;
+        buf.push(c);
+        round_bf(a.s ^ b.s, buf, a.lo() + b.lo() + skipped, false, prec)
     }
 }
 
 impl Sqr for BitFloat {
     fn sqr(&self) -> Self {
-        let mut e = 2 * self.e;
-        let (vec, c) = short_sqr_vec(&self.m, self.m.len() + 1);
-        let mut m = Mantissa::make_take(vec);
-        let mut gaurd = m.pull();
-        if c > 0 {
-            m.push(c);
-            e += 1;
-            if c >> 32 > 0 {
-                gaurd = m.pull();
-            }
-        }
-        if gaurd >> 63 == 1 {
-            if inc(&mut m) {
-                m.push(1);
-                e += 1
-            }
-        }
-        BitFloat { s: false, e, m }
+        mul_op(self, self)
     }
 }
 
 impl SqrVariants for BitFloat {
     fn full_sqr(&self) -> Self {
-        let mut e = 2 * self.e;
-        let (vec, c) = sqr_vec(&self.m);
-        let mut m = Mantissa::make_take(vec);
-        if c > 0 {
-            m.push(c);
-            e += 1;
-        }
-        BitFloat { s: false, e, m }
+        mul_bf(self, self, usize::MAX)
     }
 
+    // Short square of the top in_prec bits, rounded to out_prec bits.
     fn man_sqr(&self, in_prec: Option<usize>, out_prec: Option<usize>) -> Self {
-        let mut e = 2 * self.e;
-        let in_prec = in_prec.unwrap_or(self.m.len());
-        let out_prec = out_prec.unwrap_or(2 * in_prec - 1);
-        let (vec, c) = short_sqr_vec(&self.m.take(in_prec), out_prec);
-        let mut m = Mantissa::make_take(vec);
-        let mut gaurd = m.pull();
-        if c > 0 {
-            m.push(c);
-            e += 1;
-            if c >> 32 > 0 {
-                gaurd = m.pull();
-            }
-        }
-        if gaurd >> 63 > 0 {
-            if inc(&mut m) {
-                m.push(1);
-                m.pull();
-                e += 1;
-            }
+        if self.is_zero() || !self.is_finite() {
+            return mul_bf(self, self, 1);
         }
-        BitFloat { s: false, e, m }
+        let a = take(self, in_prec);
+        let full = 2 * a.m.len() - 1;
+        let prec = out_prec.unwrap_or(2 * a.prec());
+        let (mut buf, c) = short_sqr_vec(&a.m, prec.div_ceil(64) + 2);
+        let skipped = (full - buf.len()) as i128;
+        buf.push(c);
+        round_bf(false, buf, 2 * a.lo() + skipped, false, prec)
     }
 }
 
-fn div_bf(mut n: BitFloat, mut d: BitFloat, p: usize) -> BitFloat {
-    let nlen = n.m.len();
-    let dlen = d.m.len();
-    let dp = dlen.min(p);
-    let np = nlen.min(dp + p);
-
-    d.m.drain(dlen.saturating_sub(dp));
-    n.m.drain(nlen.saturating_sub(np));
-    n.m.put_zeros((dp + p).saturating_sub(np));
-
-    let mut m = Mantissa::make_take(div_vec(&mut n.m, &mut d.m));
-    let mut e = n.exp - d.exp + (nlen as i128 - dlen as i128) - p;
+// n / d rounded to the bits of p limbs
+pub(crate) fn div_bf(n: &BitFloat, d: &BitFloat, p: usize) -> BitFloat {
+    div_bf_mode(n, d, limb_bits(p), RoundingMode::NearestEven)
+}
 
-    let overflow = m[p] > 0;
-    if overflow {
-        e += 1;
-    } else {
-        m.pop();
+// n / d rounded to p bits in the given mode
+pub(crate) fn div_bf_mode(n: &BitFloat, d: &BitFloat, p: usize, mode: RoundingMode) -> BitFloat {
+    let s = n.s ^ d.s;
+    if n.is_nan() || d.is_nan() {
+        return BitFloat::nan();
     }
-
-    let round_up = if overflow && m.top() >> 32 > 0 {
-        m.pull() >> 63 > 0
-    } else {
-        let c = shl_buf(&mut n.m, 1);
-        c > 0 || cmp_buf(&n.m, &d.m).is_ge()
-    };
-
-    if round_up {
-        if inc(&mut m) {
-            m.push(1);
-            e += 1;
+    if d.is_zero() || n.is_inf() {
+        if n.is_zero() || d.is_inf() {
+            return invalid();
         }
+        if d.is_zero() {
+            raise(Flags::DIV_BY_ZERO);
+        }
+        return if s {
+            BitFloat::neg_inf()
+        } else {
+            BitFloat::inf()
+        };
+    }
+    if n.is_zero() || d.is_inf() {
+        let mut out = BitFloat::zero();
+        out.s = s;
+        return out;
     }
 
-    BitFloat { s: n.s ^ d.s, e, m }
+    // an exact quotient has no more bits than the numerator, so these are
+    // all an unbounded precision needs
+    let p = if p == usize::MAX {
+        64 * (n.m.len() + d.m.len())
+    } else {
+        p
+    };
+    // pad the numerator so the quotient has a limb below the p bits kept
+    let pad = (p.div_ceil(64) + 2 + d.m.len()).saturating_sub(n.m.len());
+    let mut num = vec![0; pad];
+    num.extend_from_slice(&n.m);
+    let mut den = d.m.to_vec();
+    let q = div_vec(&mut num, &mut den);
+    let sticky = num.iter().any(|&x| x != 0);
+    round_bf_mode(s, q, n.lo() - pad as i128 - d.lo(), sticky, p, mode)
 }
 
 impl Div for BitFloat {
     type Output = BitFloat;
     fn div(self, rhs: Self) -> Self::Output {
-        let p = self.m.len().min(rhs.m.len());
-        return div_bf(self, rhs, p);
+        div_op(&self, &rhs)
     }
 }
 
 impl Div for &BitFloat {
     type Output = BitFloat;
     fn div(self, rhs: Self) -> Self::Output {
-        let p = self.m.len().min(rhs.m.len());
-        return div_bf(self.clone(), rhs.clone(), p);
+        div_op(self, rhs)
     }
 }
 
 impl Div<&BitFloat> for BitFloat {
     type Output = BitFloat;
     fn div(self, rhs: &BitFloat) -> Self::Output {
-        let p = self.m.len().min(rhs.m.len());
-        return div_bf(self, rhs.clone(), p);
+        div_op(&self, rhs)
     }
 }
 
 impl Div<BitFloat> for &BitFloat {
     type Output = BitFloat;
     fn div(self, rhs: BitFloat) -> Self::Output {
-        let p = self.m.len().min(rhs.m.len());
-        return div_bf(self.clone(), rhs, p);
-    }
-}
-
-fn div_prim_bf(mut n: BitFloat, mut d: SEM) -> BitFloat {
-    let nlen = n.m.len();
-    let dlen = d.m.len();
-    let sign = n.s ^ d.s;
-
-    if dlen == 1 {
-        n.m.put(0);
-        let rem = div_prim(&mut n.m, d.m[0]);
-        let mut e = n.e - d.e - 1;
-
-        let overflow = n.m[nlen] > 0;
-        if overflow {
-            e += 1;
-        } else {
-            n.m.pop();
-        }
-
-        let round_up = if overflow && n.m.top() >> 32 > 0 {
-            n.m.pull() >> 63 > 0
-        } else {
-            rem >= (d.m[0] >> 1) + (d.m[0] & 1)
-        };
-
-        if round_up {
-            if inc(&mut n.m) {
-                n.m.push(1);
-                e += 1;
-            }
-        }
-
-        BitFloat { s: sign, e, m: n.m }
-    } else {
-        let lz = d.m[1].leading_zeros() as u8;
-        shl_buf(&mut d.m, lz);
-        let mut of = shl_buf(&mut n.m, lz);
-        n.m.put_zeros(2);
-
-        let mut q = vec![0u64; nlen + 1];
-        div_buf_of(&mut n.m, &mut of, &d.m, &mut q);
-
-        let mut m = Mantissa::make_take(q);
-        let mut e = n.e - d.e - 2;
-
-        let overflow = m[nlen] > 0;
-        if overflow {
-            e += 1;
-        } else {
-            m.pop();
-        }
-
-        let div = u128::from(d.m);
-        let rem = (n.m[0] as u128) | ((n.m[1] as u128) << 64);
-        let round_up = if overflow && m.top() >> 32 > 0 {
-            m.pull() >> 63 > 0
-        } else {
-            (rem >> 127 > 0) | (rem << 1 >= div)
-        };
-
-        if round_up {
-            if inc(&mut m) {
-                m.push(1);
-                e += 1;
-            }
-        }
-
-        BitFloat { s: sign, e, m }
-    }
-}
-
-impl<T: Into<SEM>> Div<T> for BitFloat {
-    type Output = BitFloat;
-    fn div(self, rhs: T) -> Self::Output {
-        div_prim_bf(self, rhs.into())
-    }
-}
-
-impl<T: Into<SEM>> Div<T> for &BitFloat {
-    type Output = BitFloat;
-    fn div(self, rhs: T) -> Self::Output {
-        div_prim_bf(self.clone(), rhs.into())
+        div_op(self, &rhs)
     }
 }
 
-macro_rules! impl_div_commute_bf {
-    ($($t:ty), *) => {
-        $(
-        impl Div<BitFloat> for $t{
-            type Output = BitFloat;
-            fn div(self, rhs: BitFloat) -> Self::Output{
-                let mut out = div_bf(BitFloat::from(self), rhs, rhs.m.len());
-                out.m.trim_zeros();
-                return out;
-            }
-        }
-
-        impl Div<&BitFloat> for $t{
-            type Output = BitFloat;
-            fn div(self, rhs: &BitFloat) -> Self::Output{
-                let mut out = div_bf(BitFloat::from(self), rhs.clone(), rhs.m.len());
-                out.m.trim_zeros();
-                return out;
-            }
-        }
-        )*
-    };
-}
-impl_div_commute_bf!(f64, f32, i128, u128, i64, u64, i32, u32, i16, u16, i8, u8);
-
 impl DivAssign for BitFloat {
     fn div_assign(&mut self, rhs: Self) {
-        *self = self.div(rhs);
+        *self = div_op(self, &rhs);
     }
 }
 
 impl DivAssign<&BitFloat> for BitFloat {
     fn div_assign(&mut self, rhs: &BitFloat) {
-        *self = self.div(&rhs);
+        *self = div_op(self, rhs);
     }
 }
 
-impl<T: Into<SEM>> DivAssign<T> for BitFloat {
-    fn div_assign(&mut self, rhs: T) {
-        *self = self.div(&rhs);
-    }
+// Quotient of the top l and r bits, correctly rounded to prec bits (by
+// default the operators' precision).
+fn man_div_bf(
+    n: &BitFloat,
+    d: &BitFloat,
+    l: Option<usize>,
+    r: Option<usize>,
+    prec: Option<usize>,
+) -> BitFloat {
+    let n = n.to_prec(l.unwrap_or(usize::MAX).max(1));
+    let d = d.to_prec(r.unwrap_or(usize::MAX).max(1));
+    let p = prec.unwrap_or(op_prec(&n, &d));
+    div_bf_mode(&n, &d, p, RoundingMode::NearestEven)
 }
 
 impl DivVariants for BitFloat {
     type Output = BitFloat;
 
     fn full_div(self, rhs: Self) -> Self::Output {
-        let p = self.m.len().max(rhs.m.len());
-        return div_bf(self, rhs, p);
+        let p = self.prec() + rhs.prec();
+        div_bf_mode(&self, &rhs, p, RoundingMode::NearestEven)
     }
 
     fn man_div(
@@ -1519,17 +1519,7 @@ impl DivVariants for BitFloat {
         r: Option<usize>,
         prec: Option<usize>,
     ) -> Self::Output {
-        let l = l.unwrap_or(self.m.len());
-        let r = r.unwrap_or(rhs.m.len());
-        let p = p.unwrap_or(l - r);
-
-        let mut n = self;
-        lhs.m.drain(lhs.m.len() - l);
-
-        let mut d = rhs;
-        rhs.m.drain(rhs.m.len() - r);
-
-        return div_bf(n, d, p);
+        man_div_bf(&self, &rhs, l, r, prec)
     }
 }
 
@@ -1537,8 +1527,8 @@ impl DivVariants for &BitFloat {
     type Output = BitFloat;
 
     fn full_div(self, rhs: Self) -> Self::Output {
-        let p = self.m.len().max(rhs.m.len());
-        return div_bf(self.clone(), rhs.clone(), p);
+        let p = self.prec() + rhs.prec();
+        div_bf_mode(self, rhs, p, RoundingMode::NearestEven)
     }
 
     fn man_div(
@@ -1548,74 +1538,164 @@ impl DivVariants for &BitFloat {
         r: Option<usize>,
         prec: Option<usize>,
     ) -> Self::Output {
-        let l = l.unwrap_or(self.m.len());
-        let r = r.unwrap_or(rhs.m.len());
-        let p = p.unwrap_or(l - r);
-
-        let mut n = self.clone();
-        lhs.m.drain(lhs.m.len() - l);
-
-        let mut d = rhs.clone();
-        rhs.m.drain(rhs.m.len() - r);
-
-        return div_bf(n, d, p);
+        man_div_bf(self, rhs, l, r, prec)
     }
 }
 
-impl DivVariants<&BitFloat> for BitFloat {
-    type Output = BitFloat;
+macro_rules! impl_prim_ops_bf {
+    ($($t:ty), +) => {
+        $(
+        impl Add<$t> for BitFloat {
+            type Output = BitFloat;
+            fn add(self, rhs: $t) -> Self::Output {
+                self + BitFloat::from(rhs)
+            }
+        }
 
-    fn full_div(self, rhs: &BitFloat) -> Self::Output {
-        let p = self.m.len().max(rhs.m.len());
-        return div_bf(self, rhs.clone(), p);
-    }
+        impl Add<$t> for &BitFloat {
+            type Output = BitFloat;
+            fn add(self, rhs: $t) -> Self::Output {
+                self + BitFloat::from(rhs)
+            }
+        }
 
-    fn man_div(
-        self,
-        rhs: &BitFloat,
-        l: Option<usize>,
-        r: Option<usize>,
-        prec: Option<usize>,
-    ) -> Self::Output {
-        let l = l.unwrap_or(self.m.len());
-        let r = r.unwrap_or(rhs.m.len());
-        let p = p.unwrap_or(l - r);
+        impl AddAssign<$t> for BitFloat {
+            fn add_assign(&mut self, rhs: $t) {
+                *self += BitFloat::from(rhs);
+            }
+        }
+
+        impl Sub<$t> for BitFloat {
+            type Output = BitFloat;
+            fn sub(self, rhs: $t) -> Self::Output {
+                self - BitFloat::from(rhs)
+            }
+        }
 
-        let mut n = self;
-        lhs.m.drain(lhs.m.len() - l);
+        impl Sub<$t> for &BitFloat {
+            type Output = BitFloat;
+            fn sub(self, rhs: $t) -> Self::Output {
+                self - BitFloat::from(rhs)
+            }
+        }
 
-        let mut d = rhs.clone();
-        rhs.m.drain(rhs.m.len() - r);
+        impl SubAssign<$t> for BitFloat {
+            fn sub_assign(&mut self, rhs: $t) {
+                *self -= BitFloat::from(rhs);
+            }
+        }
 
-        return div_bf(n, d, p);
-    }
-}
+        impl Mul<$t> for BitFloat {
+            type Output = BitFloat;
+            fn mul(self, rhs: $t) -> Self::Output {
+                self * BitFloat::from(rhs)
+            }
+        }
 
-impl DivVariants<BitFloat> for &BitFloat {
-    type Output = BitFloat;
+        impl Mul<$t> for &BitFloat {
+            type Output = BitFloat;
+            fn mul(self, rhs: $t) -> Self::Output {
+                self * BitFloat::from(rhs)
+            }
+        }
 
-    fn full_div(self, rhs: BitFloat) -> Self::Output {
-        let p = self.m.len().max(rhs.m.len());
-        return div_bf(self.clone(), rhs, p);
-    }
+        impl MulAssign<$t> for BitFloat {
+            fn mul_assign(&mut self, rhs: $t) {
+                *self *= BitFloat::from(rhs);
+            }
+        }
 
-    fn man_div(
-        self,
-        rhs: BitFloat,
-        l: Option<usize>,
-        r: Option<usize>,
-        prec: Option<usize>,
-    ) -> Self::Output {
-        let l = l.unwrap_or(self.m.len());
-        let r = r.unwrap_or(rhs.m.len());
-        let p = p.unwrap_or(l - r);
+        impl Div<$t> for BitFloat {
+            type Output = BitFloat;
+            fn div(self, rhs: $t) -> Self::Output {
+                self / BitFloat::from(rhs)
+            }
+        }
 
-        let mut n = self.clone();
-        lhs.m.drain(lhs.m.len() - l);
+        impl Div<$t> for &BitFloat {
+            type Output = BitFloat;
+            fn div(self, rhs: $t) -> Self::Output {
+                self / BitFloat::from(rhs)
+            }
+        }
 
-        let mut d = rhs;
-        rhs.m.drain(rhs.m.len() - r);
+        impl DivAssign<$t> for BitFloat {
+            fn div_assign(&mut self, rhs: $t) {
+                *self /= BitFloat::from(rhs);
+            }
+        }
 
-        return div_bf(n, d, p);
-    }
+        impl Div<BitFloat> for $t {
+            type Output = BitFloat;
+            fn div(self, rhs: BitFloat) -> Self::Output {
+                BitFloat::from(self) / rhs
+            }
+        }
+
+        impl Div<&BitFloat> for $t {
+            type Output = BitFloat;
+            fn div(self, rhs: &BitFloat) -> Self::Output {
+                BitFloat::from(self) / rhs
+            }
+        }
+        )+
+    };
 }
+impl_prim_ops_bf!(f64, f32, i128, u128, i64, u64, i32, u32, i16, u16, i8, u8);
+
+impl_commutative!(
+    Add,
+    add,
+    BitFloat,
+    |x| x,
+    f64,
+    f32,
+    i128,
+    u128,
+    i64,
+    u64,
+    i32,
+    u32,
+    i16,
+    u16,
+    i8,
+    u8
+);
+
+impl_commutative!(
+    Sub,
+    sub,
+    BitFloat,
+    |x| -x,
+    f64,
+    f32,
+    i128,
+    u128,
+    i64,
+    u64,
+    i32,
+    u32,
+    i16,
+    u16,
+    i8,
+    u8
+);
+
+impl_commutative!(
+    Mul,
+    mul,
+    BitFloat,
+    |x| x,
+    f64,
+    f32,
+    i128,
+    u128,
+    i64,
+    u64,
+    i32,
+    u32,
+    i16,
+    u16,
+    i8,
+    u8
+);
@@ -1,1398 +1,394 @@
-#![allow(dead_code)]
-use crate::bitint::*;
-use crate::ubitint::*;
+use super::bitfloat::{BitFloat, RoundingMode};
+use super::bitint::BitInt;
+use super::traits::{DivRem, FromErr, SignVal};
+use super::ubitint::UBitInt;
+use crate::utils::utils::{scmp, trim_lz};
 use core::fmt;
-use std::cmp::Ordering::*;
+use std::cmp::Ordering;
 use std::ops::*;
 
+/// Exact rational number `(-1)^sign * n / d` with a non-zero denominator.
+///
+/// The arithmetic does not reduce its results, which would cost a gcd per
+/// operation, so a fraction keeps whatever common factors it picks up until
+/// [`simplify`](BitFrac::simplify) removes them. Comparisons go by value,
+/// and zero has no sign.
 #[derive(Debug, Clone)]
 pub struct BitFrac {
-    pub n: UBitInt,
-    pub d: UBitInt,
-    pub sign: bool,
+    n: UBitInt,
+    d: UBitInt,
+    sign: bool,
+}
+
+// (-1)^sign * n / d for non-zero d, zero without a sign
+fn make(n: UBitInt, d: UBitInt, sign: bool) -> BitFrac {
+    let sign = sign && !n.is_zero();
+    BitFrac { n, d, sign }
+}
+
+fn trimmed(mut data: Vec<u64>) -> UBitInt {
+    trim_lz(&mut data);
+    UBitInt::make(data)
+}
+
+// the fraction with the integer value of x
+fn int_fr<T: SignVal>(x: T) -> BitFrac {
+    let (sign, n) = x.sign_and_val();
+    make(trimmed(n), UBitInt::one(), sign)
+}
+
+fn signed(n: &UBitInt, sign: bool) -> BitInt {
+    BitInt::make(n.get_data().to_vec(), sign)
+}
+
+fn unsigned(n: &BitInt) -> UBitInt {
+    UBitInt::make(n.get_data().to_vec())
+}
+
+// the exponent k with d = 2^k, if d is a power of two
+fn pow2_exp(d: &UBitInt) -> Option<usize> {
+    let data = d.get_data();
+    let (&top, rest) = data.split_last()?;
+    (top.is_power_of_two() && rest.iter().all(|&x| x == 0))
+        .then(|| 64 * rest.len() + top.trailing_zeros() as usize)
 }
 
 impl BitFrac {
-    #[inline]
     pub fn get_num(&self) -> UBitInt {
         self.n.clone()
     }
 
-    #[inline]
     pub fn get_den(&self) -> UBitInt {
         self.d.clone()
     }
 
-    #[inline]
     pub fn get_sign(&self) -> bool {
         self.sign
     }
 
-    #[inline]
+    /// `n / d` as given, or an error if `d` is zero.
     pub fn from<T: SignVal>(n: T, d: T) -> Result<BitFrac, String> {
-        let (n_sign, mut n_data) = n.sign_and_val();
-        let (d_sign, mut d_data) = d.sign_and_val();
-
-        if let Some(idx) = n_data.iter().rposition(|&x| x != 0) {
-            n_data.truncate(idx + 1);
-        } else {
-            n_data.clear();
-        }
-
-        if let Some(idx) = d_data.iter().rposition(|&x| x != 0) {
-            d_data.truncate(idx + 1);
-        } else {
+        let (n_sign, n) = n.sign_and_val();
+        let (d_sign, d) = d.sign_and_val();
+        let d = trimmed(d);
+        if d.is_zero() {
             return Err("the denominator cannot be zero".to_string());
         }
-
-        Ok(BitFrac {
-            n: UBitInt { data: n_data },
-            d: UBitInt { data: d_data },
-            sign: n_sign ^ d_sign,
-        })
+        Ok(make(trimmed(n), d, n_sign ^ d_sign))
     }
 
-    #[inline]
+    /// `n / d` as given. Panics if `d` is zero.
     pub fn from_bi(n: BitInt, d: BitInt) -> BitFrac {
+        assert!(!d.is_zero(), "the denominator cannot be zero");
+        make(unsigned(&n), unsigned(&d), n.get_sign() ^ d.get_sign())
+    }
+
+    pub fn zero() -> BitFrac {
+        BitFrac {
+            n: UBitInt::zero(),
+            d: UBitInt::one(),
+            sign: false,
+        }
+    }
+
+    pub fn one() -> BitFrac {
         BitFrac {
-            n: n.get_val(),
-            d: d.get_val(),
-            sign: n.get_sign() ^ d.get_sign(),
+            n: UBitInt::one(),
+            d: UBitInt::one(),
+            sign: false,
         }
     }
 
-    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.n.is_zero()
+    }
+
     pub fn neg(&mut self) {
-        self.sign = !self.sign;
+        self.sign = !self.sign && !self.is_zero();
     }
 
-    #[inline]
     pub fn abs(&mut self) {
         self.sign = false;
     }
 
-    #[inline]
+    /// Divides out the common factors, leaving the fraction in lowest terms.
     pub fn simplify(&mut self) {
-        let mut gcd = self.n.clone();
-        let mut rem = self.d.clone();
-        while rem > 0_usize {
-            let tmp = rem.clone();
-            rem = &gcd % rem;
-            gcd = tmp;
+        let (mut gcd, mut rem) = (self.n.clone(), self.d.clone());
+        while !rem.is_zero() {
+            let (_, r) = (&gcd).div_rem(&rem);
+            gcd = rem;
+            rem = r;
         }
-
         self.n /= &gcd;
         self.d /= gcd;
     }
 
-    #[inline]
-    pub fn abs_cmp(&self, other: &BitFrac) -> std::cmp::Ordering {
-        (&self.n * &other.d)
-            .partial_cmp(&(&other.n * &self.d))
-            .unwrap()
+    /// Compares the magnitudes.
+    pub fn abs_cmp(&self, other: &BitFrac) -> Ordering {
+        (&self.n * &other.d).cmp(&(&other.n * &self.d))
     }
-}
 
-impl fmt::Display for BitFrac {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.sign {
-            write!(f, "-{}  //  {}", self.n.to_string(), self.d.to_string())?;
-        } else {
-            write!(f, "{}  //  {}", self.n.to_string(), self.d.to_string())?;
-        }
-        Ok(())
+    /// The exact value of `x`, or an error for the infinities and NaN.
+    pub fn from_f64(x: f64) -> Result<BitFrac, FromErr> {
+        BitFrac::try_from(&BitFloat::from(x))
     }
 }
 
-impl PartialEq for BitFrac {
-    #[inline]
-    fn eq(&self, other: &Self) -> bool {
-        if self.sign ^ other.sign {
-            return false;
+impl BitFloat {
+    /// `value` rounded to `prec` bits in the given mode. Fractions whose
+    /// denominator is not a power of two need a finite `prec`.
+    pub fn from_frac(value: &BitFrac, prec: usize, mode: RoundingMode) -> BitFloat {
+        let mut n = BitFloat::from(value.n.clone());
+        if value.sign {
+            n.neg_mut();
+        }
+        match pow2_exp(&value.d) {
+            Some(k) => n.ldexp(-(k as i128)).to_prec_round(prec, mode),
+            None => n.div_round(&BitFloat::from(value.d.clone()), prec, mode),
         }
-
-        return &self.n * &other.d == &other.n * &self.d;
     }
 }
 
-macro_rules! impl_peq_bfc_prim {
-    ($($t:ty),*) => {
-        $(
-            impl PartialEq<$t> for BitFrac {
-                #[inline]
-                fn eq(&self, other: &$t) -> bool {
-                    if self.sign ^ (*other < 0) {
-                        return false;
-                    }
-
-                    return self.n == other.unsigned_abs() * &self.d;
-                }
-            }
-        )*
-    };
-}
-
-impl_peq_bfc_prim!(i128, i64, isize, i32, i16, i8);
-
-impl PartialOrd for BitFrac {
-    #[inline]
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        if self.sign ^ other.sign {
-            if self.sign {
-                return Some(Less);
-            } else {
-                return Some(Greater);
-            }
+/// Every finite `BitFloat` is a dyadic rational, so this is exact. The
+/// infinities give [`FromErr::Overflow`] and NaN gives [`FromErr::NaN`].
+impl TryFrom<&BitFloat> for BitFrac {
+    type Error = FromErr;
+    fn try_from(value: &BitFloat) -> Result<Self, Self::Error> {
+        if value.is_nan() {
+            return Err(FromErr::NaN);
         }
-
-        if self.sign {
-            return Some((&self.n * &other.d).cmp(&(&other.n * &self.d)).reverse());
-        } else {
-            return (&self.n * &other.d).partial_cmp(&(&other.n * &self.d));
+        if value.is_inf() {
+            return Err(FromErr::Overflow);
         }
+        let Some(e) = value.exponent() else {
+            return Ok(BitFrac::zero());
+        };
+        // the odd significand, scaled by its lowest bit
+        let n = value.significand();
+        let sign = value.get_s();
+        let k = e - value.prec() as i128 + 1;
+        Ok(if k >= 0 {
+            make(n << k as usize, UBitInt::one(), sign)
+        } else {
+            make(n, UBitInt::one() << (-k) as usize, sign)
+        })
     }
 }
 
-macro_rules! impl_pord_bfc_prim {
+macro_rules! impl_from_int_fr {
     ($($t:ty),*) => {
         $(
-            impl PartialOrd<$t> for BitFrac {
-                #[inline]
-                fn partial_cmp(&self, other: &$t) -> Option<std::cmp::Ordering> {
-                    if self.sign ^ (*other < 0) {
-                        if self.sign {
-                            return Some(Less);
-                        } else {
-                            return Some(Greater);
-                        }
-                    }
-
-                    if self.sign {
-                        return Some(
-                            self.n
-                                .partial_cmp(&(other.unsigned_abs() * &self.d))
-                                .unwrap()
-                                .reverse(),
-                        );
-                    } else {
-                        return self.n.partial_cmp(&(other.unsigned_abs() * &self.d));
-                    }
+            impl From<$t> for BitFrac {
+                fn from(value: $t) -> Self {
+                    int_fr(value)
                 }
             }
         )*
     };
 }
 
-impl_pord_bfc_prim!(i128, i64, isize, i32, i16, i8);
-
-impl Eq for BitFrac {}
-
-impl Ord for BitFrac {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap()
-    }
-}
-
-impl Add for BitFrac {
-    type Output = BitFrac;
+impl_from_int_fr!(BitInt, &BitInt, UBitInt, &UBitInt);
+impl_from_int_fr!(i128, i64, isize, i32, i16, i8, u128, u64, usize, u32, u16, u8);
 
-    #[inline]
-    fn add(self, rhs: Self) -> Self::Output {
-        if self.sign ^ rhs.sign {
-            match self.abs_cmp(&rhs) {
-                Greater => BitFrac {
-                    n: self.n * &rhs.d - rhs.n * &self.d,
-                    d: self.d * rhs.d,
-                    sign: self.sign,
-                },
-                Less => BitFrac {
-                    n: rhs.n * &self.d - self.n * &rhs.d,
-                    d: self.d * rhs.d,
-                    sign: rhs.sign,
-                },
-                Equal => BitFrac {
-                    n: UBitInt { data: vec![0] },
-                    d: UBitInt { data: vec![1] },
-                    sign: false,
-                },
-            }
-        } else {
-            BitFrac {
-                n: self.n * &rhs.d + rhs.n * &self.d,
-                d: self.d * rhs.d,
-                sign: self.sign,
-            }
+impl fmt::Display for BitFrac {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.sign {
+            write!(f, "-")?;
         }
+        write!(f, "{}  //  {}", self.n, self.d)
     }
 }
 
-impl Add for &BitFrac {
-    type Output = BitFrac;
-
-    #[inline]
-    fn add(self, rhs: Self) -> Self::Output {
-        if self.sign ^ rhs.sign {
-            match self.abs_cmp(&rhs) {
-                Greater => BitFrac {
-                    n: &self.n * &rhs.d - &rhs.n * &self.d,
-                    d: &self.d * &rhs.d,
-                    sign: self.sign,
-                },
-                Less => BitFrac {
-                    n: &rhs.n * &self.d - &self.n * &rhs.d,
-                    d: &self.d * &rhs.d,
-                    sign: rhs.sign,
-                },
-                Equal => BitFrac {
-                    n: UBitInt { data: vec![0] },
-                    d: UBitInt { data: vec![1] },
-                    sign: false,
-                },
-            }
-        } else {
-            BitFrac {
-                n: &self.n * &rhs.d + &rhs.n * &self.d,
-                d: &self.d * &rhs.d,
-                sign: self.sign,
-            }
-        }
+impl PartialEq for BitFrac {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
     }
 }
 
-impl Add<&BitFrac> for BitFrac {
-    type Output = BitFrac;
+impl Eq for BitFrac {}
 
-    #[inline]
-    fn add(self, rhs: &BitFrac) -> Self::Output {
-        if self.sign ^ rhs.sign {
-            match self.abs_cmp(&rhs) {
-                Greater => BitFrac {
-                    n: self.n * &rhs.d - &rhs.n * &self.d,
-                    d: self.d * &rhs.d,
-                    sign: self.sign,
-                },
-                Less => BitFrac {
-                    n: &rhs.n * &self.d - self.n * &rhs.d,
-                    d: self.d * &rhs.d,
-                    sign: rhs.sign,
-                },
-                Equal => BitFrac {
-                    n: UBitInt { data: vec![0] },
-                    d: UBitInt { data: vec![1] },
-                    sign: false,
-                },
-            }
-        } else {
-            BitFrac {
-                n: self.n * &rhs.d + &rhs.n * &self.d,
-                d: self.d * &rhs.d,
-                sign: self.sign,
-            }
-        }
+impl PartialOrd for BitFrac {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
-impl Add<BitFrac> for &BitFrac {
-    type Output = BitFrac;
-
-    #[inline]
-    fn add(self, rhs: BitFrac) -> Self::Output {
-        if self.sign ^ rhs.sign {
-            match self.abs_cmp(&rhs) {
-                Greater => BitFrac {
-                    n: &self.n * &rhs.d - rhs.n * &self.d,
-                    d: &self.d * rhs.d,
-                    sign: self.sign,
-                },
-                Less => BitFrac {
-                    n: rhs.n * &self.d - &self.n * &rhs.d,
-                    d: &self.d * rhs.d,
-                    sign: rhs.sign,
-                },
-                Equal => BitFrac {
-                    n: UBitInt { data: vec![0] },
-                    d: UBitInt { data: vec![1] },
-                    sign: false,
-                },
-            }
-        } else {
-            BitFrac {
-                n: &self.n * &rhs.d + rhs.n * &self.d,
-                d: &self.d * rhs.d,
-                sign: self.sign,
-            }
+impl Ord for BitFrac {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.sign != other.sign {
+            return other.sign.cmp(&self.sign);
         }
+        scmp(self.sign, self.abs_cmp(other))
     }
 }
 
-macro_rules! impl_add_bfc_prim {
+macro_rules! impl_peq_pord_int_fr {
     ($($t:ty),*) => {
         $(
-            impl Add<$t> for BitFrac {
-                type Output = BitFrac;
-
-                #[inline]
-                fn add(self, rhs: $t) -> Self::Output {
-                    let sc_rhs = rhs.unsigned_abs() * &self.d;
-                    if self.sign ^ (rhs < 0) {
-                        match self.n.cmp(&sc_rhs) {
-                            Greater => BitFrac {
-                                n: self.n - sc_rhs,
-                                d: self.d,
-                                sign: self.sign,
-                            },
-                            Less => BitFrac {
-                                n: sc_rhs - self.n,
-                                d: self.d,
-                                sign: rhs < 0,
-                            },
-                            Equal => BitFrac {
-                                n: UBitInt { data: vec![0] },
-                                d: UBitInt { data: vec![1] },
-                                sign: false,
-                            },
-                        }
-                    } else {
-                        BitFrac {
-                            n: self.n + sc_rhs,
-                            d: self.d,
-                            sign: self.sign,
-                        }
-                    }
+            impl PartialEq<$t> for BitFrac {
+                fn eq(&self, other: &$t) -> bool {
+                    *self == int_fr(other)
                 }
             }
 
-            impl Add<$t> for &BitFrac {
-                type Output = BitFrac;
-
-                #[inline]
-                fn add(self, rhs: $t) -> Self::Output {
-                    let sc_rhs = rhs.unsigned_abs() * &self.d;
-                    if self.sign ^ (rhs < 0) {
-                        match self.n.cmp(&sc_rhs) {
-                            Greater => BitFrac {
-                                n: &self.n - sc_rhs,
-                                d: self.d.clone(),
-                                sign: self.sign,
-                            },
-                            Less => BitFrac {
-                                n: sc_rhs - &self.n,
-                                d: self.d.clone(),
-                                sign: rhs < 0,
-                            },
-                            Equal => BitFrac {
-                                n: UBitInt { data: vec![0] },
-                                d: UBitInt { data: vec![1] },
-                                sign: false,
-                            },
-                        }
-                    } else {
-                        BitFrac {
-                            n: &self.n + sc_rhs,
-                            d: self.d.clone(),
-                            sign: self.sign,
-                        }
-                    }
+            impl PartialOrd<$t> for BitFrac {
+                fn partial_cmp(&self, other: &$t) -> Option<Ordering> {
+                    Some(self.cmp(&int_fr(other)))
                 }
             }
 
-            impl Add<BitFrac> for $t {
-                type Output = BitFrac;
-
-                #[inline]
-                fn add(self, rhs: BitFrac) -> Self::Output {
-                    let sc_self = self.unsigned_abs() * &rhs.d;
-                    if rhs.sign ^ (self < 0) {
-                        match rhs.n.cmp(&sc_self) {
-                            Greater => BitFrac {
-                                n: rhs.n - sc_self,
-                                d: rhs.d,
-                                sign: rhs.sign,
-                            },
-                            Less => BitFrac {
-                                n: sc_self - rhs.n,
-                                d: rhs.d,
-                                sign: self < 0,
-                            },
-                            Equal => BitFrac {
-                                n: UBitInt { data: vec![0] },
-                                d: UBitInt { data: vec![1] },
-                                sign: false,
-                            },
-                        }
-                    } else {
-                        BitFrac {
-                            n: rhs.n + sc_self,
-                            d: rhs.d,
-                            sign: rhs.sign,
-                        }
-                    }
+            impl PartialEq<BitFrac> for $t {
+                fn eq(&self, other: &BitFrac) -> bool {
+                    int_fr(self) == *other
                 }
             }
 
-            impl Add<&BitFrac> for $t {
-                type Output = BitFrac;
-
-                #[inline]
-                fn add(self, rhs: &BitFrac) -> Self::Output {
-                    let sc_self = self.unsigned_abs() * &rhs.d;
-                    if rhs.sign ^ (self < 0) {
-                        match rhs.n.cmp(&sc_self) {
-                            Greater => BitFrac {
-                                n: &rhs.n - sc_self,
-                                d: rhs.d.clone(),
-                                sign: rhs.sign,
-                            },
-                            Less => BitFrac {
-                                n: sc_self - &rhs.n,
-                                d: rhs.d.clone(),
-                                sign: self < 0,
-                            },
-                            Equal => BitFrac {
-                                n: UBitInt { data: vec![0] },
-                                d: UBitInt { data: vec![1] },
-                                sign: false,
-                            },
-                        }
-                    } else {
-                        BitFrac {
-                            n: &rhs.n + sc_self,
-                            d: rhs.d.clone(),
-                            sign: rhs.sign,
-                        }
-                    }
+            impl PartialOrd<BitFrac> for $t {
+                fn partial_cmp(&self, other: &BitFrac) -> Option<Ordering> {
+                    Some(int_fr(self).cmp(other))
                 }
             }
         )*
     };
 }
 
-impl_add_bfc_prim!(i128, i64, isize, i32, i16, i8);
+impl_peq_pord_int_fr!(BitInt, i128, i64, isize, i32, i16, i8, u128, u64, usize, u32, u16, u8);
 
-impl AddAssign for BitFrac {
-    #[inline]
-    fn add_assign(&mut self, rhs: Self) {
-        if self.sign ^ rhs.sign {
-            match (*self).cmp(&rhs) {
-                Greater => {
-                    self.n *= &rhs.d;
-                    self.n -= rhs.n * &self.d;
-                    self.d *= rhs.d;
-                }
-                Less => {
-                    self.n *= &rhs.d;
-                    self.n = rhs.n * &self.d - &self.n;
-                    self.d *= rhs.d;
-                    self.sign = rhs.sign;
-                }
-                Equal => {
-                    *self = BitFrac {
-                        n: UBitInt { data: vec![0] },
-                        d: UBitInt { data: vec![1] },
-                        sign: false,
-                    };
-                }
-            }
-        } else {
-            self.n *= &rhs.d;
-            self.n += rhs.n * &self.d;
-            self.d *= rhs.d;
-        }
-    }
+fn add_sub_fr(lhs: &BitFrac, rhs: &BitFrac, sub: bool) -> BitFrac {
+    let a = signed(&(&lhs.n * &rhs.d), lhs.sign);
+    let b = signed(&(&rhs.n * &lhs.d), rhs.sign ^ sub);
+    let n = &a + &b;
+    make(unsigned(&n), &lhs.d * &rhs.d, n.get_sign())
 }
 
-impl AddAssign<&BitFrac> for BitFrac {
-    #[inline]
-    fn add_assign(&mut self, rhs: &BitFrac) {
-        if self.sign ^ rhs.sign {
-            match (*self).abs_cmp(rhs) {
-                Greater => {
-                    self.n *= &rhs.d;
-                    self.n -= &rhs.n * &self.d;
-                    self.d *= &rhs.d;
-                }
-                Less => {
-                    self.n *= &rhs.d;
-                    self.n = &rhs.n * &self.d - &self.n;
-                    self.d *= &rhs.d;
-                    self.sign = rhs.sign;
-                }
-                Equal => {
-                    *self = BitFrac {
-                        n: UBitInt { data: vec![0] },
-                        d: UBitInt { data: vec![1] },
-                        sign: false,
-                    };
-                }
-            }
-        } else {
-            self.n *= &rhs.d;
-            self.n += &rhs.n * &self.d;
-            self.d *= &rhs.d;
-        }
-    }
+fn add_fr(lhs: &BitFrac, rhs: &BitFrac) -> BitFrac {
+    add_sub_fr(lhs, rhs, false)
 }
 
-macro_rules! impl_add_assign_bfc_prim {
-    ($($t:ty),*) => {
-        $(
-            impl AddAssign<$t> for BitFrac{
-                #[inline]
-                fn add_assign(&mut self, rhs: $t){
-                    let sc_rhs = rhs.unsigned_abs() * &self.d;
-                    if self.sign ^ (rhs < 0) {
-                        match self.n.cmp(&sc_rhs) {
-                            Greater => {
-                                self.n -= sc_rhs;
-                            }
-                            Less => {
-                                self.n = sc_rhs - &self.n;
-                                self.sign = rhs < 0;
-                            }
-                            Equal => {
-                                *self = BitFrac {
-                                    n: UBitInt { data: vec![0] },
-                                    d: UBitInt { data: vec![1] },
-                                    sign: false,
-                                }
-                            }
-                        }
-                    } else {
-                        self.n += sc_rhs;
-                    }
-                }
-            }
-        )*
-    };
+fn sub_fr(lhs: &BitFrac, rhs: &BitFrac) -> BitFrac {
+    add_sub_fr(lhs, rhs, true)
 }
 
-impl_add_assign_bfc_prim!(i128, i64, isize, i32, i16, i8);
-
-impl Sub for BitFrac {
-    type Output = BitFrac;
-
-    #[inline]
-    fn sub(self, rhs: Self) -> Self::Output {
-        if self.sign ^ rhs.sign {
-            BitFrac {
-                n: self.n * &rhs.d + rhs.n * &self.d,
-                d: self.d * rhs.d,
-                sign: self.sign,
-            }
-        } else {
-            match self.abs_cmp(&rhs) {
-                Greater => BitFrac {
-                    n: self.n * &rhs.d - rhs.n * &self.d,
-                    d: self.d * rhs.d,
-                    sign: self.sign,
-                },
-                Less => BitFrac {
-                    n: rhs.n * &self.d - self.n * &rhs.d,
-                    d: self.d * rhs.d,
-                    sign: rhs.sign,
-                },
-                Equal => BitFrac {
-                    n: UBitInt { data: vec![0] },
-                    d: UBitInt { data: vec![1] },
-                    sign: false,
-                },
-            }
-        }
-    }
+fn mul_fr(lhs: &BitFrac, rhs: &BitFrac) -> BitFrac {
+    make(&lhs.n * &rhs.n, &lhs.d * &rhs.d, lhs.sign ^ rhs.sign)
 }
 
-impl Sub for &BitFrac {
-    type Output = BitFrac;
-
-    #[inline]
-    fn sub(self, rhs: Self) -> Self::Output {
-        if self.sign ^ rhs.sign {
-            BitFrac {
-                n: &self.n * &rhs.d + &rhs.n * &self.d,
-                d: &self.d * &rhs.d,
-                sign: self.sign,
-            }
-        } else {
-            match self.abs_cmp(&rhs) {
-                Greater => BitFrac {
-                    n: &self.n * &rhs.d - &rhs.n * &self.d,
-                    d: &self.d * &rhs.d,
-                    sign: self.sign,
-                },
-                Less => BitFrac {
-                    n: &rhs.n * &self.d - &self.n * &rhs.d,
-                    d: &self.d * &rhs.d,
-                    sign: rhs.sign,
-                },
-                Equal => BitFrac {
-                    n: UBitInt { data: vec![0] },
-                    d: UBitInt { data: vec![1] },
-                    sign: false,
-                },
-            }
-        }
-    }
+fn div_fr(lhs: &BitFrac, rhs: &BitFrac) -> BitFrac {
+    assert!(!rhs.is_zero(), "attempt to divide by zero");
+    make(&lhs.n * &rhs.d, &lhs.d * &rhs.n, lhs.sign ^ rhs.sign)
 }
 
-impl Sub<&BitFrac> for BitFrac {
-    type Output = BitFrac;
-
-    #[inline]
-    fn sub(self, rhs: &BitFrac) -> Self::Output {
-        if self.sign ^ rhs.sign {
-            BitFrac {
-                n: self.n * &rhs.d + &rhs.n * &self.d,
-                d: self.d * &rhs.d,
-                sign: self.sign,
-            }
-        } else {
-            match self.abs_cmp(&rhs) {
-                Greater => BitFrac {
-                    n: self.n * &rhs.d - &rhs.n * &self.d,
-                    d: self.d * &rhs.d,
-                    sign: self.sign,
-                },
-                Less => BitFrac {
-                    n: &rhs.n * &self.d - self.n * &rhs.d,
-                    d: self.d * &rhs.d,
-                    sign: rhs.sign,
-                },
-                Equal => BitFrac {
-                    n: UBitInt { data: vec![0] },
-                    d: UBitInt { data: vec![1] },
-                    sign: false,
-                },
+macro_rules! impl_op_fr {
+    ($($tr:ident, $f:ident, $tra:ident, $fa:ident, $op:ident);*) => {$(
+        impl $tr for BitFrac {
+            type Output = BitFrac;
+            fn $f(self, rhs: Self) -> Self::Output {
+                $op(&self, &rhs)
             }
         }
-    }
-}
 
-impl Sub<BitFrac> for &BitFrac {
-    type Output = BitFrac;
-
-    #[inline]
-    fn sub(self, rhs: BitFrac) -> Self::Output {
-        if self.sign ^ rhs.sign {
-            BitFrac {
-                n: &self.n * &rhs.d + rhs.n * &self.d,
-                d: &self.d * rhs.d,
-                sign: self.sign,
-            }
-        } else {
-            match self.abs_cmp(&rhs) {
-                Greater => BitFrac {
-                    n: &self.n * &rhs.d - rhs.n * &self.d,
-                    d: &self.d * rhs.d,
-                    sign: self.sign,
-                },
-                Less => BitFrac {
-                    n: rhs.n * &self.d - &self.n * &rhs.d,
-                    d: &self.d * rhs.d,
-                    sign: rhs.sign,
-                },
-                Equal => BitFrac {
-                    n: UBitInt { data: vec![0] },
-                    d: UBitInt { data: vec![1] },
-                    sign: false,
-                },
+        impl $tr for &BitFrac {
+            type Output = BitFrac;
+            fn $f(self, rhs: Self) -> Self::Output {
+                $op(self, rhs)
             }
         }
-    }
-}
-
-macro_rules! impl_sub_bfc_prim {
-    ($($t:ty),*) => {
-        $(
-            impl Sub<$t> for BitFrac {
-                type Output = BitFrac;
-
-                #[inline]
-                fn sub(self, rhs: $t) -> Self::Output {
-                    let sc_rhs = rhs.unsigned_abs() * &self.d;
-                    if self.sign ^ (rhs < 0) {
-                        BitFrac {
-                            n: self.n + sc_rhs,
-                            d: self.d,
-                            sign: self.sign,
-                        }
-                    } else {
-                        match self.n.cmp(&sc_rhs) {
-                            Greater => BitFrac {
-                                n: self.n - sc_rhs,
-                                d: self.d,
-                                sign: self.sign,
-                            },
-                            Less => BitFrac {
-                                n: sc_rhs - self.n,
-                                d: self.d,
-                                sign: rhs < 0,
-                            },
-                            Equal => BitFrac {
-                                n: UBitInt { data: vec![0] },
-                                d: UBitInt { data: vec![1] },
-                                sign: false,
-                            },
-                        }
-                    }
-                }
-            }
-
-            impl Sub<$t> for &BitFrac {
-                type Output = BitFrac;
 
-                #[inline]
-                fn sub(self, rhs: $t) -> Self::Output {
-                    let sc_rhs = rhs.unsigned_abs() * &self.d;
-                    if self.sign ^ (rhs < 0) {
-                        BitFrac {
-                            n: &self.n + sc_rhs,
-                            d: self.d.clone(),
-                            sign: self.sign,
-                        }
-                    } else {
-                        match self.n.cmp(&sc_rhs) {
-                            Greater => BitFrac {
-                                n: &self.n - sc_rhs,
-                                d: self.d.clone(),
-                                sign: self.sign,
-                            },
-                            Less => BitFrac {
-                                n: sc_rhs - &self.n,
-                                d: self.d.clone(),
-                                sign: rhs < 0,
-                            },
-                            Equal => BitFrac {
-                                n: UBitInt { data: vec![0] },
-                                d: UBitInt { data: vec![1] },
-                                sign: false,
-                            },
-                        }
-                    }
-                }
-            }
-
-            impl Sub<BitFrac> for $t {
-                type Output = BitFrac;
-
-                #[inline]
-                fn sub(self, rhs: BitFrac) -> Self::Output {
-                    let sc_self = self.unsigned_abs() * &rhs.d;
-                    if rhs.sign ^ (self < 0) {
-                        BitFrac {
-                            n: rhs.n + sc_self,
-                            d: rhs.d,
-                            sign: rhs.sign,
-                        }
-                    } else {
-                        match rhs.n.cmp(&sc_self) {
-                            Greater => BitFrac {
-                                n: rhs.n - sc_self,
-                                d: rhs.d,
-                                sign: rhs.sign,
-                            },
-                            Less => BitFrac {
-                                n: sc_self - rhs.n,
-                                d: rhs.d,
-                                sign: self < 0,
-                            },
-                            Equal => BitFrac {
-                                n: UBitInt { data: vec![0] },
-                                d: UBitInt { data: vec![1] },
-                                sign: false,
-                            },
-                        }
-                    }
-                }
-            }
-
-            impl Sub<&BitFrac> for $t {
-                type Output = BitFrac;
-
-                #[inline]
-                fn sub(self, rhs: &BitFrac) -> Self::Output {
-                    let sc_self = self.unsigned_abs() * &rhs.d;
-                    if rhs.sign ^ (self < 0) {
-                        BitFrac {
-                            n: &rhs.n + sc_self,
-                            d: rhs.d.clone(),
-                            sign: rhs.sign,
-                        }
-                    } else {
-                        match rhs.n.cmp(&sc_self) {
-                            Greater => BitFrac {
-                                n: &rhs.n - sc_self,
-                                d: rhs.d.clone(),
-                                sign: rhs.sign,
-                            },
-                            Less => BitFrac {
-                                n: sc_self - &rhs.n,
-                                d: rhs.d.clone(),
-                                sign: self < 0,
-                            },
-                            Equal => BitFrac {
-                                n: UBitInt { data: vec![0] },
-                                d: UBitInt { data: vec![1] },
-                                sign: false,
-                            },
-                        }
-                    }
-                }
-            }
-        )*
-    };
-}
-
-impl_sub_bfc_prim!(i128, i64, isize, i32, i16, i8);
-
-impl SubAssign for BitFrac {
-    #[inline]
-    fn sub_assign(&mut self, rhs: Self) {
-        if self.sign ^ rhs.sign {
-            self.n *= &rhs.d;
-            self.n += rhs.n * &self.d;
-            self.d *= rhs.d;
-        } else {
-            match (*self).cmp(&rhs) {
-                Greater => {
-                    self.n *= &rhs.d;
-                    self.n -= rhs.n * &self.d;
-                    self.d *= rhs.d;
-                }
-                Less => {
-                    self.n *= &rhs.d;
-                    self.n = rhs.n * &self.d - &self.n;
-                    self.d *= rhs.d;
-                    self.sign = rhs.sign;
-                }
-                Equal => {
-                    *self = BitFrac {
-                        n: UBitInt { data: vec![0] },
-                        d: UBitInt { data: vec![1] },
-                        sign: false,
-                    };
-                }
+        impl $tr<&BitFrac> for BitFrac {
+            type Output = BitFrac;
+            fn $f(self, rhs: &BitFrac) -> Self::Output {
+                $op(&self, rhs)
             }
         }
-    }
-}
 
-impl SubAssign<&BitFrac> for BitFrac {
-    #[inline]
-    fn sub_assign(&mut self, rhs: &BitFrac) {
-        if self.sign ^ rhs.sign {
-            self.n *= &rhs.d;
-            self.n += &rhs.n * &self.d;
-            self.d *= &rhs.d;
-        } else {
-            match (*self).cmp(&rhs) {
-                Greater => {
-                    self.n *= &rhs.d;
-                    self.n -= &rhs.n * &self.d;
-                    self.d *= &rhs.d;
-                }
-                Less => {
-                    self.n *= &rhs.d;
-                    self.n = &rhs.n * &self.d - &self.n;
-                    self.d *= &rhs.d;
-                    self.sign = rhs.sign;
-                }
-                Equal => {
-                    *self = BitFrac {
-                        n: UBitInt { data: vec![0] },
-                        d: UBitInt { data: vec![1] },
-                        sign: false,
-                    };
-                }
+        impl $tr<BitFrac> for &BitFrac {
+            type Output = BitFrac;
+            fn $f(self, rhs: BitFrac) -> Self::Output {
+                $op(self, &rhs)
             }
         }
-    }
-}
 
-macro_rules! impl_sub_assign_bfc_prim {
-    ($($t:ty),*) => {
-        $(
-            impl SubAssign<$t> for BitFrac{
-                #[inline]
-                fn sub_assign(&mut self, rhs: $t){
-                    let sc_rhs = rhs.unsigned_abs() * &self.d;
-                    if self.sign ^ (rhs < 0) {
-                        self.n += sc_rhs;
-                    } else {
-                        match self.n.cmp(&sc_rhs) {
-                            Greater => {
-                                self.n -= sc_rhs;
-                            }
-                            Less => {
-                                self.n = sc_rhs - &self.n;
-                                self.sign = rhs < 0;
-                            }
-                            Equal => {
-                                *self = BitFrac {
-                                    n: UBitInt { data: vec![0] },
-                                    d: UBitInt { data: vec![1] },
-                                    sign: false,
-                                }
-                            }
-                        }
-                    }
-                }
+        impl $tra for BitFrac {
+            fn $fa(&mut self, rhs: Self) {
+                *self = $op(self, &rhs);
             }
-        )*
-    };
-}
-
-impl_sub_assign_bfc_prim!(i128, i64, isize, i32, i16, i8);
-
-impl Mul for BitFrac {
-    type Output = BitFrac;
-
-    #[inline]
-    fn mul(self, rhs: Self) -> Self::Output {
-        BitFrac {
-            n: self.n * rhs.n,
-            d: self.d * rhs.d,
-            sign: self.sign ^ rhs.sign,
-        }
-    }
-}
-
-impl Mul for &BitFrac {
-    type Output = BitFrac;
-
-    #[inline]
-    fn mul(self, rhs: Self) -> Self::Output {
-        BitFrac {
-            n: &self.n * &rhs.n,
-            d: &self.d * &rhs.d,
-            sign: self.sign ^ rhs.sign,
-        }
-    }
-}
-
-impl Mul<&BitFrac> for BitFrac {
-    type Output = BitFrac;
-
-    #[inline]
-    fn mul(self, rhs: &BitFrac) -> Self::Output {
-        BitFrac {
-            n: self.n * &rhs.n,
-            d: self.d * &rhs.d,
-            sign: self.sign ^ rhs.sign,
-        }
-    }
-}
-
-impl Mul<BitFrac> for &BitFrac {
-    type Output = BitFrac;
-
-    #[inline]
-    fn mul(self, rhs: BitFrac) -> Self::Output {
-        BitFrac {
-            n: &self.n * rhs.n,
-            d: &self.d * rhs.d,
-            sign: self.sign ^ rhs.sign,
         }
-    }
-}
-
-impl Mul<BitInt> for BitFrac {
-    type Output = BitFrac;
-
-    #[inline]
-    fn mul(self, rhs: BitInt) -> Self::Output {
-        BitFrac {
-            n: &self.n * rhs.val,
-            d: self.d,
-            sign: self.sign ^ rhs.sign,
-        }
-    }
-}
-
-impl Mul<BitInt> for &BitFrac {
-    type Output = BitFrac;
-
-    #[inline]
-    fn mul(self, rhs: BitInt) -> Self::Output {
-        BitFrac {
-            n: &self.n * rhs.val,
-            d: self.d.clone(),
-            sign: self.sign ^ rhs.sign,
-        }
-    }
-}
-
-impl Mul<&BitInt> for BitFrac {
-    type Output = BitFrac;
 
-    #[inline]
-    fn mul(self, rhs: &BitInt) -> Self::Output {
-        BitFrac {
-            n: &self.n * &rhs.val,
-            d: self.d,
-            sign: self.sign ^ rhs.sign,
-        }
-    }
-}
-
-impl Mul<&BitInt> for &BitFrac {
-    type Output = BitFrac;
-
-    #[inline]
-    fn mul(self, rhs: &BitInt) -> Self::Output {
-        BitFrac {
-            n: &self.n * &rhs.val,
-            d: self.d.clone(),
-            sign: self.sign ^ rhs.sign,
-        }
-    }
-}
-
-macro_rules! impl_mul_bfc_prim {
-    ($($t:ty),*) => {
-        $(
-            impl Mul<$t> for BitFrac{
-                type Output = BitFrac;
-
-                #[inline]
-                fn mul(self, rhs: $t) -> Self::Output{
-                    BitFrac{
-                        n: self.n*rhs.unsigned_abs(),
-                        d: self.d,
-                        sign: self.sign ^ (rhs < 0),
-                    }
-                }
-            }
-
-            impl Mul<$t> for &BitFrac{
-                type Output = BitFrac;
-
-                #[inline]
-                fn mul(self, rhs: $t) -> Self::Output{
-                    BitFrac{
-                        n: &self.n*rhs.unsigned_abs(),
-                        d: self.d.clone(),
-                        sign: self.sign ^ (rhs < 0),
-                    }
-                }
-            }
-
-            impl Mul<BitFrac> for $t{
-                type Output = BitFrac;
-
-                #[inline]
-                fn mul(self, rhs: BitFrac) -> Self::Output{
-                    BitFrac{
-                        n: self.unsigned_abs()*rhs.n,
-                        d: rhs.d,
-                        sign: rhs.sign ^ (self < 0),
-                    }
-                }
+        impl $tra<&BitFrac> for BitFrac {
+            fn $fa(&mut self, rhs: &BitFrac) {
+                *self = $op(self, rhs);
             }
-
-            impl Mul<&BitFrac> for $t{
-                type Output = BitFrac;
-
-                #[inline]
-                fn mul(self, rhs: &BitFrac) -> Self::Output{
-                    BitFrac{
-                        n: self.unsigned_abs()*&rhs.n,
-                        d: rhs.d.clone(),
-                        sign: rhs.sign ^ (self < 0),
-                    }
-                }
-            }
-        )*
-    };
-}
-
-impl_mul_bfc_prim!(i128, i64, isize, i32, i16, i8);
-
-impl MulAssign for BitFrac {
-    #[inline]
-    fn mul_assign(&mut self, rhs: Self) {
-        self.n *= rhs.n;
-        self.d *= rhs.d;
-        self.sign ^= rhs.sign;
-    }
-}
-
-impl MulAssign<&BitFrac> for BitFrac {
-    #[inline]
-    fn mul_assign(&mut self, rhs: &BitFrac) {
-        self.n *= &rhs.n;
-        self.d *= &rhs.d;
-        self.sign ^= rhs.sign;
-    }
-}
-
-impl MulAssign<BitInt> for BitFrac {
-    #[inline]
-    fn mul_assign(&mut self, rhs: BitInt) {
-        self.n *= rhs.val;
-        self.sign ^= rhs.sign;
-    }
-}
-
-impl MulAssign<&BitInt> for BitFrac {
-    #[inline]
-    fn mul_assign(&mut self, rhs: &BitInt) {
-        self.n *= &rhs.val;
-        self.sign ^= rhs.sign;
-    }
-}
-
-macro_rules! impl_mul_assign_bfc_prim {
-    ($($t:ty),*) => {
-        $(
-            impl MulAssign<$t> for BitFrac{
-                #[inline]
-                fn mul_assign(&mut self, rhs: $t){
-                    self.n *= rhs.unsigned_abs();
-                    self.sign ^= rhs < 0;
-                }
-            }
-        )*
-    };
-}
-
-impl_mul_assign_bfc_prim!(i128, i64, isize, i32, i16, i8);
-
-impl Div for BitFrac {
-    type Output = BitFrac;
-
-    #[inline]
-    fn div(self, rhs: Self) -> Self::Output {
-        BitFrac {
-            n: self.n * rhs.d,
-            d: self.d * rhs.n,
-            sign: self.sign ^ rhs.sign,
-        }
-    }
-}
-
-impl Div for &BitFrac {
-    type Output = BitFrac;
-
-    #[inline]
-    fn div(self, rhs: Self) -> Self::Output {
-        BitFrac {
-            n: &self.n * &rhs.d,
-            d: &self.d * &rhs.n,
-            sign: self.sign ^ rhs.sign,
-        }
-    }
-}
-
-impl Div<&BitFrac> for BitFrac {
-    type Output = BitFrac;
-
-    #[inline]
-    fn div(self, rhs: &BitFrac) -> Self::Output {
-        BitFrac {
-            n: self.n * &rhs.d,
-            d: self.d * &rhs.n,
-            sign: self.sign ^ rhs.sign,
-        }
-    }
-}
-
-impl Div<BitFrac> for &BitFrac {
-    type Output = BitFrac;
-
-    #[inline]
-    fn div(self, rhs: BitFrac) -> Self::Output {
-        BitFrac {
-            n: &self.n * rhs.d,
-            d: &self.d * rhs.n,
-            sign: self.sign ^ rhs.sign,
-        }
-    }
-}
-
-impl Div<BitInt> for BitFrac {
-    type Output = BitFrac;
-
-    #[inline]
-    fn div(self, rhs: BitInt) -> Self::Output {
-        BitFrac {
-            n: self.n,
-            d: self.d * rhs.val,
-            sign: self.sign ^ rhs.sign,
-        }
-    }
-}
-
-impl Div<BitInt> for &BitFrac {
-    type Output = BitFrac;
-
-    #[inline]
-    fn div(self, rhs: BitInt) -> Self::Output {
-        BitFrac {
-            n: self.n.clone(),
-            d: &self.d * rhs.val,
-            sign: self.sign ^ rhs.sign,
-        }
-    }
-}
-
-impl Div<&BitInt> for BitFrac {
-    type Output = BitFrac;
-
-    #[inline]
-    fn div(self, rhs: &BitInt) -> Self::Output {
-        BitFrac {
-            n: self.n,
-            d: self.d * &rhs.val,
-            sign: self.sign ^ rhs.sign,
         }
-    }
+    )*};
 }
 
-impl Div<&BitInt> for &BitFrac {
-    type Output = BitFrac;
+impl_op_fr!(
+    Add, add, AddAssign, add_assign, add_fr;
+    Sub, sub, SubAssign, sub_assign, sub_fr;
+    Mul, mul, MulAssign, mul_assign, mul_fr;
+    Div, div, DivAssign, div_assign, div_fr
+);
 
-    #[inline]
-    fn div(self, rhs: &BitInt) -> Self::Output {
-        BitFrac {
-            n: self.n.clone(),
-            d: &self.d * &rhs.val,
-            sign: self.sign ^ rhs.sign,
-        }
-    }
-}
-
-macro_rules! impl_div_bfc_prim {
-    ($($t:ty),*) => {
-        $(
-            impl Div<$t> for BitFrac{
-                type Output = BitFrac;
-
-                #[inline]
-                fn div(self, rhs: $t) -> Self::Output{
-                    BitFrac{
-                        n: self.n,
-                        d: self.d*rhs.unsigned_abs(),
-                        sign: self.sign ^ (rhs < 0),
-                    }
-                }
+// the operators between fractions and integers, on the integer's value
+macro_rules! impl_op_int_fr {
+    ($($t:ty),*) => {$(
+        impl_op_int_fr!(@ $t;
+            Add, add, AddAssign, add_assign, add_fr;
+            Sub, sub, SubAssign, sub_assign, sub_fr;
+            Mul, mul, MulAssign, mul_assign, mul_fr;
+            Div, div, DivAssign, div_assign, div_fr
+        );
+    )*};
+    (@ $t:ty; $($tr:ident, $f:ident, $tra:ident, $fa:ident, $op:ident);*) => {$(
+        impl $tr<$t> for BitFrac {
+            type Output = BitFrac;
+            fn $f(self, rhs: $t) -> Self::Output {
+                $op(&self, &int_fr(rhs))
             }
+        }
 
-            impl Div<$t> for &BitFrac{
-                type Output = BitFrac;
-
-                #[inline]
-                fn div(self, rhs: $t) -> Self::Output{
-                    BitFrac{
-                        n: self.n.clone(),
-                        d: &self.d*rhs.unsigned_abs(),
-                        sign: self.sign ^ (rhs < 0),
-                    }
-                }
+        impl $tr<$t> for &BitFrac {
+            type Output = BitFrac;
+            fn $f(self, rhs: $t) -> Self::Output {
+                $op(self, &int_fr(rhs))
             }
+        }
 
-            impl Div<BitFrac> for $t{
-                type Output = BitFrac;
-
-                #[inline]
-                fn div(self, rhs: BitFrac) -> Self::Output{
-                    BitFrac{
-                        n: rhs.n,
-                        d: rhs.d*self.unsigned_abs(),
-                        sign: rhs.sign ^ (self < 0),
-                    }
-                }
+        impl $tr<BitFrac> for $t {
+            type Output = BitFrac;
+            fn $f(self, rhs: BitFrac) -> Self::Output {
+                $op(&int_fr(self), &rhs)
             }
+        }
 
-            impl Div<&BitFrac> for $t{
-                type Output = BitFrac;
-
-                #[inline]
-                fn div(self, rhs: &BitFrac) -> Self::Output{
-                    BitFrac{
-                        n: rhs.n.clone(),
-                        d: &rhs.d*self.unsigned_abs(),
-                        sign: rhs.sign ^ (self < 0),
-                    }
-                }
+        impl $tr<&BitFrac> for $t {
+            type Output = BitFrac;
+            fn $f(self, rhs: &BitFrac) -> Self::Output {
+                $op(&int_fr(self), rhs)
             }
-        )*
-    };
-}
-
-impl_div_bfc_prim!(i128, i64, isize, i32, i16, i8);
-
-impl DivAssign for BitFrac {
-    #[inline]
-    fn div_assign(&mut self, rhs: Self) {
-        self.n *= rhs.d;
-        self.d *= rhs.n;
-        self.sign ^= rhs.sign;
-    }
-}
-
-impl DivAssign<&BitFrac> for BitFrac {
-    #[inline]
-    fn div_assign(&mut self, rhs: &BitFrac) {
-        self.n *= &rhs.d;
-        self.d *= &rhs.n;
-        self.sign ^= rhs.sign;
-    }
-}
-
-impl DivAssign<BitInt> for BitFrac {
-    #[inline]
-    fn div_assign(&mut self, rhs: BitInt) {
-        self.d *= rhs.val;
-        self.sign ^= rhs.sign;
-    }
-}
-
-impl DivAssign<&BitInt> for BitFrac {
-    #[inline]
-    fn div_assign(&mut self, rhs: &BitInt) {
-        self.d *= &rhs.val;
-        self.sign ^= rhs.sign;
-    }
-}
+        }
 
-macro_rules! impl_div_assign_bfc_prim {
-    ($($t:ty),*) => {
-        $(
-            impl DivAssign<$t> for BitFrac{
-                #[inline]
-                fn div_assign(&mut self, rhs: $t){
-                    self.d *= rhs.unsigned_abs();
-                    self.sign ^= rhs < 0;
-                }
+        impl $tra<$t> for BitFrac {
+            fn $fa(&mut self, rhs: $t) {
+                *self = $op(self, &int_fr(rhs));
             }
-        )*
-    };
+        }
+    )*};
 }
 
-impl_div_assign_bfc_prim!(i128, i64, isize, i32, i16, i8);
+impl_op_int_fr!(BitInt, &BitInt);
+impl_op_int_fr!(i128, i64, isize, i32, i16, i8, u128, u64, usize, u32, u16, u8);
 
 impl Neg for BitFrac {
     type Output = BitFrac;
-
-    #[inline]
     fn neg(self) -> Self::Output {
-        BitFrac {
-            n: self.n,
-            d: self.d,
-            sign: !self.sign,
-        }
+        -&self
     }
 }
 
 impl Neg for &BitFrac {
     type Output = BitFrac;
-
-    #[inline]
     fn neg(self) -> Self::Output {
-        BitFrac {
-            n: self.n.clone(),
-            d: self.d.clone(),
-            sign: !self.sign,
-        }
+        make(self.n.clone(), self.d.clone(), !self.sign)
     }
 }
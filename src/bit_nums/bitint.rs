@@ -1,4 +1,5 @@
-use super::traits::{Abs, DivRem, FromErr, FromStrErr, LogI, PowI, SmallBuf, Sqr, I};
+use super::scratch::{sealed::Limbs, with_local, ScratchInt};
+use super::traits::{Abs, DivRem, FromErr, FromStrErr, LogI, PowI, SignVal, SmallBuf, Sqr, I};
 use crate::bit_nums::ubitint::UBitInt;
 use crate::utils::{div::*, mul::*, utils::*};
 use crate::{impl_commutative, impl_commutative_div_rem, impl_commutative_peq_pord};
@@ -55,8 +56,67 @@ impl BitInt {
     pub fn is_zero(&self) -> bool {
         self.data.is_empty()
     }
+
+    // The `*_into` methods write their result into `out`, reusing its
+    // allocation, and run the kernels on a per-thread `Scratch`.
+
+    pub fn add_into(&self, rhs: &BitInt, out: &mut BitInt) {
+        out.data.clear();
+        out.data.extend_from_slice(&self.data);
+        out.sign = self.sign;
+        add_sub(out, &rhs.data, rhs.sign);
+    }
+
+    pub fn sub_into(&self, rhs: &BitInt, out: &mut BitInt) {
+        out.data.clear();
+        out.data.extend_from_slice(&self.data);
+        out.sign = self.sign;
+        add_sub(out, &rhs.data, !rhs.sign);
+    }
+
+    pub fn mul_into(&self, rhs: &BitInt, out: &mut BitInt) {
+        with_local(|ws| ws.mul_into(self, rhs, out));
+    }
+
+    pub fn square_into(&self, out: &mut BitInt) {
+        with_local(|ws| ws.sqr_into(self, out));
+    }
+
+    pub fn div_rem_into(&self, rhs: &BitInt, q: &mut BitInt, r: &mut BitInt) {
+        with_local(|ws| ws.div_rem_into(self, rhs, q, r));
+    }
+
+    /// `self += a * b`
+    pub fn add_mul(&mut self, a: &BitInt, b: &BitInt) {
+        with_local(|ws| add_sub(self, ws.product(&a.data, &b.data), a.sign ^ b.sign));
+    }
+
+    /// `self -= a * b`
+    pub fn sub_mul(&mut self, a: &BitInt, b: &BitInt) {
+        with_local(|ws| add_sub(self, ws.product(&a.data, &b.data), !(a.sign ^ b.sign)));
+    }
 }
 
+impl Limbs for BitInt {
+    fn limbs(&self) -> &[u64] {
+        &self.data
+    }
+
+    fn limbs_mut(&mut self) -> &mut Vec<u64> {
+        &mut self.data
+    }
+
+    fn sign(&self) -> bool {
+        self.sign
+    }
+
+    fn set_sign(&mut self, sign: bool) {
+        self.sign = sign;
+    }
+}
+
+impl ScratchInt for BitInt {}
+
 impl<T: I> From<T> for BitInt {
     fn from(value: T) -> Self {
         BitInt {
@@ -134,6 +194,18 @@ impl Abs for &BitInt {
     }
 }
 
+impl SignVal for BitInt {
+    fn sign_and_val(self) -> (bool, Vec<u64>) {
+        (self.sign, self.data)
+    }
+}
+
+impl SignVal for &BitInt {
+    fn sign_and_val(self) -> (bool, Vec<u64>) {
+        (self.sign, self.data.clone())
+    }
+}
+
 impl FromStr for BitInt {
     type Err = FromStrErr;
     fn from_str(str: &str) -> Result<Self, FromStrErr> {
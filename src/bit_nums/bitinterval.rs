@@ -0,0 +1,451 @@
+use super::bitfloat::{op_prec, with_inexact, BitFloat, RoundingMode};
+use super::constants::pi;
+use super::exp_log::EXP_MAX_BITS;
+use std::ops::*;
+
+const DOWN: RoundingMode = RoundingMode::TowardNegative;
+const UP: RoundingMode = RoundingMode::TowardPositive;
+
+/// Closed interval of real numbers between two [`BitFloat`] bounds, for
+/// verified numerics.
+///
+/// Every operation rounds its lower bound toward negative infinity and its
+/// upper bound toward positive infinity, so the result encloses every value
+/// the exact operation takes over its operands. The bounds may be infinite,
+/// giving unbounded intervals, and the empty interval stands for results
+/// with no values at all, such as the square root of a negative interval.
+///
+/// The operators round at the largest precision among the operands' bounds
+/// and the thread's default precision, like those of `BitFloat`, while the
+/// elementary functions take a precision in bits.
+#[derive(Debug, Clone)]
+pub struct BitInterval {
+    lo: BitFloat,
+    hi: BitFloat,
+}
+
+// the lesser of a and b, neither NaN
+fn lesser(a: BitFloat, b: BitFloat) -> BitFloat {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+// the greater of a and b, neither NaN
+fn greater(a: BitFloat, b: BitFloat) -> BitFloat {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+// The p-bit neighbours of f's correctly rounded result, which bracket the
+// exact value, or the result twice when it is exact
+fn enclose(p: usize, f: impl FnOnce() -> BitFloat) -> (BitFloat, BitFloat) {
+    let (y, inexact) = with_inexact(f);
+    if !inexact {
+        return (y.clone(), y);
+    }
+    (y.next_down(p), y.next_up(p))
+}
+
+// x * y rounded in the given mode, taking 0 * inf as 0
+fn mul_bound(x: &BitFloat, y: &BitFloat, p: usize, mode: RoundingMode) -> BitFloat {
+    if x.is_zero() || y.is_zero() {
+        return BitFloat::zero();
+    }
+    x.mul_round(y, p, mode)
+}
+
+// e^x rounded down to p bits, or up when up is set. Past exp's reach, e^x
+// lies beyond 2^(±2^EXP_MAX_BITS), which bounds it on the inner side.
+pub(crate) fn exp_bound(x: &BitFloat, p: usize, up: bool) -> BitFloat {
+    if x.is_finite() && !x.is_zero() && x.bit_exp() >= EXP_MAX_BITS {
+        return match (x.get_s(), up) {
+            (false, false) => BitFloat::pow2(1 << EXP_MAX_BITS),
+            (false, true) => BitFloat::inf(),
+            (true, false) => BitFloat::zero(),
+            (true, true) => BitFloat::pow2(-(1 << EXP_MAX_BITS)),
+        };
+    }
+    let (lo, hi) = enclose(p, || x.exp(p));
+    if up {
+        hi
+    } else {
+        lo
+    }
+}
+
+// Whether the finite [lo, hi] may hold a point π (r + 2^k n) for an integer
+// n. The bounds are divided by enclosures of π, so the answer errs toward
+// yes, and more so at low precision.
+fn may_hit(lo: &BitFloat, hi: &BitFloat, r: f64, k: i128, p: usize) -> bool {
+    let top = lo.exponent().max(hi.exponent()).unwrap_or(0).max(0);
+    let p = p.saturating_add(top as usize).saturating_add(64);
+    let pi = pi(p);
+    let (pi_lo, pi_hi) = (pi.next_down(p), pi.next_up(p));
+    let u_lo = lo.div_round(if lo.get_s() { &pi_lo } else { &pi_hi }, p, DOWN);
+    let u_hi = hi.div_round(if hi.get_s() { &pi_hi } else { &pi_lo }, p, UP);
+    let r = BitFloat::from(r);
+    let n_lo = u_lo.sub_round(&r, usize::MAX, DOWN).ldexp(-k);
+    let n_hi = u_hi.sub_round(&r, usize::MAX, UP).ldexp(-k);
+    n_lo.round_to_int(UP) <= n_hi.round_to_int(DOWN)
+}
+
+impl BitInterval {
+    /// The interval `[lo, hi]`, or `None` unless `lo <= hi` with `lo` below
+    /// +∞ and `hi` above -∞.
+    pub fn new(lo: BitFloat, hi: BitFloat) -> Option<Self> {
+        let bounded = lo.is_finite() || lo.get_s();
+        let bounded = bounded && (hi.is_finite() || !hi.get_s());
+        (bounded && lo <= hi).then_some(BitInterval { lo, hi })
+    }
+
+    /// The empty interval.
+    pub fn empty() -> Self {
+        BitInterval {
+            lo: BitFloat::nan(),
+            hi: BitFloat::nan(),
+        }
+    }
+
+    /// The whole real line, `[-∞, +∞]`.
+    pub fn entire() -> Self {
+        BitInterval {
+            lo: BitFloat::neg_inf(),
+            hi: BitFloat::inf(),
+        }
+    }
+
+    /// The lower bound, NaN for the empty interval.
+    pub fn lower(&self) -> &BitFloat {
+        &self.lo
+    }
+
+    /// The upper bound, NaN for the empty interval.
+    pub fn upper(&self) -> &BitFloat {
+        &self.hi
+    }
+
+    /// Whether `self` holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.lo.is_nan()
+    }
+
+    /// Whether `x` lies in `self`. Never true of NaN.
+    pub fn contains(&self, x: &BitFloat) -> bool {
+        !self.is_empty() && self.lo <= *x && *x <= self.hi
+    }
+
+    /// Whether every value of `other` lies in `self`, as the empty interval
+    /// does in any interval.
+    pub fn encloses(&self, other: &BitInterval) -> bool {
+        other.is_empty() || (!self.is_empty() && self.lo <= other.lo && other.hi <= self.hi)
+    }
+
+    /// The values common to `self` and `other`, which may be none.
+    pub fn intersect(&self, other: &BitInterval) -> BitInterval {
+        if self.is_empty() || other.is_empty() {
+            return BitInterval::empty();
+        }
+        let lo = greater(self.lo.clone(), other.lo.clone());
+        let hi = lesser(self.hi.clone(), other.hi.clone());
+        BitInterval::new(lo, hi).unwrap_or_else(BitInterval::empty)
+    }
+
+    /// The least interval enclosing both `self` and `other`.
+    pub fn hull(&self, other: &BitInterval) -> BitInterval {
+        if self.is_empty() {
+            return other.clone();
+        }
+        if other.is_empty() {
+            return self.clone();
+        }
+        BitInterval {
+            lo: lesser(self.lo.clone(), other.lo.clone()),
+            hi: greater(self.hi.clone(), other.hi.clone()),
+        }
+    }
+
+    /// `upper - lower` rounded up at the bounds' precision, infinite for
+    /// unbounded intervals and NaN for the empty one.
+    pub fn width(&self) -> BitFloat {
+        if self.is_empty() {
+            return BitFloat::nan();
+        }
+        self.hi.sub_round(&self.lo, op_prec(&self.lo, &self.hi), UP)
+    }
+
+    /// Square root over the part of `self` at or above zero, empty if there
+    /// is none.
+    pub fn sqrt(&self, prec: usize) -> BitInterval {
+        if self.is_empty() || self.hi < 0 {
+            return BitInterval::empty();
+        }
+        let lo = if self.lo <= 0 {
+            BitFloat::zero()
+        } else {
+            self.lo.sqrt_round(prec, DOWN)
+        };
+        BitInterval {
+            lo,
+            hi: self.hi.sqrt_round(prec, UP),
+        }
+    }
+
+    /// e^x over `self`, with bounds of `prec` bits.
+    pub fn exp(&self, prec: usize) -> BitInterval {
+        if self.is_empty() {
+            return BitInterval::empty();
+        }
+        BitInterval {
+            lo: exp_bound(&self.lo, prec, false),
+            hi: exp_bound(&self.hi, prec, true),
+        }
+    }
+
+    /// Natural logarithm over the part of `self` above zero, empty if there
+    /// is none.
+    pub fn ln(&self, prec: usize) -> BitInterval {
+        if self.is_empty() || self.hi <= 0 {
+            return BitInterval::empty();
+        }
+        let lo = if self.lo <= 0 {
+            BitFloat::neg_inf()
+        } else {
+            enclose(prec, || self.lo.ln(prec)).0
+        };
+        BitInterval {
+            lo,
+            hi: enclose(prec, || self.hi.ln(prec)).1,
+        }
+    }
+
+    // f over self for f = sin or cos, which peak at π (top + 2n) and bottom
+    // out at π (bottom + 2n)
+    fn wave(&self, prec: usize, f: impl Fn(&BitFloat) -> BitFloat, top: f64, bottom: f64) -> Self {
+        if self.is_empty() {
+            return BitInterval::empty();
+        }
+        let one = BitFloat::one();
+        if !self.lo.is_finite() || !self.hi.is_finite() {
+            return BitInterval { lo: -&one, hi: one };
+        }
+        let (a_lo, a_hi) = enclose(prec, || f(&self.lo));
+        let (b_lo, b_hi) = enclose(prec, || f(&self.hi));
+        let lo = if may_hit(&self.lo, &self.hi, bottom, 1, prec) {
+            -&one
+        } else {
+            greater(lesser(a_lo, b_lo), -&one)
+        };
+        let hi = if may_hit(&self.lo, &self.hi, top, 1, prec) {
+            one
+        } else {
+            lesser(greater(a_hi, b_hi), one)
+        };
+        BitInterval { lo, hi }
+    }
+
+    /// Sine over `self`, with bounds of `prec` bits.
+    pub fn sin(&self, prec: usize) -> BitInterval {
+        self.wave(prec, |x| x.sin(prec), 0.5, -0.5)
+    }
+
+    /// Cosine over `self`, with bounds of `prec` bits.
+    pub fn cos(&self, prec: usize) -> BitInterval {
+        self.wave(prec, |x| x.cos(prec), 0.0, 1.0)
+    }
+
+    /// Tangent over `self`, with bounds of `prec` bits. The whole real line
+    /// if `self` may hold a pole.
+    pub fn tan(&self, prec: usize) -> BitInterval {
+        if self.is_empty() {
+            return BitInterval::empty();
+        }
+        if !self.lo.is_finite() || !self.hi.is_finite() || may_hit(&self.lo, &self.hi, 0.5, 0, prec)
+        {
+            return BitInterval::entire();
+        }
+        BitInterval {
+            lo: enclose(prec, || self.lo.tan(prec)).0,
+            hi: enclose(prec, || self.hi.tan(prec)).1,
+        }
+    }
+}
+
+impl From<BitFloat> for BitInterval {
+    /// The interval holding `x` alone, or the empty one for NaN and the
+    /// infinities.
+    fn from(x: BitFloat) -> Self {
+        if !x.is_finite() {
+            return BitInterval::empty();
+        }
+        BitInterval {
+            lo: x.clone(),
+            hi: x,
+        }
+    }
+}
+
+impl PartialEq for BitInterval {
+    fn eq(&self, other: &Self) -> bool {
+        (self.is_empty() && other.is_empty()) || (self.lo == other.lo && self.hi == other.hi)
+    }
+}
+
+// precision of an operator result in bits
+fn iv_prec(lhs: &BitInterval, rhs: &BitInterval) -> usize {
+    op_prec(&lhs.lo, &lhs.hi).max(op_prec(&rhs.lo, &rhs.hi))
+}
+
+fn add_iv(lhs: &BitInterval, rhs: &BitInterval) -> BitInterval {
+    if lhs.is_empty() || rhs.is_empty() {
+        return BitInterval::empty();
+    }
+    let p = iv_prec(lhs, rhs);
+    BitInterval {
+        lo: lhs.lo.add_round(&rhs.lo, p, DOWN),
+        hi: lhs.hi.add_round(&rhs.hi, p, UP),
+    }
+}
+
+fn sub_iv(lhs: &BitInterval, rhs: &BitInterval) -> BitInterval {
+    if lhs.is_empty() || rhs.is_empty() {
+        return BitInterval::empty();
+    }
+    let p = iv_prec(lhs, rhs);
+    BitInterval {
+        lo: lhs.lo.sub_round(&rhs.hi, p, DOWN),
+        hi: lhs.hi.sub_round(&rhs.lo, p, UP),
+    }
+}
+
+fn mul_iv(lhs: &BitInterval, rhs: &BitInterval) -> BitInterval {
+    if lhs.is_empty() || rhs.is_empty() {
+        return BitInterval::empty();
+    }
+    let p = iv_prec(lhs, rhs);
+    let corners = [
+        (&lhs.lo, &rhs.lo),
+        (&lhs.lo, &rhs.hi),
+        (&lhs.hi, &rhs.lo),
+        (&lhs.hi, &rhs.hi),
+    ];
+    let lo = corners
+        .iter()
+        .map(|(x, y)| mul_bound(x, y, p, DOWN))
+        .reduce(lesser)
+        .unwrap();
+    let hi = corners
+        .iter()
+        .map(|(x, y)| mul_bound(x, y, p, UP))
+        .reduce(greater)
+        .unwrap();
+    BitInterval { lo, hi }
+}
+
+fn div_iv(lhs: &BitInterval, rhs: &BitInterval) -> BitInterval {
+    if lhs.is_empty() || rhs.is_empty() {
+        return BitInterval::empty();
+    }
+    let p = iv_prec(lhs, rhs);
+    let zero = BitFloat::zero();
+    if !rhs.contains(&zero) {
+        // a corner of two infinities is a limit of the corners beside it
+        let corners: Vec<_> = [
+            (&lhs.lo, &rhs.lo),
+            (&lhs.lo, &rhs.hi),
+            (&lhs.hi, &rhs.lo),
+            (&lhs.hi, &rhs.hi),
+        ]
+        .into_iter()
+        .filter(|(x, y)| x.is_finite() || y.is_finite())
+        .collect();
+        let lo = corners
+            .iter()
+            .map(|(x, y)| x.div_round(y, p, DOWN))
+            .reduce(lesser)
+            .unwrap();
+        let hi = corners
+            .iter()
+            .map(|(x, y)| x.div_round(y, p, UP))
+            .reduce(greater)
+            .unwrap();
+        return BitInterval { lo, hi };
+    }
+
+    let (b_lo, b_hi) = (rhs.lo.is_zero(), rhs.hi.is_zero());
+    if b_lo && b_hi {
+        return BitInterval::empty();
+    }
+    if lhs.contains(&zero) || !(b_lo || b_hi) {
+        return BitInterval::entire();
+    }
+    // a divisor touching zero from one side sends the quotient to one
+    // infinity
+    let pos = lhs.lo > 0;
+    let (lo, hi) = match (b_lo, pos) {
+        (true, true) => (lhs.lo.div_round(&rhs.hi, p, DOWN), BitFloat::inf()),
+        (true, false) => (BitFloat::neg_inf(), lhs.hi.div_round(&rhs.hi, p, UP)),
+        (false, true) => (BitFloat::neg_inf(), lhs.lo.div_round(&rhs.lo, p, UP)),
+        (false, false) => (lhs.hi.div_round(&rhs.lo, p, DOWN), BitFloat::inf()),
+    };
+    BitInterval { lo, hi }
+}
+
+macro_rules! impl_op_iv {
+    ($($tr:ident, $f:ident, $op:ident);*) => {$(
+        impl $tr for BitInterval {
+            type Output = BitInterval;
+            fn $f(self, rhs: Self) -> Self::Output {
+                $op(&self, &rhs)
+            }
+        }
+
+        impl $tr for &BitInterval {
+            type Output = BitInterval;
+            fn $f(self, rhs: Self) -> Self::Output {
+                $op(self, rhs)
+            }
+        }
+
+        impl $tr<&BitInterval> for BitInterval {
+            type Output = BitInterval;
+            fn $f(self, rhs: &BitInterval) -> Self::Output {
+                $op(&self, rhs)
+            }
+        }
+
+        impl $tr<BitInterval> for &BitInterval {
+            type Output = BitInterval;
+            fn $f(self, rhs: BitInterval) -> Self::Output {
+                $op(self, &rhs)
+            }
+        }
+    )*};
+}
+
+impl_op_iv!(Add, add, add_iv; Sub, sub, sub_iv; Mul, mul, mul_iv; Div, div, div_iv);
+
+impl Neg for BitInterval {
+    type Output = BitInterval;
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
+
+impl Neg for &BitInterval {
+    type Output = BitInterval;
+    fn neg(self) -> Self::Output {
+        if self.is_empty() {
+            return BitInterval::empty();
+        }
+        BitInterval {
+            lo: -&self.hi,
+            hi: -&self.lo,
+        }
+    }
+}
@@ -0,0 +1,273 @@
+//! Mathematical constants at any precision.
+//!
+//! Each constant is computed once to a working precision, with a bound on its
+//! error, and cached behind a lock. Requests that need more limbs grow the
+//! cache, so repeated calls at or below the largest precision seen so far
+//! only cost a rounding.
+
+use super::bitfloat::{add_sub_bf, div_bf, mul_bf, rel_err, ziv_loop, BitFloat};
+use super::exp_log::{bit_len, exp_kernel, ln_kernel};
+use super::roots::sqrt_bf;
+use std::sync::RwLock;
+
+// A constant cached at the largest working precision asked of it so far
+struct Cached {
+    compute: fn(usize) -> (BitFloat, i128),
+    slot: RwLock<Option<(usize, BitFloat, i128)>>,
+}
+
+impl Cached {
+    const fn new(compute: fn(usize) -> (BitFloat, i128)) -> Self {
+        Cached {
+            compute,
+            slot: RwLock::new(None),
+        }
+    }
+
+    // an approximation from at least w limbs of working precision, with
+    // |y - c| < 2^err
+    fn approx(&self, w: usize) -> (BitFloat, i128) {
+        if let Some((cw, y, err)) = &*self.slot.read().unwrap() {
+            if *cw >= w {
+                return (y.clone(), *err);
+            }
+        }
+
+        let mut slot = self.slot.write().unwrap();
+        let cw = match &*slot {
+            Some((cw, y, err)) if *cw >= w => return (y.clone(), *err),
+            Some((cw, ..)) => *cw,
+            None => 0,
+        };
+        // grow geometrically so a rising precision recomputes only a few times
+        let w = w.max(cw + cw / 2);
+        let (y, err) = (self.compute)(w);
+        *slot = Some((w, y.clone(), err));
+        (y, err)
+    }
+
+    // the constant rounded to the bits of w limbs, within an ulp
+    fn to_limbs(&self, w: usize) -> BitFloat {
+        self.approx(w + 1).0.to_prec(64 * w)
+    }
+
+    fn rounded(&self, prec: usize) -> BitFloat {
+        ziv_loop(prec, |w| self.approx(w))
+    }
+}
+
+static PI: Cached = Cached::new(pi_series);
+static E: Cached = Cached::new(e_series);
+static LN2: Cached = Cached::new(ln2_series);
+static LN10: Cached = Cached::new(ln10_series);
+static GAMMA: Cached = Cached::new(gamma_series);
+static CATALAN: Cached = Cached::new(catalan_series);
+static SQRT2: Cached = Cached::new(sqrt2_root);
+
+// π within an ulp at w limbs
+pub(crate) fn pi_approx(w: usize) -> BitFloat {
+    PI.to_limbs(w)
+}
+
+// ln 2 within an ulp at w limbs
+pub(crate) fn ln2_approx(w: usize) -> BitFloat {
+    LN2.to_limbs(w)
+}
+
+// ln 10 within an ulp at w limbs
+pub(crate) fn ln10_approx(w: usize) -> BitFloat {
+    LN10.to_limbs(w)
+}
+
+// Euler's γ within an ulp at w limbs
+pub(crate) fn euler_gamma_approx(w: usize) -> BitFloat {
+    GAMMA.to_limbs(w)
+}
+
+/// π, correctly rounded to `prec` bits.
+pub fn pi(prec: usize) -> BitFloat {
+    PI.rounded(prec)
+}
+
+/// Euler's number e, correctly rounded to `prec` bits.
+pub fn e(prec: usize) -> BitFloat {
+    E.rounded(prec)
+}
+
+/// ln 2, correctly rounded to `prec` bits.
+pub fn ln2(prec: usize) -> BitFloat {
+    LN2.rounded(prec)
+}
+
+/// ln 10, correctly rounded to `prec` bits.
+pub fn ln10(prec: usize) -> BitFloat {
+    LN10.rounded(prec)
+}
+
+/// The Euler–Mascheroni constant γ, correctly rounded to `prec` bits.
+pub fn euler_gamma(prec: usize) -> BitFloat {
+    GAMMA.rounded(prec)
+}
+
+/// Catalan's constant G, correctly rounded to `prec` bits.
+pub fn catalan(prec: usize) -> BitFloat {
+    CATALAN.rounded(prec)
+}
+
+/// √2, correctly rounded to `prec` bits.
+pub fn sqrt2(prec: usize) -> BitFloat {
+    SQRT2.rounded(prec)
+}
+
+// 640320^3 / 24
+const CHUD_C3_24: u128 = 10_939_058_860_032_000;
+
+// Chudnovsky terms [a, b) by binary splitting, as exact integers P, Q, T
+fn bin_split_pi(a: u128, b: u128) -> (BitFloat, BitFloat, BitFloat) {
+    if b - a == 1 {
+        if a == 0 {
+            return (BitFloat::one(), BitFloat::one(), BitFloat::from(13_591_409));
+        }
+        let p = -BitFloat::from((6 * a - 5) * (2 * a - 1) * (6 * a - 1));
+        let q = mul_bf(
+            &BitFloat::from(a * a * a),
+            &BitFloat::from(CHUD_C3_24),
+            usize::MAX,
+        );
+        let t = mul_bf(
+            &p,
+            &BitFloat::from(13_591_409 + 545_140_134 * a),
+            usize::MAX,
+        );
+        return (p, q, t);
+    }
+    let mid = (a + b) / 2;
+    let (pl, ql, tl) = bin_split_pi(a, mid);
+    let (pr, qr, tr) = bin_split_pi(mid, b);
+    let p = mul_bf(&pl, &pr, usize::MAX);
+    let q = mul_bf(&ql, &qr, usize::MAX);
+    let t = add_sub_bf(
+        &mul_bf(&tl, &qr, usize::MAX),
+        &mul_bf(&pl, &tr, usize::MAX),
+        false,
+        usize::MAX,
+    );
+    (p, q, t)
+}
+
+// π = 426880 √10005 Q / T, each term adding just over 47 bits
+fn pi_series(w: usize) -> (BitFloat, i128) {
+    let terms = (64 * w as u128 + 64) / 47 + 2;
+    let (_, q, t) = bin_split_pi(0, terms);
+    let c = mul_bf(
+        &sqrt_bf(&BitFloat::from(10_005), 64 * (w + 1)),
+        &BitFloat::from(426_880),
+        w + 1,
+    );
+    let y = div_bf(&mul_bf(&c, &q, w + 1), &t, w + 1);
+    let err = rel_err(&y, 4, w + 1);
+    (y, err)
+}
+
+fn e_series(w: usize) -> (BitFloat, i128) {
+    let (y, lost) = exp_kernel(&BitFloat::one(), w);
+    let err = rel_err(&y, lost, w);
+    (y, err)
+}
+
+// atanh(1 / m) = sum_j 1 / ((2j + 1) m^(2j + 1)) at w limbs
+fn atanh_inv(m: u64, w: usize) -> BitFloat {
+    let m2 = BitFloat::from(m * m);
+    let mut pw = div_bf(&BitFloat::one(), &BitFloat::from(m), w);
+    let mut sum = pw.clone();
+    let stop = sum.bit_exp() - 64 * w as i128 - 8;
+    for j in 1_u64.. {
+        pw = div_bf(&pw, &m2, w);
+        let term = div_bf(&pw, &BitFloat::from(2 * j + 1), w);
+        if term.bit_exp() < stop {
+            break;
+        }
+        sum = add_sub_bf(&sum, &term, false, w);
+    }
+    sum
+}
+
+// ln 2 = 2 atanh(1/3)
+fn ln2_series(w: usize) -> (BitFloat, i128) {
+    let y = atanh_inv(3, w + 1) << 1_usize;
+    let err = rel_err(&y, 4, w + 1);
+    (y, err)
+}
+
+// ln 10 = 3 ln 2 + 2 atanh(1/9)
+fn ln10_series(w: usize) -> (BitFloat, i128) {
+    let ln2 = mul_bf(&ln2_approx(w + 1), &BitFloat::from(3), w + 1);
+    let ln5_4 = atanh_inv(9, w + 1) << 1_usize;
+    let y = add_sub_bf(&ln2, &ln5_4, false, w + 1);
+    let err = rel_err(&y, 6, w + 1);
+    (y, err)
+}
+
+// Brent–McMillan: γ = U / V up to π e^(-4n), with V = sum_k (n^k / k!)^2
+// and U = sum_k (n^k / k!)^2 (H_k - ln n). The terms are past their peak and
+// negligible by k = 3.6 n.
+fn gamma_series(w: usize) -> (BitFloat, i128) {
+    let wg = w + 1;
+    let n = (64 * wg as u128 + 16) / 5 + 1;
+    let k_max = 18 * n / 5 + 8;
+    let n2 = BitFloat::from(n * n);
+    let (ln_n, lost) = ln_kernel(&BitFloat::from(n), wg);
+
+    let mut a = -ln_n;
+    let mut b = BitFloat::one();
+    let mut u = a.clone();
+    let mut v = BitFloat::one();
+    for k in 1..=k_max {
+        let kf = BitFloat::from(k);
+        b = div_bf(&mul_bf(&b, &n2, wg), &BitFloat::from(k * k), wg);
+        let an = div_bf(&mul_bf(&a, &n2, wg), &kf, wg);
+        a = div_bf(&add_sub_bf(&an, &b, false, wg), &kf, wg);
+        u = add_sub_bf(&u, &a, false, wg);
+        v = add_sub_bf(&v, &b, false, wg);
+    }
+    let y = div_bf(&u, &v, wg);
+    let err = rel_err(&y, 2 * bit_len(k_max) + lost + 12, wg);
+    (y, err)
+}
+
+// G = π/8 ln(2 + √3) + 3/8 sum_k 1 / ((2k + 1)^2 C(2k, k)), each term
+// adding two bits
+fn catalan_series(w: usize) -> (BitFloat, i128) {
+    let wg = w + 1;
+    let stop = -64 * wg as i128 - 8;
+    let mut r = BitFloat::one();
+    let mut sum = BitFloat::one();
+    let mut terms = 0;
+    for k in 1_u128.. {
+        // 1 / C(2k, k) = k / (2 (2k - 1)) / C(2k - 2, k - 1)
+        r = div_bf(
+            &mul_bf(&r, &BitFloat::from(k), wg),
+            &BitFloat::from(4 * k - 2),
+            wg,
+        );
+        let term = div_bf(&r, &BitFloat::from((2 * k + 1) * (2 * k + 1)), wg);
+        if term.bit_exp() < stop {
+            terms = k;
+            break;
+        }
+        sum = add_sub_bf(&sum, &term, false, wg);
+    }
+
+    let root3 = sqrt_bf(&BitFloat::from(3), 64 * wg);
+    let (l, lost) = ln_kernel(&add_sub_bf(&BitFloat::from(2), &root3, false, wg), wg);
+    let a = mul_bf(&pi_approx(wg), &l, wg);
+    let y = add_sub_bf(&a, &mul_bf(&sum, &BitFloat::from(3), wg), false, wg) >> 3_usize;
+    let err = rel_err(&y, bit_len(terms) + lost + 8, wg);
+    (y, err)
+}
+
+fn sqrt2_root(w: usize) -> (BitFloat, i128) {
+    let y = sqrt_bf(&BitFloat::from(2), 64 * w);
+    let err = rel_err(&y, 0, w);
+    (y, err)
+}
@@ -0,0 +1,166 @@
+use super::bitfloat::{add_sub_bf, div_bf, mul_bf, rel_err, underflow, ziv_loop, BitFloat};
+use super::constants::pi_approx;
+use super::exp_log::{bit_len, exp_kernel, EXP_MAX_BITS};
+use super::roots::sqrt_bf;
+use std::f64::consts::LOG2_E;
+
+// √π at w limbs
+fn sqrt_pi(w: usize) -> BitFloat {
+    sqrt_bf(&pi_approx(w + 1), 64 * w)
+}
+
+// e^(-x^2) at w limbs from x^2 at w limbs, and the bits of relative error
+// lost, counting those of the rounded x^2 scaled up by the exponent
+fn exp_neg_sqr(x2: &BitFloat, w: usize) -> (BitFloat, i128) {
+    let (e, lost) = exp_kernel(&-x2, w);
+    (e, lost + x2.bit_exp().max(0) + 1)
+}
+
+// limbs for x^2 that keep the fraction of e^(-x^2) to w limbs
+fn sqr_limbs(x: &BitFloat, w: usize) -> usize {
+    w + 1 + ((2 * x.bit_exp()).max(0) / 64) as usize
+}
+
+// erf(x) for finite x > 0 at w limbs as 2x/√π e^(-x^2) sum_n (2x^2)^n /
+// (1 3 ... (2n + 1)), whose terms are all positive. Returns the value and its
+// bits of relative error lost.
+fn erf_series(x: &BitFloat, w: usize) -> (BitFloat, i128) {
+    let x2 = mul_bf(x, x, sqr_limbs(x, w));
+    let r = &x2 << 1_usize;
+    let mut t = BitFloat::one();
+    let mut sum = BitFloat::one();
+    let mut n = 0_u64;
+    loop {
+        n += 1;
+        t = div_bf(&mul_bf(&t, &r, w), &BitFloat::from(2 * n + 1), w);
+        sum = add_sub_bf(&sum, &t, false, w);
+        // past 2x^2 <= n + 1 the terms at least halve, so the tail is below t
+        if t.bit_exp() < sum.bit_exp() - 64 * w as i128 - 8 && r <= n + 1 {
+            break;
+        }
+    }
+    let (e, lost) = exp_neg_sqr(&x2, w);
+    let pre = div_bf(&(x << 1_usize), &sqrt_pi(w), w);
+    let y = mul_bf(&mul_bf(&sum, &e, w), &pre, w);
+    (y, lost + 2 * bit_len(n as u128) + 4)
+}
+
+// erfc(x) = e^(-x^2) / (x √π) sum_n (-1)^n (1 3 ... (2n - 1)) / (2x^2)^n for
+// x > 0 at w limbs with its error exponent. The series envelopes erfc, so
+// the first term left out bounds the rest; None if the terms stop shrinking
+// before they pass below 2^(-64w - 8).
+fn erfc_asympt(x: &BitFloat, w: usize) -> Option<(BitFloat, i128)> {
+    let x2 = mul_bf(x, x, sqr_limbs(x, w));
+    let r = &x2 << 1_usize;
+    let stop = -64 * w as i128 - 8;
+    let mut t = BitFloat::one();
+    let mut sum = BitFloat::one();
+    let mut n = 0_u64;
+    loop {
+        n += 1;
+        if r < 2 * n {
+            return None;
+        }
+        t = div_bf(&mul_bf(&t, &BitFloat::from(2 * n - 1), w), &r, w);
+        if t.bit_exp() < stop {
+            break;
+        }
+        sum = add_sub_bf(&sum, &t, n % 2 == 1, w);
+    }
+    let (e, lost) = exp_neg_sqr(&x2, w);
+    let den = mul_bf(x, &sqrt_pi(w), w);
+    let y = div_bf(&mul_bf(&sum, &e, w), &den, w);
+    // the sum stays above 1/2, so t bounds its relative error
+    let err = rel_err(&y, lost + 2 * bit_len(n as u128) + 4, w).max(y.bit_exp() + stop + 2);
+    Some((y, err))
+}
+
+// x^2 log2(e) in f64, infinite when out of range: erfc(x) < 2^-that for x > 0
+fn sqr_log2e(x: &BitFloat) -> f64 {
+    f64::try_from(x).map_or(f64::INFINITY, |x| x * x * LOG2_E)
+}
+
+impl BitFloat {
+    /// Error function, correctly rounded to `prec` bits.
+    pub fn erf(&self, prec: usize) -> BitFloat {
+        if self.is_zero() || self.is_nan() {
+            return self.clone();
+        }
+        if self.is_inf() {
+            return if self.get_s() {
+                BitFloat::neg_one()
+            } else {
+                BitFloat::one()
+            };
+        }
+
+        let a = self.abs();
+        let bits = sqr_log2e(&a);
+        ziv_loop(prec, |w| {
+            let (mut y, err) = if bits > (64 * w + 8) as f64 {
+                // 1 - erf(x) = erfc(x) < e^(-x^2)
+                (BitFloat::one(), 1 - bits.min(1e30) as i128)
+            } else {
+                let (y, lost) = erf_series(&a, w);
+                let err = rel_err(&y, lost, w);
+                (y, err)
+            };
+            if self.get_s() {
+                y.neg_mut();
+            }
+            (y, err)
+        })
+    }
+
+    /// Complementary error function 1 - erf(self), correctly rounded to `prec`
+    /// bits without the cancellation of the subtraction.
+    pub fn erfc(&self, prec: usize) -> BitFloat {
+        if self.is_nan() {
+            return BitFloat::nan();
+        }
+        if self.is_zero() {
+            return BitFloat::one();
+        }
+        if self.is_inf() {
+            return if self.get_s() {
+                BitFloat::from(2)
+            } else {
+                BitFloat::zero()
+            };
+        }
+
+        let one = BitFloat::one();
+        let a = self.abs();
+        let bits = sqr_log2e(&a);
+        if self.get_s() {
+            // 1 + erf(|x|), with no cancellation
+            return ziv_loop(prec, |w| {
+                if bits > (64 * w + 8) as f64 {
+                    return (BitFloat::from(2), 2 - bits.min(1e30) as i128);
+                }
+                let (e, lost) = erf_series(&a, w + 1);
+                let y = add_sub_bf(&one, &e, false, w);
+                let err = rel_err(&y, lost + 2, w);
+                (y, err)
+            });
+        }
+        // e^(-x^2) is out of exp's reach
+        if 2 * self.bit_exp() >= EXP_MAX_BITS {
+            return underflow(false);
+        }
+
+        ziv_loop(prec, |w| {
+            if bits >= (64 * w + 16) as f64 {
+                if let Some(out) = erfc_asympt(self, w) {
+                    return out;
+                }
+            }
+            // 1 - erf(x) cancels about x^2 log2(e) bits, made up in advance
+            let wp = w + 1 + (bits / 64.0) as usize;
+            let (e, lost) = erf_series(self, wp);
+            let y = add_sub_bf(&one, &e, true, wp);
+            let err = rel_err(&e, lost, wp) + 1;
+            (y, err)
+        })
+    }
+}
@@ -0,0 +1,527 @@
+use super::bitfloat::{
+    add_sub_bf, div_bf, div_bf_mode, invalid, mul_bf, overflow, raise, rel_err, round_bf,
+    shl_shr_bf, underflow, ziv_loop, BitFloat, Flags, RoundingMode::NearestEven,
+};
+use super::constants::{ln10_approx, ln2_approx};
+use super::traits::Rounding;
+use crate::utils::mul::powi_vec;
+
+// |x| at or above 2^EXP_MAX_BITS overflows exp, keeping the reduction
+// multiple of ln 2 inside an i128
+pub(crate) const EXP_MAX_BITS: i128 = 125;
+
+pub(crate) fn bit_len(n: u128) -> i128 {
+    (u128::BITS - n.leading_zeros()) as i128
+}
+
+// (l, k): halvings of the argument and series terms for expm1 at w limbs, for
+// |x| < 1. The truncated tail |r|^k / (k + 1)! stays below 2^(-64w - 16).
+pub(crate) fn exp_param(w: usize) -> (usize, usize) {
+    let bits = (64 * w + 16) as f64;
+    let l = (bits.sqrt() / 2.0).ceil() as usize;
+    let mut k = 1;
+    let mut lg_fact = 1.0;
+    while (k * l) as f64 + lg_fact < bits {
+        k += 1;
+        lg_fact += ((k + 1) as f64).log2();
+    }
+    (l, k)
+}
+
+// Binary splitting of sum_{n = a + 1}^{b} r^(n - a) a! / n! = T / Q, with
+// P = r^(b - a) and Q = (a + 1)...b exact.
+fn bin_split_exp(a: usize, b: usize, r: &BitFloat, w: usize) -> (BitFloat, BitFloat, BitFloat) {
+    if b - a == 1 {
+        return (r.clone(), BitFloat::from(b), r.clone());
+    }
+    let mid = (a + b) / 2;
+    let (pl, ql, tl) = bin_split_exp(a, mid, r, w);
+    let (pr, qr, tr) = bin_split_exp(mid, b, r, w);
+    let p = mul_bf(&pl, &pr, w);
+    let q = mul_bf(&ql, &qr, usize::MAX);
+    let t = add_sub_bf(&mul_bf(&tl, &qr, w), &mul_bf(&pl, &tr, w), false, w);
+    (p, q, t)
+}
+
+// expm1(x) for |x| < 1 and the bits of relative error lost at w limbs
+pub(crate) fn expm1_kernel(x: &BitFloat, w: usize) -> (BitFloat, i128) {
+    let (l, k) = exp_param(w);
+    let r = x >> l;
+    let (_, q, t) = bin_split_exp(0, k, &r, w);
+    let mut u = div_bf(&t, &q, w);
+
+    // expm1(2r) = u (u + 2)
+    let two = BitFloat::from(2);
+    for _ in 0..l {
+        let u2 = add_sub_bf(&u, &two, false, w);
+        u = mul_bf(&u, &u2, w);
+    }
+    (u, 2 * bit_len(k as u128) + bit_len(l as u128) + 8)
+}
+
+// exp(x) for |x| < 2^EXP_MAX_BITS, as 2^n exp(r) with r = x - n ln 2
+pub(crate) fn exp_kernel(x: &BitFloat, w: usize) -> (BitFloat, i128) {
+    let bx = x.bit_exp().max(0);
+    let wr = w + 1 + ((bx + 65) / 64) as usize;
+    let ln2 = ln2_approx(wr);
+    let n = div_bf(x, &ln2, wr).round();
+    let (r, n) = if n.is_zero() {
+        (x.to_prec(64 * (w + 1)), 0)
+    } else {
+        let nl = mul_bf(&n, &ln2, usize::MAX);
+        let r = add_sub_bf(x, &nl, true, w + 1);
+        (r, i128::try_from(&n).unwrap())
+    };
+
+    let (mut y, lost) = if r.is_zero() {
+        (BitFloat::one(), 0)
+    } else {
+        let (u, lost) = expm1_kernel(&r, w);
+        (add_sub_bf(&u, &BitFloat::one(), false, w), lost)
+    };
+    shl_shr_bf(&mut y, n);
+    (y, lost + 3)
+}
+
+// atanh(z) = sum_j z^(2j + 1) / (2j + 1) for |z| <= 1/5 and the number of
+// terms summed
+pub(crate) fn atanh_series(z: &BitFloat, w: usize) -> (BitFloat, usize) {
+    let z2 = mul_bf(z, z, w);
+    let mut pw = z.clone();
+    let mut sum = z.clone();
+    let stop = sum.bit_exp() - 64 * w as i128 - 8;
+    let mut j = 1;
+    loop {
+        pw = mul_bf(&pw, &z2, w);
+        let term = div_bf(&pw, &BitFloat::from(2 * j + 1), w);
+        if term.bit_exp() < stop {
+            return (sum, j);
+        }
+        sum = add_sub_bf(&sum, &term, false, w);
+        j += 1;
+    }
+}
+
+// ln(x) for finite x > 0, x != 1: ln(m) + k ln 2 with m = x 2^-k in [3/4, 3/2)
+pub(crate) fn ln_kernel(x: &BitFloat, w: usize) -> (BitFloat, i128) {
+    let mut k = x.bit_exp();
+    let mut m = x.clone();
+    shl_shr_bf(&mut m, -k);
+    if m >= 1.5 {
+        k += 1;
+        shl_shr_bf(&mut m, -1);
+    }
+
+    let one = BitFloat::one();
+    let num = add_sub_bf(&m, &one, true, usize::MAX);
+    let (mut y, terms) = if num.is_zero() {
+        (BitFloat::zero(), 0)
+    } else {
+        let den = add_sub_bf(&m, &one, false, usize::MAX);
+        let z = div_bf(&num, &den, w);
+        let (s, terms) = atanh_series(&z, w);
+        (s << 1_usize, terms)
+    };
+
+    if k != 0 {
+        let wl = w + 1 + ((bit_len(k.unsigned_abs()) + 63) / 64) as usize;
+        let kl = mul_bf(&BitFloat::from(k), &ln2_approx(wl), wl);
+        y = add_sub_bf(&kl, &y, false, w + 1);
+    }
+    (y, bit_len(terms as u128) + 10)
+}
+
+// ln(1 + x) for |x| < 1/4, as 2 atanh(x / (2 + x))
+pub(crate) fn ln1p_kernel(x: &BitFloat, w: usize) -> (BitFloat, i128) {
+    let den = add_sub_bf(&BitFloat::from(2), x, false, w + 1);
+    let z = div_bf(x, &den, w);
+    let (s, terms) = atanh_series(&z, w);
+    (s << 1_usize, bit_len(terms as u128) + 8)
+}
+
+// NaN for a NaN argument, or for one below zero raising the invalid flag
+fn log_domain(x: &BitFloat) -> Option<BitFloat> {
+    if x.is_nan() {
+        return Some(BitFloat::nan());
+    }
+    (x.get_s() && !x.is_zero()).then(invalid)
+}
+
+// k if x = 10^k for an integer k >= 0
+fn log10_exact(x: &BitFloat) -> Option<u64> {
+    if !x.is_integer() {
+        return None;
+    }
+    let m = x.get_m();
+    let k = 64 * x.lo() as u64 + m[0].trailing_zeros() as u64;
+    let mut odd = x.clone();
+    shl_shr_bf(&mut odd, -(k as i128));
+
+    // 5^k has floor(k log2(5)) + 1 bits
+    if odd.bit_exp() != (k as f64 * 5_f64.log2()).floor() as i128 {
+        return None;
+    }
+    let pow5 = powi_vec(&[5], k as usize);
+    (odd == BitFloat::make(false, pow5.len() as i128 - 1, &pow5)).then_some(k)
+}
+
+impl BitFloat {
+    /// e^self, correctly rounded to `prec` bits.
+    pub fn exp(&self, prec: usize) -> BitFloat {
+        if self.is_nan() {
+            return BitFloat::nan();
+        }
+        if self.is_zero() {
+            return BitFloat::one();
+        }
+        if self.is_inf() {
+            return if self.get_s() {
+                BitFloat::zero()
+            } else {
+                BitFloat::inf()
+            };
+        }
+        if self.bit_exp() >= EXP_MAX_BITS {
+            return if self.get_s() {
+                underflow(false)
+            } else {
+                overflow(false)
+            };
+        }
+
+        let bx = self.bit_exp();
+        ziv_loop(prec, |w| {
+            if bx < -64 * w as i128 {
+                return (BitFloat::one(), bx + 2);
+            }
+            let (y, lost) = exp_kernel(self, w);
+            let err = rel_err(&y, lost, w);
+            (y, err)
+        })
+    }
+
+    /// 2^self, correctly rounded to `prec` bits.
+    pub fn exp2(&self, prec: usize) -> BitFloat {
+        if self.is_nan() {
+            return BitFloat::nan();
+        }
+        if self.is_zero() {
+            return BitFloat::one();
+        }
+        if self.is_inf() {
+            return if self.get_s() {
+                BitFloat::zero()
+            } else {
+                BitFloat::inf()
+            };
+        }
+        if self.bit_exp() >= EXP_MAX_BITS {
+            return if self.get_s() {
+                underflow(false)
+            } else {
+                overflow(false)
+            };
+        }
+
+        // 2^self = 2^n 2^f with n the nearest integer and |f| <= 1/2
+        let n = self.round();
+        let n_i = i128::try_from(&n).unwrap();
+        if self.is_integer() {
+            return BitFloat::pow2(n_i);
+        }
+        let f = add_sub_bf(self, &n, true, usize::MAX);
+        let bf = f.bit_exp();
+        ziv_loop(prec, |w| {
+            if bf < -64 * w as i128 - 1 {
+                return (BitFloat::pow2(n_i), n_i + bf + 1);
+            }
+            let t = mul_bf(&f, &ln2_approx(w + 1), w + 1);
+            let (mut y, lost) = exp_kernel(&t, w);
+            shl_shr_bf(&mut y, n_i);
+            let err = rel_err(&y, lost + 3, w);
+            (y, err)
+        })
+    }
+
+    /// e^self - 1, correctly rounded to `prec` bits.
+    pub fn expm1(&self, prec: usize) -> BitFloat {
+        if self.is_zero() || self.is_nan() {
+            return self.clone();
+        }
+        if self.is_inf() {
+            return if self.get_s() {
+                BitFloat::neg_one()
+            } else {
+                BitFloat::inf()
+            };
+        }
+        if self.bit_exp() >= EXP_MAX_BITS {
+            return if self.get_s() {
+                raise(Flags::INEXACT);
+                BitFloat::neg_one()
+            } else {
+                overflow(false)
+            };
+        }
+
+        let bx = self.bit_exp();
+        ziv_loop(prec, |w| {
+            // |expm1(x) - x| <= x^2 for |x| < 1
+            if bx < -64 * w as i128 {
+                return (self.clone(), 2 * bx + 2);
+            }
+            if bx < -1 {
+                let (y, lost) = expm1_kernel(self, w);
+                let err = rel_err(&y, lost + 1, w);
+                return (y, err);
+            }
+            // |x| >= 1/2 keeps |expm1(x)| above a third of e^x
+            let (e, lost) = exp_kernel(self, w + 1);
+            let y = add_sub_bf(&e, &BitFloat::one(), true, w + 1);
+            let err = rel_err(&e, lost, w + 1).max(rel_err(&y, 1, w + 1)) + 1;
+            (y, err)
+        })
+    }
+
+    /// Natural logarithm, correctly rounded to `prec` bits. NaN for negative
+    /// arguments.
+    pub fn ln(&self, prec: usize) -> BitFloat {
+        if let Some(nan) = log_domain(self) {
+            return nan;
+        }
+        if self.is_zero() {
+            raise(Flags::DIV_BY_ZERO);
+            return BitFloat::neg_inf();
+        }
+        if self.is_inf() {
+            return BitFloat::inf();
+        }
+        if *self == 1 {
+            return BitFloat::zero();
+        }
+
+        ziv_loop(prec, |w| {
+            let (y, lost) = ln_kernel(self, w);
+            let err = rel_err(&y, lost, w);
+            (y, err)
+        })
+    }
+
+    /// ln(1 + self), correctly rounded to `prec` bits. NaN if `self < -1`.
+    pub fn ln1p(&self, prec: usize) -> BitFloat {
+        if self.is_zero() || self.is_nan() {
+            return self.clone();
+        }
+        if *self < -1 {
+            return invalid();
+        }
+        if *self == -1 {
+            raise(Flags::DIV_BY_ZERO);
+            return BitFloat::neg_inf();
+        }
+        if self.is_inf() {
+            return BitFloat::inf();
+        }
+
+        let bx = self.bit_exp();
+        ziv_loop(prec, |w| {
+            // |ln1p(x) - x| <= x^2 for |x| < 1/2
+            if bx < -64 * w as i128 {
+                return (self.clone(), 2 * bx + 2);
+            }
+            if bx < -2 {
+                let (y, lost) = ln1p_kernel(self, w);
+                let err = rel_err(&y, lost, w);
+                return (y, err);
+            }
+            // |ln(1 + x)| > 1/5 here, so rounding 1 + x costs under 3 bits
+            let x1 = add_sub_bf(self, &BitFloat::one(), false, w + 2);
+            let (y, lost) = ln_kernel(&x1, w);
+            let err = rel_err(&y, lost + 3, w);
+            (y, err)
+        })
+    }
+
+    /// Base 2 logarithm, correctly rounded to `prec` bits. NaN for negative
+    /// arguments.
+    pub fn log2(&self, prec: usize) -> BitFloat {
+        if let Some(nan) = log_domain(self) {
+            return nan;
+        }
+        if self.is_zero() || self.is_inf() {
+            return self.ln(prec);
+        }
+        if let Some(k) = self.pow2_exp() {
+            return BitFloat::from(k).to_prec(prec);
+        }
+
+        ziv_loop(prec, |w| {
+            let (l, lost) = ln_kernel(self, w);
+            let y = div_bf(&l, &ln2_approx(w), w);
+            let err = rel_err(&y, lost + 3, w);
+            (y, err)
+        })
+    }
+
+    /// Base 10 logarithm, correctly rounded to `prec` bits. NaN for negative
+    /// arguments.
+    pub fn log10(&self, prec: usize) -> BitFloat {
+        if let Some(nan) = log_domain(self) {
+            return nan;
+        }
+        if self.is_zero() || self.is_inf() {
+            return self.ln(prec);
+        }
+        if let Some(k) = log10_exact(self) {
+            return BitFloat::from(k).to_prec(prec);
+        }
+
+        ziv_loop(prec, |w| {
+            let (l, lost) = ln_kernel(self, w);
+            let y = div_bf(&l, &ln10_approx(w), w);
+            let err = rel_err(&y, lost + 3, w);
+            (y, err)
+        })
+    }
+
+    /// Logarithm in `base`, correctly rounded to `prec` bits. NaN for
+    /// negative arguments or base.
+    pub fn log(&self, base: &BitFloat, prec: usize) -> BitFloat {
+        if self.is_nan() || base.is_nan() {
+            return BitFloat::nan();
+        }
+        if let Some(nan) = log_domain(self).or_else(|| log_domain(base)) {
+            return nan;
+        }
+        let one = BitFloat::one();
+        if *base == one
+            || !self.is_finite()
+            || !base.is_finite()
+            || self.is_zero()
+            || base.is_zero()
+        {
+            return div_bf_mode(&self.ln(prec), &base.ln(prec), prec.max(1), NearestEven);
+        }
+        if *self == one {
+            return BitFloat::zero();
+        }
+        if self == base {
+            return one;
+        }
+        if let (Some(k), Some(kb)) = (self.pow2_exp(), base.pow2_exp()) {
+            return div_bf_mode(
+                &BitFloat::from(k),
+                &BitFloat::from(kb),
+                prec.max(1),
+                NearestEven,
+            );
+        }
+
+        ziv_loop(prec, |w| {
+            let (l, lost) = ln_kernel(self, w);
+            let (lb, lost_b) = ln_kernel(base, w);
+            let y = div_bf(&l, &lb, w);
+            let err = rel_err(&y, lost.max(lost_b) + 3, w);
+            (y, err)
+        })
+    }
+
+    /// self^rhs, correctly rounded to `prec` bits. NaN for a negative base
+    /// with a non-integer exponent.
+    pub fn powf(&self, rhs: &BitFloat, prec: usize) -> BitFloat {
+        let one = BitFloat::one();
+        if rhs.is_zero() || *self == one {
+            return one;
+        }
+        if self.is_nan() || rhs.is_nan() {
+            return BitFloat::nan();
+        }
+        let big = |pos: bool, neg: bool| {
+            let mut out = if pos {
+                BitFloat::inf()
+            } else {
+                BitFloat::zero()
+            };
+            if neg {
+                out.neg_mut();
+            }
+            out
+        };
+        // a finite result past any exponent we can reach
+        let out_of_range = |pos: bool, neg: bool| {
+            if pos {
+                overflow(neg)
+            } else {
+                underflow(neg)
+            }
+        };
+        if rhs.is_inf() {
+            let ord = self.abs().partial_cmp(&one).unwrap();
+            if ord.is_eq() {
+                return one;
+            }
+            return big(ord.is_gt() != rhs.get_s(), false);
+        }
+
+        let neg = self.get_s() && rhs.is_odd();
+        if self.is_zero() || self.is_inf() {
+            if self.is_zero() && rhs.get_s() {
+                raise(Flags::DIV_BY_ZERO);
+            }
+            return big(self.is_inf() != rhs.get_s(), neg);
+        }
+        let y_int = rhs.is_integer();
+        if self.get_s() && !y_int {
+            return invalid();
+        }
+
+        // small integral powers are computed exactly
+        if y_int && rhs.bit_exp() < 64 {
+            let n = u64::try_from(&rhs.abs()).unwrap() as usize;
+            if n.saturating_mul(self.prec()) <= 4 * prec + 1024 {
+                let m = powi_vec(self.get_m(), n);
+                let pw = round_bf(neg, m, self.lo() * n as i128, false, usize::MAX);
+                return if rhs.get_s() {
+                    div_bf_mode(&one, &pw, prec.max(1), NearestEven)
+                } else {
+                    pw.to_prec(prec.max(1))
+                };
+            }
+        }
+
+        // (2^k)^y is exact when k y is an integer
+        if let Some(k) = self.pow2_exp() {
+            let ky = mul_bf(&BitFloat::from(k), rhs, usize::MAX);
+            if ky.is_integer() {
+                if !ky.is_zero() && ky.bit_exp() >= EXP_MAX_BITS {
+                    return out_of_range(!ky.get_s(), neg);
+                }
+                let mut out = BitFloat::pow2(i128::try_from(&ky).unwrap());
+                if neg {
+                    out.neg_mut();
+                }
+                return out;
+            }
+        }
+
+        // self^y = e^(y ln|self|)
+        let x = self.abs();
+        let (l, _) = ln_kernel(&x, 2);
+        let t = mul_bf(rhs, &l, 2);
+        if t.bit_exp() >= EXP_MAX_BITS {
+            return out_of_range(!t.get_s(), neg);
+        }
+        let tb = t.bit_exp().max(0) as usize;
+        ziv_loop(prec, |w| {
+            let wl = w + 2 + tb / 64;
+            let (l, lost_l) = ln_kernel(&x, wl);
+            let t = mul_bf(rhs, &l, wl);
+            let (mut y, lost) = exp_kernel(&t, w);
+            if neg {
+                y.neg_mut();
+            }
+            let err = rel_err(&y, lost.max(lost_l) + 4, w);
+            (y, err)
+        })
+    }
+}
@@ -0,0 +1,97 @@
+use super::bitfloat::{add_sub_bf_mode, invalid, mul_bf, round_bf_mode, BitFloat, RoundingMode};
+use crate::utils::utils::{acc, twos_comp};
+
+// Sum of xs rounded once to p bits. The terms are added exactly into a two's
+// complement buffer whose limb i weighs 2^(64 (lo + i)), spanning every bit
+// from the top of the largest term to the bottom of the lowest. A spare limb
+// on top holds the sign and the carries of up to 2^63 terms.
+pub(crate) fn exact_sum(xs: &[BitFloat], p: usize, mode: RoundingMode) -> BitFloat {
+    if xs.iter().any(BitFloat::is_nan) {
+        return BitFloat::nan();
+    }
+    let pos_inf = xs.iter().any(|x| x.is_inf() && !x.get_s());
+    let neg_inf = xs.iter().any(|x| x.is_inf() && x.get_s());
+    match (pos_inf, neg_inf) {
+        (true, true) => return invalid(),
+        (true, false) => return BitFloat::inf(),
+        (false, true) => return BitFloat::neg_inf(),
+        (false, false) => {}
+    }
+
+    // an exact zero sum is -0 only from negative zeros alone, or when
+    // rounding toward negative infinity
+    let neg_zero = mode == RoundingMode::TowardNegative;
+    let terms: Vec<&BitFloat> = xs.iter().filter(|x| !x.is_zero()).collect();
+    if terms.is_empty() {
+        let s = if neg_zero {
+            xs.iter().any(BitFloat::get_s)
+        } else {
+            !xs.is_empty() && xs.iter().all(BitFloat::get_s)
+        };
+        return BitFloat::make(s, 0, &[]);
+    }
+
+    let lo = terms.iter().map(|x| x.lo()).min().unwrap();
+    let hi = terms.iter().map(|x| x.get_e()).max().unwrap();
+    let mut buf = vec![0; (hi - lo) as usize + 2];
+    for x in terms {
+        acc(
+            &mut buf[(x.lo() - lo) as usize..],
+            x.get_m(),
+            x.get_s() as u8,
+        );
+    }
+    let s = buf[buf.len() - 1] >> 63 == 1;
+    if s {
+        twos_comp(&mut buf);
+    }
+
+    let mut out = round_bf_mode(s, buf, lo, false, p, mode);
+    if out.is_zero() && out.get_s() != neg_zero {
+        out.neg_mut();
+    }
+    out
+}
+
+impl BitFloat {
+    /// self * a + b with a single rounding, correctly rounded to `prec` bits.
+    pub fn mul_add(&self, a: &BitFloat, b: &BitFloat, prec: usize) -> BitFloat {
+        self.mul_add_round(a, b, prec, RoundingMode::NearestEven)
+    }
+
+    /// self * a + b with a single rounding to `prec` bits in the given mode.
+    pub fn mul_add_round(
+        &self,
+        a: &BitFloat,
+        b: &BitFloat,
+        prec: usize,
+        mode: RoundingMode,
+    ) -> BitFloat {
+        // the full product is exact, leaving the sum as the only rounding
+        let prod = mul_bf(self, a, usize::MAX);
+        add_sub_bf_mode(&prod, b, false, prec.max(1), mode)
+    }
+
+    /// Sum of `xs`, correctly rounded to `prec` bits however much the terms
+    /// cancel. The terms are accumulated exactly, so memory grows with the
+    /// spread of their exponents.
+    pub fn sum(xs: &[BitFloat], prec: usize) -> BitFloat {
+        exact_sum(xs, prec.max(1), RoundingMode::NearestEven)
+    }
+
+    /// Dot product of `a` and `b`, correctly rounded to `prec` bits from the
+    /// exact products. Panics if the slices differ in length.
+    pub fn dot(a: &[BitFloat], b: &[BitFloat], prec: usize) -> BitFloat {
+        assert_eq!(
+            a.len(),
+            b.len(),
+            "dot product of slices of different lengths"
+        );
+        let prods: Vec<BitFloat> = a
+            .iter()
+            .zip(b)
+            .map(|(x, y)| mul_bf(x, y, usize::MAX))
+            .collect();
+        exact_sum(&prods, prec.max(1), RoundingMode::NearestEven)
+    }
+}
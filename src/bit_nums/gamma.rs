@@ -0,0 +1,434 @@
+use super::bitfloat::{
+    add_sub_bf, div_bf, div_bf_mode, invalid, mul_bf, overflow, raise, rel_err, underflow,
+    ziv_loop, BitFloat, Flags, RoundingMode::NearestEven,
+};
+use super::constants::{ln2_approx, pi_approx};
+use super::exp_log::{bit_len, exp_kernel, ln_kernel, EXP_MAX_BITS};
+use super::trig::sin_cos_pi_kernel;
+use std::sync::RwLock;
+
+// Tangent numbers T_1, T_2, ... as exact integers, grown on demand. They give
+// the Bernoulli numbers as B_2k = (-1)^(k-1) 2k T_k / (4^k (4^k - 1)).
+static TANGENT: RwLock<Vec<BitFloat>> = RwLock::new(Vec::new());
+
+// T_1 ..= T_n by the in-place recurrence of Brent and Harvey
+fn tangent_numbers(n: usize) -> Vec<BitFloat> {
+    let mut t = vec![BitFloat::one(); n];
+    for k in 1..n {
+        t[k] = mul_bf(&t[k - 1], &BitFloat::from(k), usize::MAX);
+    }
+    for k in 1..n {
+        for j in k..n {
+            let a = mul_bf(&t[j - 1], &BitFloat::from(j - k), usize::MAX);
+            let b = mul_bf(&t[j], &BitFloat::from(j - k + 2), usize::MAX);
+            t[j] = add_sub_bf(&a, &b, false, usize::MAX);
+        }
+    }
+    t
+}
+
+// runs f on at least the first n tangent numbers
+fn with_tangents<R>(n: usize, f: impl FnOnce(&[BitFloat]) -> R) -> R {
+    {
+        let t = TANGENT.read().unwrap();
+        if t.len() >= n {
+            return f(&t);
+        }
+    }
+    let mut t = TANGENT.write().unwrap();
+    if t.len() < n {
+        // grow geometrically so a rising precision recomputes only a few times
+        *t = tangent_numbers(n.max(t.len() + t.len() / 2));
+    }
+    f(&t)
+}
+
+// The asymptotic tail of ln Γ(z), sum_k B_2k / (2k (2k - 1) z^(2k - 1)), or
+// with digamma that of ψ(z), sum_k B_2k / (2k z^2k), for z >= 16 at w limbs.
+// Both series envelope their value, so the first term below 2^(-64w - 8)
+// bounds what is left out. Returns the sum and its error exponent.
+fn bernoulli_tail(z: &BitFloat, w: usize, digamma: bool) -> (BitFloat, i128) {
+    let stop = -64 * w as i128 - 8;
+    let z2 = mul_bf(z, z, w);
+    let mut zp = if digamma { z2.clone() } else { z.clone() };
+    // at z >= 32w the terms pass below 2^stop by k = 8w
+    let kmax = 8 * w + 8;
+    with_tangents(kmax, |t| {
+        let mut sum = BitFloat::zero();
+        let mut k = 1;
+        while k <= kmax {
+            // T_k / (4^k (4^k - 1) z^(2k - 1) (2k - 1)), or over z^2k
+            let q = add_sub_bf(
+                &BitFloat::pow2(2 * k as i128),
+                &BitFloat::one(),
+                true,
+                usize::MAX,
+            );
+            let mut den = mul_bf(&q, &zp, w);
+            if !digamma {
+                den = mul_bf(&den, &BitFloat::from(2 * k - 1), w);
+            }
+            let mut term = div_bf(&t[k - 1], &den, w);
+            term = &term >> (2 * k);
+            if term.bit_exp() < stop {
+                break;
+            }
+            if k % 2 == 0 {
+                term.neg_mut();
+            }
+            sum = add_sub_bf(&sum, &term, false, w);
+            zp = mul_bf(&zp, &z2, w);
+            k += 1;
+        }
+        // each term is within 2k + 4 roundings, and the first is below 2^-7
+        let err = bit_len(2 * k as u128 + 4) - 6 - 64 * (w as i128 - 1);
+        (sum, err.max(stop) + 1)
+    })
+}
+
+// (z, n) with z = x + n >= 32w + 16 rounded to w limbs, for finite x > 0
+fn shift_up(x: &BitFloat, w: usize) -> (BitFloat, u64) {
+    let z0 = 32 * w as u64 + 16;
+    let n = if *x >= z0 {
+        0
+    } else {
+        z0 - f64::try_from(x).unwrap() as u64
+    };
+    (add_sub_bf(x, &BitFloat::from(n), false, w), n)
+}
+
+// ln(2π) / 2 at w limbs
+fn half_ln_2pi(w: usize) -> BitFloat {
+    let (lpi, _) = ln_kernel(&pi_approx(w + 1), w);
+    &add_sub_bf(&lpi, &ln2_approx(w), false, w) >> 1_usize
+}
+
+// ln Γ(x) for finite x > 0 at w limbs and its error exponent. Small x is first
+// shifted up to z = x + n, where Stirling's series reaches w limbs, using
+// ln Γ(x) = ln Γ(z) - ln(x (x + 1) ... (x + n - 1)).
+pub(crate) fn lgamma_pos(x: &BitFloat, w: usize) -> (BitFloat, i128) {
+    let wp = w + 1;
+    let one = BitFloat::one();
+    let (z, n) = shift_up(x, wp);
+
+    // (z - 1/2) ln z - z + ln(2π)/2 + tail, led by the product which also
+    // absorbs the rounding of z
+    let (lz, lost) = ln_kernel(&z, wp);
+    let zh = add_sub_bf(&z, &(&one >> 1_usize), true, wp);
+    let lead = mul_bf(&zh, &lz, wp);
+    let mut y = add_sub_bf(&lead, &z, true, wp);
+    y = add_sub_bf(&y, &half_ln_2pi(wp), false, wp);
+    let (tail, err_tail) = bernoulli_tail(&z, wp, false);
+    y = add_sub_bf(&y, &tail, false, wp);
+    let mut err = rel_err(&lead, lost + 4, wp).max(err_tail);
+
+    if n > 0 {
+        let mut prod = x.clone();
+        for i in 1..n {
+            let xi = add_sub_bf(x, &BitFloat::from(i), false, wp);
+            prod = mul_bf(&prod, &xi, wp);
+        }
+        let (lp, lost_p) = ln_kernel(&prod, wp);
+        y = add_sub_bf(&y, &lp, true, wp);
+        // the 2n roundings of the product err by about 2n ulps in its ln
+        let err_p = rel_err(&lp, lost_p, wp).max(bit_len(n as u128) + 2 - 64 * w as i128);
+        err = err.max(err_p);
+    }
+    (y, err + 2)
+}
+
+// ln|Γ(x)|, its error exponent and whether Γ(x) < 0, at w limbs for finite x
+// other than zero and the negative integers. Below zero it reflects through
+// ln|Γ(x)| = ln π - ln|sin π x| - ln Γ(1 - x).
+fn lgamma_approx(x: &BitFloat, w: usize) -> (BitFloat, i128, bool) {
+    if !x.get_s() {
+        let (y, err) = lgamma_pos(x, w);
+        return (y, err, false);
+    }
+    let wp = w + 1;
+    let one = BitFloat::one();
+    let x1 = add_sub_bf(&one, x, true, usize::MAX);
+    let (lg, err_g) = lgamma_pos(&x1, wp);
+    let (lpi, lost_pi) = ln_kernel(&pi_approx(wp + 1), wp);
+    let (s, _, lost_s) = sin_cos_pi_kernel(x, wp);
+    let sa = s.abs();
+    let (ls, err_s) = if sa == one {
+        (BitFloat::zero(), i128::MIN / 2)
+    } else {
+        // a relative error in sin π x is an absolute one in its ln
+        let (ls, lost) = ln_kernel(&sa, wp);
+        let err = rel_err(&ls, lost, wp).max(lost_s + 1 - 64 * w as i128);
+        (ls, err)
+    };
+
+    let mut y = add_sub_bf(&lpi, &ls, true, wp);
+    y = add_sub_bf(&y, &lg, true, wp);
+    let err = err_g
+        .max(err_s)
+        .max(rel_err(&lpi, lost_pi, wp))
+        .max(rel_err(&y, 1, wp));
+    (y, err + 2, s.get_s())
+}
+
+// ψ(x) for finite x > 0 at w limbs and its error exponent, shifted up like
+// lgamma_pos by ψ(x) = ψ(z) - sum_{i < n} 1 / (x + i), with the asymptotic
+// ψ(z) = ln z - 1/(2z) - sum_k B_2k / (2k z^2k)
+fn digamma_pos(x: &BitFloat, w: usize) -> (BitFloat, i128) {
+    let wp = w + 1;
+    let one = BitFloat::one();
+    let (z, n) = shift_up(x, wp);
+
+    let (lz, lost) = ln_kernel(&z, wp);
+    let inv2z = &div_bf(&one, &z, wp) >> 1_usize;
+    let (tail, err_tail) = bernoulli_tail(&z, wp, true);
+    let mut y = add_sub_bf(&lz, &inv2z, true, wp);
+    y = add_sub_bf(&y, &tail, true, wp);
+    let mut err = rel_err(&lz, lost + 2, wp).max(err_tail);
+
+    if n > 0 {
+        // all terms are positive, each within 2 roundings
+        let mut sum = BitFloat::zero();
+        for i in 0..n {
+            let xi = add_sub_bf(x, &BitFloat::from(i), false, wp);
+            sum = add_sub_bf(&sum, &div_bf(&one, &xi, wp), false, wp);
+        }
+        y = add_sub_bf(&y, &sum, true, wp);
+        err = err.max(rel_err(&sum, bit_len(n as u128) + 2, wp));
+    }
+    (y, err + 2)
+}
+
+// ψ(x) at w limbs for finite x other than zero and the negative integers,
+// reflecting below zero through ψ(x) = ψ(1 - x) - π cot π x
+fn digamma_approx(x: &BitFloat, w: usize) -> (BitFloat, i128) {
+    if !x.get_s() {
+        return digamma_pos(x, w);
+    }
+    let wp = w + 1;
+    let x1 = add_sub_bf(&BitFloat::one(), x, true, usize::MAX);
+    let (d, err_d) = digamma_pos(&x1, wp);
+    let (s, c, lost) = sin_cos_pi_kernel(x, wp);
+    // cot π x vanishes exactly at the half-integers
+    if c.is_zero() {
+        return (d, err_d);
+    }
+    let cot = mul_bf(&div_bf(&c, &s, wp), &pi_approx(wp), wp);
+    let y = add_sub_bf(&d, &cot, true, wp);
+    let err = err_d
+        .max(rel_err(&cot, lost + 4, wp))
+        .max(rel_err(&y, 1, wp));
+    (y, err + 1)
+}
+
+// whether Γ has a pole at x: zero or a negative integer
+fn is_pole(x: &BitFloat) -> bool {
+    x.is_zero() || (x.get_s() && x.is_integer())
+}
+
+// n! exactly, when it spans at most `max_bits` bits
+fn factorial_exact(n: &BitFloat, max_bits: usize) -> Option<BitFloat> {
+    if n.is_zero() {
+        return Some(BitFloat::one());
+    }
+    if n.bit_exp() >= 32 {
+        return None;
+    }
+    let n = u64::try_from(n).unwrap();
+    if (n as f64) * (n.max(2) as f64).log2() > max_bits as f64 {
+        return None;
+    }
+    let mut f = BitFloat::one();
+    for i in 2..=n {
+        f = mul_bf(&f, &BitFloat::from(i), usize::MAX);
+    }
+    Some(f)
+}
+
+// The bound on the factorials worked out exactly rather than by Ziv's loop
+fn exact_bits(prec: usize) -> usize {
+    4 * prec + 1024
+}
+
+// ±e^l for a logarithm l found at 1 limb, or the flagged overflow or
+// underflow once it is out of exp's reach
+fn exp_range(l: &BitFloat, neg: bool) -> Option<BitFloat> {
+    (l.bit_exp() >= EXP_MAX_BITS).then(|| {
+        if l.get_s() {
+            underflow(neg)
+        } else {
+            overflow(neg)
+        }
+    })
+}
+
+// ±e^l at w limbs given ln approx(w) = (l, err, neg), with the limbs spent on
+// the integer part of l added on top
+fn exp_of_log(
+    w: usize,
+    wl: usize,
+    approx: impl Fn(usize) -> (BitFloat, i128, bool),
+) -> (BitFloat, i128) {
+    let (l, err_l, neg) = approx(w + wl);
+    let (mut y, lost) = exp_kernel(&l, w);
+    if neg {
+        y.neg_mut();
+    }
+    // an absolute error in l is a relative one in e^l
+    let err = rel_err(&y, lost, w).max(y.bit_exp() + err_l + 1);
+    (y, err + 1)
+}
+
+// limbs above w that keep the fraction of a logarithm near l to w limbs
+fn log_limbs(l: &BitFloat) -> usize {
+    if l.is_zero() {
+        return 1;
+    }
+    (l.bit_exp().max(0) / 64) as usize + 1
+}
+
+impl BitFloat {
+    /// Gamma function, correctly rounded to `prec` bits. Infinite with the
+    /// sign of zero at ±0, and NaN at the negative integers and -∞.
+    pub fn gamma(&self, prec: usize) -> BitFloat {
+        if self.is_nan() {
+            return BitFloat::nan();
+        }
+        if self.is_zero() {
+            raise(Flags::DIV_BY_ZERO);
+            return if self.get_s() {
+                BitFloat::neg_inf()
+            } else {
+                BitFloat::inf()
+            };
+        }
+        if self.is_inf() {
+            return if self.get_s() {
+                invalid()
+            } else {
+                BitFloat::inf()
+            };
+        }
+        if is_pole(self) {
+            return invalid();
+        }
+        if self.is_integer() {
+            let n1 = add_sub_bf(self, &BitFloat::one(), true, usize::MAX);
+            if let Some(f) = factorial_exact(&n1, exact_bits(prec)) {
+                return f.to_prec(prec.max(1));
+            }
+        }
+
+        let (l0, _, neg) = lgamma_approx(self, 1);
+        if let Some(out) = exp_range(&l0, neg) {
+            return out;
+        }
+        let wl = log_limbs(&l0);
+        ziv_loop(prec, |w| exp_of_log(w, wl, |w| lgamma_approx(self, w)))
+    }
+
+    /// ln|Γ(self)|, correctly rounded to `prec` bits. +∞ at the poles and at
+    /// ±∞.
+    pub fn lgamma(&self, prec: usize) -> BitFloat {
+        if self.is_nan() {
+            return BitFloat::nan();
+        }
+        if self.is_inf() {
+            return BitFloat::inf();
+        }
+        if is_pole(self) {
+            raise(Flags::DIV_BY_ZERO);
+            return BitFloat::inf();
+        }
+        if *self == 1 || *self == 2 {
+            return BitFloat::zero();
+        }
+
+        ziv_loop(prec, |w| {
+            let (y, err, _) = lgamma_approx(self, w);
+            (y, err)
+        })
+    }
+
+    /// Digamma function ψ = Γ'/Γ, correctly rounded to `prec` bits. Infinite
+    /// against the sign of zero at ±0, and NaN at the negative integers and
+    /// -∞.
+    pub fn digamma(&self, prec: usize) -> BitFloat {
+        if self.is_nan() {
+            return BitFloat::nan();
+        }
+        if self.is_zero() {
+            raise(Flags::DIV_BY_ZERO);
+            return if self.get_s() {
+                BitFloat::inf()
+            } else {
+                BitFloat::neg_inf()
+            };
+        }
+        if self.is_inf() {
+            return if self.get_s() {
+                invalid()
+            } else {
+                BitFloat::inf()
+            };
+        }
+        if is_pole(self) {
+            return invalid();
+        }
+
+        ziv_loop(prec, |w| digamma_approx(self, w))
+    }
+
+    /// Beta function Γ(self) Γ(b) / Γ(self + b), correctly rounded to `prec`
+    /// bits. NaN when either argument is a pole of Γ, and zero when only
+    /// their sum is.
+    pub fn beta(&self, b: &BitFloat, prec: usize) -> BitFloat {
+        let a = self;
+        if a.is_nan() || b.is_nan() {
+            return BitFloat::nan();
+        }
+        if a.is_inf() || b.is_inf() {
+            // B(a, b) ~ Γ(b) a^(-b) vanishes as a grows, for b > 0
+            let pos = |x: &BitFloat| !x.get_s() && !x.is_zero();
+            return if pos(a) && pos(b) {
+                BitFloat::zero()
+            } else {
+                invalid()
+            };
+        }
+        if is_pole(a) || is_pole(b) {
+            return invalid();
+        }
+        let s = add_sub_bf(a, b, false, usize::MAX);
+        if is_pole(&s) {
+            return BitFloat::zero();
+        }
+
+        let one = BitFloat::one();
+        if a.is_integer() && b.is_integer() {
+            // (a - 1)! (b - 1)! / (a + b - 1)!, with a single rounding
+            let s1 = add_sub_bf(&s, &one, true, usize::MAX);
+            if let Some(den) = factorial_exact(&s1, exact_bits(prec)) {
+                let fa = factorial_exact(&add_sub_bf(a, &one, true, usize::MAX), usize::MAX);
+                let fb = factorial_exact(&add_sub_bf(b, &one, true, usize::MAX), usize::MAX);
+                let num = mul_bf(&fa.unwrap(), &fb.unwrap(), usize::MAX);
+                return div_bf_mode(&num, &den, prec.max(1), NearestEven);
+            }
+        }
+
+        let log_beta = |w: usize| {
+            let (la, ea, na) = lgamma_approx(a, w);
+            let (lb, eb, nb) = lgamma_approx(b, w);
+            let (ls, es, ns) = lgamma_approx(&s, w);
+            let y = add_sub_bf(&add_sub_bf(&la, &lb, false, w + 1), &ls, true, w);
+            let err = ea.max(eb).max(es).max(rel_err(&y, 1, w)) + 2;
+            (y, err, na ^ nb ^ ns)
+        };
+        let (l0, _, neg) = log_beta(1);
+        if let Some(out) = exp_range(&l0, neg) {
+            return out;
+        }
+        // ln Γ(a) and ln Γ(a + b) may cancel far above l
+        let wl = log_limbs(&l0) + log_limbs(a).max(log_limbs(b));
+        ziv_loop(prec, |w| exp_of_log(w, wl, log_beta))
+    }
+}
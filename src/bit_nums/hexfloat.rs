@@ -0,0 +1,123 @@
+use super::bitfloat::BitFloat;
+use super::traits::FromStrErr;
+use super::ubitint::UBitInt;
+use core::fmt;
+use std::num::IntErrorKind;
+
+// the low nd hex digits of n, zero padded
+fn hex_digits(n: &UBitInt, nd: usize) -> String {
+    let mut s: String = n
+        .get_data()
+        .iter()
+        .rev()
+        .map(|l| format!("{l:016x}"))
+        .collect();
+    if s.len() < nd {
+        s.insert_str(0, &"0".repeat(nd - s.len()));
+    } else {
+        s.drain(..s.len() - nd);
+    }
+    s
+}
+
+// x in the C99 `%a` form, lowercase: a leading 1, every further bit of the
+// significand in hex digits after the point, and a binary exponent
+fn hex_string(x: &BitFloat) -> String {
+    let sign = if x.get_s() { "-" } else { "" };
+    if x.is_nan() {
+        return "nan".to_string();
+    }
+    if x.is_inf() {
+        return format!("{sign}inf");
+    }
+    let Some(e) = x.exponent() else {
+        return format!("{sign}0x0p+0");
+    };
+
+    // the bits below the leading one, left aligned to whole digits
+    let k = x.prec() - 1;
+    let nd = k.div_ceil(4);
+    let frac = (x.significand() - (UBitInt::one() << k)) << (4 * nd - k);
+    let point = if nd > 0 { "." } else { "" };
+    format!("{sign}0x1{point}{}p{e:+}", hex_digits(&frac, nd))
+}
+
+/// Hexadecimal float in the C99 `%a` form, such as `0x1.8p+3`, which holds
+/// every bit of the significand and so reads back exactly with
+/// [`BitFloat::from_hex_str`].
+impl fmt::LowerHex for BitFloat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&hex_string(self))
+    }
+}
+
+/// As [`fmt::LowerHex`], in capitals: `0X1.8P+3`.
+impl fmt::UpperHex for BitFloat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&hex_string(self).to_uppercase())
+    }
+}
+
+// the value of `0x` and what follows, lowercase
+fn parse_hex(s: &str) -> Result<BitFloat, FromStrErr> {
+    let body = s
+        .strip_prefix("0x")
+        .ok_or(FromStrErr::MalformedExpression)?;
+    let (digits, exp) = match body.split_once('p') {
+        Some((digits, exp)) => {
+            let exp = exp.parse::<i128>().map_err(|err| match err.kind() {
+                IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => FromStrErr::Overflow,
+                _ => FromStrErr::MalformedExpression,
+            })?;
+            (digits, exp)
+        }
+        None => (body, 0),
+    };
+    let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+    if int.is_empty() && frac.is_empty() {
+        return Err(FromStrErr::MalformedExpression);
+    }
+
+    // the digits as one integer, lowest nibble first
+    let nibbles = int.len() + frac.len();
+    let mut limbs = vec![0; nibbles.div_ceil(16)];
+    for (i, b) in int.bytes().chain(frac.bytes()).rev().enumerate() {
+        let d = (b as char)
+            .to_digit(16)
+            .ok_or(FromStrErr::MalformedExpression)?;
+        limbs[i / 16] |= (d as u64) << (4 * (i % 16));
+    }
+    let scale = exp
+        .checked_sub(4 * frac.len() as i128)
+        .ok_or(FromStrErr::Overflow)?;
+    Ok(BitFloat::from(UBitInt::make(limbs)).ldexp(scale))
+}
+
+impl BitFloat {
+    /// Parses a hexadecimal float such as `0x1.8p+3`, `-0X.Cp-1` or `0x10`,
+    /// exactly, and `inf`, `infinity` or `nan` in any case. The `0x` prefix
+    /// is required and the binary exponent is optional.
+    pub fn from_hex_str(s: &str) -> Result<BitFloat, FromStrErr> {
+        if s.is_empty() {
+            return Err(FromStrErr::Empty);
+        }
+        if s.trim() != s {
+            return Err(FromStrErr::Whitespace);
+        }
+        let (neg, rest) = match s.as_bytes()[0] {
+            b'-' => (true, &s[1..]),
+            b'+' => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let lower = rest.to_ascii_lowercase();
+        let mut out = match lower.as_str() {
+            "inf" | "infinity" => BitFloat::inf(),
+            "nan" => return Ok(BitFloat::nan()),
+            _ => parse_hex(&lower)?,
+        };
+        if neg {
+            out.neg_mut();
+        }
+        Ok(out)
+    }
+}
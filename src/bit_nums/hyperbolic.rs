@@ -0,0 +1,237 @@
+use super::bitfloat::{
+    add_sub_bf, div_bf, invalid, mul_bf, overflow, raise, rel_err, ziv_loop, BitFloat, Flags,
+};
+use super::exp_log::{
+    atanh_series, bit_len, exp_kernel, expm1_kernel, ln1p_kernel, ln_kernel, EXP_MAX_BITS,
+};
+use super::roots::sqrt_bf;
+
+// expm1(x) for finite x > 0 and the bits of relative error lost at w limbs
+fn expm1_pos(x: &BitFloat, w: usize) -> (BitFloat, i128) {
+    if x.bit_exp() < -1 {
+        let (u, lost) = expm1_kernel(x, w);
+        return (u, lost + 1);
+    }
+    // e^x > 3/2 here, so dropping the 1 costs under 2 bits
+    let (e, lost) = exp_kernel(x, w + 1);
+    (add_sub_bf(&e, &BitFloat::one(), true, w), lost + 2)
+}
+
+// sinh(x) and cosh(x) for finite non-zero x with |x| < 2^EXP_MAX_BITS at w
+// limbs, and the bits of relative error lost. As in sinh, both come from
+// u = expm1(|x|) without cancellation.
+pub(crate) fn sinh_cosh_approx(x: &BitFloat, w: usize) -> (BitFloat, BitFloat, i128) {
+    let (u, lost) = expm1_pos(&x.abs(), w + 1);
+    let u1 = add_sub_bf(&u, &BitFloat::one(), false, w + 1);
+    let s = add_sub_bf(&u, &div_bf(&u, &u1, w + 1), false, w) >> 1_usize;
+    let c = add_sub_bf(&u1, &div_bf(&BitFloat::one(), &u1, w + 1), false, w) >> 1_usize;
+    (with_sign(s, x.get_s()), c, lost + 3)
+}
+
+fn with_sign(mut y: BitFloat, neg: bool) -> BitFloat {
+    if neg {
+        y.neg_mut();
+    }
+    y
+}
+
+impl BitFloat {
+    /// Hyperbolic sine, correctly rounded to `prec` bits.
+    pub fn sinh(&self, prec: usize) -> BitFloat {
+        if self.is_zero() || !self.is_finite() {
+            return self.clone();
+        }
+        if self.bit_exp() >= EXP_MAX_BITS {
+            return overflow(self.get_s());
+        }
+
+        let bx = self.bit_exp();
+        let a = self.abs();
+        ziv_loop(prec, |w| {
+            // |sinh(x) - x| <= |x|^3 / 4 for |x| < 1/2
+            if bx < -32 * w as i128 {
+                return (self.clone(), 3 * bx + 1);
+            }
+            // sinh(a) = (u + u / (u + 1)) / 2 with u = expm1(a), all positive
+            let (u, lost) = expm1_pos(&a, w + 1);
+            let u1 = add_sub_bf(&u, &BitFloat::one(), false, w + 1);
+            let y = add_sub_bf(&u, &div_bf(&u, &u1, w + 1), false, w) >> 1_usize;
+            let err = rel_err(&y, lost + 3, w);
+            (with_sign(y, self.get_s()), err)
+        })
+    }
+
+    /// Hyperbolic cosine, correctly rounded to `prec` bits.
+    pub fn cosh(&self, prec: usize) -> BitFloat {
+        if self.is_zero() {
+            return BitFloat::one();
+        }
+        if self.is_nan() {
+            return BitFloat::nan();
+        }
+        if self.is_inf() {
+            return BitFloat::inf();
+        }
+        if self.bit_exp() >= EXP_MAX_BITS {
+            return overflow(false);
+        }
+
+        let bx = self.bit_exp();
+        let a = self.abs();
+        ziv_loop(prec, |w| {
+            // |cosh(x) - 1| <= x^2 for |x| < 1/2
+            if bx < -32 * w as i128 {
+                return (BitFloat::one(), 2 * bx + 1);
+            }
+            // cosh(a) = (e^a + e^-a) / 2
+            let (e, lost) = exp_kernel(&a, w + 1);
+            let inv = div_bf(&BitFloat::one(), &e, w + 1);
+            let y = add_sub_bf(&e, &inv, false, w) >> 1_usize;
+            let err = rel_err(&y, lost + 3, w);
+            (y, err)
+        })
+    }
+
+    /// Hyperbolic tangent, correctly rounded to `prec` bits.
+    pub fn tanh(&self, prec: usize) -> BitFloat {
+        if self.is_zero() || self.is_nan() {
+            return self.clone();
+        }
+        if self.is_inf() {
+            return with_sign(BitFloat::one(), self.get_s());
+        }
+
+        let bx = self.bit_exp();
+        let a = self.abs();
+        ziv_loop(prec, |w| {
+            // |tanh(x) - x| <= |x|^3 / 3
+            if bx < -32 * w as i128 {
+                return (self.clone(), 3 * bx + 2);
+            }
+            // 1 - tanh(a) = 2 / (e^2a + 1) < 2^(1 - 2a)
+            let far = 32 * w + 8;
+            if a > BitFloat::from(far) {
+                let err = 1 - 2 * far as i128;
+                return (with_sign(BitFloat::one(), self.get_s()), err);
+            }
+            // tanh(a) = u / (u + 2) with u = expm1(2a)
+            let (u, lost) = expm1_pos(&(&a << 1_usize), w + 1);
+            let u2 = add_sub_bf(&u, &BitFloat::from(2), false, w + 1);
+            let y = div_bf(&u, &u2, w);
+            let err = rel_err(&y, lost + 3, w);
+            (with_sign(y, self.get_s()), err)
+        })
+    }
+
+    /// Inverse hyperbolic sine, correctly rounded to `prec` bits.
+    pub fn asinh(&self, prec: usize) -> BitFloat {
+        if self.is_zero() || !self.is_finite() {
+            return self.clone();
+        }
+
+        let bx = self.bit_exp();
+        let a = self.abs();
+        let one = BitFloat::one();
+        ziv_loop(prec, |w| {
+            // |asinh(x) - x| <= |x|^3 / 6
+            if bx < -32 * w as i128 {
+                return (self.clone(), 3 * bx + 1);
+            }
+            let s = sqrt_bf(
+                &add_sub_bf(&mul_bf(&a, &a, w + 2), &one, false, w + 2),
+                64 * (w + 2),
+            );
+            let (y, err) = if bx < -2 {
+                // asinh(a) = ln1p(a + a^2 / (1 + sqrt(1 + a^2)))
+                let s1 = add_sub_bf(&s, &one, false, w + 2);
+                let q = div_bf(&mul_bf(&a, &a, w + 2), &s1, w + 2);
+                let (y, lost) = ln1p_kernel(&add_sub_bf(&a, &q, false, w + 2), w);
+                let err = rel_err(&y, lost + 3, w);
+                (y, err)
+            } else {
+                // asinh(a) = ln(a + sqrt(a^2 + 1)) > 1/5
+                let (y, lost) = ln_kernel(&add_sub_bf(&a, &s, false, w + 2), w);
+                let err = rel_err(&y, lost + 5, w);
+                (y, err)
+            };
+            (with_sign(y, self.get_s()), err)
+        })
+    }
+
+    /// Inverse hyperbolic cosine, correctly rounded to `prec` bits. NaN if
+    /// `self < 1`.
+    pub fn acosh(&self, prec: usize) -> BitFloat {
+        let one = BitFloat::one();
+        if self.is_nan() {
+            return BitFloat::nan();
+        }
+        if *self < one {
+            return invalid();
+        }
+        if *self == one {
+            return BitFloat::zero();
+        }
+        if self.is_inf() {
+            return BitFloat::inf();
+        }
+
+        ziv_loop(prec, |w| {
+            // acosh(x) = ln1p(d + sqrt(d (x + 1))) with d = x - 1
+            let d = add_sub_bf(self, &one, true, w + 2);
+            let x1 = add_sub_bf(self, &one, false, w + 2);
+            let t = add_sub_bf(
+                &d,
+                &sqrt_bf(&mul_bf(&d, &x1, w + 2), 64 * (w + 2)),
+                false,
+                w + 2,
+            );
+            if d.bit_exp() < -2 {
+                // t is at most 1 here, keeping the atanh series argument under 1/3
+                let (y, lost) = ln1p_kernel(&t, w);
+                let err = rel_err(&y, lost + 3, w);
+                return (y, err);
+            }
+            // acosh(x) > 2/3 here
+            let (y, lost) = ln_kernel(&add_sub_bf(&t, &one, false, w + 2), w);
+            let err = rel_err(&y, lost + 4, w);
+            (y, err)
+        })
+    }
+
+    /// Inverse hyperbolic tangent, correctly rounded to `prec` bits. NaN if
+    /// `|self| > 1`.
+    pub fn atanh(&self, prec: usize) -> BitFloat {
+        if self.is_zero() || self.is_nan() {
+            return self.clone();
+        }
+        if self.abs() > 1 {
+            return invalid();
+        }
+        if self.abs() == 1 {
+            raise(Flags::DIV_BY_ZERO);
+            return with_sign(BitFloat::inf(), self.get_s());
+        }
+
+        let bx = self.bit_exp();
+        let a = self.abs();
+        let one = BitFloat::one();
+        ziv_loop(prec, |w| {
+            // |atanh(x) - x| <= |x|^3 / 2 for |x| < 1/2
+            if bx < -32 * w as i128 {
+                return (self.clone(), 3 * bx + 2);
+            }
+            if bx < -2 {
+                let (y, terms) = atanh_series(self, w);
+                let err = rel_err(&y, bit_len(terms as u128) + 8, w);
+                return (y, err);
+            }
+            // atanh(a) = ln((1 + a) / (1 - a)) / 2 > 1/4
+            let num = add_sub_bf(&one, &a, false, w + 2);
+            let den = add_sub_bf(&one, &a, true, w + 2);
+            let (y, lost) = ln_kernel(&div_bf(&num, &den, w + 2), w);
+            let y = y >> 1_usize;
+            let err = rel_err(&y, lost + 5, w);
+            (with_sign(y, self.get_s()), err)
+        })
+    }
+}
@@ -0,0 +1,209 @@
+use super::bitfloat::{
+    nan_field, nan_with, overflows, raise, to_ieee, BitFloat, Flags, RoundingMode,
+};
+use super::traits::FromErr;
+use super::ubitint::UBitInt;
+use super::ubitint_static::UBitIntStatic;
+use crate::utils::utils::trim_lz;
+
+// The value of an IEEE binary encoding with `frac` fraction bits and exponent
+// bias `bias`, from its sign, biased exponent and fraction field. A NaN keeps
+// its sign, payload and quiet bit.
+fn from_fields(s: bool, biased: i128, f: Vec<u64>, frac: u32, bias: i128) -> BitFloat {
+    let mut f = f;
+    trim_lz(&mut f);
+    if biased == 2 * bias + 1 {
+        if f.is_empty() {
+            return if s {
+                BitFloat::neg_inf()
+            } else {
+                BitFloat::inf()
+            };
+        }
+        let quiet = frac as usize - 1;
+        let signaling = f
+            .get(quiet / 64)
+            .is_none_or(|&x| x >> (quiet % 64) & 1 == 0);
+        if !signaling {
+            f[quiet / 64] ^= 1 << (quiet % 64);
+            trim_lz(&mut f);
+        }
+        return nan_with(s, signaling, &UBitInt::make(f));
+    }
+    let f = BitFloat::from(UBitInt::make(f));
+    let mut out = if biased == 0 {
+        f
+    } else {
+        f.add_round(
+            &BitFloat::pow2(frac as i128),
+            usize::MAX,
+            RoundingMode::NearestEven,
+        )
+    };
+    out = out.ldexp(biased.max(1) - bias - frac as i128);
+    if s {
+        out.neg_mut();
+    }
+    out
+}
+
+// from_fields for encodings of up to 128 bits, sign bit on top
+fn from_ieee(bits: u128, frac: u32, bias: i128) -> BitFloat {
+    let exp_bits = (2 * bias + 2).ilog2();
+    let s = (bits >> (frac + exp_bits)) & 1 == 1;
+    let biased = ((bits >> frac) & ((1 << exp_bits) - 1)) as i128;
+    let f = bits & ((1 << frac) - 1);
+    from_fields(s, biased, vec![f as u64, (f >> 64) as u64], frac, bias)
+}
+
+// to_ieee for encodings too wide for a u128: the bits without the sign
+fn to_ieee_wide(
+    bf: &BitFloat,
+    frac: u32,
+    bias: i128,
+    mode: RoundingMode,
+) -> Result<(bool, UBitInt), FromErr> {
+    let max_biased = UBitInt::from((2 * bias + 1) as u128);
+    if bf.is_zero() {
+        return Ok((bf.get_s(), UBitInt::zero()));
+    }
+    if bf.is_nan() {
+        let field = UBitInt::make(nan_field(bf, frac)?);
+        return Ok((bf.get_s(), (max_biased << frac as usize) + field));
+    }
+    if bf.is_inf() {
+        return Ok((bf.get_s(), max_biased << frac as usize));
+    }
+
+    let max_finite = (&max_biased << frac as usize) - UBitInt::one();
+    let exp = bf.exponent().unwrap();
+    if exp > bias {
+        if overflows(bf.get_s(), mode) {
+            return Err(FromErr::Overflow);
+        }
+        return Ok((bf.get_s(), max_finite));
+    }
+    // the significand as an integer over the lowest bit the format keeps
+    let min_exp = 1 - bias;
+    let scaled = bf.ldexp(frac as i128 - exp.max(min_exp));
+    let q = scaled.round_to_int(mode);
+    if q != scaled {
+        raise(if exp < min_exp {
+            Flags::UNDERFLOW
+        } else {
+            Flags::INEXACT
+        });
+    }
+    let q = q.to_bitint(mode).unwrap();
+
+    // a carry out of the significand bumps the exponent field on its own
+    let field = UBitInt::from((exp.max(min_exp) + bias - 1) as u128) << frac as usize;
+    let bits = field + UBitInt::make(q.get_data().to_vec());
+    if bits > max_finite {
+        if overflows(bf.get_s(), mode) {
+            return Err(FromErr::Overflow);
+        }
+        return Ok((bf.get_s(), max_finite));
+    }
+    Ok((bf.get_s(), bits))
+}
+
+// x87 extended: sign and 15-bit exponent over a 64-bit significand with an
+// explicit integer bit
+const X87_BIAS: i128 = 16383;
+const X87_INT_BIT: u128 = 1 << 63;
+
+impl BitFloat {
+    /// The value of IEEE 754 binary16 bits. A NaN keeps its sign, payload
+    /// and quiet bit.
+    pub fn from_bits_binary16(bits: u16) -> BitFloat {
+        from_ieee(bits as u128, 10, 15)
+    }
+
+    /// `self` rounded to IEEE 754 binary16 in the given mode, as bits, or
+    /// an error where it rounds to infinity or for a NaN payload too wide
+    /// for it.
+    pub fn to_binary16(&self, mode: RoundingMode) -> Result<u16, FromErr> {
+        let (s, bits) = to_ieee(self, 10, 15, mode)?;
+        Ok(((s as u16) << 15) | bits as u16)
+    }
+
+    /// The value of bfloat16 bits, the top half of an `f32`. A NaN keeps its
+    /// sign, payload and quiet bit.
+    pub fn from_bits_bfloat16(bits: u16) -> BitFloat {
+        from_ieee(bits as u128, 7, 127)
+    }
+
+    /// `self` rounded to bfloat16 in the given mode, as bits, or an error
+    /// where it rounds to infinity or for a NaN payload too wide for it.
+    pub fn to_bfloat16(&self, mode: RoundingMode) -> Result<u16, FromErr> {
+        let (s, bits) = to_ieee(self, 7, 127, mode)?;
+        Ok(((s as u16) << 15) | bits as u16)
+    }
+
+    /// The value of IEEE 754 binary128 bits, as written for `real*16` or
+    /// `__float128`. A NaN keeps its sign, payload and quiet bit.
+    pub fn from_bits_binary128(bits: u128) -> BitFloat {
+        from_ieee(bits, 112, 16383)
+    }
+
+    /// `self` rounded to IEEE 754 binary128 in the given mode, as bits, or
+    /// an error where it rounds to infinity or for a NaN payload too wide
+    /// for it.
+    pub fn to_binary128(&self, mode: RoundingMode) -> Result<u128, FromErr> {
+        let (s, bits) = to_ieee(self, 112, 16383, mode)?;
+        Ok(((s as u128) << 127) | bits)
+    }
+
+    /// The value of IEEE 754 binary256 bits. A NaN keeps its sign, payload
+    /// and quiet bit.
+    pub fn from_bits_binary256(bits: UBitIntStatic<4>) -> BitFloat {
+        let mut limbs = bits.get_data();
+        let top = limbs[3];
+        limbs[3] &= (1 << 44) - 1;
+        from_fields(
+            top >> 63 == 1,
+            ((top >> 44) & 0x7FFFF) as i128,
+            limbs.to_vec(),
+            236,
+            262143,
+        )
+    }
+
+    /// `self` rounded to IEEE 754 binary256 in the given mode, as bits, or
+    /// an error where it rounds to infinity.
+    pub fn to_binary256(&self, mode: RoundingMode) -> Result<UBitIntStatic<4>, FromErr> {
+        let (s, bits) = to_ieee_wide(self, 236, 262143, mode)?;
+        let mut limbs = [0; 4];
+        limbs[..bits.get_data().len()].copy_from_slice(bits.get_data());
+        limbs[3] |= (s as u64) << 63;
+        Ok(UBitIntStatic::make(limbs))
+    }
+
+    /// The value of x87 80-bit extended bits, held in the low 80 bits.
+    /// Pseudo-denormals are read by value and a NaN keeps its sign, payload
+    /// and quiet bit, while the encodings the x87 rejects, unnormals and
+    /// pseudo-infinities or pseudo-NaNs, give the plain NaN.
+    pub fn from_bits_x87(bits: u128) -> BitFloat {
+        let s = (bits >> 79) & 1 == 1;
+        let biased = ((bits >> 64) & 0x7FFF) as i128;
+        let sig = bits & u64::MAX as u128;
+        if biased != 0 && sig & X87_INT_BIT == 0 {
+            return BitFloat::nan();
+        }
+        // the integer bit of a pseudo-denormal weighs as much as the one a
+        // normal value implies
+        let f = if biased == 0 { sig } else { sig & !X87_INT_BIT };
+        from_fields(s, biased, vec![f as u64], 63, X87_BIAS)
+    }
+
+    /// `self` rounded to x87 80-bit extended in the given mode, as bits in
+    /// the low 80, or an error where it rounds to infinity or for a NaN
+    /// payload too wide for it.
+    pub fn to_x87(&self, mode: RoundingMode) -> Result<u128, FromErr> {
+        let (s, bits) = to_ieee(self, 63, X87_BIAS, mode)?;
+        let biased = bits >> 63;
+        let int_bit = if biased != 0 { X87_INT_BIT } else { 0 };
+        Ok(((s as u128) << 79) | (biased << 64) | int_bit | (bits & (X87_INT_BIT - 1)))
+    }
+}
@@ -1,7 +1,28 @@
+mod bessel;
+pub mod bitball;
+pub mod bitcomplex;
+pub mod bitdecimal;
+pub mod bitfixed;
+pub mod bitfloat;
+pub mod bitfrac;
+pub mod bitinterval;
 pub mod bitint;
 pub mod bitint_static;
+pub mod constants;
+mod erf;
+mod exp_log;
+mod fused;
+mod gamma;
+mod hexfloat;
+mod hyperbolic;
+mod interchange;
+mod roots;
+pub mod scratch;
+mod trig;
 pub mod ubitint;
 pub mod ubitint_static;
+mod ulp;
+mod zeta;
 
 #[macro_use]
 pub mod traits;
@@ -0,0 +1,238 @@
+use super::bitfloat::{
+    add_sub_bf, div_bf, invalid, mul_bf, quietly, raise, rel_err, round_bf, shl_shr_bf, ziv_cap,
+    ziv_loop, ziv_loop_round, BitFloat, Flags, RoundingMode,
+};
+use super::exp_log::bit_len;
+use super::traits::{MulVariants, SqrVariants};
+
+// y^n from short products of the top q limbs, for n >= 1
+fn pow_short(y: &BitFloat, n: u32, q: usize) -> BitFloat {
+    let b = 64 * q;
+    let mut out = y.to_prec(b);
+    for i in (0..n.ilog2()).rev() {
+        out = out.man_sqr(Some(b), Some(b));
+        if (n >> i) & 1 == 1 {
+            out = out.man_mul(y, Some(b), Some(b), Some(b));
+        }
+    }
+    out
+}
+
+// y^n exactly
+fn pow_exact(y: &BitFloat, n: u32) -> BitFloat {
+    let mut out = y.clone();
+    for i in (0..n.ilog2()).rev() {
+        out = out.full_sqr();
+        if (n >> i) & 1 == 1 {
+            out = mul_bf(&out, y, usize::MAX);
+        }
+    }
+    out
+}
+
+// |x|^(-1/n) for finite non-zero x to about 64 w + 16 bits. With |x| = m 2^k
+// and k a multiple of n, the Newton steps y += y (1 - m y^n) / n run on short
+// products from an f64 seed, each one roughly doubling the bits.
+fn inv_root_approx(x: &BitFloat, n: u32, w: usize) -> BitFloat {
+    let k = x.bit_exp().div_euclid(n as i128) * n as i128;
+    let mut m = x.abs();
+    shl_shr_bf(&mut m, -k);
+    let t = m.bit_exp();
+    let mut top = m.clone();
+    shl_shr_bf(&mut top, -t);
+    let lg = t as f64 + f64::try_from(&top).unwrap().log2();
+    let mut y = BitFloat::from((-lg / n as f64).exp2());
+
+    // a step from relative error d lands within (n + 1) d^2 / 2
+    let slack = bit_len(n as u128) + 2;
+    let target = 64 * w as i128 + 16;
+    let one = BitFloat::one();
+    let nf = BitFloat::from(n);
+    let mut good = 48;
+    while good < target {
+        good = 2 * good - slack;
+        let q = ((good.min(target) + 2 * slack) / 64) as usize + 2;
+        let b = Some(64 * q);
+        let my = m.man_mul(&pow_short(&y, n, q), b, None, b);
+        let e = add_sub_bf(&one, &my, true, q);
+        let corr = y.man_mul(&div_bf(&e, &nf, q), b, None, b);
+        y = add_sub_bf(&y, &corr, false, q);
+    }
+    shl_shr_bf(&mut y, -k / n as i128);
+    y
+}
+
+// |x|^(1/n) = |x| y^(n - 1) with y = |x|^(-1/n), and the bits of relative
+// error lost at w limbs
+fn root_approx(x: &BitFloat, n: u32, w: usize) -> (BitFloat, i128) {
+    let y = inv_root_approx(x, n, w);
+    let r = if n == 2 {
+        y
+    } else {
+        pow_short(&y, n - 1, w + 1)
+    };
+    let b = Some(64 * (w + 1));
+    let out = x.abs().man_mul(&r, b, None, b);
+    (out, 2 * bit_len(n as u128) + 4)
+}
+
+// |x|^(1/n) when it is exact in at most p + 2 bits. Its significand is then
+// the n-th root of the one of x, so it has at most prec(x) / n + 1 bits. A
+// longer exact root is neither representable at p bits nor a midpoint, so
+// the probe need not work past the target.
+fn exact_root(x: &BitFloat, n: u32, p: usize) -> Option<BitFloat> {
+    let q = (x.prec() / n as usize + 1).min(p.saturating_add(2));
+    let r = quietly(|| root_approx(x, n, q / 64 + 2).0.to_prec(q));
+    if n as usize * (r.prec() - 1) >= x.prec() {
+        return None;
+    }
+    (pow_exact(&r, n) == x.abs()).then_some(r)
+}
+
+// x^(1/n) correctly rounded to p bits, NaN for x < 0 under even n
+pub(crate) fn root_bf(x: &BitFloat, n: u32, p: usize) -> BitFloat {
+    root_bf_mode(x, n, p, RoundingMode::NearestEven)
+}
+
+// root_bf in any rounding mode
+fn root_bf_mode(x: &BitFloat, n: u32, p: usize, mode: RoundingMode) -> BitFloat {
+    if x.is_zero() || x.is_nan() {
+        return x.clone();
+    }
+    if x.get_s() && n.is_multiple_of(2) {
+        return invalid();
+    }
+    if x.is_inf() {
+        return x.clone();
+    }
+
+    // directed modes round the signed root
+    let signed = |mut y: BitFloat| {
+        if x.get_s() {
+            y.neg_mut();
+        }
+        y
+    };
+    match exact_root(x, n, p) {
+        Some(r) => signed(r).to_prec_round(p, mode),
+        // a longer exact root may still sit closer to a rounding boundary
+        // than the loop resolves before its cap, where it is rounded exactly
+        None => ziv_loop_round(p, mode, |w| {
+            if w >= ziv_cap(p) {
+                if let Some(r) = exact_root(x, n, usize::MAX) {
+                    return (signed(r), i128::MIN / 2);
+                }
+            }
+            let (y, lost) = root_approx(x, n, w);
+            let err = rel_err(&y, lost, w);
+            (signed(y), err)
+        }),
+    }
+}
+
+// sqrt(x) correctly rounded to p bits
+pub(crate) fn sqrt_bf(x: &BitFloat, p: usize) -> BitFloat {
+    root_bf(x, 2, p)
+}
+
+impl BitFloat {
+    /// Square root, correctly rounded to `prec` bits. NaN for values below
+    /// zero, while `sqrt(-0) = -0`.
+    pub fn sqrt(&self, prec: usize) -> BitFloat {
+        sqrt_bf(self, prec)
+    }
+
+    /// Square root rounded to `prec` bits in the given mode. NaN for values
+    /// below zero.
+    pub fn sqrt_round(&self, prec: usize, mode: RoundingMode) -> BitFloat {
+        root_bf_mode(self, 2, prec, mode)
+    }
+
+    /// Cube root, correctly rounded to `prec` bits. Negative values have
+    /// negative cube roots.
+    pub fn cbrt(&self, prec: usize) -> BitFloat {
+        root_bf(self, 3, prec)
+    }
+
+    /// `n`-th root, correctly rounded to `prec` bits. Panics for `n = 0`, and
+    /// NaN for values below zero under even `n`.
+    pub fn nth_root(&self, n: u32, prec: usize) -> BitFloat {
+        if n == 0 {
+            panic!("zeroth root");
+        }
+        if n == 1 {
+            return self.to_prec(prec);
+        }
+        root_bf(self, n, prec)
+    }
+
+    /// 1 / sqrt(self), correctly rounded to `prec` bits. NaN for values
+    /// below zero.
+    pub fn rsqrt(&self, prec: usize) -> BitFloat {
+        if self.is_nan() {
+            return BitFloat::nan();
+        }
+        if self.is_zero() {
+            raise(Flags::DIV_BY_ZERO);
+            return if self.get_s() {
+                BitFloat::neg_inf()
+            } else {
+                BitFloat::inf()
+            };
+        }
+        if self.get_s() {
+            return invalid();
+        }
+        if self.is_inf() {
+            return BitFloat::zero();
+        }
+        // only even powers of two have dyadic reciprocal roots
+        if let Some(k) = self.pow2_exp().filter(|k| k % 2 == 0) {
+            return BitFloat::pow2(-k / 2);
+        }
+
+        ziv_loop(prec, |w| {
+            let y = inv_root_approx(self, 2, w);
+            let err = rel_err(&y, 8, w);
+            (y, err)
+        })
+    }
+
+    /// sqrt(self^2 + other^2), correctly rounded to `prec` bits. The squares
+    /// are taken exactly on values scaled next to 1, so nothing overflows.
+    pub fn hypot(&self, other: &BitFloat, prec: usize) -> BitFloat {
+        // an infinite side wins even over NaN
+        if self.is_inf() || other.is_inf() {
+            return BitFloat::inf();
+        }
+        if self.is_nan() || other.is_nan() {
+            return BitFloat::nan();
+        }
+        let (a, b) = if self.abs() >= other.abs() {
+            (self.abs(), other.abs())
+        } else {
+            (other.abs(), self.abs())
+        };
+        if b.is_zero() {
+            return a.to_prec(prec);
+        }
+
+        // b^2 / 2a, the most b adds to a, is below both the last bit of a and
+        // half an ulp at prec bits
+        let cut = a.bit_exp() + 1 - a.prec().max(prec) as i128;
+        if 2 * b.bit_exp() - a.bit_exp() + 2 < cut - 1 {
+            if a.prec() <= prec {
+                raise(Flags::INEXACT);
+                return a;
+            }
+            return round_bf(false, a.get_m().to_vec(), a.lo(), true, prec);
+        }
+
+        let e = a.get_e();
+        let a1 = BitFloat::make(false, 0, a.get_m());
+        let b1 = BitFloat::make(false, b.get_e() - e, b.get_m());
+        let s = add_sub_bf(&a1.full_sqr(), &b1.full_sqr(), false, usize::MAX);
+        let r = sqrt_bf(&s, prec);
+        BitFloat::make(false, r.get_e() + e, r.get_m())
+    }
+}
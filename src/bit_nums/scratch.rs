@@ -0,0 +1,164 @@
+use super::ubitint_static::UBitIntStatic;
+use crate::utils::{
+    div::*,
+    mul::*,
+    utils::{buf_len, trim_lz},
+};
+use std::cell::RefCell;
+
+pub(crate) mod sealed {
+    pub trait Limbs {
+        fn limbs(&self) -> &[u64];
+        fn limbs_mut(&mut self) -> &mut Vec<u64>;
+        fn sign(&self) -> bool;
+        fn set_sign(&mut self, sign: bool);
+    }
+}
+
+/// Integer types the [`Scratch`] kernels can read from and write into.
+pub trait ScratchInt: sealed::Limbs {}
+
+/// Reusable workspace for the multiply, square, power and division kernels.
+///
+/// The buffers here and in the outputs only ever grow, so once a loop has run
+/// each `*_into` call at its largest operand sizes it stops allocating.
+#[derive(Debug, Clone, Default)]
+pub struct Scratch {
+    buf: Vec<u64>,
+    tmp: Vec<u64>,
+    prod: Vec<u64>,
+}
+
+thread_local! {
+    static LOCAL: RefCell<Scratch> = RefCell::new(Scratch::new());
+}
+
+// per-thread workspace behind the `*_into` methods on the integer types
+pub(crate) fn with_local<R>(f: impl FnOnce(&mut Scratch) -> R) -> R {
+    LOCAL.with(|ws| f(&mut ws.borrow_mut()))
+}
+
+fn set_zero<T: ScratchInt>(out: &mut T) {
+    out.limbs_mut().clear();
+    out.set_sign(false);
+}
+
+// clears out and makes sure it can hold n limbs plus a carry
+fn prepare<T: ScratchInt>(out: &mut T, n: usize) -> &mut Vec<u64> {
+    let data = out.limbs_mut();
+    data.clear();
+    data.reserve(n + 1);
+    data
+}
+
+impl Scratch {
+    pub fn new() -> Self {
+        Scratch::default()
+    }
+
+    /// Limbs currently held by the workspace.
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity() + self.tmp.capacity() + self.prod.capacity()
+    }
+
+    // a * b held in the workspace, for the fused multiply-accumulate methods
+    pub(crate) fn product(&mut self, a: &[u64], b: &[u64]) -> &[u64] {
+        if a.is_empty() || b.is_empty() {
+            self.prod.clear();
+            return &self.prod;
+        }
+        self.prod.reserve(a.len() + b.len());
+        let c = mul_into(a, b, &mut self.prod, &mut self.buf);
+        if c > 0 {
+            self.prod.push(c);
+        }
+        &self.prod
+    }
+
+    /// `out = a * b`
+    pub fn mul_into<T: ScratchInt>(&mut self, a: &T, b: &T, out: &mut T) {
+        let (a_data, b_data) = (a.limbs(), b.limbs());
+        if a_data.is_empty() || b_data.is_empty() {
+            return set_zero(out);
+        }
+        let data = prepare(out, a_data.len() + b_data.len());
+        let c = mul_into(a_data, b_data, data, &mut self.buf);
+        if c > 0 {
+            data.push(c);
+        }
+        out.set_sign(a.sign() ^ b.sign());
+    }
+
+    /// `out = a * a`
+    pub fn sqr_into<T: ScratchInt>(&mut self, a: &T, out: &mut T) {
+        let a_data = a.limbs();
+        if a_data.is_empty() {
+            return set_zero(out);
+        }
+        let data = prepare(out, 2 * a_data.len());
+        let c = sqr_into(a_data, data, &mut self.buf);
+        if c > 0 {
+            data.push(c);
+        }
+        out.set_sign(false);
+    }
+
+    /// `out = a^pow`, with `0^0 = 1`
+    pub fn powi_into<T: ScratchInt>(&mut self, a: &T, pow: usize, out: &mut T) {
+        if a.limbs().is_empty() && pow > 0 {
+            return set_zero(out);
+        }
+        powi_into(
+            a.limbs(),
+            pow,
+            out.limbs_mut(),
+            &mut self.tmp,
+            &mut self.buf,
+        );
+        out.set_sign(a.sign() && pow % 2 == 1);
+    }
+
+    /// Truncating division, `q = n / d` and `r = n % d` with `r` taking the
+    /// sign of `n`. Panics if `d` is zero.
+    pub fn div_rem_into<T: ScratchInt>(&mut self, n: &T, d: &T, q: &mut T, r: &mut T) {
+        if d.limbs().is_empty() {
+            panic!("division by zero");
+        }
+
+        // the kernel normalizes the divisor in place and leaves the remainder in n
+        self.tmp.clear();
+        self.tmp.extend_from_slice(d.limbs());
+        let rem = r.limbs_mut();
+        rem.clear();
+        rem.extend_from_slice(n.limbs());
+        div_into(rem, &mut self.tmp, q.limbs_mut(), &mut self.buf);
+        trim_lz(rem);
+        trim_lz(q.limbs_mut());
+
+        let q_sign = !q.limbs().is_empty() && (n.sign() ^ d.sign());
+        let r_sign = !r.limbs().is_empty() && n.sign();
+        q.set_sign(q_sign);
+        r.set_sign(r_sign);
+    }
+
+    /// `(n / d, n % d)` for the stack allocated [`UBitIntStatic`], with the
+    /// division's working space held here rather than on the stack. Panics
+    /// if `d` is zero.
+    pub fn div_rem_static<const N: usize>(
+        &mut self,
+        n: &UBitIntStatic<N>,
+        d: &UBitIntStatic<N>,
+    ) -> (UBitIntStatic<N>, UBitIntStatic<N>) {
+        let (mut n, mut d) = (n.get_data(), d.get_data());
+        let (n_len, d_len) = (buf_len(&n), buf_len(&d));
+        if d_len == 0 {
+            panic!("division by zero");
+        }
+        let mut q = [0; N];
+        if n_len < d_len {
+            return (UBitIntStatic::make(q), UBitIntStatic::make(n));
+        }
+        div_arr_into(&mut n[..n_len], &mut d[..d_len], &mut q, &mut self.buf);
+        (UBitIntStatic::make(q), UBitIntStatic::make(n))
+    }
+}
@@ -36,6 +36,7 @@ impl DerefMut for SmallBuf {
 pub enum FromErr {
     Overflow,
     Underflow,
+    NaN,
 }
 
 impl TryFrom<&[u64]> for SmallBuf {
@@ -161,6 +162,47 @@ impl SEM {
         e: i128::MAX,
         m: SmallBuf::ZERO,
     };
+
+    // the plain quiet NaN, its mantissa 4 payload + 2 signaling + 1 as
+    // BitFloat holds a NaN
+    pub(crate) const NAN: Self = SEM {
+        s: false,
+        e: i128::MAX,
+        m: SmallBuf {
+            limbs: [1, 0],
+            len: 1,
+        },
+    };
+}
+
+impl SEM {
+    // NaN of sign s from an IEEE fraction field with the quiet bit at `quiet`
+    fn nan(s: bool, field: u64, quiet: u32) -> Self {
+        let payload = field & ((1 << quiet) - 1);
+        let signaling = (field >> quiet) & 1 == 0;
+        SEM {
+            s,
+            m: SmallBuf {
+                limbs: [4 * payload + 2 * signaling as u64 + 1, 0],
+                len: 1,
+            },
+            ..SEM::NAN
+        }
+    }
+
+    // The IEEE fraction field with the quiet bit at `quiet` of a NaN, or an
+    // error for a payload too wide for it, and None for any other value
+    fn nan_field(&self, quiet: u32) -> Option<Result<u64, FromErr>> {
+        if self.e != i128::MAX || self.m.is_empty() {
+            return None;
+        }
+        let payload = self.m[0] >> 2;
+        let quiet_bit = ((self.m[0] >> 1) & 1 ^ 1) << quiet;
+        if self.m.len() > 1 || payload >> quiet != 0 {
+            return Some(Err(FromErr::NaN));
+        }
+        Some(Ok(quiet_bit | payload))
+    }
 }
 
 impl From<f64> for SEM {
@@ -171,8 +213,9 @@ impl From<f64> for SEM {
         let mantissa_bits = bits & 0xFFFFFFFFFFFFF;
 
         let (sig, exp) = match (exp_bits, mantissa_bits) {
-            (0x7FF, _) => return if s { SEM::NEG_INF } else { SEM::POS_INF },
-            (0, 0) => return SEM::ZERO,
+            (0x7FF, 0) => return if s { SEM::NEG_INF } else { SEM::POS_INF },
+            (0x7FF, _) => return SEM::nan(s, mantissa_bits, 51),
+            (0, 0) => return SEM { s, ..SEM::ZERO },
             (0, _) => {
                 let sh = mantissa_bits.leading_zeros() - 11;
                 let sig = mantissa_bits << sh;
@@ -213,8 +256,9 @@ impl From<f32> for SEM {
         let mantissa_bits = bits & 0x7FFFFF;
 
         let (sig, exp) = match (exp_bits, mantissa_bits) {
-            (0xFF, _) => return if s { SEM::NEG_INF } else { SEM::POS_INF },
-            (0, 0) => return SEM::ZERO,
+            (0xFF, 0) => return if s { SEM::NEG_INF } else { SEM::POS_INF },
+            (0xFF, _) => return SEM::nan(s, mantissa_bits as u64, 22),
+            (0, 0) => return SEM { s, ..SEM::ZERO },
             (0, _) => {
                 let sh = mantissa_bits.leading_zeros() - 8;
                 let sig = mantissa_bits << sh;
@@ -282,11 +326,56 @@ impl From<i64> for SEM {
     }
 }
 
+impl From<u128> for SEM {
+    fn from(value: u128) -> Self {
+        if value == 0 {
+            return SEM::ZERO;
+        }
+        let m = SmallBuf::from(value);
+        SEM {
+            s: false,
+            e: m.len() as i128 - 1,
+            m,
+        }
+    }
+}
+
+impl From<u64> for SEM {
+    fn from(value: u64) -> Self {
+        if value == 0 {
+            return SEM::ZERO;
+        }
+        SEM {
+            s: false,
+            e: 0,
+            m: SmallBuf::from(value),
+        }
+    }
+}
+
+macro_rules! impl_sem_prim {
+    ($wide:ty; $($t:ty),+) => {
+        $(
+        impl From<$t> for SEM {
+            fn from(value: $t) -> Self {
+                SEM::from(value as $wide)
+            }
+        }
+        )+
+    };
+}
+impl_sem_prim!(i64; i32, i16, i8, isize);
+impl_sem_prim!(u64; u32, u16, u8, usize);
+
 impl TryFrom<SEM> for f64 {
     type Error = FromErr;
     fn try_from(value: SEM) -> Result<Self, Self::Error> {
-        if value == SEM::ZERO {
-            return Ok(0.0);
+        if value.e == i128::MIN {
+            return Ok(if value.s { -0.0 } else { 0.0 });
+        }
+        if let Some(field) = value.nan_field(51) {
+            let sign_bit = (value.s as u64) << 63;
+            return Ok(f64::from_bits(sign_bit | 0x7FF << 52 | field? as u64));
         }
         if value == SEM::POS_INF {
             return Ok(f64::INFINITY);
@@ -328,8 +417,12 @@ impl TryFrom<SEM> for f64 {
 impl TryFrom<SEM> for f32 {
     type Error = FromErr;
     fn try_from(value: SEM) -> Result<Self, Self::Error> {
-        if value == SEM::ZERO {
-            return Ok(0.0);
+        if value.e == i128::MIN {
+            return Ok(if value.s { -0.0 } else { 0.0 });
+        }
+        if let Some(field) = value.nan_field(22) {
+            let sign_bit = (value.s as u32) << 31;
+            return Ok(f32::from_bits(sign_bit | 0xFF << 23 | field? as u32));
         }
         if value == SEM::POS_INF {
             return Ok(f32::INFINITY);
@@ -418,6 +511,34 @@ pub enum FromStrErr {
     MalformedExpression,
 }
 
+/// A sign and magnitude limbs, least significant first, to build a number
+/// from.
+pub trait SignVal {
+    fn sign_and_val(self) -> (bool, Vec<u64>);
+}
+
+macro_rules! impl_sign_val_prim {
+    ($($t:ty),+; |$x:ident| $sign:expr, $mag:expr) => {
+        $(
+        impl SignVal for $t {
+            fn sign_and_val(self) -> (bool, Vec<u64>) {
+                let $x = self;
+                let m = $mag as u128;
+                ($sign, vec![m as u64, (m >> 64) as u64])
+            }
+        }
+
+        impl SignVal for &$t {
+            fn sign_and_val(self) -> (bool, Vec<u64>) {
+                (*self).sign_and_val()
+            }
+        }
+        )+
+    };
+}
+impl_sign_val_prim!(i128, i64, i32, i16, i8, isize; |x| x < 0, x.unsigned_abs());
+impl_sign_val_prim!(u128, u64, u32, u16, u8, usize; |x| false, x);
+
 pub trait Abs {
     type I;
     type U;
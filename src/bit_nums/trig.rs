@@ -0,0 +1,473 @@
+use super::bitfloat::{
+    add_sub_bf, div_bf, invalid, mul_bf, rel_err, ziv_loop, ziv_loop_n, BitFloat, RoundingMode,
+};
+use super::constants::pi_approx;
+use super::exp_log::{bit_len, exp_param};
+use super::roots::sqrt_bf;
+use super::traits::Rounding;
+
+// num π / 2^sh correctly rounded to prec bits
+fn pi_frac(num: i32, sh: usize, prec: usize) -> BitFloat {
+    ziv_loop(prec, |w| {
+        let y = (pi_approx(w) * num) >> sh;
+        let err = rel_err(&y, 8, w);
+        (y, err)
+    })
+}
+
+// Binary splitting of sum_{n = a + 1}^{b} prod_{i = a + 1}^{n} x / ((2i + off)(2i + off + 1))
+// = T / Q, with P = x^(b - a) and Q exact.
+fn bin_split_trig(
+    a: usize,
+    b: usize,
+    x: &BitFloat,
+    off: usize,
+    w: usize,
+) -> (BitFloat, BitFloat, BitFloat) {
+    if b - a == 1 {
+        let n = 2 * b + off;
+        return (x.clone(), BitFloat::from(n * (n + 1)), x.clone());
+    }
+    let mid = (a + b) / 2;
+    let (pl, ql, tl) = bin_split_trig(a, mid, x, off, w);
+    let (pr, qr, tr) = bin_split_trig(mid, b, x, off, w);
+    let p = mul_bf(&pl, &pr, w);
+    let q = mul_bf(&ql, &qr, usize::MAX);
+    let t = add_sub_bf(&mul_bf(&tl, &qr, w), &mul_bf(&pl, &tr, w), false, w);
+    (p, q, t)
+}
+
+// sin(r), 1 - cos(r) for 0 < |r| < 1 and the bits of relative error lost at
+// w limbs. The series run on r / 2^l and the halvings are undone with
+// sin 2a = 2 sin a (1 - vers a) and vers 2a = 2 sin^2 a, which never cancel.
+fn sin_vers_kernel(r: &BitFloat, w: usize) -> (BitFloat, BitFloat, i128) {
+    let (l, k) = exp_param(w);
+    let k = k / 2 + 1;
+    let a = r >> l;
+    let mut x = mul_bf(&a, &a, w);
+    x.neg_mut();
+
+    let one = BitFloat::one();
+    let (_, q, t) = bin_split_trig(0, k, &x, 0, w);
+    let mut s = mul_bf(&a, &add_sub_bf(&one, &div_bf(&t, &q, w), false, w), w);
+    let (_, q, t) = bin_split_trig(0, k, &x, 1, w);
+    let mut v = mul_bf(&x, &add_sub_bf(&one, &div_bf(&t, &q, w), false, w), w) >> 1_usize;
+    v.neg_mut();
+
+    for _ in 0..l {
+        let c = add_sub_bf(&one, &v, true, w);
+        v = mul_bf(&s, &s, w) << 1_usize;
+        s = mul_bf(&s, &c, w) << 1_usize;
+    }
+    (s, v, 2 * bit_len(k as u128) + bit_len(l as u128) + 10)
+}
+
+// k mod 4 for an integral k
+fn quadrant(k: &BitFloat) -> usize {
+    if k.is_zero() || k.lo() > 0 {
+        return 0;
+    }
+    let q = (k.get_m()[0] & 3) as usize;
+    if k.get_s() {
+        (4 - q) % 4
+    } else {
+        q
+    }
+}
+
+// sin and cos of k π/2 + r from sin r and cos r
+fn by_quadrant(s: BitFloat, c: BitFloat, q: usize) -> (BitFloat, BitFloat) {
+    match q {
+        0 => (s, c),
+        1 => (c, -s),
+        2 => (-s, -c),
+        _ => (-c, s),
+    }
+}
+
+// x = k π/2 + r with |r| just over π/4 at most, r rounded to w + 1 limbs and
+// good to 64 (w + 1) bits. π is taken to as many limbs as the cancellation in
+// x - k π/2 turns out to need.
+fn reduce_trig(x: &BitFloat, w: usize) -> (BitFloat, usize) {
+    let bx = x.bit_exp();
+    if bx < -1 {
+        return (x.to_prec(64 * (w + 1)), 0);
+    }
+
+    let wk = (bx / 64) as usize + 2;
+    let mut extra = 1;
+    loop {
+        let wr = w + 2 + extra + wk;
+        let pio2 = pi_approx(wr) >> 1_usize;
+        let k = div_bf(x, &pio2, wk).round();
+        if k.is_zero() {
+            return (x.to_prec(64 * (w + 1)), 0);
+        }
+        let r = add_sub_bf(x, &mul_bf(&k, &pio2, usize::MAX), true, usize::MAX);
+        // pi_approx(wr) is within an ulp, which k scales up
+        let err = k.bit_exp() + 8 - 64 * (wr as i128 - 1);
+        if !r.is_zero() && r.bit_exp() - err >= 64 * (w as i128 + 1) {
+            return (r.to_prec(64 * (w + 1)), quadrant(&k));
+        }
+        extra *= 2;
+    }
+}
+
+// sin(x) and cos(x) for finite x at w limbs and the bits of relative error
+// lost
+pub(crate) fn sin_cos_approx(x: &BitFloat, w: usize) -> (BitFloat, BitFloat, i128) {
+    let (r, q) = reduce_trig(x, w);
+    let (s, v, lost) = sin_vers_kernel(&r, w);
+    let c = add_sub_bf(&BitFloat::one(), &v, true, w);
+    let (sin, cos) = by_quadrant(s, c, q);
+    (sin, cos, lost + 3)
+}
+
+// x = n/2 + f exactly, with |f| <= 1/4 and q = n mod 4
+fn reduce_pi(x: &BitFloat) -> (BitFloat, usize) {
+    let n = (x << 1_usize).round();
+    let f = add_sub_bf(x, &(&n >> 1_usize), true, usize::MAX);
+    (f, quadrant(&n))
+}
+
+fn sin_cos_pi_approx(f: &BitFloat, q: usize, w: usize) -> (BitFloat, BitFloat, i128) {
+    let t = mul_bf(f, &pi_approx(w + 1), w + 1);
+    let (s, v, lost) = sin_vers_kernel(&t, w);
+    let c = add_sub_bf(&BitFloat::one(), &v, true, w);
+    let (sin, cos) = by_quadrant(s, c, q);
+    (sin, cos, lost + 3)
+}
+
+// sin and cos of q π/2
+fn quadrant_exact(q: usize) -> (BitFloat, BitFloat) {
+    by_quadrant(BitFloat::zero(), BitFloat::one(), q)
+}
+
+// sin(π x) and cos(π x) for finite x at w limbs and the bits of relative
+// error lost, exact when 2x is an integer
+pub(crate) fn sin_cos_pi_kernel(x: &BitFloat, w: usize) -> (BitFloat, BitFloat, i128) {
+    let (f, q) = reduce_pi(x);
+    if f.is_zero() {
+        let (s, c) = quadrant_exact(q);
+        return (s, c, 0);
+    }
+    sin_cos_pi_approx(&f, q, w)
+}
+
+// Newton step y + (x cos y - sin y) cos y towards atan(x) at w limbs,
+// returning the new y, the correction and the bits lost in sin and cos
+fn atan_step(x: &BitFloat, y: &BitFloat, w: usize) -> (BitFloat, BitFloat, i128) {
+    let one = BitFloat::one();
+    let (s, c, lost) = if y.is_zero() {
+        (BitFloat::zero(), one, 0)
+    } else {
+        let (s, v, lost) = sin_vers_kernel(y, w);
+        (s, add_sub_bf(&one, &v, true, w), lost)
+    };
+    let d = add_sub_bf(&mul_bf(x, &c, w), &s, true, w);
+    let corr = mul_bf(&d, &c, w);
+    (add_sub_bf(y, &corr, false, w), corr, lost)
+}
+
+// atan(x) for finite non-zero x and its error exponent at w limbs
+fn atan_kernel(x: &BitFloat, w: usize) -> (BitFloat, i128) {
+    let one = BitFloat::one();
+    if x.abs() > one {
+        // atan(x) = ±π/2 - atan(1/x)
+        let (a, err) = atan_kernel(&div_bf(&one, x, w + 1), w);
+        let mut hp = pi_approx(w + 1) >> 1_usize;
+        if x.get_s() {
+            hp.neg_mut();
+        }
+        let y = add_sub_bf(&hp, &a, true, w);
+        let err = err.max(rel_err(&y, 10, w)) + 1;
+        return (y, err);
+    }
+
+    // Newton from an f64 seed, doubling the precision each step
+    let seed = f64::try_from(x).map_or(0.0, f64::atan);
+    let mut y = BitFloat::from(seed);
+    let mut p = 2;
+    while p < w {
+        y = atan_step(x, &y, p).0;
+        p *= 2;
+    }
+    // a step from |atan(x) - y| = d lands within 2 d^2 for |x| <= 1
+    let (y, corr, lost) = atan_step(x, &y, w);
+    let newton = if corr.is_zero() {
+        i128::MIN / 2
+    } else {
+        2 * corr.bit_exp() + 4
+    };
+    let err = newton.max(rel_err(&y, lost + 4, w));
+    (y, err)
+}
+
+// NaN for a NaN argument, or for an infinite one raising the invalid flag
+fn trig_domain(x: &BitFloat) -> Option<BitFloat> {
+    if x.is_nan() {
+        return Some(BitFloat::nan());
+    }
+    x.is_inf().then(invalid)
+}
+
+// NaN for a NaN argument, or for one outside [-1, 1] raising the invalid flag
+fn unit_domain(x: &BitFloat) -> Option<BitFloat> {
+    if x.is_nan() {
+        return Some(BitFloat::nan());
+    }
+    (x.abs() > BitFloat::one()).then(invalid)
+}
+
+impl BitFloat {
+    /// Sine, correctly rounded to `prec` bits. The argument is reduced with
+    /// as many limbs of π as it needs, so huge arguments stay accurate.
+    pub fn sin(&self, prec: usize) -> BitFloat {
+        if let Some(nan) = trig_domain(self) {
+            return nan;
+        }
+        if self.is_zero() {
+            return self.clone();
+        }
+
+        let bx = self.bit_exp();
+        ziv_loop(prec, |w| {
+            // |sin(x) - x| <= |x|^3 / 6
+            if bx < -32 * w as i128 {
+                return (self.clone(), 3 * bx + 1);
+            }
+            let (y, _, lost) = sin_cos_approx(self, w);
+            let err = rel_err(&y, lost, w);
+            (y, err)
+        })
+    }
+
+    /// Cosine, correctly rounded to `prec` bits.
+    pub fn cos(&self, prec: usize) -> BitFloat {
+        if let Some(nan) = trig_domain(self) {
+            return nan;
+        }
+        if self.is_zero() {
+            return BitFloat::one();
+        }
+
+        let bx = self.bit_exp();
+        ziv_loop(prec, |w| {
+            // |cos(x) - 1| <= x^2 / 2
+            if bx < -32 * w as i128 {
+                return (BitFloat::one(), 2 * bx + 1);
+            }
+            let (_, y, lost) = sin_cos_approx(self, w);
+            let err = rel_err(&y, lost, w);
+            (y, err)
+        })
+    }
+
+    /// Sine and cosine sharing one argument reduction, each correctly rounded
+    /// to `prec` bits.
+    pub fn sin_cos(&self, prec: usize) -> (BitFloat, BitFloat) {
+        if let Some(nan) = trig_domain(self) {
+            return (nan.clone(), nan);
+        }
+        if self.is_zero() {
+            return (self.clone(), BitFloat::one());
+        }
+
+        let bx = self.bit_exp();
+        let [sin, cos] = ziv_loop_n(prec, RoundingMode::NearestEven, |w| {
+            if bx < -32 * w as i128 {
+                return [(self.clone(), 3 * bx + 1), (BitFloat::one(), 2 * bx + 1)];
+            }
+            let (s, c, lost) = sin_cos_approx(self, w);
+            let (err_s, err_c) = (rel_err(&s, lost, w), rel_err(&c, lost, w));
+            [(s, err_s), (c, err_c)]
+        });
+        (sin, cos)
+    }
+
+    /// Tangent, correctly rounded to `prec` bits.
+    pub fn tan(&self, prec: usize) -> BitFloat {
+        if let Some(nan) = trig_domain(self) {
+            return nan;
+        }
+        if self.is_zero() {
+            return self.clone();
+        }
+
+        let bx = self.bit_exp();
+        ziv_loop(prec, |w| {
+            // |tan(x) - x| <= |x|^3 / 2 for |x| < 1/2
+            if bx < -32 * w as i128 {
+                return (self.clone(), 3 * bx + 2);
+            }
+            let (s, c, lost) = sin_cos_approx(self, w);
+            let y = div_bf(&s, &c, w);
+            let err = rel_err(&y, lost + 2, w);
+            (y, err)
+        })
+    }
+
+    /// sin(π self), correctly rounded to `prec` bits. The reduction is exact.
+    pub fn sinpi(&self, prec: usize) -> BitFloat {
+        if let Some(nan) = trig_domain(self) {
+            return nan;
+        }
+        let (f, q) = reduce_pi(self);
+        if f.is_zero() {
+            let y = quadrant_exact(q).0;
+            // a zero takes the sign of self
+            return if y.is_zero() {
+                BitFloat::make(self.get_s(), 0, &[])
+            } else {
+                y
+            };
+        }
+        ziv_loop(prec, |w| {
+            let (y, _, lost) = sin_cos_pi_approx(&f, q, w);
+            let err = rel_err(&y, lost, w);
+            (y, err)
+        })
+    }
+
+    /// cos(π self), correctly rounded to `prec` bits. The reduction is exact.
+    pub fn cospi(&self, prec: usize) -> BitFloat {
+        if let Some(nan) = trig_domain(self) {
+            return nan;
+        }
+        let (f, q) = reduce_pi(self);
+        if f.is_zero() {
+            let y = quadrant_exact(q).1;
+            return if y.is_zero() { BitFloat::zero() } else { y };
+        }
+        ziv_loop(prec, |w| {
+            let (_, y, lost) = sin_cos_pi_approx(&f, q, w);
+            let err = rel_err(&y, lost, w);
+            (y, err)
+        })
+    }
+
+    /// Arcsine in [-π/2, π/2], correctly rounded to `prec` bits. NaN if
+    /// `|self| > 1`.
+    pub fn asin(&self, prec: usize) -> BitFloat {
+        if let Some(nan) = unit_domain(self) {
+            return nan;
+        }
+        if self.is_zero() {
+            return self.clone();
+        }
+        if self.abs() == 1 {
+            return pi_frac(if self.get_s() { -1 } else { 1 }, 1, prec);
+        }
+
+        let bx = self.bit_exp();
+        let one = BitFloat::one();
+        ziv_loop(prec, |w| {
+            // |asin(x) - x| <= |x|^3 / 4 for |x| < 1/2
+            if bx < -32 * w as i128 {
+                return (self.clone(), 3 * bx + 1);
+            }
+            // asin(x) = atan(x / sqrt((1 - x)(1 + x)))
+            let a = add_sub_bf(&one, self, true, w + 2);
+            let b = add_sub_bf(&one, self, false, w + 2);
+            let d = sqrt_bf(&mul_bf(&a, &b, w + 2), 64 * (w + 1));
+            let (y, err) = atan_kernel(&div_bf(self, &d, w + 1), w);
+            let err = err.max(rel_err(&y, 4, w)) + 1;
+            (y, err)
+        })
+    }
+
+    /// Arccosine in [0, π], correctly rounded to `prec` bits. NaN if
+    /// `|self| > 1`.
+    pub fn acos(&self, prec: usize) -> BitFloat {
+        if let Some(nan) = unit_domain(self) {
+            return nan;
+        }
+        let one = BitFloat::one();
+        if *self == one {
+            return BitFloat::zero();
+        }
+        if self.is_zero() {
+            return pi_frac(1, 1, prec);
+        }
+        if *self == -1 {
+            return pi_frac(1, 0, prec);
+        }
+
+        ziv_loop(prec, |w| {
+            // acos(x) = 2 atan(sqrt((1 - x) / (1 + x)))
+            let a = add_sub_bf(&one, self, true, w + 2);
+            let b = add_sub_bf(&one, self, false, w + 2);
+            let t = sqrt_bf(&div_bf(&a, &b, w + 2), 64 * (w + 1));
+            let (y, err) = atan_kernel(&t, w);
+            let y = y << 1_usize;
+            let err = (err + 1).max(rel_err(&y, 4, w)) + 1;
+            (y, err)
+        })
+    }
+
+    /// Arctangent in [-π/2, π/2], correctly rounded to `prec` bits.
+    pub fn atan(&self, prec: usize) -> BitFloat {
+        if self.is_zero() || self.is_nan() {
+            return self.clone();
+        }
+        if self.is_inf() {
+            return pi_frac(if self.get_s() { -1 } else { 1 }, 1, prec);
+        }
+
+        let bx = self.bit_exp();
+        ziv_loop(prec, |w| {
+            // |atan(x) - x| <= |x|^3 / 3
+            if bx < -32 * w as i128 {
+                return (self.clone(), 3 * bx + 2);
+            }
+            atan_kernel(self, w)
+        })
+    }
+
+    /// Angle of the point `(other, self)` in [-π, π], correctly rounded to
+    /// `prec` bits, matching `f64::atan2` with `self` as `y`.
+    pub fn atan2(&self, other: &BitFloat, prec: usize) -> BitFloat {
+        let (y, x) = (self, other);
+        if y.is_nan() || x.is_nan() {
+            return BitFloat::nan();
+        }
+        // a zero angle takes the sign of y, as does π for x < 0 or x = -0
+        let sign = if y.get_s() { -1 } else { 1 };
+        let zero = BitFloat::make(y.get_s(), 0, &[]);
+        if y.is_inf() || x.is_inf() {
+            return match (y.is_inf(), x.is_inf(), x.get_s()) {
+                (true, true, false) => pi_frac(sign, 2, prec),
+                (true, true, true) => pi_frac(3 * sign, 2, prec),
+                (true, false, _) => pi_frac(sign, 1, prec),
+                (false, _, false) => zero,
+                (false, _, true) => pi_frac(sign, 0, prec),
+            };
+        }
+        if y.is_zero() {
+            return if x.get_s() {
+                pi_frac(sign, 0, prec)
+            } else {
+                zero
+            };
+        }
+        if x.is_zero() {
+            return pi_frac(sign, 1, prec);
+        }
+
+        ziv_loop(prec, |w| {
+            let (a, err) = atan_kernel(&div_bf(y, x, w + 1), w);
+            if !x.get_s() {
+                let err = err.max(rel_err(&a, 4, w)) + 1;
+                return (a, err);
+            }
+            // atan(y/x) ± π for x < 0, taking the sign of y
+            let mut p = pi_approx(w + 1);
+            if y.get_s() {
+                p.neg_mut();
+            }
+            let out = add_sub_bf(&a, &p, false, w);
+            let err = err.max(rel_err(&out, 10, w)) + 1;
+            (out, err)
+        })
+    }
+}
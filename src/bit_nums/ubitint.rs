@@ -1,4 +1,5 @@
-use super::traits::{DivRem, FromErr, FromStrErr, LogI, PowI, SmallBuf, Sqr, U};
+use super::scratch::{sealed::Limbs, with_local, ScratchInt};
+use super::traits::{DivRem, FromErr, FromStrErr, LogI, PowI, SignVal, SmallBuf, Sqr, U};
 use crate::utils::{div::*, mul::*, utils::*};
 use crate::{impl_commutative, impl_commutative_div_rem, impl_commutative_peq_pord};
 use core::fmt;
@@ -30,8 +31,65 @@ impl UBitInt {
     pub fn is_zero(&self) -> bool {
         self.data.is_empty()
     }
+
+    // The `*_into` methods write their result into `out`, reusing its
+    // allocation, and run the kernels on a per-thread `Scratch`.
+
+    pub fn add_into(&self, rhs: &UBitInt, out: &mut UBitInt) {
+        out.data.clear();
+        out.data.extend_from_slice(&self.data);
+        add_assign_ubi(out, &rhs.data);
+    }
+
+    pub fn sub_into(&self, rhs: &UBitInt, out: &mut UBitInt) {
+        out.data.clear();
+        out.data.extend_from_slice(&self.data);
+        let of = sub_ubi(out, &rhs.data);
+        debug_assert!(!of, "attempt to subtract with overflow");
+    }
+
+    pub fn mul_into(&self, rhs: &UBitInt, out: &mut UBitInt) {
+        with_local(|ws| ws.mul_into(self, rhs, out));
+    }
+
+    pub fn square_into(&self, out: &mut UBitInt) {
+        with_local(|ws| ws.sqr_into(self, out));
+    }
+
+    pub fn div_rem_into(&self, rhs: &UBitInt, q: &mut UBitInt, r: &mut UBitInt) {
+        with_local(|ws| ws.div_rem_into(self, rhs, q, r));
+    }
+
+    /// `self += a * b`
+    pub fn add_mul(&mut self, a: &UBitInt, b: &UBitInt) {
+        with_local(|ws| add_assign_ubi(self, ws.product(&a.data, &b.data)));
+    }
+
+    /// `self -= a * b`
+    pub fn sub_mul(&mut self, a: &UBitInt, b: &UBitInt) {
+        let of = with_local(|ws| sub_ubi(self, ws.product(&a.data, &b.data)));
+        debug_assert!(!of, "attempt to subtract with overflow");
+    }
 }
 
+impl Limbs for UBitInt {
+    fn limbs(&self) -> &[u64] {
+        &self.data
+    }
+
+    fn limbs_mut(&mut self) -> &mut Vec<u64> {
+        &mut self.data
+    }
+
+    fn sign(&self) -> bool {
+        false
+    }
+
+    fn set_sign(&mut self, _sign: bool) {}
+}
+
+impl ScratchInt for UBitInt {}
+
 impl<T: Into<SmallBuf>> From<T> for UBitInt {
     fn from(value: T) -> Self {
         UBitInt {
@@ -68,6 +126,18 @@ impl TryFrom<&UBitInt> for u64 {
     }
 }
 
+impl SignVal for UBitInt {
+    fn sign_and_val(self) -> (bool, Vec<u64>) {
+        (false, self.data)
+    }
+}
+
+impl SignVal for &UBitInt {
+    fn sign_and_val(self) -> (bool, Vec<u64>) {
+        (false, self.data.clone())
+    }
+}
+
 impl FromStr for UBitInt {
     type Err = FromStrErr;
     fn from_str(str: &str) -> Result<Self, FromStrErr> {
@@ -0,0 +1,153 @@
+use super::bitfloat::{
+    add_sub_bf, int_limbs, invalid, mul_bf, quietly, shl_shr_bf, BitFloat, RoundingMode,
+};
+use super::traits::Rounding;
+use super::ubitint::UBitInt;
+
+// 2^(e - p + 1), the gap between p-bit values in the binade of 2^e
+fn ulp_exp(e: i128, p: usize) -> BitFloat {
+    BitFloat::pow2(e - p as i128 + 1)
+}
+
+// The p-bit values above zero, numbered in order: the largest at or below
+// |x| for finite non-zero x is e 2^(p-1) + floor(|x| 2^(p-1-e)), e being the
+// exponent of x, so that the count between two of them is a difference.
+fn step_index(x: &BitFloat, p: usize) -> BitFloat {
+    let e = x.bit_exp();
+    let mut m = x.abs();
+    shl_shr_bf(&mut m, p as i128 - 1 - e);
+    let base = mul_bf(
+        &BitFloat::from(e),
+        &BitFloat::pow2(p as i128 - 1),
+        usize::MAX,
+    );
+    add_sub_bf(&base, &m.floor(), false, usize::MAX)
+}
+
+impl BitFloat {
+    /// The least value of `prec` bits above `self`. Infinities are fixed
+    /// points, while zero has no neighbour in an unbounded exponent range and
+    /// gives NaN, raising the invalid flag.
+    pub fn next_up(&self, prec: usize) -> BitFloat {
+        if self.is_nan() {
+            return BitFloat::nan();
+        }
+        if self.is_inf() {
+            return self.clone();
+        }
+        if self.is_zero() {
+            return invalid();
+        }
+
+        let p = prec.max(1);
+        let up = quietly(|| self.to_prec_round(p, RoundingMode::TowardPositive));
+        if up != *self {
+            return up;
+        }
+        // a power of two steps down into the finer binade below it
+        let e = self.bit_exp();
+        let gap = match (self.get_s(), self.pow2_exp().is_some()) {
+            (true, true) => ulp_exp(e - 1, p),
+            _ => ulp_exp(e, p),
+        };
+        add_sub_bf(self, &gap, false, usize::MAX)
+    }
+
+    /// The greatest value of `prec` bits below `self`, mirroring `next_up`.
+    pub fn next_down(&self, prec: usize) -> BitFloat {
+        let mut out = (-self).next_up(prec);
+        if !out.is_nan() {
+            out.neg_mut();
+        }
+        out
+    }
+
+    /// The gap between `prec` bit values in the binade of `self`,
+    /// 2^(exponent - prec + 1). Infinite for the infinities, and NaN for
+    /// zero, raising the invalid flag.
+    pub fn ulp(&self, prec: usize) -> BitFloat {
+        if self.is_nan() {
+            return BitFloat::nan();
+        }
+        if self.is_inf() {
+            return BitFloat::inf();
+        }
+        if self.is_zero() {
+            return invalid();
+        }
+        ulp_exp(self.bit_exp(), prec.max(1))
+    }
+
+    /// `(m, e)` with `self = m * 2^e` and `1/2 <= |m| < 1`. Zero, the
+    /// infinities and NaN come back unchanged with `e = 0`.
+    pub fn frexp(&self) -> (BitFloat, i128) {
+        if self.is_zero() || !self.is_finite() {
+            return (self.clone(), 0);
+        }
+        let e = self.bit_exp() + 1;
+        (self.ldexp(-e), e)
+    }
+
+    /// `self * 2^e`, exactly.
+    pub fn ldexp(&self, e: i128) -> BitFloat {
+        let mut out = self.clone();
+        shl_shr_bf(&mut out, e);
+        out
+    }
+
+    /// floor(log2|self|), or `None` for zero, the infinities and NaN.
+    pub fn exponent(&self) -> Option<i128> {
+        (!self.is_zero() && self.is_finite()).then(|| self.bit_exp())
+    }
+
+    /// The odd integer `m` with `|self| = m * 2^(exponent - bits(m) + 1)`,
+    /// holding every significant bit of `self`. Zero for zero, the
+    /// infinities and NaN.
+    pub fn significand(&self) -> UBitInt {
+        if self.is_zero() || !self.is_finite() {
+            return UBitInt::zero();
+        }
+        let mut m = self.abs();
+        shl_shr_bf(&mut m, self.prec() as i128 - 1 - self.bit_exp());
+        UBitInt::make(int_limbs(&m))
+    }
+
+    /// The magnitude of `self` with the sign of `sign`.
+    pub fn copysign(&self, sign: &BitFloat) -> BitFloat {
+        let mut out = self.clone();
+        if out.get_s() != sign.get_s() {
+            out.neg_mut();
+        }
+        out
+    }
+
+    /// How many values of `prec` bits lie between `self` and `other`,
+    /// counting those above the smaller magnitude up to the larger one: the
+    /// number of `next_up` steps between them when both have `prec` bits.
+    /// `None` if either is NaN or infinite, or they lie on either side of
+    /// zero, unless the two are equal.
+    pub fn ulp_distance(&self, other: &BitFloat, prec: usize) -> Option<UBitInt> {
+        if self.is_nan() || other.is_nan() {
+            return None;
+        }
+        if self == other {
+            return Some(UBitInt::zero());
+        }
+        if !self.is_finite()
+            || !other.is_finite()
+            || self.is_zero()
+            || other.is_zero()
+            || self.get_s() != other.get_s()
+        {
+            return None;
+        }
+        let p = prec.max(1);
+        let d = add_sub_bf(
+            &step_index(self, p),
+            &step_index(other, p),
+            true,
+            usize::MAX,
+        );
+        Some(UBitInt::make(int_limbs(&d)))
+    }
+}
@@ -0,0 +1,183 @@
+use super::bitfloat::{
+    add_sub_bf, div_bf, invalid, mul_bf, overflow, raise, rel_err, ziv_loop, BitFloat, Flags,
+};
+use super::constants::{ln2_approx, pi_approx};
+use super::exp_log::{bit_len, exp_kernel, expm1_kernel, ln_kernel, EXP_MAX_BITS};
+use super::gamma::lgamma_pos;
+use super::traits::Rounding;
+use super::trig::sin_cos_pi_kernel;
+
+// 1 - 2^(1 - s) = -expm1((1 - s) ln 2) for s >= 1/2, s != 1, at w limbs and
+// its bits of relative error lost
+fn one_minus_pow2(s: &BitFloat, w: usize) -> (BitFloat, i128) {
+    let one = BitFloat::one();
+    let s1 = add_sub_bf(&one, s, true, usize::MAX);
+    let t = mul_bf(&s1, &ln2_approx(w + 1), w + 1);
+    let (y, lost) = if t.bit_exp() < -1 {
+        expm1_kernel(&t, w)
+    } else {
+        // t <= -1/2 keeps 1 - e^t above 2/5
+        let (e, lost) = exp_kernel(&t, w + 1);
+        (add_sub_bf(&e, &one, true, w), lost + 2)
+    };
+    (-y, lost + 2)
+}
+
+// k^(-s) for k = 1 ..= n at w limbs, and the bits of relative error lost in
+// any of them. Only primes take an exp and a ln; the rest are products of
+// two earlier powers.
+fn inv_powers(s: &BitFloat, n: usize, w: usize) -> (Vec<BitFloat>, i128) {
+    let mut pw = vec![BitFloat::one(); n + 1];
+    let mut lost = 0;
+    for k in 2..=n {
+        match (2..k).take_while(|d| d * d <= k).find(|d| k % d == 0) {
+            Some(d) => pw[k] = mul_bf(&pw[d], &pw[k / d], w),
+            None => {
+                let (l, lost_l) = ln_kernel(&BitFloat::from(k), w);
+                let t = mul_bf(s, &l, w);
+                let (p, lost_e) = exp_kernel(&-&t, w);
+                // the relative error of t scales up by its size in e^-t
+                lost = lost.max(lost_e.max(lost_l) + t.bit_exp().max(0) + 2);
+                pw[k] = p;
+            }
+        }
+    }
+    // a power of k is a product of at most log2(k) prime powers
+    (pw, lost + 2 * bit_len(n as u128))
+}
+
+// The Dirichlet eta function η(s) = sum_k (-1)^k / (k + 1)^s for s >= 1/2 by
+// Borwein's algorithm 2, sum_{k < n} (-1)^k (1 - d_k / d_n) / (k + 1)^s
+// with d_k = n sum_{i <= k} (n + i - 1)! 4^i / ((n - i)! (2i)!), which is
+// within 3 / (3 + √8)^n < 2^(2 - 2.54n). Returns η at w limbs and its error
+// exponent.
+fn eta_borwein(s: &BitFloat, w: usize) -> (BitFloat, i128) {
+    let wp = w + 1;
+    let bits = 64 * w as i128 + 8;
+    let n = (bits as f64 / 2.54).ceil() as usize + 1;
+
+    // the terms of d_n, each 4 (n + i - 1) (n - i + 1) / (2i (2i - 1)) times
+    // the one before
+    let mut d = Vec::with_capacity(n + 1);
+    let mut e = BitFloat::one();
+    d.push(e.clone());
+    for i in 1..=n {
+        let num = 4 * (n + i - 1) as u128 * (n - i + 1) as u128;
+        let den = (2 * i * (2 * i - 1)) as u128;
+        e = div_bf(
+            &mul_bf(&e, &BitFloat::from(num), wp),
+            &BitFloat::from(den),
+            wp,
+        );
+        d.push(add_sub_bf(&d[i - 1], &e, false, wp));
+    }
+
+    let (pw, lost) = inv_powers(s, n, wp);
+    let mut sum = BitFloat::zero();
+    for k in 0..n {
+        let c = div_bf(&add_sub_bf(&d[n], &d[k], true, wp), &d[n], wp);
+        let term = mul_bf(&c, &pw[k + 1], wp);
+        sum = add_sub_bf(&sum, &term, k % 2 == 1, wp);
+    }
+    // every coefficient is within about 6n ulps of its magnitude below 1
+    let err = 2 * bit_len(n as u128) + lost + 4 - 64 * w as i128;
+    (sum, err.max(2 - (2.54 * n as f64) as i128))
+}
+
+// ζ(s) = η(s) / (1 - 2^(1 - s)) for s >= 1/2, s != 1, at w limbs and its
+// error exponent
+fn zeta_borwein(s: &BitFloat, w: usize) -> (BitFloat, i128) {
+    let (eta, err_eta) = eta_borwein(s, w + 1);
+    let (d, lost) = one_minus_pow2(s, w + 1);
+    let y = div_bf(&eta, &d, w);
+    // η(s) > 1/2 here, so its absolute error bounds its relative one
+    let err = rel_err(&y, lost + 2, w).max(y.bit_exp() + err_eta + 2);
+    (y, err)
+}
+
+// ln(2^s π^(s - 1) Γ(1 - s)) for s < 1/2 at w limbs and its error exponent
+fn reflect_log(s: &BitFloat, w: usize) -> (BitFloat, i128) {
+    let one = BitFloat::one();
+    let u = add_sub_bf(&one, s, true, usize::MAX);
+    let (lg, err_g) = lgamma_pos(&u, w);
+    let (lpi, lost_pi) = ln_kernel(&pi_approx(w + 1), w);
+    let a = mul_bf(s, &ln2_approx(w), w);
+    let b = mul_bf(&add_sub_bf(s, &one, true, w), &lpi, w);
+    let y = add_sub_bf(&add_sub_bf(&a, &b, false, w), &lg, false, w);
+    let err = err_g
+        .max(rel_err(&a, 2, w))
+        .max(rel_err(&b, lost_pi + 3, w))
+        .max(rel_err(&y, 1, w));
+    (y, err + 2)
+}
+
+// ζ(s) for s < 1/2 at w limbs through the functional equation
+// ζ(s) = 2^s π^(s - 1) sin(π s / 2) Γ(1 - s) ζ(1 - s), with the factors in
+// front of the sine found as one exponential
+fn zeta_reflect(s: &BitFloat, w: usize, wl: usize) -> (BitFloat, i128) {
+    let wp = w + 1;
+    let u = add_sub_bf(&BitFloat::one(), s, true, usize::MAX);
+    let (z, err_z) = zeta_borwein(&u, wp);
+    let (l, err_l) = reflect_log(s, wp + wl);
+    let (e, lost_e) = exp_kernel(&l, wp);
+    let (sn, _, lost_s) = sin_cos_pi_kernel(&(s >> 1_usize), wp);
+    let y = mul_bf(&mul_bf(&e, &sn, wp), &z, w);
+    // the relative errors of the three factors add up
+    let err = (lost_e.max(lost_s) + 2 - 64 * w as i128)
+        .max(err_l + 1)
+        .max(err_z - z.bit_exp());
+    let err = y.bit_exp() + err + 3;
+    (y, err)
+}
+
+impl BitFloat {
+    /// Riemann zeta function of a real argument, correctly rounded to `prec`
+    /// bits. +∞ at the pole s = 1 and NaN at -∞.
+    pub fn zeta(&self, prec: usize) -> BitFloat {
+        if self.is_nan() {
+            return BitFloat::nan();
+        }
+        if self.is_inf() {
+            return if self.get_s() {
+                invalid()
+            } else {
+                BitFloat::one()
+            };
+        }
+        if self.is_zero() {
+            return BitFloat::from(-0.5);
+        }
+        if *self == 1 {
+            raise(Flags::DIV_BY_ZERO);
+            return BitFloat::inf();
+        }
+        // the trivial zeros
+        if self.get_s() && self.is_integer() && !self.is_odd() {
+            return BitFloat::zero();
+        }
+
+        if *self >= 0.5 {
+            // ζ(s) - 1 < 2^(2 - s) for s >= 2
+            let s_int = if self.bit_exp() < 62 {
+                i128::try_from(&self.floor()).unwrap()
+            } else {
+                1 << 62
+            };
+            return ziv_loop(prec, |w| {
+                if s_int > 64 * w as i128 + 8 {
+                    return (BitFloat::one(), 2 - s_int);
+                }
+                zeta_borwein(self, w)
+            });
+        }
+
+        let (l0, _) = reflect_log(self, 1);
+        if l0.bit_exp() >= EXP_MAX_BITS {
+            // ζ(1 - s) > 1 leaves the sign to the sine
+            let (sn, _, _) = sin_cos_pi_kernel(&(self >> 1_usize), 1);
+            return overflow(sn.get_s());
+        }
+        let wl = (l0.bit_exp().max(0) / 64) as usize + 1;
+        ziv_loop(prec, |w| zeta_reflect(self, w, wl))
+    }
+}
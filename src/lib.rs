@@ -4,9 +4,11 @@ extern crate core;
 pub mod bit_nums;
 pub(crate) mod utils;
 
+pub use utils::tuning::{ThresholdErr, Thresholds};
+
 #[cfg(test)]
 mod tests;
 
 #[cfg(feature = "_bench_internals")]
 #[doc(hidden)]
-pub use utils::{mul::*, utils::*};
+pub use utils::{div::*, mul::*, utils::*};
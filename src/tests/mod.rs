@@ -1,11 +1,25 @@
 mod test_utils;
 mod test_mul;
+mod test_bitfloat;
+mod test_bitfrac;
+mod test_bitball;
+mod test_bitcomplex;
+mod test_bitdecimal;
+mod test_bitfixed;
+mod test_bitinterval;
 mod test_div;
+mod test_scratch;
+mod test_tuning;
 
+use crate::bit_nums::bitball::BitBall;
+use crate::bit_nums::bitfloat::{BitFloat, RoundingMode};
+use crate::bit_nums::bitinterval::BitInterval;
 use crate::utils::mul::mul_vec;
 use crate::utils::utils::{acc, eq_buf, trim_lz};
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
+use std::fmt::Debug;
+use std::ops::Range;
 
 /// Decode a small buffer (≤2 limbs) to u128 for easy expected-value arithmetic.
 pub(super) fn to_u128(buf: &[u64]) -> u128 {
@@ -64,3 +78,69 @@ pub(super) fn rand_nonzero_vec(len: usize, seed: u64) -> Vec<u64> {
     }
     v
 }
+
+/// A random f64 of either sign, with magnitude under 2^e for e drawn from `exps`.
+pub(super) fn rand_scaled(rng: &mut StdRng, exps: Range<i32>) -> f64 {
+    rng.gen_range(-1.0..1.0) * 2f64.powi(rng.gen_range(exps))
+}
+
+/// `n` random f64s of either sign and magnitudes between 2^-80 and 2^80.
+pub(super) fn rand_f64s(n: usize, seed: u64) -> Vec<f64> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..n).map(|_| rand_scaled(&mut rng, -80..80)).collect()
+}
+
+/// A function on enclosures and the float function it encloses.
+pub(super) type Unary<T> = (fn(&T) -> T, fn(&BitFloat) -> BitFloat);
+
+/// An enclosure of a set of floats, as the interval and ball types are.
+pub(super) trait Enclosure: Debug {
+    /// The exact lower and upper ends.
+    fn ends(&self) -> (BitFloat, BitFloat);
+    fn holds(&self, x: &BitFloat) -> bool;
+}
+
+impl Enclosure for BitInterval {
+    fn ends(&self) -> (BitFloat, BitFloat) {
+        (self.lower().clone(), self.upper().clone())
+    }
+    fn holds(&self, x: &BitFloat) -> bool {
+        self.contains(x)
+    }
+}
+
+impl Enclosure for BitBall {
+    fn ends(&self) -> (BitFloat, BitFloat) {
+        let exact = RoundingMode::NearestEven;
+        (
+            self.mid().sub_round(self.rad(), usize::MAX, exact),
+            self.mid().add_round(self.rad(), usize::MAX, exact),
+        )
+    }
+    fn holds(&self, x: &BitFloat) -> bool {
+        self.contains(x)
+    }
+}
+
+/// The ends of x and the point halfway between them.
+pub(super) fn samples<T: Enclosure>(x: &T) -> [BitFloat; 3] {
+    let (lo, hi) = x.ends();
+    let mid = lo.add_round(&hi, usize::MAX, RoundingMode::NearestEven) >> 1_usize;
+    [lo, mid, hi]
+}
+
+/// Check that each function in `fs` maps every x in `xs` to an enclosure of
+/// its float function at the samples of x, where that is a number.
+pub(super) fn assert_encloses<T: Enclosure>(xs: &[T], fs: &[Unary<T>]) {
+    for x in xs {
+        for (enc_f, f) in fs {
+            let got = enc_f(x);
+            for s in samples(x) {
+                let z = f(&s);
+                if !z.is_nan() {
+                    assert!(got.holds(&z), "{got:?} misses {z:?} at {s:?}");
+                }
+            }
+        }
+    }
+}
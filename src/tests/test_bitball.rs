@@ -0,0 +1,189 @@
+use super::{assert_encloses, rand_scaled, samples, Unary};
+use crate::bit_nums::bitball::{evaluate_to, BitBall};
+use crate::bit_nums::bitfloat::{BitFloat, RoundingMode};
+use crate::bit_nums::constants;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const P: usize = 300;
+
+fn ball(mid: f64, rad: f64) -> BitBall {
+    BitBall::new(BitFloat::from(mid), BitFloat::from(rad)).unwrap()
+}
+
+/// Random balls of mixed sign and magnitude, with radii from none up to
+/// about a hundredth of the midpoint.
+fn rand_balls(n: usize, seed: u64) -> Vec<BitBall> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..n)
+        .map(|_| {
+            let mid = rand_scaled(&mut rng, -10..10);
+            let rad = match rng.gen_range(0..3) {
+                0 => 0.0,
+                _ => mid.abs() * 2f64.powi(rng.gen_range(-60..-7)),
+            };
+            ball(mid, rad)
+        })
+        .collect()
+}
+
+// ─── Construction and queries ───────────────────────────────────────────────
+
+#[test]
+fn test_bitball_construction() {
+    assert!(BitBall::new(BitFloat::nan(), BitFloat::zero()).is_none());
+    assert!(BitBall::new(BitFloat::inf(), BitFloat::zero()).is_none());
+    assert!(BitBall::new(BitFloat::one(), BitFloat::from(-1)).is_none());
+    assert!(BitBall::new(BitFloat::one(), BitFloat::nan()).is_none());
+
+    let x = ball(1.5, 0.0);
+    assert!(x.is_exact() && x.is_finite());
+    assert_eq!(x.rel_accuracy_bits(), i128::MAX);
+    assert!(BitBall::from(BitFloat::nan()).mid().is_nan());
+    assert!(!BitBall::from(BitFloat::inf()).is_finite());
+
+    // the radius keeps a few bits, rounded up
+    let r = BitFloat::from(1.0 + f64::EPSILON);
+    let y = BitBall::new(BitFloat::from(8), r.clone()).unwrap();
+    assert!(*y.rad() > r && y.rad().prec() <= 30);
+    assert_eq!(y.rel_accuracy_bits(), 3);
+}
+
+#[test]
+fn test_bitball_queries() {
+    let x = ball(2.0, 0.5);
+    assert!(x.contains(&BitFloat::from(1.5)));
+    assert!(x.contains(&BitFloat::from(2.5)));
+    assert!(!x.contains(&BitFloat::from(2.75)));
+    assert!(!x.contains(&BitFloat::nan()));
+    assert!(BitBall::indeterminate().contains(&BitFloat::from(1e300)));
+
+    let iv = x.to_interval();
+    assert_eq!(*iv.lower(), 1.5);
+    assert_eq!(*iv.upper(), 2.5);
+    assert!(BitBall::indeterminate().to_interval().width().is_inf());
+
+    assert_eq!(ball(1.0, 2f64.powi(-40)).rel_accuracy_bits(), 40);
+    assert_eq!(ball(0.0, 1.0).rel_accuracy_bits(), i128::MIN);
+}
+
+// ─── Error propagation ──────────────────────────────────────────────────────
+
+#[test]
+fn test_bitball_arithmetic_encloses() {
+    let xs = rand_balls(80, 3019);
+    for pair in xs.chunks(2) {
+        let (a, b) = (&pair[0], &pair[1]);
+        let results = [a + b, a - b, a * b, a / b];
+        let ops: [fn(&BitFloat, &BitFloat) -> BitFloat; 4] = [
+            |x, y| x.add_round(y, P, RoundingMode::NearestEven),
+            |x, y| x.sub_round(y, P, RoundingMode::NearestEven),
+            |x, y| x.mul_round(y, P, RoundingMode::NearestEven),
+            |x, y| x.div_round(y, P, RoundingMode::NearestEven),
+        ];
+        for (got, op) in results.iter().zip(ops) {
+            assert!(got.is_finite());
+            for x in samples(a) {
+                for y in samples(b) {
+                    let z = op(&x, &y);
+                    assert!(got.contains(&z), "{got:?} misses {z:?}");
+                }
+            }
+            // the radius stays close to what the operands carry
+            let carried = a.rel_accuracy_bits().min(b.rel_accuracy_bits()).min(60);
+            assert!(got.rel_accuracy_bits() >= carried - 4, "{got:?}");
+        }
+    }
+}
+
+#[test]
+fn test_bitball_functions_enclose() {
+    let xs = rand_balls(40, 3020);
+    let fs: [Unary<BitBall>; 5] = [
+        (|x| x.exp(64), |x| x.exp(P)),
+        (|x| x.sin(64), |x| x.sin(P)),
+        (|x| x.cos(64), |x| x.cos(P)),
+        (
+            |x| (x * x).sqrt(64),
+            |x| x.mul_round(x, P, RoundingMode::NearestEven).sqrt(P),
+        ),
+        (
+            |x| (x * x).ln(64),
+            |x| x.mul_round(x, P, RoundingMode::NearestEven).ln(P),
+        ),
+    ];
+    assert_encloses(&xs, &fs);
+    for x in &xs {
+        assert!(fs.iter().all(|(ball_f, _)| ball_f(x).is_finite()), "{x:?}");
+    }
+}
+
+#[test]
+fn test_bitball_exact_and_indeterminate() {
+    // exact operations keep a zero radius
+    assert!((ball(1.5, 0.0) + ball(2.25, 0.0)).is_exact());
+    assert!((ball(3.0, 0.0) * ball(-0.5, 0.0)).is_exact());
+    assert!(ball(9.0, 0.0).sqrt(64).is_exact());
+    assert!(ball(0.0, 0.0).exp(64).is_exact());
+    // while a rounded midpoint gains an ulp of radius
+    let third = ball(1.0, 0.0) / ball(3.0, 0.0);
+    assert!(!third.is_exact());
+    assert!(third.rel_accuracy_bits() >= 62);
+
+    assert!(!(ball(1.0, 0.0) / ball(0.5, 1.0)).is_finite());
+    assert!(!(ball(1.0, 0.0) / ball(0.0, 0.0)).is_finite());
+    assert!(!ball(0.5, 1.0).sqrt(64).is_finite());
+    assert!(!ball(0.5, 0.5).ln(64).is_finite());
+    assert!(!(BitBall::indeterminate() + ball(1.0, 0.0)).is_finite());
+    assert!(!(-BitBall::indeterminate()).is_finite());
+    // e^x past exp's reach has no finite midpoint
+    assert!(!ball(1e300, 0.0).exp(64).is_finite());
+    assert!(!ball(-1e300, 0.0).exp(64).is_finite());
+}
+
+// ─── Adaptive evaluation ────────────────────────────────────────────────────
+
+#[test]
+fn test_bitball_evaluate_to() {
+    // e^x - 1 - x for tiny x cancels most of the bits of e^x
+    let x = BitFloat::from(1e-30);
+    let got = evaluate_to(
+        |p| {
+            let x = BitBall::from(x.clone());
+            x.exp(p) - BitBall::from(BitFloat::one()) - x
+        },
+        100,
+    )
+    .unwrap();
+    assert!(got.rel_accuracy_bits() >= 100);
+    let want = x.expm1(2 * P).sub_round(&x, P, RoundingMode::NearestEven);
+    assert!(got.contains(&want), "{got:?}");
+    assert_eq!(BitFloat::default_prec(), 64);
+
+    // (π - 355/113) needs the bits of π past the fraction's 22 or so
+    let got = evaluate_to(
+        |p| {
+            let pi = BitBall::new(constants::pi(p), BitFloat::one().ldexp(1 - p as i128)).unwrap();
+            let f = BitBall::from(BitFloat::from(355)) / BitBall::from(BitFloat::from(113));
+            pi - f
+        },
+        80,
+    )
+    .unwrap();
+    assert!(got.rel_accuracy_bits() >= 80);
+    assert!(*got.mid() < 0 && *got.mid() > -1e-6);
+
+    // an exact zero computed inexactly never gains relative accuracy
+    let zero = evaluate_to(
+        |p| {
+            let pi = BitBall::new(constants::pi(p), BitFloat::one().ldexp(1 - p as i128)).unwrap();
+            pi.sin(p)
+        },
+        64,
+    );
+    assert!(zero.is_none());
+    // a panic in f still restores the default precision
+    let caught = std::panic::catch_unwind(|| evaluate_to(|_| panic!("f failed"), 64));
+    assert!(caught.is_err());
+    assert_eq!(BitFloat::default_prec(), 64);
+}
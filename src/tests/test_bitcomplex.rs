@@ -0,0 +1,203 @@
+use crate::bit_nums::bitcomplex::BitComplex;
+use crate::bit_nums::bitfloat::{BitFloat, Flags};
+use crate::bit_nums::constants;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+fn c(re: f64, im: f64) -> BitComplex {
+    BitComplex::new(BitFloat::from(re), BitFloat::from(im))
+}
+
+fn to_f64s(z: &BitComplex) -> (f64, f64) {
+    (
+        f64::try_from(z.re()).unwrap(),
+        f64::try_from(z.im()).unwrap(),
+    )
+}
+
+fn rand_complex(n: usize, seed: u64) -> Vec<BitComplex> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..n)
+        .map(|_| {
+            let re: f64 = rng.gen_range(-4.0..4.0);
+            let im: f64 = rng.gen_range(-4.0..4.0);
+            c(re, im)
+        })
+        .collect()
+}
+
+// ─── Arithmetic ─────────────────────────────────────────────────────────────
+
+#[test]
+fn test_bitcomplex_arithmetic_exact_cases() {
+    let a = c(1.0, 2.0);
+    let b = c(3.0, 4.0);
+    assert_eq!(&a + &b, c(4.0, 6.0));
+    assert_eq!(&a - &b, c(-2.0, -2.0));
+    assert_eq!(&a * &b, c(-5.0, 10.0));
+    assert_eq!(c(-5.0, 10.0) / &b, a);
+    assert_eq!(c(1.0, 1.0) / c(1.0, -1.0), BitComplex::i());
+    assert_eq!(BitComplex::i() * BitComplex::i(), c(-1.0, 0.0));
+    assert_eq!(-&a, c(-1.0, -2.0));
+    assert_eq!(a.conj(), c(1.0, -2.0));
+    assert_eq!(b.abs(53), 5);
+    assert_eq!(a.mul_gauss(&b, 53), c(-5.0, 10.0));
+}
+
+#[test]
+fn test_bitcomplex_arithmetic_correctly_rounded() {
+    let zs = rand_complex(40, 3021);
+    for pair in zs.chunks(2) {
+        let (a, b) = (&pair[0], &pair[1]);
+        let (prod, quot) = (a * b, a / b);
+        BitFloat::set_default_prec(400);
+        let (prod_hi, quot_hi) = (a * b, a / b);
+        BitFloat::set_default_prec(64);
+        // the parts of the product are exact at 400 bits
+        assert_eq!(*prod.re(), prod_hi.re().to_prec(64));
+        assert_eq!(*prod.im(), prod_hi.im().to_prec(64));
+        assert_eq!(*quot.re(), quot_hi.re().to_prec(64), "{a:?} / {b:?}");
+        assert_eq!(*quot.im(), quot_hi.im().to_prec(64), "{a:?} / {b:?}");
+
+        // Gauss's form agrees up to its intermediate rounding
+        let g = a.mul_gauss(b, 64);
+        let tol = prod.abs(64) * BitFloat::from(2f64.powi(-120));
+        assert!((g.re() - prod.re()).abs() <= tol);
+        assert!((g.im() - prod.im()).abs() <= tol);
+    }
+}
+
+// ─── Elementary functions ───────────────────────────────────────────────────
+
+#[test]
+fn test_bitcomplex_functions_match_mpmath() {
+    // (z, [exp, ln, sqrt, sin, cos, tan, z^(1/2 - 5i/4)]) from mpmath,
+    // rounded to f64
+    #[rustfmt::skip]
+    let cases = [
+        (0.5, 0.75, [(1.2063510016467853, 1.1238323225841311), (-0.10381968238912224, 0.982793723247329), (0.8370746140177701, 0.44798873806491907), (0.620704231078055, 0.7216508242975646), (1.136191473803348, -0.3942396421115833), (0.2908934618296181, 0.736084170551191), (2.6374188142543646, 1.88754528100578)]),
+        (-1.25, 2.0, [(-0.11922806486894977, 0.2605180745581855), (0.8580238237461792, 2.129395642138459), (0.7444781000856072, 1.3432228562331254), (-3.5702658458442267, 1.1436301918801897), (1.186304433105791, 3.4418347435965493), (-0.022577814528265846, 1.0295327778220809), (21.993034606786708, -0.17225205879597838)]),
+        (3.0, -0.5, [(17.62671694999471, -9.629519357553853), (1.112311775762167, -0.16514867741462683), (1.738013415533538, -0.14384238796180673), (0.15913058529844, 0.5158804424525267), (-1.116341244526152, 0.0735369737112366), (-0.11162105077158345, -0.4694699934258854), (0.13857044229529503, -1.4118865817278674)]),
+        (-0.375, -1.5, [(0.048616920317887086, -0.6855676099407111), (0.4357774190163818, -1.8157749899217608), (0.7652334969166675, -0.9800930082412135), (-0.8616230192214802, -1.981310762147027), (2.188935076843656, -0.77989657114854), (-0.06311849374710778, -0.9276367675209433), (0.015151650413582341, -0.12760312238940283)]),
+        (0.001, 20.0, [(0.408490347984267, 0.9138586526031762), (2.995732274803991, 1.5707463267949382), (3.1623567180980707, 3.162198604215112), (242582.55727446423, 242582476.4136064), (242582476.4136064, -242582.55727446423), (1.6993405692223942e-20, 1.0), (-31.330418165578287, -5.775674155863028)]),
+        (-7.5, 0.125, [(0.0005487690218416833, 6.895564638771773e-05), (2.0150418901446017, 3.1249275298758525), (0.02282098090298595, 2.7387078699944203), (-0.9453376483935719, 0.04344233993362506), (0.3493469342494621, 0.11755557426544244), (-2.3931820501698793, 0.9296610284010075), (78.4865558819889, -111.23773515005936)]),
+    ];
+    let w = c(0.5, -1.25);
+    let names = ["exp", "ln", "sqrt", "sin", "cos", "tan", "pow"];
+    for (re, im, want) in cases {
+        let z = c(re, im);
+        let got = [
+            z.exp(53),
+            z.ln(53),
+            z.sqrt(53),
+            z.sin(53),
+            z.cos(53),
+            z.tan(53),
+            z.pow(&w, 53),
+        ];
+        for ((g, want), name) in got.iter().zip(want).zip(names) {
+            // correctly rounded to 53 bits, so exactly the f64 values
+            assert_eq!(to_f64s(g), want, "{name}({re} + {im}i)");
+        }
+    }
+}
+
+#[test]
+fn test_bitcomplex_functions_correctly_rounded() {
+    let zs = rand_complex(6, 3022);
+    let w = c(-1.5, 0.75);
+    for z in &zs {
+        for p in [24, 53, 113, 300] {
+            let check = |f: &dyn Fn(usize) -> BitComplex, what: &str| {
+                let r = f(p + 100);
+                // skip exact midpoints, where rounding twice is off
+                if r.re().prec() != p + 1 && r.im().prec() != p + 1 {
+                    let want = BitComplex::new(r.re().to_prec(p), r.im().to_prec(p));
+                    assert_eq!(f(p), want, "{what} z={z:?}, p={p}");
+                }
+            };
+            check(&|q| z.exp(q), "exp");
+            check(&|q| z.ln(q), "ln");
+            check(&|q| z.sqrt(q), "sqrt");
+            check(&|q| z.sin(q), "sin");
+            check(&|q| z.cos(q), "cos");
+            check(&|q| z.tan(q), "tan");
+            check(&|q| z.pow(&w, q), "pow");
+        }
+    }
+}
+
+#[test]
+fn test_bitcomplex_branch_cuts() {
+    let pi = constants::pi(64);
+    // the sign of a zero imaginary part picks the side of the cut
+    let up = BitComplex::new(BitFloat::from(-1), BitFloat::zero());
+    let down = BitComplex::new(BitFloat::from(-1), BitFloat::neg_zero());
+    assert_eq!(up.ln(64), BitComplex::new(BitFloat::zero(), pi.clone()));
+    assert_eq!(down.ln(64), BitComplex::new(BitFloat::zero(), -&pi));
+    assert_eq!(up.arg(64), pi);
+    assert_eq!(down.arg(64), -&pi);
+
+    let four = BitFloat::from(-4);
+    let s = BitComplex::new(four.clone(), BitFloat::zero()).sqrt(64);
+    assert_eq!(s, c(0.0, 2.0));
+    assert!(!s.re().get_s());
+    let s = BitComplex::new(four, BitFloat::neg_zero()).sqrt(64);
+    assert_eq!(s, c(0.0, -2.0));
+    assert_eq!(c(0.0, 8.0).sqrt(64), c(2.0, 2.0));
+    assert_eq!(c(0.0, -8.0).sqrt(64), c(2.0, -2.0));
+
+    // the principal cube root of -8 lies off the real axis
+    let third = BitComplex::from(BitFloat::one() / BitFloat::from(3));
+    let r = c(-8.0, 0.0).pow(&third, 64);
+    let (re, im) = to_f64s(&r);
+    assert!((re - 1.0).abs() < 1e-15 && (im - 3f64.sqrt()).abs() < 1e-15);
+}
+
+#[test]
+fn test_bitcomplex_exact_and_special_values() {
+    assert_eq!(c(3.0, 4.0).sqrt(64), c(2.0, 1.0));
+    assert_eq!(c(-3.0, 4.0).sqrt(64), c(1.0, 2.0));
+    assert_eq!(c(0.0, 0.0).exp(64), c(1.0, 0.0));
+    assert_eq!(c(1.0, 0.0).ln(64), c(0.0, 0.0));
+    assert_eq!(c(0.0, 0.0).sin(64), c(0.0, 0.0));
+    assert_eq!(c(0.0, 0.0).cos(64), c(1.0, 0.0));
+    assert_eq!(c(0.0, 0.0).tan(64), c(0.0, 0.0));
+    assert_eq!(
+        c(5.0, 0.0).exp(64),
+        BitComplex::from(BitFloat::from(5).exp(64))
+    );
+    assert_eq!(
+        c(0.0, 2.0).sin(64),
+        BitComplex::new(BitFloat::zero(), BitFloat::from(2).sinh(64))
+    );
+
+    // integer powers are exact before rounding
+    let i = BitComplex::i();
+    assert_eq!(i.pow(&c(2.0, 0.0), 64), c(-1.0, 0.0));
+    assert_eq!(c(1.0, 1.0).pow(&c(-2.0, 0.0), 64), c(0.0, -0.5));
+    assert_eq!(c(1.0, 2.0).pow(&c(3.0, 0.0), 64), c(-11.0, -2.0));
+    assert_eq!(c(4.0, 0.0).pow(&c(0.5, 0.0), 64), c(2.0, 0.0));
+    assert_eq!(c(0.0, 0.0).pow(&c(2.0, 1.0), 64), c(0.0, 0.0));
+    assert_eq!(c(7.0, 3.0).pow(&c(0.0, 0.0), 64), c(1.0, 0.0));
+
+    BitFloat::clear_flags();
+    assert!(c(0.0, 0.0).pow(&c(-1.0, 0.0), 64).is_nan());
+    assert!(BitFloat::flags().invalid);
+    BitFloat::clear_flags();
+    let l = c(0.0, 0.0).ln(64);
+    assert!(l.re().is_inf() && l.re().get_s() && l.im().is_zero());
+    assert_eq!(
+        BitFloat::flags(),
+        Flags {
+            div_by_zero: true,
+            ..Flags::default()
+        }
+    );
+
+    // tan(x + iy) reaches ±i far from the real axis
+    let t = c(1.0, 1e300).tan(64);
+    assert!(t.re().is_zero() && *t.im() == 1);
+    assert!(c(f64::NAN, 1.0).exp(64).is_nan());
+    assert!(c(f64::INFINITY, 1.0).exp(64).re().is_inf());
+}
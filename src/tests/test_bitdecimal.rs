@@ -0,0 +1,284 @@
+use crate::bit_nums::bitdecimal::BitDecimal;
+use crate::bit_nums::bitfloat::RoundingMode;
+use crate::bit_nums::bitint::BitInt;
+use crate::bit_nums::traits::FromStrErr;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+fn dec(s: &str) -> BitDecimal {
+    s.parse().unwrap()
+}
+
+// ─── Parsing and formatting ─────────────────────────────────────────────────
+
+#[test]
+fn test_bitdecimal_string_round_trip() {
+    for s in [
+        "0",
+        "1",
+        "-1",
+        "0.1",
+        "1.50",
+        "-0.005",
+        "123456789012345678901234567890.0123456789",
+        "0.000",
+        "7e3",
+        "-42e1",
+    ] {
+        assert_eq!(dec(s).to_string(), s);
+    }
+    let x = dec("1.50");
+    assert_eq!(*x.get_coeff(), BitInt::from(150_i64));
+    assert_eq!((x.get_exp(), x.scale()), (-2, 2));
+    assert_eq!(dec("+2.5E-3").to_string(), "0.0025");
+    assert_eq!(dec("12.5e2").to_string(), "125e1");
+    assert_eq!(dec(".5").to_string(), "0.5");
+    assert_eq!(dec("5.").to_string(), "5");
+    assert_eq!(dec("-0.00").to_string(), "0.00");
+    assert_eq!(format!("{:>8}", dec("-1.5")), "    -1.5");
+    assert_eq!(
+        BitDecimal::new(BitInt::from(-5_i64), -3).to_string(),
+        "-0.005"
+    );
+
+    let mut rng = StdRng::seed_from_u64(3029);
+    for _ in 0..200 {
+        let c = rng.gen::<i64>() as i128 * rng.gen::<u64>() as i128;
+        let x = BitDecimal::new(BitInt::from(c), rng.gen_range(-40..5));
+        let y: BitDecimal = x.to_string().parse().unwrap();
+        assert_eq!(y.get_coeff(), x.get_coeff());
+        assert_eq!(y.get_exp(), x.get_exp());
+    }
+
+    for bad in [
+        ".", "-", "1.2.3", "1e", "1e+", "abc", "1_000", "--1", "0x10", "١",
+    ] {
+        assert!(
+            matches!(
+                bad.parse::<BitDecimal>(),
+                Err(FromStrErr::MalformedExpression)
+            ),
+            "{bad}"
+        );
+    }
+    assert!(matches!("".parse::<BitDecimal>(), Err(FromStrErr::Empty)));
+    assert!(matches!(
+        " 1".parse::<BitDecimal>(),
+        Err(FromStrErr::Whitespace)
+    ));
+    assert!(matches!(
+        "1e999999999999999999999999999999999999999".parse::<BitDecimal>(),
+        Err(FromStrErr::Overflow)
+    ));
+}
+
+// ─── Arithmetic ─────────────────────────────────────────────────────────────
+
+#[test]
+fn test_bitdecimal_exact_arithmetic() {
+    // the classic binary failure
+    assert_eq!(dec("0.1") + dec("0.2"), dec("0.3"));
+    assert_eq!((dec("0.1") + dec("0.2")).to_string(), "0.3");
+    assert_eq!((dec("19.99") + dec("0.01")).to_string(), "20.00");
+    assert_eq!((dec("1.5") - dec("1.50")).to_string(), "0.00");
+    assert_eq!((dec("-1.5") + dec("1.5")).to_string(), "0.0");
+    assert_eq!((dec("3") - dec("4.25")).to_string(), "-1.25");
+    assert_eq!((dec("1.25") * dec("-0.2")).to_string(), "-0.250");
+    assert_eq!((dec("0") * dec("-0.2")).to_string(), "0.0");
+    assert_eq!((dec("12e3") * dec("0.5")).to_string(), "60e2");
+    assert_eq!((-dec("2.50")).to_string(), "-2.50");
+    assert_eq!((-dec("0.0")).to_string(), "0.0");
+    assert_eq!(dec("-2.50").abs().to_string(), "2.50");
+
+    assert_eq!(dec("1.5"), dec("1.50000"));
+    assert!(dec("-0.01") < dec("0"));
+    assert!(dec("2e1") > dec("19.99"));
+    assert!(dec("-2e1") < dec("-19.99"));
+    assert_eq!(BitDecimal::from(7_i64), dec("7.0"));
+
+    // against i128 arithmetic on the coefficients at a common scale
+    let mut rng = StdRng::seed_from_u64(3030);
+    for _ in 0..200 {
+        let (a, b) = (
+            rng.gen_range(-1_000_000_i64..1_000_000),
+            rng.gen_range(-1_000_000_i64..1_000_000),
+        );
+        let x = BitDecimal::new(BitInt::from(a), -3);
+        let y = BitDecimal::new(BitInt::from(b), -5);
+        let sum = &x + &y;
+        assert_eq!(sum.get_exp(), -5);
+        assert_eq!(*sum.get_coeff(), BitInt::from(a as i128 * 100 + b as i128));
+        let prod = &x * &y;
+        assert_eq!(
+            (prod.get_exp(), prod.get_coeff().clone()),
+            (-8, BitInt::from(a as i128 * b as i128))
+        );
+        assert_eq!(&(&x - &y) + &y, x);
+    }
+}
+
+#[test]
+fn test_bitdecimal_rounding() {
+    use RoundingMode::*;
+    // value, then the result at scale 0 for each mode
+    let cases = [
+        ("2.5", ["2", "3", "2", "3", "2"]),
+        ("3.5", ["4", "4", "3", "4", "3"]),
+        ("-2.5", ["-2", "-3", "-2", "-2", "-3"]),
+        ("2.51", ["3", "3", "2", "3", "2"]),
+        ("-2.49", ["-2", "-2", "-2", "-2", "-3"]),
+        ("-0.4", ["0", "0", "0", "0", "-1"]),
+        ("7", ["7", "7", "7", "7", "7"]),
+    ];
+    let modes = [
+        NearestEven,
+        NearestAway,
+        TowardZero,
+        TowardPositive,
+        TowardNegative,
+    ];
+    for (x, want) in cases {
+        for (mode, w) in modes.iter().zip(want) {
+            assert_eq!(
+                dec(x).round_to_scale(0, *mode).unwrap().to_string(),
+                w,
+                "{x} {mode:?}"
+            );
+        }
+    }
+    assert_eq!(
+        dec("1.005")
+            .round_to_scale(2, NearestEven)
+            .unwrap()
+            .to_string(),
+        "1.00"
+    );
+    assert_eq!(
+        dec("1.015")
+            .round_to_scale(2, NearestEven)
+            .unwrap()
+            .to_string(),
+        "1.02"
+    );
+    assert_eq!(
+        dec("1.005")
+            .round_to_scale(2, NearestAway)
+            .unwrap()
+            .to_string(),
+        "1.01"
+    );
+    assert_eq!(
+        dec("1.5")
+            .round_to_scale(3, NearestEven)
+            .unwrap()
+            .to_string(),
+        "1.500"
+    );
+    assert_eq!(
+        dec("1234.5")
+            .round_to_scale(-2, NearestEven)
+            .unwrap()
+            .to_string(),
+        "12e2"
+    );
+
+    // division to a scale
+    let third = dec("1").div_to_scale(&dec("3"), 10, NearestEven).unwrap();
+    assert_eq!(third.to_string(), "0.3333333333");
+    let two_thirds = dec("-2").div_to_scale(&dec("3"), 4, NearestEven).unwrap();
+    assert_eq!(two_thirds.to_string(), "-0.6667");
+    assert_eq!(
+        dec("-2")
+            .div_to_scale(&dec("3"), 4, TowardZero)
+            .unwrap()
+            .to_string(),
+        "-0.6666"
+    );
+    assert_eq!(
+        dec("100.00")
+            .div_to_scale(&dec("0.08"), 2, NearestEven)
+            .unwrap()
+            .to_string(),
+        "1250.00"
+    );
+    assert_eq!(
+        dec("1")
+            .div_to_scale(&dec("8"), 2, NearestEven)
+            .unwrap()
+            .to_string(),
+        "0.12"
+    );
+    assert_eq!(
+        dec("1")
+            .div_to_scale(&dec("8"), 2, NearestAway)
+            .unwrap()
+            .to_string(),
+        "0.13"
+    );
+    assert_eq!(
+        dec("1e5")
+            .div_to_scale(&dec("3"), -3, NearestEven)
+            .unwrap()
+            .to_string(),
+        "33e3"
+    );
+
+    // splitting a bill: the rounded shares differ from the total by less
+    // than half a cent per share
+    let mut rng = StdRng::seed_from_u64(3031);
+    for _ in 0..100 {
+        let total = BitDecimal::new(BitInt::from(rng.gen_range(1_i64..10_000_000)), -2);
+        let n = rng.gen_range(1_i64..50);
+        let share = total
+            .div_to_scale(&BitDecimal::from(n), 2, NearestEven)
+            .unwrap();
+        let diff = (&(&share * &BitDecimal::from(n)) - &total).abs();
+        assert!(diff * BitDecimal::from(2_i64) <= BitDecimal::new(BitInt::from(n), -2));
+    }
+}
+
+#[test]
+#[should_panic(expected = "attempt to divide by zero")]
+fn test_bitdecimal_div_by_zero() {
+    dec("1").div_to_scale(&dec("0.00"), 2, RoundingMode::NearestEven);
+}
+
+#[test]
+fn test_bitdecimal_extreme_exponents() {
+    use RoundingMode::*;
+    // exponents too low to pad out print with the exponent and read back
+    let min = format!("1e{}", i128::MIN);
+    let x = dec(&min);
+    assert_eq!(x.get_exp(), i128::MIN);
+    assert_eq!(x.to_string(), min);
+    assert_eq!(x.scale(), i128::MAX);
+    let y = dec("-25e-18446744073709551616");
+    assert_eq!(y.to_string(), "-25e-18446744073709551616");
+    assert_eq!(
+        y.to_string().parse::<BitDecimal>().unwrap().get_exp(),
+        y.get_exp()
+    );
+
+    // far apart values compare by their orders of magnitude
+    let huge = dec(&format!("3e{}", i128::MAX));
+    assert!(x < dec("1e-5") && x > dec("0") && x > -&x);
+    assert!(huge > dec("9e99") && -&huge < dec("-9e99"));
+    assert!(-&x > -&huge && y < x);
+    assert!(dec("99999e-4") < dec("1e1") && dec("-99999e-4") > dec("-1e1"));
+
+    // scales out of reach give None
+    assert!(dec("1").round_to_scale(i128::MIN, NearestEven).is_none());
+    assert!(x.round_to_scale(0, NearestEven).is_none());
+    assert!(dec("1").div_to_scale(&x, 0, NearestEven).is_none());
+    assert!(x.div_to_scale(&huge, 0, NearestEven).is_none());
+    assert!(dec("1")
+        .div_to_scale(&dec("3"), i128::MIN, NearestEven)
+        .is_none());
+}
+
+#[test]
+#[should_panic(expected = "BitDecimal exponent out of range")]
+fn test_bitdecimal_mul_exponent_overflow() {
+    let x = dec(&format!("1e{}", i128::MAX));
+    let _ = &x * &x;
+}
@@ -0,0 +1,275 @@
+use crate::bit_nums::bitfixed::BitFixed;
+use crate::bit_nums::bitfloat::{BitFloat, RoundingMode};
+use crate::bit_nums::bitint_static::BitIntStatic;
+use crate::bit_nums::traits::FromErr;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// 64.64 and a wider 192.128 format
+type Q64 = BitFixed<1, 1>;
+type Wide = BitFixed<3, 2>;
+
+const EXACT: RoundingMode = RoundingMode::NearestEven;
+
+fn q(x: f64) -> Q64 {
+    Q64::try_from(x).unwrap()
+}
+
+fn bf<const I: usize, const F: usize>(x: BitFixed<I, F>) -> BitFloat {
+    BitFloat::from(x)
+}
+
+/// Random fixed-point values with every fraction bit in use.
+fn rand_wide(n: usize, seed: u64) -> Vec<Wide> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..n)
+        .map(|_| {
+            let m = BitFloat::from(rng.gen_range(-1.0..1.0));
+            let x = m.ldexp(rng.gen_range(-120..100))
+                + BitFloat::pow2(-128) * BitFloat::from(rng.gen::<u64>());
+            Wide::from_bitfloat(&x.to_prec(300), RoundingMode::TowardZero).unwrap()
+        })
+        .collect()
+}
+
+// ─── Conversions ────────────────────────────────────────────────────────────
+
+#[test]
+fn test_bitfixed_conversions() {
+    let x = q(-2.75);
+    assert!(x.is_negative());
+    assert_eq!(x.get_int(), BitIntStatic::<1>::from(-2_i64));
+    assert_eq!(x.get_frac().get_data(), [3 << 62]);
+    assert_eq!(f64::try_from(x).unwrap(), -2.75);
+    assert_eq!(Q64::from(BitIntStatic::<1>::from(7_i64)), q(7.0));
+
+    // to the nearest step of 2^-64, or in a given mode
+    let tiny = BitFloat::pow2(-66);
+    assert!(Q64::try_from(&tiny).unwrap().is_zero());
+    let up = Q64::from_bitfloat(&tiny, RoundingMode::TowardPositive).unwrap();
+    assert_eq!(bf(up), BitFloat::pow2(-64));
+    let third = BitFloat::one().div_round(&BitFloat::from(3), 200, EXACT);
+    let t = Q64::try_from(&third).unwrap();
+    assert_eq!(t.get_frac().get_data(), [0x5555_5555_5555_5555]);
+    assert_eq!(t.to_f64_round(RoundingMode::TowardZero).unwrap(), 1.0 / 3.0);
+
+    assert!(matches!(Q64::try_from(f64::NAN), Err(FromErr::NaN)));
+    assert!(matches!(
+        Q64::try_from(f64::INFINITY),
+        Err(FromErr::Overflow)
+    ));
+    assert!(matches!(
+        Q64::try_from(2f64.powi(64)),
+        Err(FromErr::Overflow)
+    ));
+    assert!(matches!(
+        f64::try_from(BitFixed::<17, 0>::max_value()),
+        Err(FromErr::Overflow)
+    ));
+    assert_eq!(
+        bf(Q64::max_value()),
+        BitFloat::pow2(64).sub_round(&BitFloat::pow2(-64), usize::MAX, EXACT)
+    );
+    assert_eq!(Q64::min_value(), -Q64::max_value());
+    assert!(!(-Q64::zero()).is_negative());
+
+    // every value converts to BitFloat and back exactly
+    for x in rand_wide(100, 3032) {
+        assert_eq!(
+            Wide::from_bitfloat(&bf(x), RoundingMode::TowardZero).unwrap(),
+            x
+        );
+    }
+}
+
+// ─── Arithmetic ─────────────────────────────────────────────────────────────
+
+#[test]
+fn test_bitfixed_arithmetic_correctly_rounded() {
+    let xs = rand_wide(200, 3033);
+    let step = BitFloat::pow2(-128);
+    let half_step = BitFloat::pow2(-129);
+    for pair in xs.chunks(2) {
+        let (a, b) = (pair[0], pair[1]);
+        let (fa, fb) = (bf(a), bf(b));
+        assert_eq!(bf(a + b), fa.add_round(&fb, usize::MAX, EXACT));
+        assert_eq!(bf(a - b), fa.sub_round(&fb, usize::MAX, EXACT));
+        assert!(a - b < a + b || b.is_negative() || b.is_zero());
+
+        // the product rounds to a multiple of the step
+        let exact = fa.mul_round(&fb, usize::MAX, EXACT);
+        for (mode, lo, hi) in [
+            (RoundingMode::NearestEven, -&half_step, half_step.clone()),
+            (RoundingMode::TowardNegative, -&step, BitFloat::zero()),
+            (RoundingMode::TowardPositive, BitFloat::zero(), step.clone()),
+        ] {
+            let p = bf(a.mul_round(&b, mode).unwrap());
+            let err = p.sub_round(&exact, usize::MAX, EXACT);
+            assert!(err >= lo && err <= hi, "{mode:?} {a:?} * {b:?}");
+        }
+        assert_eq!(a * b, a.mul_round(&b, EXACT).unwrap());
+
+        // and so does the quotient: |q b - a| within |b| times the error
+        if !b.is_zero() && (fa.clone() / &fb).abs() < BitFloat::pow2(190) {
+            for (mode, lo, hi) in [
+                (RoundingMode::NearestEven, -&half_step, half_step.clone()),
+                (RoundingMode::TowardZero, -&step, step.clone()),
+            ] {
+                let d = bf(a.div_round(&b, mode).unwrap());
+                let resid = d
+                    .mul_round(&fb, usize::MAX, EXACT)
+                    .sub_round(&fa, usize::MAX, EXACT);
+                let bound_lo = lo.mul_round(&fb.abs(), usize::MAX, EXACT);
+                let bound_hi = hi.mul_round(&fb.abs(), usize::MAX, EXACT);
+                assert!(
+                    resid >= bound_lo && resid <= bound_hi,
+                    "{mode:?} {a:?} / {b:?}"
+                );
+                if mode == RoundingMode::TowardZero {
+                    // |d b| <= |a|, so d is no larger than the quotient
+                    let db = d.mul_round(&fb, usize::MAX, EXACT);
+                    assert!(db.abs() <= fa.abs());
+                }
+            }
+        }
+    }
+
+    // ties go to even
+    let eps = Q64::from_bitfloat(&BitFloat::pow2(-64), EXACT).unwrap();
+    let half = q(0.5);
+    assert!((eps * half).is_zero());
+    assert!((Q64::zero() * eps).is_zero());
+    assert_eq!((eps * q(1.5)), q(2.0) * eps);
+    assert_eq!(
+        eps.mul_round(&half, RoundingMode::NearestAway).unwrap(),
+        eps
+    );
+    assert_eq!(
+        q(1.0) / q(3.0),
+        Q64::try_from(&BitFloat::one().div_round(&BitFloat::from(3), 200, EXACT)).unwrap()
+    );
+    assert_eq!(q(-7.5) / q(2.5), q(-3.0));
+}
+
+#[test]
+fn test_bitfixed_overflow() {
+    let max = Q64::max_value();
+    let one = q(1.0);
+    assert!(max.checked_add(&one).is_none());
+    assert_eq!(max.saturating_add(&one), max);
+    assert_eq!((-max).saturating_sub(&one), Q64::min_value());
+    // the magnitude wraps modulo 2^64
+    assert_eq!(
+        max.wrapping_add(&one),
+        q(1.0) - Q64::from_bitfloat(&BitFloat::pow2(-64), EXACT).unwrap()
+    );
+    assert_eq!(
+        q(-1.5e19).wrapping_sub(&q(5e18)),
+        q(-(2e19 - 2f64.powi(64)))
+    );
+    assert_eq!(
+        q(2f64.powi(40)).saturating_mul(&q(-2f64.powi(30))),
+        Q64::min_value()
+    );
+    assert_eq!(q(2f64.powi(40)).wrapping_mul(&q(2f64.powi(30))), q(0.0));
+    assert!(q(2f64.powi(40))
+        .mul_round(&q(2f64.powi(30)), EXACT)
+        .is_none());
+    assert_eq!(q(1.0).saturating_div(&q(2f64.powi(-64))), max);
+    assert_eq!(q(2.0).wrapping_div(&q(2f64.powi(-63))), q(0.0));
+    assert!(q(1.0).div_round(&q(2f64.powi(-64)), EXACT).is_none());
+
+    // the wide type holds what overflows the narrow one
+    let big = Wide::try_from(2f64.powi(90)).unwrap();
+    assert_eq!(bf(big * big), BitFloat::pow2(180));
+    assert!(big.mul_round(&(big * big), EXACT).is_none());
+}
+
+#[test]
+fn test_bitfixed_limb_edges() {
+    let max = Q64::max_value();
+    let eps = Q64::from_bitfloat(&BitFloat::pow2(-64), EXACT).unwrap();
+    // a magnitude that wraps to zero still saturates on its sign
+    assert!(max.wrapping_add(&eps).is_zero());
+    assert_eq!(max.saturating_add(&eps), max);
+    assert_eq!((-max).saturating_sub(&eps), Q64::min_value());
+    assert_eq!(eps - eps, Q64::zero());
+    assert!(!(eps - eps).is_negative());
+    assert_eq!(q(-2.5) + q(4.0), q(1.5));
+    assert_eq!(q(2.5) - q(4.0), q(-1.5));
+
+    // (2^32 - 2^-64)(2^32 + 2^-64) = 2^64 - 2^-128 is below the range's top
+    // and only overflows when rounding carries into the next limb
+    let two32 = q(2f64.powi(32));
+    let (a, b) = (two32 - eps, two32 + eps);
+    assert!(a.mul_round(&b, EXACT).is_none());
+    assert_eq!(a.mul_round(&b, RoundingMode::TowardZero), Some(max));
+    assert_eq!(a.saturating_mul(&b), max);
+    assert_eq!((-a).saturating_mul(&b), Q64::min_value());
+
+    assert!(q(-1.0) < q(-0.5) && q(-0.5) < Q64::zero() && Q64::zero() < eps);
+    assert_eq!(-Q64::zero(), Q64::zero());
+
+    // without fraction or integer limbs
+    type Int = BitFixed<2, 0>;
+    type Frac = BitFixed<0, 2>;
+    let (x, y) = (Int::try_from(-1e30).unwrap(), Int::try_from(3e5).unwrap());
+    let (fx, fy) = (bf(x), bf(y));
+    assert_eq!(bf(x * y), fx.mul_round(&fy, usize::MAX, EXACT));
+    assert_eq!(bf(x - y), fx.sub_round(&fy, usize::MAX, EXACT));
+    assert!(x.mul_round(&x, EXACT).is_none());
+    let (x, y) = (Frac::try_from(0.75).unwrap(), Frac::try_from(-0.5).unwrap());
+    assert_eq!(x * y, Frac::try_from(-0.375).unwrap());
+    assert_eq!(x + y, Frac::try_from(0.25).unwrap());
+    assert!(x.checked_sub(&y).is_none());
+    assert!(Frac::max_value() > x && Frac::min_value() < y);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "attempt to add with overflow")]
+fn test_bitfixed_operator_overflow() {
+    let _ = Q64::max_value() + q(1.0);
+}
+
+#[test]
+fn test_bitfixed_sqrt() {
+    assert_eq!(q(2.25).sqrt().unwrap(), q(1.5));
+    assert_eq!(q(0.0).sqrt().unwrap(), q(0.0));
+    assert!(q(-1.0).sqrt().is_none());
+    let two = q(2.0).sqrt().unwrap();
+    let want = BitFloat::from(2).sqrt(200);
+    assert_eq!(two, Q64::try_from(&want).unwrap());
+    assert_eq!(Q64::max_value().sqrt().unwrap(), q(2f64.powi(32)));
+
+    let step = BitFloat::pow2(-128);
+    for x in rand_wide(100, 3034) {
+        let x = x.abs();
+        for mode in [
+            RoundingMode::NearestEven,
+            RoundingMode::TowardZero,
+            RoundingMode::TowardPositive,
+        ] {
+            let r = bf(x.sqrt_round(mode).unwrap());
+            let fx = bf(x);
+            // the exact root lies within the step the mode allows
+            let (lo, hi) = match mode {
+                RoundingMode::NearestEven => (
+                    r.sub_round(&BitFloat::pow2(-129), usize::MAX, EXACT),
+                    r.add_round(&BitFloat::pow2(-129), usize::MAX, EXACT),
+                ),
+                RoundingMode::TowardZero => (r.clone(), r.add_round(&step, usize::MAX, EXACT)),
+                _ => (r.sub_round(&step, usize::MAX, EXACT), r.clone()),
+            };
+            let sq = |y: &BitFloat| y.mul_round(y, usize::MAX, EXACT);
+            assert!(lo <= 0 || sq(&lo) <= fx, "{mode:?} {x:?}");
+            assert!(sq(&hi) >= fx, "{mode:?} {x:?}");
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "attempt to divide by zero")]
+fn test_bitfixed_div_by_zero() {
+    let _ = q(1.0).saturating_div(&Q64::zero());
+}
@@ -0,0 +1,2116 @@
+use super::rand_f64s;
+use crate::bit_nums::bitfloat::{BitFloat, Flags, RoundingMode};
+use crate::bit_nums::bitint::BitInt;
+use crate::bit_nums::constants;
+use crate::bit_nums::traits::{DivVariants, MulVariants, Rounding, SqrVariants};
+use crate::bit_nums::traits::{FromErr, FromStrErr};
+use crate::bit_nums::ubitint::UBitInt;
+use crate::bit_nums::ubitint_static::UBitIntStatic;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+fn to_f64(x: &BitFloat) -> f64 {
+    f64::try_from(x).unwrap()
+}
+
+// within one f64 ulp, since the std functions are not correctly rounded
+fn assert_close(got: f64, want: f64, what: &str) {
+    let tol = want.abs() * f64::EPSILON;
+    assert!(
+        (got - want).abs() <= tol,
+        "{what}: got {got:e}, want {want:e}"
+    );
+}
+
+// ─── Arithmetic and conversions ─────────────────────────────────────────────
+
+#[test]
+fn test_bitfloat_f64_round_trip() {
+    let specials = [
+        1.0,
+        -0.5,
+        3.0e300,
+        -1.0e-300,
+        5.0e-324,
+        2.2250738585072014e-308,
+        f64::MAX,
+    ];
+    for &x in specials.iter().chain(rand_f64s(200, 3000).iter()) {
+        assert_eq!(to_f64(&BitFloat::from(x)), x, "x={x:e}");
+        assert_eq!(
+            f32::try_from(&BitFloat::from(x as f32)).unwrap(),
+            x as f32,
+            "x={x:e}"
+        );
+    }
+    assert!(f64::try_from(BitFloat::from(f64::MAX) * 2).is_err());
+    assert_eq!(to_f64(&(BitFloat::from(5.0e-324) >> 1_usize)), 0.0);
+    assert_eq!(to_f64(&(BitFloat::from(5.0e-324) * 0.75)), 5.0e-324);
+}
+
+#[test]
+fn test_bitfloat_exact_ops_match_f64() {
+    let xs = rand_f64s(100, 3001);
+    let ys = rand_f64s(100, 3002);
+    for (&x, &y) in xs.iter().zip(&ys) {
+        let (a, b) = (BitFloat::from(x), BitFloat::from(y));
+        assert_eq!(to_f64(&a.full_mul(&b)), x * y, "mul x={x:e}, y={y:e}");
+        assert_eq!(to_f64(&(&a).full_div(&b)), x / y, "div x={x:e}, y={y:e}");
+        assert_close(to_f64(&(&a + &b)), x + y, "add");
+        assert_close(to_f64(&(&a - &b)), x - y, "sub");
+        assert_eq!(a < b, x < y);
+    }
+}
+
+#[test]
+fn test_bitfloat_add_rounds_to_nearest_even() {
+    let one = BitFloat::one();
+    // 1 + 2^-64 is a tie at the default 64 bits, and rounds to the even 1
+    let half_ulp = BitFloat::make(false, -1, &[1]);
+    assert_eq!(&one + &half_ulp, one);
+    // and below half an ulp of 3
+    assert_eq!(&BitFloat::from(3) + &half_ulp, BitFloat::from(3));
+    // a tie against an odd last bit rounds up
+    let odd = BitFloat::make(false, 0, &[2, 1]);
+    assert_eq!(&odd + &half_ulp, BitFloat::make(false, 0, &[4, 1]));
+    assert_eq!(
+        BitFloat::make(false, 0, &[u64::MAX, 1]).to_prec(64),
+        BitFloat::from(2)
+    );
+    assert_eq!(BitFloat::from(1.5).to_prec(1), BitFloat::from(2));
+    assert_eq!(BitFloat::from(2.5).to_prec(2), BitFloat::from(2));
+    assert_eq!(BitFloat::from(-11).to_prec(3), BitFloat::from(-12));
+    // the operators keep every bit of the wider operand
+    let a = BitFloat::make(false, 0, &[5, 1]);
+    assert_eq!(a.prec(), 65);
+    assert_eq!(&a - &one, BitFloat::make(false, -1, &[5]));
+    assert_eq!(&a * &one, a);
+}
+
+#[test]
+fn test_bitfloat_bit_precision() {
+    // at 53 and 24 bits the correctly rounded operations are f64 and f32 ones
+    let xs = rand_f64s(100, 3009);
+    let ys = rand_f64s(100, 3010);
+    let ne = RoundingMode::NearestEven;
+    for (&x, &y) in xs.iter().zip(&ys) {
+        let (a, b) = (BitFloat::from(x), BitFloat::from(y));
+        assert_eq!(to_f64(&a.add_round(&b, 53, ne)), x + y, "x={x:e}, y={y:e}");
+        assert_eq!(to_f64(&a.sub_round(&b, 53, ne)), x - y, "x={x:e}, y={y:e}");
+        assert_eq!(to_f64(&a.mul_round(&b, 53, ne)), x * y, "x={x:e}, y={y:e}");
+        assert_eq!(to_f64(&a.div_round(&b, 53, ne)), x / y, "x={x:e}, y={y:e}");
+        assert_eq!(to_f64(&a.abs().sqrt(53)), x.abs().sqrt(), "x={x:e}");
+
+        let (xf, yf) = (x as f32, y as f32);
+        let (a, b) = (BitFloat::from(xf), BitFloat::from(yf));
+        if (xf / yf).is_normal() {
+            let got = f32::try_from(&a.div_round(&b, 24, ne)).unwrap();
+            assert_eq!(got, xf / yf, "x={xf:e}, y={yf:e}");
+        }
+        assert_eq!(a.to_prec(24), a);
+        assert!(BitFloat::from(x).to_prec(24).prec() <= 24);
+    }
+
+    // binary128 and binary256 precisions
+    let sqrt2 = BitFloat::from(0x16A09E667F3BCC908B2FB1366EA95_u128) >> 112_usize;
+    assert_eq!(BitFloat::from(2).sqrt(113), sqrt2);
+    assert_eq!(constants::sqrt2(113), sqrt2);
+    let pi = [
+        0x082EFA98EC400000,
+        0xA4093822299F31D0,
+        0x13198A2E03707344,
+        0x243F6A8885A308D3,
+        3,
+    ];
+    assert_eq!(constants::pi(237), BitFloat::make(false, 0, &pi));
+}
+
+#[test]
+fn test_bitfloat_div_unbounded_precision() {
+    use RoundingMode::*;
+    // quotients that terminate come out exactly
+    let x = BitFloat::from(0x1234_5678_9ABC_DEF0_1234_5678_u128).mul_round(
+        &BitFloat::from(3).ldexp(-300),
+        usize::MAX,
+        NearestEven,
+    );
+    let y = BitFloat::from(3).ldexp(40);
+    let q = x.div_round(&y, usize::MAX, NearestEven);
+    assert_eq!(q.mul_round(&y, usize::MAX, NearestEven), x);
+    assert_eq!(
+        BitFloat::one().div_round(&BitFloat::from(-1024), usize::MAX, TowardZero),
+        -BitFloat::pow2(-10)
+    );
+    // and the others round at the bits of both operands
+    for mode in [NearestEven, TowardZero, TowardPositive, TowardNegative] {
+        let third = BitFloat::one().div_round(&BitFloat::from(3), usize::MAX, mode);
+        assert_eq!(
+            third,
+            BitFloat::one().div_round(&BitFloat::from(3), 128, mode)
+        );
+    }
+}
+
+#[test]
+fn test_bitfloat_zero_precision() {
+    use RoundingMode::*;
+    // a precision of zero rounds to one bit
+    assert_eq!(BitFloat::one().to_prec(0), 1);
+    assert_eq!(BitFloat::from(3).to_prec(0), 4);
+    assert_eq!(BitFloat::from(3).to_prec_round(0, TowardZero), 2);
+    assert_eq!(BitFloat::from(-5).to_prec_round(0, TowardNegative), -8);
+    let (a, b) = (BitFloat::from(5), BitFloat::from(2));
+    assert_eq!(a.add_round(&b, 0, NearestEven), 8);
+    assert_eq!(a.sub_round(&b, 0, TowardZero), 2);
+    assert_eq!(a.mul_round(&b, 0, TowardPositive), 16);
+    assert_eq!(a.div_round(&b, 0, NearestEven), 2);
+    assert_eq!(BitFloat::from(7).sqrt_round(0, TowardZero), 2);
+}
+
+#[test]
+fn test_bitfloat_default_prec() {
+    assert_eq!(BitFloat::default_prec(), 64);
+    let third = BitFloat::one() / 3_u32;
+    assert_eq!(third.prec(), 64);
+    BitFloat::set_default_prec(200);
+    let ne = RoundingMode::NearestEven;
+    let third = BitFloat::one() / 3_u32;
+    assert_eq!(
+        third,
+        BitFloat::one().div_round(&BitFloat::from(3), 200, ne)
+    );
+    assert_eq!(&third * 3_u32, BitFloat::one());
+    assert_eq!(
+        (&third).man_div(&BitFloat::from(7), None, None, None),
+        third.div_round(&BitFloat::from(7), 200, ne)
+    );
+    // a wider operand still wins
+    let wide = third.add_round(&BitFloat::make(false, -10, &[1]), usize::MAX, ne);
+    assert_eq!(&wide * 1_u32, wide);
+    BitFloat::set_default_prec(64);
+    assert_eq!((BitFloat::one() / 3_u32).prec(), 64);
+}
+
+#[test]
+fn test_bitfloat_nan_and_signed_zero() {
+    let nan = BitFloat::nan();
+    let one = BitFloat::one();
+    assert!(nan != nan);
+    assert_eq!(nan.partial_cmp(&one), None);
+    assert_eq!(one.partial_cmp(&nan), None);
+    assert!((&nan + &one).is_nan());
+    assert!(nan.exp(64).is_nan());
+    assert!(nan.sin(64).is_nan());
+    assert!(BitFloat::from(f64::NAN).is_nan());
+    assert!(f64::try_from(&nan).unwrap().is_nan());
+    assert!(i64::try_from(&nan).is_err());
+    // an infinite side beats NaN
+    assert_eq!(BitFloat::inf().hypot(&nan, 64), BitFloat::inf());
+
+    let (zero, neg_zero) = (BitFloat::zero(), BitFloat::neg_zero());
+    assert_eq!(zero, neg_zero);
+    assert_eq!(zero.total_cmp(&neg_zero), std::cmp::Ordering::Greater);
+    assert_eq!(nan.total_cmp(&BitFloat::inf()), std::cmp::Ordering::Greater);
+    let back = f64::try_from(&BitFloat::from(-0.0)).unwrap();
+    assert!(back == 0.0 && back.is_sign_negative());
+
+    // IEEE signs of zero results
+    assert!((-&zero).get_s());
+    assert!(!(&one - &one).get_s());
+    assert!(one
+        .sub_round(&one, 64, RoundingMode::TowardNegative)
+        .get_s());
+    assert!((&neg_zero + &neg_zero).get_s());
+    assert!(!(&neg_zero + &zero).get_s());
+    assert!((&neg_zero * &one).get_s());
+    assert!((BitFloat::from(-1) / BitFloat::inf()).get_s());
+    assert!(neg_zero.sin(64).get_s());
+    assert!(neg_zero.sqrt(64).get_s());
+    assert!(BitFloat::from(-3).sinpi(64).get_s());
+    assert!(BitFloat::from(-0.25).round().get_s());
+    assert!(neg_zero.atan2(&BitFloat::from(2), 64).get_s());
+    assert_close(
+        to_f64(&zero.atan2(&neg_zero, 64)),
+        0_f64.atan2(-0.0),
+        "atan2(0, -0)",
+    );
+    assert_eq!(neg_zero.powf(&BitFloat::from(3), 64), neg_zero);
+    assert!(neg_zero.powf(&BitFloat::from(3), 64).get_s());
+    assert_eq!(neg_zero.powf(&BitFloat::from(-3), 64), BitFloat::neg_inf());
+}
+
+#[test]
+fn test_bitfloat_flags() {
+    let (one, zero, inf) = (BitFloat::one(), BitFloat::zero(), BitFloat::inf());
+    BitFloat::clear_flags();
+    assert_eq!(BitFloat::flags(), Flags::default());
+
+    // exact results raise nothing
+    assert_eq!(BitFloat::from(4).sqrt(64), 2);
+    assert_eq!(BitFloat::from(8).log2(64), 3);
+    let _ = &one + &one;
+    assert_eq!(BitFloat::flags(), Flags::default());
+
+    let _ = &one / 3_u32;
+    assert!(BitFloat::flags().inexact);
+    BitFloat::clear_flags();
+    let _ = BitFloat::from(2).ln(64);
+    assert_eq!(
+        BitFloat::flags(),
+        Flags {
+            inexact: true,
+            ..Flags::default()
+        }
+    );
+
+    BitFloat::clear_flags();
+    assert!((&inf - &inf).is_nan());
+    assert!((&zero * &inf).is_nan());
+    assert!((&zero / &zero).is_nan());
+    assert!(BitFloat::from(-1).sqrt(64).is_nan());
+    assert!(BitFloat::from(-2).powf(&BitFloat::from(0.5), 64).is_nan());
+    assert!(BitFloat::from(2).atanh(64).is_nan());
+    assert!(inf.sin(64).is_nan());
+    assert!(BitFloat::flags().invalid);
+    assert!(!BitFloat::flags().div_by_zero);
+
+    BitFloat::clear_flags();
+    assert_eq!(&one / &zero, inf);
+    assert_eq!(zero.ln(64), BitFloat::neg_inf());
+    assert_eq!(BitFloat::from(-1).atanh(64), BitFloat::neg_inf());
+    assert_eq!(
+        BitFloat::flags(),
+        Flags {
+            div_by_zero: true,
+            ..Flags::default()
+        }
+    );
+
+    BitFloat::clear_flags();
+    let huge = BitFloat::pow2(1 << 70);
+    assert_eq!(huge.exp(64), inf);
+    assert!(BitFloat::flags().overflow);
+    assert!((-&huge).exp(64).is_zero());
+    assert!(BitFloat::flags().underflow);
+
+    BitFloat::clear_flags();
+    assert_eq!(f64::try_from(&BitFloat::pow2(-1074)).unwrap(), 5e-324);
+    assert!(!BitFloat::flags().underflow);
+    assert_eq!(f64::try_from(&BitFloat::pow2(-1080)).unwrap(), 0.0);
+    assert!(BitFloat::flags().underflow);
+}
+
+#[test]
+fn test_bitfloat_mul_add() {
+    // (1 + 2^-60)^2 - (1 + 2^-59) = 2^-120, lost by a rounded product
+    let x = BitFloat::one() + BitFloat::pow2(-60);
+    let c = -(BitFloat::one() + BitFloat::pow2(-59));
+    assert_eq!(x.mul_add(&x, &c, 64), BitFloat::pow2(-120));
+    assert!((&x * &x + &c).is_zero());
+
+    let xs = rand_f64s(30, 3011);
+    for w in xs.chunks(3) {
+        let (a, b, c) = (
+            BitFloat::from(w[0]),
+            BitFloat::from(w[1]),
+            BitFloat::from(w[2]),
+        );
+        let exact = a
+            .full_mul(&b)
+            .add_round(&c, usize::MAX, RoundingMode::NearestEven);
+        for p in [24, 53, 113] {
+            assert_eq!(a.mul_add(&b, &c, p), exact.to_prec(p), "fma at {p} bits");
+        }
+        assert_eq!(
+            f64::try_from(a.mul_add(&b, &c, 53)).unwrap(),
+            w[0].mul_add(w[1], w[2])
+        );
+    }
+    assert!(BitFloat::zero()
+        .mul_add(&BitFloat::inf(), &BitFloat::one(), 64)
+        .is_nan());
+}
+
+#[test]
+fn test_bitfloat_mul_add_directed() {
+    use RoundingMode::*;
+    // a product with limbs below where the addend is cut to a sticky bit:
+    // (1 + 2^-400) * 1 - 2^-320 is just below 1
+    let x = BitFloat::one().add_round(&BitFloat::pow2(-400), usize::MAX, NearestEven);
+    let c = -BitFloat::pow2(-320);
+    let below = BitFloat::one().next_down(64);
+    assert_eq!(
+        x.mul_add_round(&BitFloat::one(), &c, 64, TowardNegative),
+        below
+    );
+    assert_eq!(x.mul_add_round(&BitFloat::one(), &c, 64, TowardZero), below);
+    assert_eq!(
+        x.mul_add_round(&BitFloat::one(), &c, 64, TowardPositive),
+        BitFloat::one()
+    );
+
+    let xs = rand_f64s(30, 3035);
+    for w in xs.chunks(3) {
+        let a = BitFloat::from(w[0]).add_round(
+            &BitFloat::from(w[0]).ldexp(-300),
+            usize::MAX,
+            NearestEven,
+        );
+        let (b, c) = (BitFloat::from(w[1]), BitFloat::from(w[2]).ldexp(-200));
+        let exact = a.full_mul(&b).add_round(&c, usize::MAX, NearestEven);
+        for p in [24, 64, 113] {
+            let down = a.mul_add_round(&b, &c, p, TowardNegative);
+            let up = a.mul_add_round(&b, &c, p, TowardPositive);
+            assert!(down <= exact && exact <= up, "fma at {p} bits");
+            assert_eq!(down, exact.to_prec_round(p, TowardNegative));
+            assert_eq!(up, exact.to_prec_round(p, TowardPositive));
+        }
+    }
+}
+
+#[test]
+fn test_bitfloat_sum_and_dot() {
+    let ne = RoundingMode::NearestEven;
+    let big = BitFloat::from(1e300);
+    let terms = [
+        big.clone(),
+        BitFloat::from(0.1),
+        -big.clone(),
+        BitFloat::from(0.2),
+    ];
+    let want = BitFloat::from(0.1).add_round(&BitFloat::from(0.2), usize::MAX, ne);
+    assert_eq!(BitFloat::sum(&terms, 200), want);
+
+    let xs = rand_f64s(60, 3012);
+    let bs: Vec<BitFloat> = xs.iter().map(|&x| BitFloat::from(x)).collect();
+    let (l, r) = bs.split_at(30);
+    let mut exact_sum = BitFloat::zero();
+    let mut exact_dot = BitFloat::zero();
+    for (x, y) in l.iter().zip(r) {
+        exact_sum = exact_sum.add_round(x, usize::MAX, ne);
+        exact_sum = exact_sum.add_round(y, usize::MAX, ne);
+        exact_dot = exact_dot.add_round(&x.full_mul(y), usize::MAX, ne);
+    }
+    for p in [1, 24, 53, 113, 300] {
+        assert_eq!(
+            BitFloat::sum(&bs, p),
+            exact_sum.to_prec(p),
+            "sum at {p} bits"
+        );
+        assert_eq!(
+            BitFloat::dot(l, r, p),
+            exact_dot.to_prec(p),
+            "dot at {p} bits"
+        );
+    }
+
+    // signed zeros and special values
+    let (zero, neg_zero) = (BitFloat::zero(), BitFloat::neg_zero());
+    assert!(!BitFloat::sum(&[], 64).get_s());
+    assert!(BitFloat::sum(&[neg_zero.clone(), neg_zero.clone()], 64).get_s());
+    assert!(!BitFloat::sum(&[neg_zero.clone(), zero.clone()], 64).get_s());
+    assert!(!BitFloat::sum(&[big.clone(), -big.clone()], 64).get_s());
+    assert_eq!(
+        BitFloat::sum(&[BitFloat::inf(), big.clone()], 64),
+        BitFloat::inf()
+    );
+    BitFloat::clear_flags();
+    assert!(BitFloat::sum(&[BitFloat::inf(), BitFloat::neg_inf()], 64).is_nan());
+    assert!(BitFloat::flags().invalid);
+    BitFloat::clear_flags();
+    assert_eq!(BitFloat::dot(l, r, usize::MAX), exact_dot);
+    assert!(!BitFloat::flags().inexact);
+}
+
+#[test]
+fn test_bitfloat_next_up_and_down() {
+    for &x in &rand_f64s(40, 3014) {
+        let bf = BitFloat::from(x);
+        assert_eq!(bf.next_up(53), BitFloat::from(x.next_up()), "x={x:e}");
+        assert_eq!(bf.next_down(53), BitFloat::from(x.next_down()), "x={x:e}");
+        assert_eq!(bf.next_up(53).next_down(53), bf);
+        // values with more bits than asked step to their directed roundings
+        let f = x as f32;
+        let up = if f as f64 > x { f } else { f.next_up() };
+        assert_eq!(bf.next_up(24), BitFloat::from(up), "x={x:e}");
+    }
+
+    let one = BitFloat::one();
+    let below = BitFloat::from(1.0 - f64::EPSILON / 2.0);
+    assert_eq!(one.next_down(53), below);
+    assert_eq!((-&one).next_up(53), -below);
+    assert_eq!(one.next_up(1), BitFloat::from(2));
+    assert_eq!(one.next_down(1), BitFloat::from(0.5));
+
+    assert_eq!(BitFloat::inf().next_up(53), BitFloat::inf());
+    assert_eq!(BitFloat::neg_inf().next_down(53), BitFloat::neg_inf());
+    assert!(BitFloat::nan().next_up(53).is_nan());
+    BitFloat::clear_flags();
+    assert!(BitFloat::zero().next_up(53).is_nan());
+    assert!(BitFloat::neg_zero().next_down(53).is_nan());
+    assert!(BitFloat::zero().ulp(53).is_nan());
+    assert!(BitFloat::flags().invalid);
+}
+
+#[test]
+fn test_bitfloat_decomposition() {
+    for &x in &rand_f64s(40, 3015) {
+        let bf = BitFloat::from(x);
+        let (m, e) = bf.frexp();
+        assert_eq!(e, (x.to_bits() >> 52 & 0x7FF) as i128 - 1022, "x={x:e}");
+        assert!(m.abs() >= 0.5 && m.abs() < 1);
+        assert_eq!(m.ldexp(e), bf);
+        assert_eq!(bf.exponent(), Some(e - 1));
+
+        // |x| = significand * 2^(exponent - bits + 1)
+        let sig = bf.significand();
+        let bits = bf.prec() as i128;
+        let back = BitFloat::make(false, sig.get_data().len() as i128 - 1, sig.get_data());
+        assert_eq!(back.ldexp(e - bits), bf.abs());
+        assert_eq!(bf.ulp(53), BitFloat::one().ldexp(e - 53));
+    }
+
+    assert_eq!(BitFloat::from(8).frexp(), (BitFloat::from(0.5), 4));
+    assert_eq!(BitFloat::from(-0.75).frexp(), (BitFloat::from(-0.75), 0));
+    assert_eq!(BitFloat::zero().frexp(), (BitFloat::zero(), 0));
+    assert_eq!(
+        BitFloat::from(3).ldexp(-70),
+        BitFloat::make(false, -2, &[3 << 58])
+    );
+    assert_eq!(BitFloat::from(12).exponent(), Some(3));
+    assert_eq!(BitFloat::zero().exponent(), None);
+    assert_eq!(BitFloat::inf().exponent(), None);
+
+    assert_eq!(BitFloat::from(12).significand(), UBitInt::make(vec![3]));
+    assert_eq!(BitFloat::from(-0.75).significand(), UBitInt::make(vec![3]));
+    let wide = BitFloat::make(false, 1, &[1, 1 << 6]);
+    assert_eq!(wide.significand(), UBitInt::make(vec![1, 1 << 6]));
+    assert_eq!(BitFloat::nan().significand(), UBitInt::zero());
+
+    assert_eq!(BitFloat::one().ulp(53), BitFloat::from(f64::EPSILON));
+    assert_eq!(BitFloat::from(1.99).ulp(53), BitFloat::from(f64::EPSILON));
+    assert_eq!(BitFloat::from(-2).ulp(1), BitFloat::from(2));
+
+    let neg = BitFloat::from(-2.5);
+    assert_eq!(neg.copysign(&BitFloat::one()), BitFloat::from(2.5));
+    assert_eq!(neg.copysign(&BitFloat::neg_zero()), neg);
+    assert!(BitFloat::zero().copysign(&neg).get_s());
+
+    assert!(BitFloat::from(-1e30).is_integer());
+    assert!(BitFloat::neg_zero().is_integer());
+    assert!(!BitFloat::from(2.5).is_integer());
+    assert!(!BitFloat::inf().is_integer());
+    assert!(!BitFloat::nan().is_integer());
+}
+
+#[test]
+fn test_bitfloat_ulp_distance() {
+    // for f64 values of one sign the distance is that of their bit patterns
+    let xs = rand_f64s(40, 3016);
+    for w in xs.windows(2) {
+        let (a, b) = (w[0].abs(), w[1].abs());
+        let want = a.to_bits().abs_diff(b.to_bits());
+        let got = BitFloat::from(a).ulp_distance(&BitFloat::from(b), 53);
+        assert_eq!(got, Some(UBitInt::from(want)), "a={a:e}, b={b:e}");
+    }
+
+    let one = BitFloat::one();
+    let three_up = one.next_up(53).next_up(53).next_up(53);
+    assert_eq!(one.ulp_distance(&three_up, 53), Some(UBitInt::from(3_u64)));
+    // one step down from 1 is half the size of one step up
+    assert_eq!(
+        one.next_down(53).ulp_distance(&one.next_up(53), 53),
+        Some(UBitInt::from(2_u64))
+    );
+    assert_eq!(
+        BitFloat::from(-0.1).ulp_distance(&BitFloat::from(-0.1), 24),
+        Some(UBitInt::zero())
+    );
+    assert_eq!(
+        BitFloat::zero().ulp_distance(&BitFloat::neg_zero(), 24),
+        Some(UBitInt::zero())
+    );
+    assert_eq!(one.ulp_distance(&BitFloat::neg_one(), 53), None);
+    assert_eq!(one.ulp_distance(&BitFloat::zero(), 53), None);
+    assert_eq!(one.ulp_distance(&BitFloat::inf(), 53), None);
+    assert_eq!(one.ulp_distance(&BitFloat::nan(), 53), None);
+}
+
+#[test]
+fn test_bitfloat_int_conversions_and_rounding() {
+    assert_eq!(i64::try_from(BitFloat::from(-7.9)).unwrap(), -7);
+    assert_eq!(
+        u128::try_from(BitFloat::from(u128::MAX)).unwrap(),
+        u128::MAX
+    );
+    assert!(u32::try_from(BitFloat::from(-3)).is_err());
+    assert!(i8::try_from(BitFloat::from(300)).is_err());
+    assert!(u64::try_from(BitFloat::inf()).is_err());
+
+    for &x in &[2.5_f64, -2.5, 0.25, -0.75, 7.0, 1.0e20 + 0.5, -3.0e-30] {
+        let bf = BitFloat::from(x);
+        assert_eq!(to_f64(&bf.floor()), x.floor(), "floor {x}");
+        assert_eq!(to_f64(&bf.ceil()), x.ceil(), "ceil {x}");
+        assert_eq!(to_f64(&bf.round()), x.round(), "round {x}");
+        assert_eq!(to_f64(&bf.trunc()), x.trunc(), "trunc {x}");
+        assert_eq!(to_f64(&bf.fract()), x.fract(), "fract {x}");
+    }
+}
+
+#[test]
+fn test_bitfloat_bitint_conversions() {
+    // integers of any size convert exactly and back
+    let big = UBitInt::make(vec![0, 0, 1 << 63, 5, 0x1234]);
+    let bf = BitFloat::from(big.clone());
+    assert_eq!(bf.prec(), 64 * 4 + 12 - 191 + 1);
+    assert_eq!(
+        bf.significand(),
+        UBitInt::make(vec![5 << 1 | 1, 0x1234 << 1])
+    );
+    let back = bf.to_bitint(RoundingMode::NearestEven).unwrap();
+    assert_eq!(back, BitInt::make(big.get_data().to_vec(), false));
+
+    let neg = BitInt::make(vec![u64::MAX, u64::MAX, 7], true);
+    let bf = BitFloat::from(neg.clone());
+    assert!(bf < 0 && bf.exponent() == Some(130));
+    assert_eq!(bf.to_bitint(RoundingMode::TowardZero).unwrap(), neg);
+
+    assert!(BitFloat::from(UBitInt::zero()).is_zero());
+    assert!(BitFloat::from(BitInt::zero()).is_zero());
+    assert_eq!(BitFloat::from(BitInt::from(-42_i64)), -42);
+    assert_eq!(BitFloat::from(UBitIntStatic::<3>::make([9, 0, 0])), 9);
+    assert_eq!(
+        BitFloat::from(UBitIntStatic::<2>::make([0, 1])),
+        BitFloat::pow2(64)
+    );
+
+    // rounding follows the mode, and small magnitudes may round to zero
+    let cases: [(f64, [i64; 5]); 5] = [
+        (2.5, [2, 3, 2, 3, 2]),
+        (-2.5, [-2, -3, -2, -2, -3]),
+        (-0.25, [0, 0, 0, 0, -1]),
+        (7.75, [8, 8, 7, 8, 7]),
+        (-1e-30, [0, 0, 0, 0, -1]),
+    ];
+    let modes = [
+        RoundingMode::NearestEven,
+        RoundingMode::NearestAway,
+        RoundingMode::TowardZero,
+        RoundingMode::TowardPositive,
+        RoundingMode::TowardNegative,
+    ];
+    for (x, want) in cases {
+        for (mode, w) in modes.iter().zip(want) {
+            let got = BitFloat::from(x).to_bitint(*mode).unwrap();
+            assert_eq!(got, BitInt::from(w), "{x} {mode:?}");
+        }
+    }
+    assert!(!BitFloat::from(-0.25)
+        .to_bitint(RoundingMode::TowardZero)
+        .unwrap()
+        .get_sign());
+
+    let mode = RoundingMode::NearestEven;
+    assert!(matches!(
+        BitFloat::inf().to_bitint(mode),
+        Err(FromErr::Overflow)
+    ));
+    assert!(matches!(
+        BitFloat::neg_inf().to_bitint(mode),
+        Err(FromErr::Overflow)
+    ));
+    assert!(matches!(BitFloat::nan().to_bitint(mode), Err(FromErr::NaN)));
+}
+
+// ─── Exponentials and logarithms ────────────────────────────────────────────
+
+#[test]
+fn test_bitfloat_exp_log_match_f64() {
+    for &x in &[0.5_f64, -0.3, 1.0, 2.75, -20.0, 100.5, 1.0e-10, -700.0] {
+        let bf = BitFloat::from(x);
+        assert_close(to_f64(&bf.exp(128)), x.exp(), "exp");
+        assert_close(to_f64(&bf.exp2(128)), x.exp2(), "exp2");
+        assert_close(to_f64(&bf.expm1(128)), x.exp_m1(), "expm1");
+    }
+    for &x in &[0.5_f64, 1.5, 3.0, 1.0e-10, 12345.678, 1.0e300, 0.999] {
+        let bf = BitFloat::from(x);
+        assert_close(to_f64(&bf.ln(128)), x.ln(), "ln");
+        assert_close(to_f64(&bf.log2(128)), x.log2(), "log2");
+        assert_close(to_f64(&bf.log10(128)), x.log10(), "log10");
+        assert_close(to_f64(&bf.log(&BitFloat::from(7), 128)), x.log(7.0), "log7");
+        assert_close(to_f64(&bf.ln1p(128)), x.ln_1p(), "ln1p");
+        assert_close(
+            to_f64(&bf.powf(&BitFloat::from(-2.5), 128)),
+            x.powf(-2.5),
+            "powf",
+        );
+    }
+}
+
+#[test]
+fn test_bitfloat_known_constants() {
+    let ln2 = BitFloat::from(2).ln(128);
+    assert_eq!(
+        ln2,
+        BitFloat::make(false, -1, &[0xC9E3B39803F2F6AF, 0xB17217F7D1CF79AB])
+    );
+    let e = BitFloat::one().exp(66);
+    assert_eq!(e, BitFloat::make(false, 0, &[0xB7E151628AED2A6B, 2]));
+}
+
+#[test]
+fn test_bitfloat_exact_cases() {
+    assert_eq!(BitFloat::from(10).exp2(192), BitFloat::from(1024));
+    assert_eq!(
+        BitFloat::from(-200).exp2(64),
+        BitFloat::make(false, -4, &[1 << 56])
+    );
+    assert_eq!(BitFloat::from(0.125).log2(64), BitFloat::from(-3));
+    assert_eq!(BitFloat::from(10u128.pow(25)).log10(64), BitFloat::from(25));
+    assert_eq!(
+        BitFloat::from(81).log(&BitFloat::from(81), 64),
+        BitFloat::one()
+    );
+    assert_eq!(
+        BitFloat::from(8).log(&BitFloat::from(4), 64),
+        BitFloat::from(1.5)
+    );
+    assert_eq!(
+        BitFloat::from(-3).powf(&BitFloat::from(3), 64),
+        BitFloat::from(-27)
+    );
+    assert_eq!(
+        BitFloat::from(2).powf(&BitFloat::from(-2), 64),
+        BitFloat::from(0.25)
+    );
+    assert_eq!(
+        BitFloat::from(4).powf(&BitFloat::from(1.5), 64),
+        BitFloat::from(8)
+    );
+    assert_eq!(BitFloat::one().ln(256), BitFloat::zero());
+    assert_eq!(BitFloat::zero().exp(256), BitFloat::one());
+
+    let tiny = BitFloat::make(false, -40, &[12345]);
+    assert_eq!(tiny.expm1(64), tiny);
+    assert_eq!(tiny.ln1p(64), tiny);
+    assert_eq!(tiny.exp(192), BitFloat::one());
+
+    // 9^0.5 is exact but only settled at the working precision cap, where
+    // the rounding cannot tell it is and raises the inexact flag
+    BitFloat::clear_flags();
+    assert_eq!(BitFloat::from(9).powf(&BitFloat::from(0.5), 64), 3);
+    assert!(BitFloat::flags().inexact);
+}
+
+#[test]
+fn test_bitfloat_special_values() {
+    assert_eq!(BitFloat::zero().ln(64), BitFloat::neg_inf());
+    assert_eq!(BitFloat::inf().exp(64), BitFloat::inf());
+    assert_eq!(BitFloat::neg_inf().exp(64), BitFloat::zero());
+    assert_eq!(BitFloat::neg_inf().expm1(64), BitFloat::neg_one());
+    assert_eq!(BitFloat::from(-1).ln1p(64), BitFloat::neg_inf());
+    assert_eq!(
+        BitFloat::zero().powf(&BitFloat::from(-1), 64),
+        BitFloat::inf()
+    );
+    assert_eq!(
+        BitFloat::from(0.5).powf(&BitFloat::inf(), 64),
+        BitFloat::zero()
+    );
+    assert_eq!(BitFloat::from(1.0e40).exp(64), BitFloat::inf());
+}
+
+#[test]
+fn test_bitfloat_ln_negative_is_nan() {
+    BitFloat::clear_flags();
+    assert!(BitFloat::from(-2).ln(64).is_nan());
+    assert!(BitFloat::flags().invalid);
+}
+
+#[test]
+fn test_bitfloat_transcendentals_correctly_rounded() {
+    // rounding a more precise result must land on the same value, also at
+    // precisions between limb boundaries
+    let xs = rand_f64s(12, 3003);
+    for (i, &x) in xs.iter().enumerate() {
+        let bf = BitFloat::from(x) * (i as u32 + 1);
+        let pos = bf.abs();
+        let y = BitFloat::from(1.0 + x.abs());
+        for p in [24, 53, 113, 300] {
+            let check = |f: &dyn Fn(usize) -> BitFloat, what: &str| {
+                // skip exact midpoints, where rounding twice is off
+                let r = f(p + 100);
+                if r.prec() != p + 1 {
+                    assert_eq!(f(p), r.to_prec(p), "{what} x={x:e}, p={p}");
+                }
+            };
+            check(&|q| bf.exp(q), "exp");
+            check(&|q| bf.expm1(q), "expm1");
+            check(&|q| bf.exp2(q), "exp2");
+            check(&|q| pos.ln(q), "ln");
+            check(&|q| pos.ln1p(q), "ln1p");
+            check(&|q| pos.log10(q), "log10");
+            check(&|q| pos.powf(&y, q), "powf");
+            check(&|q| y.powf(&bf, q), "powf");
+        }
+    }
+}
+
+#[test]
+fn test_bitfloat_exp_ln_inverse() {
+    let x = BitFloat::make(false, 0, &[0x243F6A8885A308D3, 0x13198A2E03707344, 3]);
+    let back = x.exp(384).ln(384);
+    assert!(f64::try_from(&((&back - &x) / &x)).unwrap().abs() < 1.0e-100);
+    let sq = BitFloat::from(2).powf(&BitFloat::from(0.5), 256);
+    let diff = sq.full_mul(&sq) - 2;
+    assert!(f64::try_from(&diff).unwrap().abs() < 1.0e-50);
+}
+
+// ─── Trigonometric functions ────────────────────────────────────────────────
+
+#[test]
+fn test_bitfloat_trig_match_f64() {
+    for &x in &[0.5_f64, -0.3, 1.0, 2.75, -20.0, 100.5, 1.0e-10, 1.0e6] {
+        let bf = BitFloat::from(x);
+        assert_close(to_f64(&bf.sin(128)), x.sin(), "sin");
+        assert_close(to_f64(&bf.cos(128)), x.cos(), "cos");
+        assert_close(to_f64(&bf.tan(128)), x.tan(), "tan");
+        assert_close(to_f64(&bf.atan(128)), x.atan(), "atan");
+        let (s, c) = bf.sin_cos(128);
+        assert_eq!((s, c), (bf.sin(128), bf.cos(128)));
+        for &y in &[1.5_f64, -0.25, -7.0] {
+            let got = bf.atan2(&BitFloat::from(y), 128);
+            assert_close(to_f64(&got), x.atan2(y), "atan2");
+        }
+    }
+    for &x in &[0.5_f64, -0.3, 0.999, -1.0e-10, 0.0625] {
+        let bf = BitFloat::from(x);
+        assert_close(to_f64(&bf.asin(128)), x.asin(), "asin");
+        assert_close(to_f64(&bf.acos(128)), x.acos(), "acos");
+    }
+    for &x in &[0.3_f64, -0.2, 0.125, 0.001] {
+        let bf = BitFloat::from(x);
+        let pi = std::f64::consts::PI;
+        assert_close(to_f64(&bf.sinpi(128)), (x * pi).sin(), "sinpi");
+        assert_close(to_f64(&bf.cospi(128)), (x * pi).cos(), "cospi");
+        // the reduction is exact, so whole periods drop out
+        let far = BitFloat::make(x < 0.0, 0, &[bf.get_m()[0], 1_000_000]);
+        assert_eq!(far.sinpi(192), bf.sinpi(192));
+        assert_eq!(far.cospi(192), bf.cospi(192));
+    }
+}
+
+#[test]
+fn test_bitfloat_trig_huge_arguments() {
+    // the classic hard case for argument reduction
+    assert_close(
+        to_f64(&BitFloat::from(1.0e22).sin(128)),
+        -0.8522008497671888,
+        "sin(1e22)",
+    );
+    assert_close(
+        to_f64(&BitFloat::from(std::f64::consts::PI).sin(128)),
+        1.2246467991473532e-16,
+        "sin(pi)",
+    );
+    let big = BitFloat::from(2).powf(&BitFloat::from(300), 64);
+    let (s, c) = big.sin_cos(192);
+    let one = &s.full_mul(&s) + &c.full_mul(&c) - 1;
+    assert!(f64::try_from(&one).unwrap().abs() < 1.0e-50);
+}
+
+#[test]
+fn test_bitfloat_trig_exact_cases() {
+    let pi = BitFloat::make(false, 0, &[0x243F6A8885A308D3, 3]);
+    assert_eq!(BitFloat::from(-1).acos(66), pi);
+    assert_eq!(BitFloat::zero().atan2(&BitFloat::from(-1), 66), pi);
+    assert_eq!(
+        BitFloat::one().atan(128),
+        BitFloat::make(false, -1, &[0xC4C6628B80DC1CD1, 0xC90FDAA22168C234])
+    );
+    let half_pi = BitFloat::zero().acos(65);
+    assert_eq!(half_pi, BitFloat::make(false, 0, &[0x921FB54442D1846A, 1]));
+    assert_eq!(BitFloat::one().asin(65), half_pi);
+    assert_eq!(BitFloat::inf().atan(65), half_pi);
+
+    assert_eq!(BitFloat::from(0.5).sinpi(192), BitFloat::one());
+    assert_eq!(BitFloat::from(-0.5).sinpi(192), BitFloat::neg_one());
+    assert_eq!(BitFloat::from(7).sinpi(192), BitFloat::zero());
+    assert_eq!(BitFloat::from(3).cospi(192), BitFloat::neg_one());
+    assert_eq!(BitFloat::from(2.5).cospi(192), BitFloat::zero());
+    assert_eq!(BitFloat::one().acos(64), BitFloat::zero());
+    assert_eq!(BitFloat::zero().cos(64), BitFloat::one());
+
+    let tiny = BitFloat::make(false, -40, &[12345]);
+    assert_eq!(tiny.sin(64), tiny);
+    assert_eq!(tiny.tan(64), tiny);
+    assert_eq!(tiny.atan(64), tiny);
+    assert_eq!(tiny.asin(64), tiny);
+}
+
+#[test]
+fn test_bitfloat_asin_out_of_range_is_nan() {
+    BitFloat::clear_flags();
+    assert!(BitFloat::from(1.5).asin(64).is_nan());
+    assert!(BitFloat::flags().invalid);
+}
+
+#[test]
+fn test_bitfloat_trig_correctly_rounded() {
+    let xs = rand_f64s(12, 3004);
+    for (i, &x) in xs.iter().enumerate() {
+        let bf = BitFloat::from(x) * (i as u32 + 1);
+        let unit = BitFloat::from(x.sin());
+        for p in [24, 53, 113, 300] {
+            let check = |f: &dyn Fn(usize) -> BitFloat, what: &str| {
+                // skip exact midpoints, where rounding twice is off
+                let r = f(p + 100);
+                if r.prec() != p + 1 {
+                    assert_eq!(f(p), r.to_prec(p), "{what} x={x:e}, p={p}");
+                }
+            };
+            check(&|q| bf.sin(q), "sin");
+            check(&|q| bf.cos(q), "cos");
+            check(&|q| bf.tan(q), "tan");
+            check(&|q| bf.atan(q), "atan");
+            check(&|q| bf.sinpi(q), "sinpi");
+            check(&|q| unit.asin(q), "asin");
+            check(&|q| unit.acos(q), "acos");
+            check(&|q| unit.atan2(&bf, q), "atan2");
+        }
+    }
+}
+
+// ─── Hyperbolic functions ───────────────────────────────────────────────────
+
+#[test]
+fn test_bitfloat_hyperbolic_match_f64() {
+    for &x in &[0.5_f64, -0.3, 1.0, 2.75, -20.0, 100.5, 1.0e-10, -1.0e-3] {
+        let bf = BitFloat::from(x);
+        assert_close(to_f64(&bf.sinh(128)), x.sinh(), "sinh");
+        assert_close(to_f64(&bf.cosh(128)), x.cosh(), "cosh");
+        assert_close(to_f64(&bf.tanh(128)), x.tanh(), "tanh");
+        assert_close(to_f64(&bf.asinh(128)), x.asinh(), "asinh");
+    }
+    for &x in &[1.25_f64, 1.5, 3.0, 12345.678, 1.0e300] {
+        assert_close(to_f64(&BitFloat::from(x).acosh(128)), x.acosh(), "acosh");
+    }
+    // std loses digits to the cancellation in x - 1 here
+    assert_close(
+        to_f64(&BitFloat::from(1.0000001).acosh(128)),
+        4.4721359190373473e-4,
+        "acosh near 1",
+    );
+    for &x in &[0.5_f64, -0.3, 0.999, -1.0e-10, 0.0625] {
+        assert_close(to_f64(&BitFloat::from(x).atanh(128)), x.atanh(), "atanh");
+    }
+}
+
+#[test]
+fn test_bitfloat_hyperbolic_special_values() {
+    let tiny = BitFloat::make(true, -40, &[12345]);
+    assert_eq!(tiny.sinh(64), tiny);
+    assert_eq!(tiny.tanh(64), tiny);
+    assert_eq!(tiny.asinh(64), tiny);
+    assert_eq!(tiny.atanh(64), tiny);
+    assert_eq!(tiny.cosh(64), BitFloat::one());
+
+    assert_eq!(BitFloat::from(-1000).tanh(128), BitFloat::neg_one());
+    assert_eq!(BitFloat::neg_inf().tanh(128), BitFloat::neg_one());
+    assert_eq!(BitFloat::neg_inf().sinh(64), BitFloat::neg_inf());
+    assert_eq!(BitFloat::neg_inf().cosh(64), BitFloat::inf());
+    assert_eq!(BitFloat::from(1.0e40).sinh(64), BitFloat::inf());
+    assert_eq!(BitFloat::neg_inf().asinh(64), BitFloat::neg_inf());
+    assert_eq!(BitFloat::one().acosh(64), BitFloat::zero());
+    assert_eq!(BitFloat::neg_one().atanh(64), BitFloat::neg_inf());
+}
+
+#[test]
+fn test_bitfloat_acosh_below_one_is_nan() {
+    BitFloat::clear_flags();
+    assert!(BitFloat::from(0.5).acosh(64).is_nan());
+    assert!(BitFloat::flags().invalid);
+}
+
+#[test]
+fn test_bitfloat_hyperbolic_correctly_rounded() {
+    let xs = rand_f64s(12, 3005);
+    for (i, &x) in xs.iter().enumerate() {
+        let bf = BitFloat::from(x) * (i as u32 + 1);
+        let big = BitFloat::from(1.0 + x.abs());
+        let unit = BitFloat::from(x.tanh());
+        for p in [24, 53, 113, 300] {
+            let check = |f: &dyn Fn(usize) -> BitFloat, what: &str| {
+                // skip exact midpoints, where rounding twice is off
+                let r = f(p + 100);
+                if r.prec() != p + 1 {
+                    assert_eq!(f(p), r.to_prec(p), "{what} x={x:e}, p={p}");
+                }
+            };
+            check(&|q| bf.sinh(q), "sinh");
+            check(&|q| bf.cosh(q), "cosh");
+            check(&|q| bf.tanh(q), "tanh");
+            check(&|q| bf.asinh(q), "asinh");
+            check(&|q| big.acosh(q), "acosh");
+            check(&|q| unit.atanh(q), "atanh");
+        }
+    }
+}
+
+// ─── Roots ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_bitfloat_roots_match_f64() {
+    for &x in &[0.5_f64, 2.0, 3.0, 1.0e-10, 12345.678, 1.0e300, 5.0e-324] {
+        let bf = BitFloat::from(x);
+        assert_close(to_f64(&bf.sqrt(128)), x.sqrt(), "sqrt");
+        assert_close(to_f64(&bf.rsqrt(128)), 1.0 / x.sqrt(), "rsqrt");
+        assert_close(to_f64(&bf.cbrt(128)), x.cbrt(), "cbrt");
+        assert_close(to_f64(&(-&bf).cbrt(128)), -x.cbrt(), "cbrt");
+        // powf(1/7) is off by more than an ulp, so go back the other way
+        let back = bf.nth_root(7, 128).powf(&BitFloat::from(7), 128);
+        assert_close(to_f64(&back), x, "root7");
+        for &y in &[1.5_f64, -0.25, 7.0e200] {
+            let got = bf.hypot(&BitFloat::from(y), 128);
+            assert_close(to_f64(&got), x.hypot(y), "hypot");
+        }
+    }
+}
+
+#[test]
+fn test_bitfloat_roots_exact_cases() {
+    assert_eq!(BitFloat::from(2.25).sqrt(2), BitFloat::from(1.5));
+    assert_eq!(BitFloat::from(-27).cbrt(2), BitFloat::from(-3));
+    assert_eq!(BitFloat::from(0.25).rsqrt(1), BitFloat::from(2));
+    assert_eq!(
+        BitFloat::from(3u128.pow(80)).nth_root(20, 7),
+        BitFloat::from(81)
+    );
+    assert_eq!(
+        BitFloat::from(3).hypot(&BitFloat::from(-4), 3),
+        BitFloat::from(5)
+    );
+    // (2^128 + 1)^2 needs 257 bits, and its root needs 129
+    let r = BitFloat::make(false, 2, &[1, 0, 1]);
+    assert_eq!(r.full_sqr().sqrt(128), BitFloat::make(false, 2, &[1]));
+    assert_eq!(r.full_sqr().sqrt(129), r);
+
+    // a long exact root is still found at low precision, and one too long
+    // to matter there rounds like any other
+    let r = BitFloat::one().add_round(
+        &BitFloat::pow2(-10000),
+        usize::MAX,
+        RoundingMode::NearestEven,
+    );
+    let x = r.full_sqr();
+    assert_eq!(x.sqrt(10001), r);
+    assert_eq!(x.sqrt(10000), BitFloat::one());
+    assert_eq!(x.sqrt(24), BitFloat::one());
+    assert_eq!(
+        x.sqrt_round(24, RoundingMode::TowardPositive),
+        BitFloat::one() + BitFloat::pow2(-23)
+    );
+    assert_eq!(x.sqrt_round(24, RoundingMode::TowardZero), BitFloat::one());
+
+    // far apart, the smaller value only breaks the tie
+    let a = BitFloat::make(false, 0, &[1, 1]);
+    let b = BitFloat::make(false, -100, &[1]);
+    assert_eq!(a.hypot(&b, 64), BitFloat::make(false, 0, &[2, 1]));
+    assert_eq!(a.hypot(&BitFloat::zero(), 64), BitFloat::one());
+
+    let huge = BitFloat::make(false, 1 << 100, &[3]);
+    let root18 = BitFloat::from(18).sqrt(128);
+    assert_eq!(
+        huge.hypot(&huge, 128),
+        BitFloat::make(false, 1 << 100, root18.get_m())
+    );
+    assert_eq!(BitFloat::zero().rsqrt(1), BitFloat::inf());
+    assert_eq!(BitFloat::inf().sqrt(1), BitFloat::inf());
+}
+
+#[test]
+fn test_bitfloat_even_root_of_negative_is_nan() {
+    BitFloat::clear_flags();
+    assert!(BitFloat::from(-16).nth_root(4, 64).is_nan());
+    assert!(BitFloat::flags().invalid);
+}
+
+#[test]
+fn test_bitfloat_roots_correctly_rounded() {
+    let xs = rand_f64s(12, 3006);
+    for (i, &x) in xs.iter().enumerate() {
+        let bf = BitFloat::from(x) * (i as u32 + 1);
+        let pos = bf.abs();
+        let y = BitFloat::from(x.sin());
+        for p in [24, 53, 113, 300] {
+            let check = |f: &dyn Fn(usize) -> BitFloat, what: &str| {
+                // skip exact midpoints, where rounding twice is off
+                let r = f(p + 100);
+                if r.prec() != p + 1 {
+                    assert_eq!(f(p), r.to_prec(p), "{what} x={x:e}, p={p}");
+                }
+            };
+            check(&|q| pos.sqrt(q), "sqrt");
+            check(&|q| pos.rsqrt(q), "rsqrt");
+            check(&|q| bf.cbrt(q), "cbrt");
+            check(&|q| pos.nth_root(5, q), "nth_root");
+            check(&|q| bf.hypot(&y, q), "hypot");
+        }
+    }
+}
+
+// ─── Constants ──────────────────────────────────────────────────────────────
+
+#[test]
+fn test_bitfloat_constants_known_values() {
+    assert_eq!(
+        constants::pi(66),
+        BitFloat::make(false, 0, &[0x243F6A8885A308D3, 3])
+    );
+    assert_eq!(
+        constants::e(66),
+        BitFloat::make(false, 0, &[0xB7E151628AED2A6B, 2])
+    );
+    assert_eq!(
+        constants::ln2(128),
+        BitFloat::make(false, -1, &[0xC9E3B39803F2F6AF, 0xB17217F7D1CF79AB])
+    );
+    assert_eq!(
+        constants::ln10(66),
+        BitFloat::make(false, 0, &[0x4D763776AAA2B05C, 2])
+    );
+    assert_eq!(
+        constants::sqrt2(65),
+        BitFloat::make(false, 0, &[0x6A09E667F3BCC909, 1])
+    );
+    assert_eq!(
+        constants::euler_gamma(128),
+        BitFloat::make(false, -1, &[0xD1BE3F810152CB57, 0x93C467E37DB0C7A4])
+    );
+    assert_eq!(
+        constants::catalan(128),
+        BitFloat::make(false, -1, &[0x215822E37D32D0C6, 0xEA7CB89F409AE845])
+    );
+
+    let pi = [
+        0x452821E638D01378,
+        0x082EFA98EC4E6C89,
+        0xA4093822299F31D0,
+        0x13198A2E03707344,
+        0x243F6A8885A308D3,
+        3,
+    ];
+    assert_eq!(constants::pi(322), BitFloat::make(false, 0, &pi));
+    let gamma = [
+        0xD0649CCB621057D1,
+        0x8E4B59FA03A9F0EE,
+        0x0C03DF34709AFFBD,
+        0xA1CECC3AF65CC019,
+        0xD1BE3F810152CB56,
+        0x93C467E37DB0C7A4,
+    ];
+    assert_eq!(
+        constants::euler_gamma(384),
+        BitFloat::make(false, -1, &gamma)
+    );
+    let catalan = [
+        0x8F924D663F739C43,
+        0x94DC058A46EEC585,
+        0x94A263E5A3CCD76F,
+        0x3EC43E1381C2FF80,
+        0x215822E37D32D0C6,
+        0xEA7CB89F409AE845,
+    ];
+    assert_eq!(constants::catalan(384), BitFloat::make(false, -1, &catalan));
+}
+
+#[test]
+fn test_bitfloat_constants_cache_grows_and_shrinks() {
+    // asking for more bits grows the cache, and smaller requests round it
+    let big = constants::pi(2560);
+    for p in [1, 190, 640, 2500] {
+        assert_eq!(constants::pi(p), big.to_prec(p), "p={p}");
+    }
+    assert_eq!(constants::e(768), BitFloat::one().exp(768));
+    assert_eq!(constants::ln10(768), BitFloat::from(10).ln(768));
+    assert_eq!(constants::sqrt2(768), BitFloat::from(2).sqrt(768));
+    assert_eq!(constants::pi(768), BitFloat::from(-1).acos(768));
+
+    let handles: Vec<_> = (1..=8)
+        .map(|i| std::thread::spawn(move || (i, constants::euler_gamma(250 * i))))
+        .collect();
+    let gamma = constants::euler_gamma(2560);
+    for h in handles {
+        let (i, g) = h.join().unwrap();
+        assert_eq!(g, gamma.to_prec(250 * i));
+    }
+}
+
+// ─── Special functions ──────────────────────────────────────────────────────
+
+#[test]
+fn test_bitfloat_gamma_family_match_mpmath() {
+    // (x, Γ(x), ln|Γ(x)|, ψ(x)) from mpmath
+    let cases = [
+        (
+            0.5,
+            1.772453850905516,
+            0.5723649429247001,
+            -1.9635100260214235,
+        ),
+        (
+            1.5,
+            0.886226925452758,
+            -0.12078223763524522,
+            0.03648997397857652,
+        ),
+        (
+            3.25,
+            2.5492569667185294,
+            0.9358019311087253,
+            1.016990911068179,
+        ),
+        (10.0, 362880.0, 12.801827480081469, 2.251752589066721),
+        (
+            30.7,
+            9.528117499079478e31,
+            73.63438504676965,
+            3.4078876007507035,
+        ),
+        (
+            -0.5,
+            -3.544907701811032,
+            1.2655121234846454,
+            0.03648997397857652,
+        ),
+        (
+            -2.5,
+            -0.9453087204829419,
+            -0.056243716497674054,
+            1.103156640645243,
+        ),
+        (
+            -7.3,
+            0.000418387873013548,
+            -7.779101629826852,
+            4.33730730551005,
+        ),
+        (
+            1e-5,
+            99999.42279422555,
+            11.512919692895826,
+            -100000.57719921567,
+        ),
+    ];
+    for (x, g, lg, dg) in cases {
+        let bf = BitFloat::from(x);
+        assert_close(to_f64(&bf.gamma(128)), g, "gamma");
+        assert_close(to_f64(&bf.lgamma(128)), lg, "lgamma");
+        assert_close(to_f64(&bf.digamma(128)), dg, "digamma");
+    }
+    let big = BitFloat::from(1e10);
+    assert_close(to_f64(&big.lgamma(128)), 220258509288.81058, "lgamma");
+    assert_close(to_f64(&big.digamma(128)), 23.025850929890456, "digamma");
+
+    // (a, b, B(a, b))
+    let cases = [
+        (0.5, 0.5, std::f64::consts::PI),
+        (2.5, 3.25, 0.04301393117088918),
+        (-0.5, 1.25, -2.6220575542921196),
+        (10.0, 0.1, 7.5913800009109895),
+        (100.5, 200.25, 1.8792571927173906e-84),
+    ];
+    for (a, b, want) in cases {
+        let got = BitFloat::from(a).beta(&BitFloat::from(b), 128);
+        assert_close(to_f64(&got), want, "beta");
+    }
+}
+
+#[test]
+fn test_bitfloat_erf_zeta_match_mpmath() {
+    // (x, erf(x), erfc(x))
+    let cases = [
+        (0.25, 0.27632639016823696, 0.7236736098317631),
+        (-0.75, -0.7111556336535151, 1.7111556336535152),
+        (1.5, 0.9661051464753108, 0.033894853524689274),
+        (3.0, 0.9999779095030014, 2.209049699858544e-5),
+        (5.5, 0.9999999999999927, 7.357847917974398e-15),
+        (1e-8, 1.1283791670955126e-8, 0.9999999887162083),
+        (-3.0, -0.9999779095030014, 1.9999779095030015),
+        (10.0, 1.0, 2.088487583762545e-45),
+        (27.0, 1.0, 5.23705e-319),
+    ];
+    for (x, e, ec) in cases {
+        let bf = BitFloat::from(x);
+        assert_close(to_f64(&bf.erf(128)), e, "erf");
+        assert_close(to_f64(&bf.erfc(128)), ec, "erfc");
+    }
+
+    let cases = [
+        (2.0, 1.6449340668482264),
+        (3.0, 1.2020569031595942),
+        (0.5, -1.4603545088095868),
+        (0.75, -3.4412853869452227),
+        (1.5, 2.612375348685488),
+        (10.5, 1.000700842641736),
+        (-1.0, -0.08333333333333333),
+        (-3.5, 0.004441011335479432),
+        (0.25, -0.8132784052618917),
+        (-20.5, -108.21747505877606),
+    ];
+    for (s, want) in cases {
+        assert_close(to_f64(&BitFloat::from(s).zeta(128)), want, "zeta");
+    }
+}
+
+#[test]
+fn test_bitfloat_bessel_match_mpmath() {
+    // (x, J0(x), J1(x), J5(x), Y0(|x|), Y1(|x|))
+    let cases = [
+        (
+            0.5,
+            0.9384698072408129,
+            0.2422684576748739,
+            8.053627241357474e-6,
+            -0.44451873350670656,
+            -1.471472392670243,
+        ),
+        (
+            1.0,
+            0.7651976865579666,
+            0.4400505857449335,
+            0.00024975773021123444,
+            0.08825696421567696,
+            -0.7812128213002887,
+        ),
+        (
+            2.404825557695773,
+            -6.10876525973673e-17,
+            0.5191474972894667,
+            0.01638924320480585,
+            0.509924383448479,
+            0.1027466824382596,
+        ),
+        (
+            7.25,
+            0.291996924191779,
+            0.06858170065313174,
+            0.3203580732712001,
+            0.04842252986520283,
+            -0.28934799419758706,
+        ),
+        (
+            20.0,
+            0.16702466434058316,
+            0.06683312417585005,
+            0.15116976798239498,
+            0.06264059680938383,
+            -0.1655116143625213,
+        ),
+        (
+            -3.5,
+            -0.3801277399872634,
+            -0.1373775273623272,
+            -0.08044198664799178,
+            0.1890219439208265,
+            0.41018841788751187,
+        ),
+        (
+            60.0,
+            -0.09147180408906187,
+            0.046598383758166315,
+            0.0274547442283441,
+            0.0473589522094494,
+            0.09186960936986689,
+        ),
+        (
+            250.0,
+            -0.026053373425204234,
+            -0.04326903841033075,
+            -0.04446943851215875,
+            -0.04321684544036627,
+            0.025966992185484584,
+        ),
+    ];
+    for (x, j0, j1, j5, y0, y1) in cases {
+        let bf = BitFloat::from(x);
+        let abs = bf.abs();
+        assert_close(to_f64(&bf.j0(128)), j0, "j0");
+        assert_close(to_f64(&bf.j1(128)), j1, "j1");
+        assert_close(to_f64(&bf.jn(5, 128)), j5, "j5");
+        assert_close(to_f64(&bf.jn(-5, 128)), -j5, "j-5");
+        assert_close(to_f64(&abs.y0(128)), y0, "y0");
+        assert_close(to_f64(&abs.y1(128)), y1, "y1");
+    }
+}
+
+#[test]
+fn test_bitfloat_special_functions_known_values() {
+    let half = BitFloat::from(0.5);
+    let one = BitFloat::one();
+    assert_eq!(
+        half.gamma(128),
+        BitFloat::make(false, 0, &[0xC3B0520D5DB93840, 0xC5BF891B4EF6AA79, 1])
+    );
+    assert_eq!(
+        half.lgamma(128),
+        BitFloat::make(false, -1, &[0xAFCAB635421FA4CC, 0x928682473D0DE85E])
+    );
+    assert_eq!(one.digamma(128), -constants::euler_gamma(128));
+    assert_eq!(
+        BitFloat::from(3).zeta(128),
+        BitFloat::make(false, 0, &[0x71715C59E6907F1C, 0x33BA004F00621383, 1])
+    );
+    assert_eq!(
+        one.erf(128),
+        BitFloat::make(false, -1, &[0x80887EDD869379D3, 0xD7BB3D3A08445636])
+    );
+    assert_eq!(
+        one.j0(128),
+        BitFloat::make(false, -1, &[0xF1A9861159A676E2, 0xC3E3FEDEBDC77C1C])
+    );
+    assert_eq!(
+        one.y0(128),
+        BitFloat::make(
+            false,
+            -1,
+            &[0x6000000000000000, 0xEFCC5D79265550EB, 0x16980226F358DF29]
+        )
+    );
+
+    // exact values
+    assert_eq!(
+        BitFloat::from(21).gamma(200),
+        BitFloat::from(2432902008176640000_u64)
+    );
+    let b = BitFloat::one().div_round(&BitFloat::from(504), 64, RoundingMode::NearestEven);
+    assert_eq!(BitFloat::from(6).beta(&BitFloat::from(4), 64), b);
+    // 0! in the exact paths
+    assert_eq!(BitFloat::one().gamma(53), BitFloat::one());
+    assert_eq!(BitFloat::from(2).gamma(53), BitFloat::one());
+    assert_eq!(BitFloat::one().beta(&BitFloat::one(), 53), BitFloat::one());
+    for k in 2..12 {
+        let inv = BitFloat::one().div_round(&BitFloat::from(k), 64, RoundingMode::NearestEven);
+        assert_eq!(BitFloat::one().beta(&BitFloat::from(k), 64), inv, "k={k}");
+        assert_eq!(BitFloat::from(k).beta(&BitFloat::one(), 64), inv, "k={k}");
+    }
+    assert_eq!(BitFloat::from(2).lgamma(64), BitFloat::zero());
+    assert_eq!(BitFloat::from(-2).zeta(64), BitFloat::zero());
+    assert_eq!(BitFloat::zero().zeta(64), BitFloat::from(-0.5));
+    assert_eq!(BitFloat::from(200).zeta(64), BitFloat::one());
+    assert_eq!(
+        BitFloat::from(-1.5).beta(&BitFloat::from(-2.5), 64),
+        BitFloat::zero()
+    );
+}
+
+#[test]
+fn test_bitfloat_special_functions_special_values() {
+    let (zero, neg_zero) = (BitFloat::zero(), BitFloat::neg_zero());
+    let (inf, neg_inf) = (BitFloat::inf(), BitFloat::neg_inf());
+    let nan = BitFloat::nan();
+    let poles = [BitFloat::from(-3), BitFloat::from(-1e20)];
+
+    BitFloat::clear_flags();
+    assert_eq!(zero.gamma(64), inf);
+    assert_eq!(neg_zero.gamma(64), neg_inf);
+    assert_eq!(zero.digamma(64), neg_inf);
+    assert_eq!(neg_zero.digamma(64), inf);
+    assert_eq!(poles[0].lgamma(64), inf);
+    assert_eq!(BitFloat::one().zeta(64), inf);
+    assert_eq!(zero.y0(64), neg_inf);
+    assert!(BitFloat::flags().div_by_zero && !BitFloat::flags().invalid);
+
+    for x in poles.iter().chain([&neg_inf]) {
+        BitFloat::clear_flags();
+        assert!(x.gamma(64).is_nan());
+        assert!(x.digamma(64).is_nan());
+        assert!(BitFloat::flags().invalid);
+    }
+    BitFloat::clear_flags();
+    assert!(neg_inf.zeta(64).is_nan());
+    assert!(BitFloat::from(-2).y1(64).is_nan());
+    assert!(poles[0].beta(&BitFloat::from(0.5), 64).is_nan());
+    assert!(BitFloat::flags().invalid);
+
+    for f in [
+        BitFloat::gamma,
+        BitFloat::lgamma,
+        BitFloat::digamma,
+        BitFloat::erf,
+        BitFloat::erfc,
+        BitFloat::zeta,
+        BitFloat::j0,
+        BitFloat::y0,
+    ] {
+        assert!(f(&nan, 64).is_nan());
+    }
+
+    assert_eq!(inf.gamma(64), inf);
+    assert_eq!(neg_inf.lgamma(64), inf);
+    assert_eq!(inf.zeta(64), BitFloat::one());
+    assert_eq!(inf.erf(64), BitFloat::one());
+    assert_eq!(neg_inf.erfc(64), BitFloat::from(2));
+    assert_eq!(inf.erfc(64), zero);
+    assert_eq!(neg_inf.j0(64), zero);
+    assert_eq!(inf.y1(64), zero);
+    assert_eq!(inf.beta(&BitFloat::from(2), 64), zero);
+    assert!(neg_zero.erf(64).get_s());
+    assert!(neg_zero.j1(64).get_s());
+    assert!(!neg_zero.jn(-1, 64).get_s());
+    assert_eq!(zero.j0(64), BitFloat::one());
+    assert_eq!(zero.erfc(64), BitFloat::one());
+
+    BitFloat::clear_flags();
+    assert_eq!(BitFloat::from(1e40).gamma(64), inf);
+    assert!(BitFloat::flags().overflow);
+    BitFloat::clear_flags();
+    assert_eq!(BitFloat::from(1e20).erfc(64), zero);
+    assert!(BitFloat::flags().underflow);
+    let half = BitFloat::from(0.5);
+    let x = BitFloat::from(-1e40).sub_round(&half, usize::MAX, RoundingMode::NearestEven);
+    BitFloat::clear_flags();
+    assert!(x.gamma(64).is_zero());
+    assert!(BitFloat::flags().underflow);
+}
+
+#[test]
+fn test_bitfloat_special_functions_correctly_rounded() {
+    let xs = rand_f64s(6, 3013);
+    for (i, &x) in xs.iter().enumerate() {
+        // arguments of moderate size, where the series and the recurrences
+        // all take part
+        let bf = BitFloat::from(x.signum() * (x.abs().fract() + 2.0 * i as f64 + 0.1));
+        let pos = bf.abs();
+        for p in [24, 53, 113, 300] {
+            let check = |f: &dyn Fn(usize) -> BitFloat, what: &str| {
+                // skip exact midpoints, where rounding twice is off
+                let r = f(p + 100);
+                if r.prec() != p + 1 {
+                    assert_eq!(f(p), r.to_prec(p), "{what} x={bf:?}, p={p}");
+                }
+            };
+            check(&|q| bf.gamma(q), "gamma");
+            check(&|q| bf.lgamma(q), "lgamma");
+            check(&|q| bf.digamma(q), "digamma");
+            check(&|q| bf.beta(&pos, q), "beta");
+            check(&|q| bf.erf(q), "erf");
+            check(&|q| bf.erfc(q), "erfc");
+            check(&|q| bf.jn(3, q), "jn");
+            check(&|q| pos.y1(q), "y1");
+            if i < 3 {
+                check(&|q| bf.zeta(q), "zeta");
+            }
+        }
+    }
+}
+
+// ─── Rounding modes ─────────────────────────────────────────────────────────
+
+#[test]
+fn test_bitfloat_rounding_modes_to_prec() {
+    use RoundingMode::*;
+    assert_eq!(RoundingMode::default(), NearestEven);
+    // at 2 bits: 1.25 is a tie with an even 1, 1.3125 is above it and 1.75
+    // is a tie with an odd 1.5
+    let cases = [
+        (NearestEven, [1.0, 1.5, 2.0]),
+        (NearestAway, [1.5, 1.5, 2.0]),
+        (TowardZero, [1.0, 1.0, 1.5]),
+        (TowardPositive, [1.5, 1.5, 2.0]),
+        (TowardNegative, [1.0, 1.0, 1.5]),
+        (Faithful, [1.0, 1.0, 1.5]),
+    ];
+    for (mode, want) in cases {
+        let got = [1.25, 1.3125, 1.75].map(|x| BitFloat::from(x).to_prec_round(2, mode));
+        assert_eq!(got, want.map(BitFloat::from), "{mode:?}");
+    }
+    // directed modes mirror for negative values
+    let neg = BitFloat::from(-1.25);
+    assert_eq!(neg.to_prec_round(2, TowardPositive), BitFloat::from(-1));
+    assert_eq!(neg.to_prec_round(2, TowardNegative), BitFloat::from(-1.5));
+    assert_eq!(neg.to_prec_round(2, TowardZero), BitFloat::from(-1));
+    // a tie across a limb boundary
+    let tie = BitFloat::make(false, 0, &[1 << 63, 1]);
+    assert_eq!(tie.to_prec_round(1, NearestEven), BitFloat::from(2));
+    assert_eq!(tie.to_prec_round(1, TowardZero), BitFloat::one());
+
+    for (x, ne, na, up, down) in [(2.5, 2, 3, 3, 2), (-2.5, -2, -3, -2, -3), (0.7, 1, 1, 1, 0)] {
+        let bf = BitFloat::from(x);
+        assert_eq!(bf.round_to_int(NearestEven), ne, "{x}");
+        assert_eq!(bf.round_to_int(NearestAway), na, "{x}");
+        assert_eq!(bf.round_to_int(TowardPositive), up, "{x}");
+        assert_eq!(bf.round_to_int(TowardNegative), down, "{x}");
+    }
+}
+
+#[test]
+fn test_bitfloat_directed_ops_bracket_exact() {
+    use RoundingMode::*;
+    let xs = rand_f64s(40, 3007);
+    let ys = rand_f64s(40, 3008);
+    for (&x, &y) in xs.iter().zip(&ys) {
+        let (a, b) = (BitFloat::from(x), BitFloat::from(y));
+        let ops: [(&dyn Fn(RoundingMode) -> BitFloat, BitFloat); 3] = [
+            (
+                &|m| a.add_round(&b, 64, m),
+                a.add_round(&b, usize::MAX, NearestEven),
+            ),
+            (
+                &|m| a.sub_round(&b, 64, m),
+                a.sub_round(&b, usize::MAX, NearestEven),
+            ),
+            (&|m| a.mul_round(&b, 64, m), a.full_mul(&b)),
+        ];
+        for (op, exact) in ops {
+            let (down, up) = (op(TowardNegative), op(TowardPositive));
+            assert!(down <= exact && exact <= up, "x={x:e}, y={y:e}");
+            let near = op(NearestEven);
+            assert!(near == down || near == up);
+            let faithful = op(Faithful);
+            assert!(faithful == down || faithful == up);
+            let zero = op(TowardZero);
+            assert_eq!(zero, if exact.get_s() { up } else { down });
+        }
+
+        // b * down <= a <= b * up for the quotient a / b
+        let (down, up) = (
+            a.div_round(&b, 128, TowardNegative),
+            a.div_round(&b, 128, TowardPositive),
+        );
+        let (lo, hi) = if b.get_s() {
+            (&up, &down)
+        } else {
+            (&down, &up)
+        };
+        assert!(
+            b.full_mul(lo) <= a && a <= b.full_mul(hi),
+            "x={x:e}, y={y:e}"
+        );
+        let near = a.div_round(&b, 128, NearestEven);
+        assert!(near == down || near == up);
+    }
+}
+
+#[test]
+fn test_bitfloat_directed_ops_low_limbs_below_window() {
+    use RoundingMode::*;
+    let exact_sum = |a: &BitFloat, b: &BitFloat| a.add_round(b, usize::MAX, NearestEven);
+    let pow2 = BitFloat::pow2;
+    // 1 + 2^-384 - 2^-320 is just below 1
+    let x = exact_sum(&BitFloat::one(), &pow2(-384));
+    assert_eq!(
+        x.sub_round(&pow2(-320), 64, TowardNegative),
+        BitFloat::one().next_down(64)
+    );
+    assert_eq!(
+        x.sub_round(&pow2(-320), 64, TowardZero),
+        BitFloat::one().next_down(64)
+    );
+
+    // a with limbs far below its top, and b cut to a sticky bit below them;
+    // 2 - 2^-384 carries into the top limb when anything is added
+    let ones = BitFloat::from(2).sub_round(&pow2(-384), usize::MAX, NearestEven);
+    let heads = [x, ones, exact_sum(&BitFloat::from(3), &pow2(-500))];
+    for a in &heads {
+        for k in [-200, -320, -383, -385, -450, -600] {
+            for b in [pow2(k), exact_sum(&pow2(k), &pow2(k - 300))] {
+                for (sub, exact) in [
+                    (false, exact_sum(a, &b)),
+                    (true, a.sub_round(&b, usize::MAX, NearestEven)),
+                ] {
+                    let op = |m| {
+                        if sub {
+                            a.sub_round(&b, 64, m)
+                        } else {
+                            a.add_round(&b, 64, m)
+                        }
+                    };
+                    let (down, up) = (op(TowardNegative), op(TowardPositive));
+                    assert!(down < exact && exact < up, "a={a:?}, k={k}, sub={sub}");
+                    assert_eq!(up, down.next_up(64), "a={a:?}, k={k}, sub={sub}");
+                    assert_eq!(op(TowardZero), down);
+                    let near = op(NearestEven);
+                    assert!(near == down || near == up);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_bitfloat_directed_sqrt_and_f64() {
+    use RoundingMode::*;
+    let two = BitFloat::from(2);
+    let (down, up) = (
+        two.sqrt_round(150, TowardNegative),
+        two.sqrt_round(150, TowardPositive),
+    );
+    assert!(down.full_sqr() < two && two < up.full_sqr());
+    assert_eq!(two.sqrt_round(150, NearestEven), two.sqrt(150));
+    for mode in [TowardZero, TowardPositive, TowardNegative, Faithful] {
+        assert_eq!(
+            BitFloat::from(6.25).sqrt_round(3, mode),
+            BitFloat::from(2.5)
+        );
+    }
+
+    let tenth = BitFloat::one().div_round(&BitFloat::from(10), 150, NearestEven);
+    let down = tenth.to_f64_round(TowardNegative).unwrap();
+    let up = tenth.to_f64_round(TowardPositive).unwrap();
+    assert_eq!(tenth.to_f64_round(NearestEven).unwrap(), 0.1);
+    assert_eq!(f64::from_bits(down.to_bits() + 1), up);
+    assert!(tenth > down && tenth < up);
+    assert_eq!(
+        tenth.to_f32_round(TowardZero).unwrap(),
+        f32::from_bits(0.1_f32.to_bits() - 1)
+    );
+
+    let tiny = BitFloat::make(false, -20, &[1]);
+    assert_eq!(tiny.to_f64_round(TowardPositive).unwrap(), 5.0e-324);
+    assert_eq!(tiny.to_f64_round(NearestEven).unwrap(), 0.0);
+    assert_eq!((-&tiny).to_f64_round(TowardNegative).unwrap(), -5.0e-324);
+}
+
+// ─── Interchange formats ───────────────────────────────────────────────────
+
+#[test]
+fn test_bitfloat_half_formats() {
+    use RoundingMode::*;
+    // every binary16 and bfloat16 encoding reads back to itself, NaNs
+    // included
+    for bits in 0..=u16::MAX {
+        let x = BitFloat::from_bits_binary16(bits);
+        assert_eq!(x.to_binary16(NearestEven).unwrap(), bits, "{bits:#x}");
+        let y = BitFloat::from_bits_bfloat16(bits);
+        let f = f32::from_bits((bits as u32) << 16);
+        if f.is_nan() {
+            assert!(y.is_nan());
+        } else {
+            assert_eq!(y, BitFloat::from(f));
+            assert_eq!(y.get_s(), f.is_sign_negative());
+        }
+        assert_eq!(y.to_bfloat16(NearestEven).unwrap(), bits, "{bits:#x}");
+    }
+
+    assert_eq!(BitFloat::from_bits_binary16(0x3C00), 1);
+    assert_eq!(BitFloat::from_bits_binary16(0x7BFF), 65504);
+    assert_eq!(BitFloat::from_bits_binary16(0x0001), BitFloat::pow2(-24));
+    assert!(BitFloat::from_bits_binary16(0x7C01).is_nan());
+    assert_eq!(
+        BitFloat::from(0.1).to_binary16(NearestEven).unwrap(),
+        0x2E66
+    );
+    assert!(BitFloat::from(65520).to_binary16(NearestEven).is_err());
+    assert_eq!(
+        BitFloat::from(65520).to_binary16(TowardZero).unwrap(),
+        0x7BFF
+    );
+    assert_eq!(
+        BitFloat::neg_inf().to_binary16(NearestEven).unwrap(),
+        0xFC00
+    );
+
+    // bfloat16 rounds the f32 bits to nearest, ties to even
+    let mut rng = StdRng::seed_from_u64(3025);
+    for _ in 0..2000 {
+        let f = f32::from_bits(rng.gen::<u32>() & 0x7F7F_FFFF);
+        let bits = f.to_bits();
+        let want = ((bits + 0x7FFF + ((bits >> 16) & 1)) >> 16) as u16;
+        let got = BitFloat::from(f).to_bfloat16(NearestEven);
+        if want >= 0x7F80 {
+            assert!(got.is_err());
+        } else {
+            assert_eq!(got.unwrap(), want, "{f:e}");
+        }
+    }
+}
+
+#[test]
+fn test_bitfloat_binary128_and_x87() {
+    use RoundingMode::*;
+    let pi = constants::pi(300);
+    assert_eq!(BitFloat::from_bits_binary128(0x3FFF << 112), 1);
+    assert_eq!(
+        pi.to_binary128(NearestEven).unwrap(),
+        0x4000_921F_B544_42D1_8469_898C_C517_01B8
+    );
+    let third = BitFloat::one().div_round(&BitFloat::from(3), 300, NearestEven);
+    assert_eq!(
+        third.to_binary128(NearestEven).unwrap(),
+        0x3FFD_5555_5555_5555_5555_5555_5555_5555
+    );
+    assert_eq!(
+        BitFloat::from_bits_binary128(0x8000_0000_0000_0000_0000_0000_0000_0001),
+        -BitFloat::pow2(-16494)
+    );
+    assert_eq!(
+        BitFloat::from_bits_binary128(0x7FFE_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF),
+        BitFloat::pow2(16384).sub_round(&BitFloat::pow2(16271), usize::MAX, NearestEven)
+    );
+    assert!(BitFloat::from_bits_binary128(0x7FFF_0000_0000_0000_0000_0000_0000_0001).is_nan());
+    assert!(BitFloat::pow2(16384).to_binary128(NearestEven).is_err());
+
+    // subnormals round at a fixed bit, raising underflow when inexact
+    BitFloat::clear_flags();
+    assert_eq!(BitFloat::pow2(-16495).to_binary128(NearestEven).unwrap(), 0);
+    assert!(BitFloat::flags().underflow);
+    let x = BitFloat::pow2(-16495) * BitFloat::from(3);
+    assert_eq!(x.to_binary128(NearestEven).unwrap(), 2);
+
+    for x in rand_f64s(200, 3026) {
+        let bf = BitFloat::from(x) * &pi;
+        let q = bf.to_prec(113);
+        assert_eq!(
+            BitFloat::from_bits_binary128(bf.to_binary128(NearestEven).unwrap()),
+            q
+        );
+        let e = bf.to_prec(64);
+        assert_eq!(BitFloat::from_bits_x87(bf.to_x87(NearestEven).unwrap()), e);
+    }
+
+    // x87 keeps its integer bit explicit
+    assert_eq!(
+        BitFloat::one().to_x87(NearestEven).unwrap(),
+        0x3FFF_8000_0000_0000_0000
+    );
+    assert_eq!(pi.to_x87(NearestEven).unwrap(), 0x4000_C90F_DAA2_2168_C235);
+    assert_eq!(
+        BitFloat::inf().to_x87(NearestEven).unwrap(),
+        0x7FFF_8000_0000_0000_0000
+    );
+    assert_eq!(
+        BitFloat::nan().to_x87(NearestEven).unwrap(),
+        0x7FFF_C000_0000_0000_0000
+    );
+    let denormal = BitFloat::pow2(-16400);
+    assert_eq!(denormal.to_x87(NearestEven).unwrap(), 1 << 45);
+    assert_eq!(BitFloat::from_bits_x87(1 << 45), denormal);
+    // a pseudo-denormal reads as the smallest normal
+    assert_eq!(
+        BitFloat::from_bits_x87(0x0000_8000_0000_0000_0000),
+        BitFloat::pow2(-16382)
+    );
+    assert_eq!(
+        BitFloat::from_bits_x87(0x0001_8000_0000_0000_0000),
+        BitFloat::pow2(-16382)
+    );
+    // while unnormals and pseudo-infinities are invalid
+    assert!(BitFloat::from_bits_x87(0x3FFF_4000_0000_0000_0000).is_nan());
+    assert!(BitFloat::from_bits_x87(0x7FFF_0000_0000_0000_0000).is_nan());
+    assert!(BitFloat::from_bits_x87(0xFFFF_8000_0000_0000_0000).is_inf());
+}
+
+#[test]
+fn test_bitfloat_interchange_nan_payloads() {
+    use RoundingMode::*;
+    let ne = NearestEven;
+    // a signaling binary128 NaN with the sign set
+    let bits = 0xFFFF_0000_0000_0000_0000_0000_0000_0005;
+    let x = BitFloat::from_bits_binary128(bits);
+    assert!(x.is_nan() && x.get_s() && x.is_signaling_nan());
+    assert_eq!(x.nan_payload().unwrap(), UBitInt::from(5_u64));
+    assert_eq!(x.to_binary128(ne).unwrap(), bits);
+    // the payload fits the narrower formats, which keep it with the sign
+    assert_eq!(x.to_binary16(ne).unwrap(), 0xFC05);
+    assert_eq!(x.to_x87(ne).unwrap(), 0xFFFF_8000_0000_0000_0005);
+    assert_eq!(x.to_f64_round(ne).unwrap().to_bits(), 0xFFF0_0000_0000_0005);
+    assert_eq!(
+        BitFloat::from_bits_binary16(0xFC05)
+            .to_binary128(ne)
+            .unwrap(),
+        bits
+    );
+
+    // a quiet NaN whose payload is too wide for binary16 is an error
+    let wide = BitFloat::from_bits_binary128(0x7FFF_8000_0000_0000_0000_0000_0000_0400);
+    assert!(!wide.is_signaling_nan());
+    assert!(matches!(wide.to_binary16(ne), Err(FromErr::NaN)));
+    assert!(matches!(wide.to_bfloat16(ne), Err(FromErr::NaN)));
+    assert_eq!(
+        wide.to_binary128(ne).unwrap(),
+        0x7FFF_8000_0000_0000_0000_0000_0000_0400
+    );
+    assert_eq!(
+        wide.to_f64_round(ne).unwrap().to_bits(),
+        0x7FF8_0000_0000_0400
+    );
+    assert_eq!(wide.to_f32_round(ne).unwrap().to_bits(), 0x7FC0_0400);
+
+    // binary256 and x87 carry payloads in and out
+    let limbs = [7, 0, 1, 0x7FFFF << 44];
+    let y = BitFloat::from_bits_binary256(UBitIntStatic::make(limbs));
+    assert!(y.is_signaling_nan());
+    assert_eq!(y.to_binary256(ne).unwrap().get_data(), limbs);
+    assert!(matches!(y.to_binary128(ne), Err(FromErr::NaN)));
+    assert!(matches!(y.to_f64_round(ne), Err(FromErr::NaN)));
+    let z = BitFloat::from_bits_x87(0x7FFF_C000_0000_0000_1234);
+    assert_eq!(z.nan_payload().unwrap(), UBitInt::from(0x1234_u64));
+    assert_eq!(z.to_x87(ne).unwrap(), 0x7FFF_C000_0000_0000_1234);
+    assert_eq!(
+        z.to_binary128(ne).unwrap(),
+        0x7FFF_8000_0000_0000_0000_0000_0000_1234
+    );
+
+    // f64 and f32 NaNs keep theirs on the way in
+    for bits in [
+        0xFFF0_0000_0000_0005_u64,
+        0x7FF8_0000_0000_0400,
+        0xFFF8_0000_0000_0000,
+    ] {
+        let x = BitFloat::from(f64::from_bits(bits));
+        assert!(x.is_nan() && x.get_s() == (bits >> 63 == 1));
+        assert_eq!(x.to_f64_round(ne).unwrap().to_bits(), bits);
+    }
+    let x = BitFloat::from(f32::from_bits(0xFF80_0003));
+    assert!(x.is_signaling_nan() && x.get_s());
+    assert_eq!(x.nan_payload().unwrap(), UBitInt::from(3_u64));
+    assert_eq!(x.to_binary16(ne).unwrap(), 0xFC03);
+    assert_eq!(
+        BitFloat::from(f64::NAN).nan_payload().unwrap(),
+        UBitInt::zero()
+    );
+
+    // the NaNs of the arithmetic are the plain quiet NaN
+    assert_eq!(BitFloat::nan().nan_payload().unwrap(), UBitInt::zero());
+    assert!(!BitFloat::nan().is_signaling_nan());
+    assert_eq!(BitFloat::one().nan_payload(), None);
+}
+
+#[test]
+fn test_bitfloat_interchange_overflow() {
+    use RoundingMode::*;
+    type Encode = fn(&BitFloat, RoundingMode) -> Result<u128, FromErr>;
+    // each format with the power of two past its largest finite value, its
+    // precision, the bits of that value and the sign bit
+    let formats: [(Encode, i128, i128, u128, u128); 6] = [
+        (
+            |x, m| x.to_binary16(m).map(u128::from),
+            16,
+            11,
+            0x7BFF,
+            1 << 15,
+        ),
+        (
+            |x, m| x.to_bfloat16(m).map(u128::from),
+            128,
+            8,
+            0x7F7F,
+            1 << 15,
+        ),
+        (
+            |x, m| x.to_binary128(m),
+            16384,
+            113,
+            0x7FFE_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF,
+            1 << 127,
+        ),
+        (
+            |x, m| x.to_x87(m),
+            16384,
+            64,
+            0x7FFE_FFFF_FFFF_FFFF_FFFF,
+            1 << 79,
+        ),
+        (
+            |x, m| x.to_f64_round(m).map(|f| f.to_bits() as u128),
+            1024,
+            53,
+            0x7FEF_FFFF_FFFF_FFFF,
+            1 << 63,
+        ),
+        (
+            |x, m| x.to_f32_round(m).map(|f| f.to_bits() as u128),
+            128,
+            24,
+            0x7F7F_FFFF,
+            1 << 31,
+        ),
+    ];
+    let modes = [
+        NearestEven,
+        NearestAway,
+        TowardZero,
+        TowardPositive,
+        TowardNegative,
+        Faithful,
+    ];
+    for (encode, e, p, max, sign) in formats {
+        // past the exponent range, and halfway to it from the largest value
+        let big = BitFloat::pow2(e);
+        let tie = big.sub_round(&BitFloat::pow2(e - p - 1), usize::MAX, NearestEven);
+        // only the first overflows when rounded toward zero
+        for (x, over) in [(big, true), (tie, false)] {
+            for s in [false, true] {
+                let x = if s { -&x } else { x.clone() };
+                for mode in modes {
+                    // the modes rounding toward zero at this sign saturate
+                    let saturates = match mode {
+                        TowardZero | Faithful => true,
+                        TowardPositive => s,
+                        TowardNegative => !s,
+                        _ => false,
+                    };
+                    BitFloat::clear_flags();
+                    let got = encode(&x, mode);
+                    if saturates {
+                        let want = if s { sign | max } else { max };
+                        assert_eq!(got.unwrap(), want, "{e} {s} {mode:?}");
+                        assert_eq!(BitFloat::flags().overflow, over);
+                    } else {
+                        assert!(matches!(got, Err(FromErr::Overflow)), "{e} {s} {mode:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    let max = UBitIntStatic::<4>::make([u64::MAX, u64::MAX, u64::MAX, (0x7FFFF << 44) - 1]);
+    let big = BitFloat::pow2(262144);
+    assert_eq!(big.to_binary256(TowardZero).unwrap(), max);
+    assert_eq!(big.to_binary256(TowardNegative).unwrap(), max);
+    assert!(matches!(
+        big.to_binary256(TowardPositive),
+        Err(FromErr::Overflow)
+    ));
+    let tie = big.sub_round(&BitFloat::pow2(262144 - 238), usize::MAX, NearestEven);
+    assert!(matches!(
+        tie.to_binary256(NearestEven),
+        Err(FromErr::Overflow)
+    ));
+    let neg = -&tie;
+    let mut limbs = max.get_data();
+    limbs[3] |= 1 << 63;
+    assert_eq!(neg.to_binary256(TowardPositive).unwrap().get_data(), limbs);
+    assert!(matches!(
+        neg.to_binary256(TowardNegative),
+        Err(FromErr::Overflow)
+    ));
+}
+
+#[test]
+fn test_bitfloat_binary256() {
+    use RoundingMode::*;
+    let bits = |limbs: [u64; 4]| UBitIntStatic::<4>::make(limbs);
+    assert_eq!(
+        BitFloat::from_bits_binary256(bits([0, 0, 0, 0x3FFFF << 44])),
+        1
+    );
+    assert_eq!(
+        BitFloat::one().to_binary256(NearestEven).unwrap(),
+        bits([0, 0, 0, 0x3FFFF << 44])
+    );
+    assert_eq!(
+        BitFloat::from_bits_binary256(bits([1, 0, 0, 1 << 63])),
+        -BitFloat::pow2(-262378)
+    );
+    assert!(BitFloat::from_bits_binary256(bits([0, 0, 0, 0x7FFFF << 44])).is_inf());
+    assert!(BitFloat::from_bits_binary256(bits([0, 0, 1, 0x7FFFF << 44])).is_nan());
+    assert_eq!(
+        BitFloat::nan().to_binary256(NearestEven).unwrap(),
+        bits([0, 0, 0, 0x7FFFF << 44 | 1 << 43])
+    );
+    assert_eq!(
+        BitFloat::neg_zero().to_binary256(NearestEven).unwrap(),
+        bits([0, 0, 0, 1 << 63])
+    );
+    assert!(BitFloat::pow2(262144).to_binary256(NearestEven).is_err());
+
+    // rounding to 237 bits matches to_prec, and reads back exactly
+    let pi = constants::pi(400);
+    for x in rand_f64s(100, 3027) {
+        let bf = BitFloat::from(x) * &pi;
+        for mode in [NearestEven, TowardZero, TowardPositive, TowardNegative] {
+            let b = bf.to_binary256(mode).unwrap();
+            assert_eq!(
+                BitFloat::from_bits_binary256(b),
+                bf.to_prec_round(237, mode)
+            );
+        }
+    }
+    // a carry out of the significand moves to the next binade
+    let below_two = BitFloat::from(2).sub_round(&BitFloat::pow2(-300), usize::MAX, NearestEven);
+    assert_eq!(
+        below_two.to_binary256(NearestEven).unwrap(),
+        bits([0, 0, 0, 0x40000 << 44])
+    );
+    BitFloat::clear_flags();
+    let tiny = BitFloat::pow2(-262378) * BitFloat::from(5);
+    assert_eq!(tiny.to_binary256(TowardZero).unwrap(), bits([5, 0, 0, 0]));
+    assert!(!BitFloat::flags().inexact);
+    let tiny = BitFloat::pow2(-262380) * BitFloat::from(5);
+    assert_eq!(tiny.to_binary256(NearestEven).unwrap(), bits([1, 0, 0, 0]));
+    assert!(BitFloat::flags().underflow);
+}
+
+#[test]
+fn test_bitfloat_hex_float() {
+    let hex = |x: f64| format!("{:x}", BitFloat::from(x));
+    assert_eq!(hex(12.0), "0x1.8p+3");
+    assert_eq!(hex(1.0), "0x1p+0");
+    assert_eq!(hex(0.1), "0x1.999999999999ap-4");
+    assert_eq!(hex(-0.0), "-0x0p+0");
+    assert_eq!(hex(5e-324), "0x1p-1074");
+    assert_eq!(hex(f64::MAX), "0x1.fffffffffffffp+1023");
+    assert_eq!(hex(-f64::INFINITY), "-inf");
+    assert_eq!(hex(f64::NAN), "nan");
+    assert_eq!(format!("{:X}", BitFloat::from(-0.75)), "-0X1.8P-1");
+    assert_eq!(format!("{:>10x}", BitFloat::one()), "    0x1p+0");
+    // bits below the last whole digit are shifted up into it
+    assert_eq!(format!("{:x}", BitFloat::from(0b10011)), "0x1.3p+4");
+    assert_eq!(format!("{:x}", BitFloat::from(0b100001)), "0x1.08p+5");
+
+    // every bit survives the round trip, at any precision
+    let pi = constants::pi(1000);
+    for x in rand_f64s(100, 3028) {
+        for p in [1, 7, 64, 65, 300, 1000] {
+            let y = (BitFloat::from(x) * &pi).to_prec(p);
+            let back = BitFloat::from_hex_str(&format!("{y:x}")).unwrap();
+            assert_eq!(back, y);
+            assert_eq!(back.prec(), y.prec());
+            assert_eq!(BitFloat::from_hex_str(&format!("{y:X}")).unwrap(), y);
+        }
+    }
+    let far = BitFloat::pow2(1 << 100);
+    assert_eq!(BitFloat::from_hex_str(&format!("{far:x}")).unwrap(), far);
+
+    let parse = |s: &str| BitFloat::from_hex_str(s).unwrap();
+    assert_eq!(parse("0x.8p1"), 1);
+    assert_eq!(parse("0X1P-2"), 0.25);
+    assert_eq!(parse("-0x10"), -16);
+    assert_eq!(parse("+0x1.8"), 1.5);
+    assert_eq!(parse("0x0.000000000000000000001p+84"), 1);
+    assert!(parse("-0x0p+0").is_zero() && parse("-0x0p+0").get_s());
+    assert!(parse("Infinity").is_inf());
+    assert!(parse("-inf").get_s());
+    assert!(parse("NaN").is_nan());
+
+    for bad in [
+        "0x",
+        "0x.",
+        "0x1p",
+        "0x1pq",
+        "0xg",
+        "1.5",
+        "0x1.8p+1e",
+        "0x1..2",
+        "--0x1",
+    ] {
+        assert!(
+            matches!(
+                BitFloat::from_hex_str(bad),
+                Err(FromStrErr::MalformedExpression)
+            ),
+            "{bad}"
+        );
+    }
+    assert!(matches!(BitFloat::from_hex_str(""), Err(FromStrErr::Empty)));
+    assert!(matches!(
+        BitFloat::from_hex_str(" 0x1p0"),
+        Err(FromStrErr::Whitespace)
+    ));
+    assert!(matches!(
+        BitFloat::from_hex_str("0x1p999999999999999999999999999999999999999"),
+        Err(FromStrErr::Overflow)
+    ));
+}
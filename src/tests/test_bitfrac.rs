@@ -0,0 +1,202 @@
+use super::rand_f64s;
+use crate::bit_nums::bitfloat::{BitFloat, RoundingMode};
+use crate::bit_nums::bitfrac::BitFrac;
+use crate::bit_nums::bitint::BitInt;
+use crate::bit_nums::traits::FromErr;
+use crate::bit_nums::ubitint::UBitInt;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Ordering;
+
+fn frac(n: i64, d: i64) -> BitFrac {
+    BitFrac::from(n, d).unwrap()
+}
+
+// ─── Construction and arithmetic ────────────────────────────────────────────
+
+#[test]
+fn test_bitfrac_construction() {
+    let mut x = frac(6, -4);
+    assert!(x.get_sign());
+    assert_eq!(x.get_num(), 6_u64);
+    assert_eq!(x.get_den(), 4_u64);
+    assert_eq!(x.to_string(), "-6  //  4");
+    assert_eq!(x, frac(-3, 2));
+    x.simplify();
+    assert_eq!(x.get_num(), 3_u64);
+    assert_eq!(x.get_den(), 2_u64);
+    assert_eq!(x.to_string(), "-3  //  2");
+
+    assert_eq!(frac(0, -7), BitFrac::zero());
+    assert!(!frac(0, -7).get_sign());
+    let mut z = frac(0, -7);
+    z.simplify();
+    assert_eq!(z.get_den(), 1_u64);
+    assert!(BitFrac::from(1, 0).is_err());
+    assert_eq!(
+        BitFrac::from(BitInt::from(-6_i64), BitInt::from(4_i64)).unwrap(),
+        x
+    );
+    assert_eq!(
+        BitFrac::from(UBitInt::from(7_u64), UBitInt::one()).unwrap(),
+        7
+    );
+    assert_eq!(
+        BitFrac::from_bi(BitInt::from(9_i64), BitInt::from(-6_i64)),
+        x
+    );
+    let y: BitFrac = BitInt::from(-6_i64).into();
+    assert_eq!(y, frac(-12, 2));
+    assert_eq!(BitFrac::from(u128::MAX, 1).unwrap().get_num(), u128::MAX);
+
+    let mut y = frac(-5, 3);
+    y.abs();
+    assert_eq!(y, frac(5, 3));
+    y.neg();
+    assert_eq!(y, frac(-5, 3));
+    let mut z = BitFrac::zero();
+    z.neg();
+    assert!(!z.get_sign());
+    assert_eq!(frac(-5, 3).abs_cmp(&frac(10, 6)), Ordering::Equal);
+    assert_eq!(frac(-5, 3).abs_cmp(&frac(1, 2)), Ordering::Greater);
+}
+
+#[test]
+#[should_panic(expected = "the denominator cannot be zero")]
+fn test_bitfrac_from_bi_zero_den() {
+    BitFrac::from_bi(BitInt::one(), BitInt::zero());
+}
+
+#[test]
+fn test_bitfrac_arithmetic() {
+    assert_eq!(frac(1, 2) + frac(1, 3), frac(5, 6));
+    assert_eq!(frac(1, 2) - frac(5, 6), frac(-1, 3));
+    assert_eq!(frac(-2, 3) * frac(9, 4), frac(-3, 2));
+    assert_eq!(frac(-2, 3) / frac(-4, 9), frac(3, 2));
+    assert_eq!(frac(1, 3) - frac(1, 3), BitFrac::zero());
+    assert!(!(frac(1, 3) - frac(1, 3)).get_sign());
+    assert!(!(frac(-1, 3) * BitInt::zero()).get_sign());
+    assert_eq!(-frac(1, 3), frac(-1, 3));
+    assert!(!(-BitFrac::zero()).get_sign());
+
+    // the results keep their common factors until simplified
+    let mut sum = frac(1, 2) + frac(1, 2);
+    assert_eq!(sum.get_den(), 4_u64);
+    sum.simplify();
+    assert_eq!(
+        (sum.get_num(), sum.get_den()),
+        (UBitInt::one(), UBitInt::one())
+    );
+
+    let mut x = frac(1, 2);
+    x += frac(1, 3);
+    x -= &frac(1, 6);
+    assert_eq!(x, frac(2, 3));
+    x *= &frac(-3, 4);
+    assert_eq!(x, frac(-1, 2));
+    x /= frac(1, 4);
+    assert_eq!(x, -2);
+
+    // against integers, on either side
+    let i = BitInt::from(-3_i64);
+    assert_eq!(frac(1, 2) + 1, frac(3, 2));
+    assert_eq!(1_u8 - frac(1, 2), frac(1, 2));
+    assert_eq!(frac(1, 2) * &i, frac(-3, 2));
+    assert_eq!(&i / frac(3, 4), -4);
+    assert_eq!(2_i128 * &frac(-1, 4), frac(-1, 2));
+    assert_eq!(frac(-5, 2) - i.clone(), frac(1, 2));
+    let mut y = frac(7, 3);
+    y -= 2;
+    y *= i.clone();
+    y += &i;
+    y /= -2_i64;
+    assert_eq!(y, 2);
+
+    assert!(frac(-1, 2) < frac(1, 3));
+    assert!(frac(-1, 2) < frac(-1, 3));
+    assert!(frac(2, 3) > frac(3, 5));
+    assert!(frac(7, 2) > 3 && frac(7, 2) < 4_u64);
+    assert!(frac(-7, 2) < i && -4 < frac(-7, 2));
+    assert_eq!(frac(-9, 3), i);
+    assert_eq!(frac(-9, 3), -3);
+    assert_ne!(frac(9, 3), -3);
+    assert_eq!(BitInt::from(-3_i64), frac(6, -2));
+}
+
+#[test]
+#[should_panic(expected = "attempt to divide by zero")]
+fn test_bitfrac_div_by_zero() {
+    let _ = frac(1, 2) / 0;
+}
+
+// ─── Float conversions ──────────────────────────────────────────────────────
+
+#[test]
+fn test_bitfrac_from_float_exact() {
+    // 0.1 as an f64 is 3602879701896397 / 2^55
+    let x = BitFrac::from_f64(0.1).unwrap();
+    assert_eq!(x.get_num(), 3602879701896397_u64);
+    assert_eq!(x.get_den(), 1_u64 << 55);
+    assert_eq!(BitFrac::from_f64(-0.0).unwrap(), BitFrac::zero());
+    assert_eq!(
+        BitFrac::from_f64(-6.0e20).unwrap(),
+        frac(-6, 1) * frac(100_000_000_000, 1) * frac(1_000_000_000, 1)
+    );
+    assert!(matches!(BitFrac::from_f64(f64::NAN), Err(FromErr::NaN)));
+    assert!(matches!(
+        BitFrac::from_f64(f64::INFINITY),
+        Err(FromErr::Overflow)
+    ));
+
+    let big = BitFloat::pow2(1000) + BitFloat::pow2(-1000);
+    let f = BitFrac::try_from(&big).unwrap();
+    assert_eq!(
+        BitFloat::from_frac(&f, usize::MAX, RoundingMode::NearestEven),
+        big
+    );
+    for x in [f64::INFINITY, f64::NEG_INFINITY] {
+        let inf = BitFloat::from(x);
+        assert!(matches!(BitFrac::try_from(&inf), Err(FromErr::Overflow)));
+    }
+    let nan = BitFrac::try_from(&BitFloat::nan());
+    assert!(matches!(nan, Err(FromErr::NaN)));
+
+    for x in rand_f64s(200, 3023) {
+        let f = BitFrac::from_f64(x).unwrap();
+        let back = BitFloat::from_frac(&f, 53, RoundingMode::NearestEven);
+        assert_eq!(back, x, "x={x:e}");
+        assert_eq!(back.get_s(), x < 0.0);
+    }
+}
+
+#[test]
+fn test_bitfrac_to_float_correctly_rounded() {
+    // the f64 operations round correctly, so they agree with the exact
+    // result rounded once
+    let xs = rand_f64s(400, 3024);
+    for pair in xs.chunks(2) {
+        let (a, b) = (pair[0], pair[1]);
+        let (fa, fb) = (BitFrac::from_f64(a).unwrap(), BitFrac::from_f64(b).unwrap());
+        let round = |f: BitFrac| BitFloat::from_frac(&f, 53, RoundingMode::NearestEven);
+        assert_eq!(round(&fa + &fb), a + b, "{a:e} + {b:e}");
+        assert_eq!(round(&fa - &fb), a - b, "{a:e} - {b:e}");
+        assert_eq!(round(&fa * &fb), a * b, "{a:e} * {b:e}");
+        assert_eq!(round(&fa / &fb), a / b, "{a:e} / {b:e}");
+    }
+
+    let third = frac(1, 3);
+    let down = BitFloat::from_frac(&third, 24, RoundingMode::TowardNegative);
+    let up = BitFloat::from_frac(&third, 24, RoundingMode::TowardPositive);
+    // 1/3 as an f32 rounds up
+    assert_eq!(up, (1.0_f32 / 3.0) as f64);
+    assert_eq!(down, up.next_down(24));
+    let (lo, hi) = (BitFrac::try_from(&down), BitFrac::try_from(&up));
+    assert!(lo.unwrap() < third && third < hi.unwrap());
+    let neg = BitFloat::from_frac(&-third, 24, RoundingMode::TowardZero);
+    assert_eq!(neg, -&down);
+    // a dyadic fraction rounds like the float it equals
+    let f = frac(0b1011, 16);
+    assert_eq!(BitFloat::from_frac(&f, 3, RoundingMode::NearestEven), 0.75);
+    assert_eq!(BitFloat::from_frac(&f, 3, RoundingMode::TowardZero), 0.625);
+    assert!(BitFloat::from_frac(&BitFrac::zero(), 53, RoundingMode::TowardNegative).is_zero());
+}
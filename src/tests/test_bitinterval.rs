@@ -0,0 +1,293 @@
+use super::{assert_encloses, rand_scaled, samples, Unary};
+use crate::bit_nums::bitfloat::{BitFloat, RoundingMode};
+use crate::bit_nums::bitinterval::BitInterval;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::f64::consts::PI;
+
+const P: usize = 300;
+
+fn iv(lo: f64, hi: f64) -> BitInterval {
+    BitInterval::new(BitFloat::from(lo), BitFloat::from(hi)).unwrap()
+}
+
+/// Random intervals with bounds of mixed sign and magnitude.
+fn rand_intervals(n: usize, seed: u64) -> Vec<BitInterval> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..n)
+        .map(|_| {
+            let a = rand_scaled(&mut rng, -20..20);
+            let b = a + rng.gen_range(0.0..1.0) * 2f64.powi(rng.gen_range(-30..10));
+            iv(a, b)
+        })
+        .collect()
+}
+
+/// Whether the bounds of `got` lie within one `prec` bit step outside of
+/// `lo` and `hi`, the exact extremes.
+fn is_tight(got: &BitInterval, lo: &BitFloat, hi: &BitFloat, prec: usize) -> bool {
+    let lo = lo.to_prec_round(prec, RoundingMode::TowardNegative);
+    let hi = hi.to_prec_round(prec, RoundingMode::TowardPositive);
+    let lo = if lo.is_zero() { lo } else { lo.next_down(prec) };
+    let hi = if hi.is_zero() { hi } else { hi.next_up(prec) };
+    *got.lower() >= lo && *got.upper() <= hi
+}
+
+// ─── Construction and queries ───────────────────────────────────────────────
+
+#[test]
+fn test_bitinterval_construction() {
+    let one = BitFloat::one();
+    assert!(BitInterval::new(BitFloat::from(2), one.clone()).is_none());
+    assert!(BitInterval::new(BitFloat::nan(), one.clone()).is_none());
+    assert!(BitInterval::new(BitFloat::inf(), BitFloat::inf()).is_none());
+    assert!(BitInterval::new(BitFloat::neg_inf(), BitFloat::neg_inf()).is_none());
+    assert!(BitInterval::new(BitFloat::neg_inf(), BitFloat::inf()).is_some());
+
+    let x = BitInterval::from(BitFloat::from(1.5));
+    assert_eq!(x, iv(1.5, 1.5));
+    assert_eq!(x.width(), 0);
+    assert!(BitInterval::from(BitFloat::nan()).is_empty());
+    assert!(BitInterval::from(BitFloat::inf()).is_empty());
+    assert_eq!(BitInterval::empty(), BitInterval::empty());
+    assert!(BitInterval::empty().width().is_nan());
+    assert!(BitInterval::entire().width().is_inf());
+}
+
+#[test]
+fn test_bitinterval_set_queries() {
+    let a = iv(-1.0, 2.0);
+    let b = iv(1.0, 3.0);
+    assert!(a.contains(&BitFloat::from(-1)));
+    assert!(a.contains(&BitFloat::from(2)));
+    assert!(!a.contains(&BitFloat::from(2.5)));
+    assert!(!a.contains(&BitFloat::nan()));
+    assert!(!BitInterval::empty().contains(&BitFloat::zero()));
+
+    assert_eq!(a.intersect(&b), iv(1.0, 2.0));
+    assert_eq!(a.hull(&b), iv(-1.0, 3.0));
+    assert_eq!(a.intersect(&iv(2.0, 5.0)), iv(2.0, 2.0));
+    assert!(a.intersect(&iv(2.5, 5.0)).is_empty());
+    assert_eq!(a.hull(&BitInterval::empty()), a);
+    assert_eq!(BitInterval::empty().hull(&b), b);
+
+    assert!(a.encloses(&iv(0.0, 1.0)));
+    assert!(a.encloses(&a));
+    assert!(!a.encloses(&b));
+    assert!(a.encloses(&BitInterval::empty()));
+    assert!(!BitInterval::empty().encloses(&a));
+    assert!(BitInterval::entire().encloses(&a));
+
+    assert_eq!(a.width(), 3);
+    // the width rounds up when the difference needs more bits
+    let c = iv(1.0, 1.0 + f64::EPSILON);
+    let d = BitInterval::new(BitFloat::from(-1e300), BitFloat::from(1)).unwrap();
+    assert_eq!(c.width(), f64::EPSILON);
+    assert!(d.width() > 1e300);
+}
+
+// ─── Arithmetic ─────────────────────────────────────────────────────────────
+
+#[test]
+fn test_bitinterval_arithmetic_encloses() {
+    let xs = rand_intervals(60, 3017);
+    for pair in xs.chunks(2) {
+        let (a, b) = (&pair[0], &pair[1]);
+        let results = [a + b, a - b, a * b];
+        let ops: [fn(&BitFloat, &BitFloat) -> BitFloat; 3] = [
+            |x, y| x.add_round(y, usize::MAX, RoundingMode::NearestEven),
+            |x, y| x.sub_round(y, usize::MAX, RoundingMode::NearestEven),
+            |x, y| x.mul_round(y, usize::MAX, RoundingMode::NearestEven),
+        ];
+        for (got, op) in results.iter().zip(ops) {
+            let mut lo = BitFloat::inf();
+            let mut hi = BitFloat::neg_inf();
+            for x in samples(a) {
+                for y in samples(b) {
+                    let z = op(&x, &y);
+                    assert!(got.contains(&z), "{got:?} misses {z:?}");
+                    lo = if z < lo { z.clone() } else { lo };
+                    hi = if z > hi { z } else { hi };
+                }
+            }
+            // the extremes sit at the corners, so nothing looser is needed
+            assert!(is_tight(got, &lo, &hi, 64), "{got:?}");
+        }
+
+        if !b.contains(&BitFloat::zero()) {
+            let q = a / b;
+            for x in samples(a) {
+                for y in samples(b) {
+                    let z = x.div_round(&y, P, RoundingMode::NearestEven);
+                    assert!(q.contains(&z), "{q:?} misses {z:?}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_bitinterval_outward_rounding() {
+    // 1/3 is not a binary fraction, so its bounds straddle it by one step
+    let third = iv(1.0, 1.0) / iv(3.0, 3.0);
+    assert!(third.lower() < third.upper());
+    assert_eq!(third.lower().next_up(64), *third.upper());
+    let three = BitFloat::from(3);
+    let exact = |x: &BitFloat| x.mul_round(&three, usize::MAX, RoundingMode::NearestEven);
+    assert!(exact(third.lower()) < 1 && exact(third.upper()) > 1);
+    // exact results stay points
+    assert_eq!(iv(1.0, 2.0) + iv(0.5, 0.25 + 0.5), iv(1.5, 2.75));
+    assert_eq!(iv(-2.0, 3.0) * iv(-1.0, 4.0), iv(-8.0, 12.0));
+    assert_eq!(-iv(-2.0, 3.0), iv(-3.0, 2.0));
+
+    // higher default precision tightens the bounds
+    let wide = third.width();
+    BitFloat::set_default_prec(200);
+    let narrow = (iv(1.0, 1.0) / iv(3.0, 3.0)).width();
+    BitFloat::set_default_prec(64);
+    assert!(narrow < wide);
+    assert!(narrow > 0);
+}
+
+#[test]
+fn test_bitinterval_unbounded_and_empty() {
+    let inf = f64::INFINITY;
+    let pos = iv(1.0, inf);
+    assert_eq!(&pos + &iv(-2.0, 1.0), iv(-1.0, inf));
+    assert_eq!(&pos - &pos, BitInterval::entire());
+    // 0 * inf counts as 0
+    assert_eq!(iv(0.0, 0.0) * BitInterval::entire(), iv(0.0, 0.0));
+    assert_eq!(iv(0.0, 2.0) * &pos, iv(0.0, inf));
+    assert_eq!(&pos / &pos, iv(0.0, inf));
+    assert_eq!(iv(2.0, 4.0) / &pos, iv(0.0, 4.0));
+
+    assert!((iv(1.0, 2.0) + BitInterval::empty()).is_empty());
+    assert!((BitInterval::empty() * iv(1.0, 2.0)).is_empty());
+    assert!((-BitInterval::empty()).is_empty());
+}
+
+#[test]
+fn test_bitinterval_division_by_zero() {
+    let inf = f64::INFINITY;
+    assert!((iv(1.0, 2.0) / iv(0.0, 0.0)).is_empty());
+    assert_eq!(iv(1.0, 2.0) / iv(-1.0, 1.0), BitInterval::entire());
+    assert_eq!(iv(-1.0, 2.0) / iv(0.0, 1.0), BitInterval::entire());
+    // a divisor touching zero from one side leaves a half line
+    assert_eq!(iv(1.0, 2.0) / iv(0.0, 4.0), iv(0.25, inf));
+    assert_eq!(iv(-2.0, -1.0) / iv(0.0, 4.0), iv(-inf, -0.25));
+    assert_eq!(iv(1.0, 2.0) / iv(-4.0, 0.0), iv(-inf, -0.25));
+    assert_eq!(iv(-2.0, -1.0) / iv(-4.0, 0.0), iv(0.25, inf));
+}
+
+// ─── Elementary functions ───────────────────────────────────────────────────
+
+#[test]
+fn test_bitinterval_functions_enclose() {
+    let xs = rand_intervals(40, 3018);
+    let fs: [Unary<BitInterval>; 5] = [
+        (|x| x.exp(64), |x| x.exp(P)),
+        (|x| x.sin(64), |x| x.sin(P)),
+        (|x| x.cos(64), |x| x.cos(P)),
+        (|x| abs_iv(x).sqrt(64), |x| x.abs().sqrt(P)),
+        (|x| abs_iv(x).ln(64), |x| x.abs().ln(P)),
+    ];
+    assert_encloses(&xs, &fs);
+    for x in &xs {
+        let t = x.tan(64);
+        for s in samples(x) {
+            let z = s.tan(P);
+            assert!(t.contains(&z), "{t:?} misses {z:?} at {s:?}");
+        }
+    }
+}
+
+/// |x| over an interval.
+fn abs_iv(x: &BitInterval) -> BitInterval {
+    let (lo, hi) = (x.lower().abs(), x.upper().abs());
+    let (lo, hi) = if lo > hi { (hi, lo) } else { (lo, hi) };
+    if x.contains(&BitFloat::zero()) {
+        return BitInterval::new(BitFloat::zero(), hi).unwrap();
+    }
+    BitInterval::new(lo, hi).unwrap()
+}
+
+#[test]
+fn test_bitinterval_monotone_functions_are_tight() {
+    let x = iv(0.5, 3.0);
+    let e = x.exp(100);
+    assert!(is_tight(
+        &e,
+        &BitFloat::from(0.5).exp(P),
+        &BitFloat::from(3).exp(P),
+        100
+    ));
+    let l = x.ln(100);
+    assert!(is_tight(
+        &l,
+        &BitFloat::from(0.5).ln(P),
+        &BitFloat::from(3).ln(P),
+        100
+    ));
+    let s = x.sqrt(100);
+    assert!(is_tight(
+        &s,
+        &BitFloat::from(0.5).sqrt(P),
+        &BitFloat::from(3).sqrt(P),
+        100
+    ));
+
+    // exact values keep their bounds
+    assert_eq!(iv(0.0, 0.0).exp(64), iv(1.0, 1.0));
+    assert_eq!(iv(1.0, 1.0).ln(64), iv(0.0, 0.0));
+    assert_eq!(iv(4.0, 9.0).sqrt(64), iv(2.0, 3.0));
+    assert_eq!(iv(0.0, 0.0).sin(64), iv(0.0, 0.0));
+}
+
+#[test]
+fn test_bitinterval_function_domains() {
+    let inf = f64::INFINITY;
+    assert_eq!(iv(-4.0, 9.0).sqrt(64), iv(0.0, 3.0));
+    assert!(iv(-4.0, -1.0).sqrt(64).is_empty());
+    assert_eq!(iv(-1.0, 1.0).ln(64), iv(-inf, 0.0));
+    assert!(iv(-1.0, 0.0).ln(64).is_empty());
+    assert_eq!(iv(0.0, inf).ln(64), BitInterval::entire());
+    assert_eq!(BitInterval::entire().exp(64), iv(0.0, inf));
+    assert_eq!(BitInterval::entire().sin(64), iv(-1.0, 1.0));
+
+    // past exp's reach the bounds stay on the right side of zero and infinity
+    let huge = BitFloat::from(2f64.powi(200));
+    let e = BitInterval::new(huge.clone(), huge.clone())
+        .unwrap()
+        .exp(64);
+    assert!(e.lower().is_finite() && e.lower() > &1e300 && e.upper().is_inf());
+    let e = BitInterval::new(-&huge, -&huge).unwrap().exp(64);
+    assert!(e.lower().is_zero() && e.upper() > &0 && e.upper() < &1e-300);
+}
+
+#[test]
+fn test_bitinterval_trig_extremes() {
+    // intervals over a peak or trough reach ±1 exactly
+    assert_eq!(*iv(1.0, 2.0).sin(64).upper(), 1);
+    assert_eq!(*iv(4.0, 5.0).sin(64).lower(), -1);
+    assert_eq!(*iv(3.0, 3.5).cos(64).lower(), -1);
+    assert_eq!(*iv(-0.5, 0.5).cos(64).upper(), 1);
+    // while those between keep their endpoint values
+    let s = iv(2.0, 4.0).sin(64);
+    assert!(s.upper() < &1 && s.lower() > &-1);
+    assert!(s.contains(&BitFloat::from(2).sin(P)));
+    assert!(s.contains(&BitFloat::from(4).sin(P)));
+    assert_eq!(iv(0.0, 7.0).sin(64), iv(-1.0, 1.0));
+
+    // tan is monotone between its poles
+    let t = iv(-1.0, 1.0).tan(64);
+    assert!(t.lower() < &-1.5 && t.upper() > &1.5 && t.upper().is_finite());
+    assert_eq!(iv(1.0, 2.0).tan(64), BitInterval::entire());
+    assert!(*iv(PI, 4.0).tan(64).lower() < 0);
+
+    // huge arguments reduce against enough bits of π
+    let big = BitFloat::from(2f64.powi(100));
+    let x = BitInterval::new(big.clone(), &big + 1).unwrap();
+    let s = x.sin(64);
+    assert!(s.contains(&big.sin(P)));
+    assert!(s.width() < 2);
+}
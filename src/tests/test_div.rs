@@ -1,5 +1,6 @@
 use super::{rand_nonzero_vec, to_u128, verify_divmod};
 use crate::utils::div::*;
+use crate::utils::thresholds::BZ_CUTOFF;
 use crate::utils::utils::{buf_len, cmp_buf, trim_lz};
 
 // ─── div_prim ───────────────────────────────────────────────────────────────
@@ -300,6 +301,30 @@ fn test_div_vec_invariant_near_equal_sizes() {
     }
 }
 
+// ─── div_into ───────────────────────────────────────────────────────────────
+
+#[test]
+fn test_div_into_reuse_matches_div_vec() {
+    let mut out = Vec::new();
+    let mut scratch = Vec::new();
+    for (i, &(n_len, d_len)) in [(400, 150), (5, 2), (260, 130), (3, 7), (40, 1)]
+        .iter()
+        .enumerate()
+    {
+        let n = rand_nonzero_vec(n_len, 1200 + i as u64);
+        let d = rand_nonzero_vec(d_len, 1300 + i as u64);
+
+        let (mut n1, mut d1) = (n.clone(), d.clone());
+        div_into(&mut n1, &mut d1, &mut out, &mut scratch);
+        let (mut n2, mut d2) = (n.clone(), d.clone());
+        let q = div_vec(&mut n2, &mut d2);
+
+        assert_eq!(out, q, "n_len={n_len}, d_len={d_len}");
+        assert_eq!(n1, n2, "remainder, n_len={n_len}, d_len={d_len}");
+        assert_eq!(d1, d, "divisor restored");
+    }
+}
+
 // ─── div_arr ────────────────────────────────────────────────────────────────
 
 #[test]
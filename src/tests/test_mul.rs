@@ -1,5 +1,6 @@
 use super::{rand_nonzero_vec, rand_vec};
 use crate::utils::mul::*;
+use crate::utils::thresholds::*;
 use crate::utils::utils::{buf_len, trim_lz};
 
 // ─── mul_prim ───────────────────────────────────────────────────────────────
@@ -60,12 +61,7 @@ fn test_mul_prim_multi_limb_carry() {
 /// Verify mul_prim reconstructed result matches original * prim (for 1-limb inputs).
 #[test]
 fn test_mul_prim_correctness_single_limb() {
-    for &(a, p) in &[
-        (1u64, 5u64),
-        (7, 11),
-        (u64::MAX, 3),
-        (0xDEAD_BEEF, 0xCAFE),
-    ] {
+    for &(a, p) in &[(1u64, 5u64), (7, 11), (u64::MAX, 3), (0xDEAD_BEEF, 0xCAFE)] {
         let expected = (a as u128) * (p as u128);
         let mut v = vec![a];
         let c = mul_prim(&mut v, p);
@@ -240,7 +236,42 @@ fn test_mul_vec_karatsuba_boundary() {
 
         trim_lz(&mut vec_result);
         trim_lz(&mut buf_result);
-        assert_eq!(vec_result, buf_result, "Karatsuba boundary failed at len={len}");
+        assert_eq!(
+            vec_result, buf_result,
+            "Karatsuba boundary failed at len={len}"
+        );
+    }
+}
+
+/// Unbalanced shapes around short == ceil(long / 2) and exact multiples of
+/// short, which select between the chunked and recursive Karatsuba paths, and
+/// shapes whose middle Karatsuba term spills into the carry limb.
+#[test]
+fn test_mul_vec_unbalanced_matches_mul_buf() {
+    let shapes = [
+        (300, 120),
+        (120, 60),
+        (121, 61),
+        (240, 120),
+        (180, 60),
+        (101, 51),
+        (26, 14),
+        (35, 19),
+        (64, 49),
+        (106, 30),
+    ];
+    for (i, &(n, m)) in shapes.iter().enumerate() {
+        for (a, b) in [
+            (
+                rand_nonzero_vec(n, 1400 + i as u64),
+                rand_nonzero_vec(m, 1500 + i as u64),
+            ),
+            (vec![u64::MAX; n], vec![u64::MAX; m]),
+        ] {
+            let mut expected = vec![0u64; n + m - 1];
+            let c_buf = mul_buf(&a, &b, &mut expected);
+            assert_eq!(mul_vec(&a, &b), (expected, c_buf), "n={n}, m={m}");
+        }
     }
 }
 
@@ -422,7 +453,10 @@ fn test_sqr_arr_matches_sqr_vec() {
     trim_lz(&mut expected);
 
     let (arr, ac) = sqr_arr::<16>(&v).expect("sqr_arr failed");
-    assert_eq!(ac, 0, "oveflow from sqr of 4 buffer into 16 buffer should be zero");
+    assert_eq!(
+        ac, 0,
+        "oveflow from sqr of 4 buffer into 16 buffer should be zero"
+    );
     let mut got: Vec<u64> = arr[..2 * v.len()].to_vec();
     trim_lz(&mut got);
     assert_eq!(got, expected);
@@ -498,6 +532,22 @@ fn test_powi_vec_recursive_property() {
     }
 }
 
+/// Two-limb bases take the mul_prim2 path inside karatsuba_alg.
+#[test]
+fn test_powi_vec_two_limb_base_matches_repeated_mul() {
+    let a = rand_nonzero_vec(2, 1600);
+    let mut expected = a.clone();
+    for pow in 2..20 {
+        let (mut next, c) = mul_vec(&expected, &a);
+        if c > 0 {
+            next.push(c);
+        }
+        trim_lz(&mut next);
+        expected = next;
+        assert_eq!(powi_vec(&a, pow), expected, "pow={pow}");
+    }
+}
+
 // ─── powi_arr ───────────────────────────────────────────────────────────────
 
 #[test]
@@ -546,8 +596,8 @@ fn test_short_mul_vec_full_precision() {
 #[test]
 fn test_short_mul_vec_top_one_limb() {
     let a = vec![2u64, 1]; // value = 2 + 2^64
-    let b = vec![3u64];    // value = 3
-    // full product = [6, 3], full_len=2, top 1 limb = [3]
+    let b = vec![3u64]; // value = 3
+                        // full product = [6, 3], full_len=2, top 1 limb = [3]
     let (short, _) = short_mul_vec(&a, &b, 1);
     assert_eq!(short, vec![3]);
 }
@@ -582,7 +632,11 @@ fn test_short_mul_vec_cutoff_boundary() {
 
     // p = SHORT_CUTOFF+1 uses the mul_vec path (p > SHORT_CUTOFF), giving exact top-p limbs.
     let (short, _) = short_mul_vec(&a, &b, SHORT_CUTOFF + 1);
-    assert_eq!(short, full[full_len - (SHORT_CUTOFF + 1)..], "p=SHORT_CUTOFF+1");
+    assert_eq!(
+        short,
+        full[full_len - (SHORT_CUTOFF + 1)..],
+        "p=SHORT_CUTOFF+1"
+    );
 }
 
 // ─── short_sqr_vec ──────────────────────────────────────────────────────────
@@ -627,5 +681,255 @@ fn test_short_sqr_vec_cutoff_boundary() {
 
     // p = SHORT_SQR_CUTOFF+1 uses the sqr_vec path (p > SHORT_SQR_CUTOFF), giving exact top-p limbs.
     let (short, _) = short_sqr_vec(&v, SHORT_SQR_CUTOFF + 1);
-    assert_eq!(short, full[full_len - (SHORT_SQR_CUTOFF + 1)..], "p=SHORT_SQR_CUTOFF+1");
+    assert_eq!(
+        short,
+        full[full_len - (SHORT_SQR_CUTOFF + 1)..],
+        "p=SHORT_SQR_CUTOFF+1"
+    );
+}
+
+// ─── Karatsuba regressions ──────────────────────────────────────────────────
+
+fn mul_ref(a: &[u64], b: &[u64]) -> (Vec<u64>, u64) {
+    let mut out = vec![0u64; a.len() + b.len() - 1];
+    let c = mul_buf(a, b, &mut out);
+    (out, c)
+}
+
+/// A two-limb last chunk multiplies through mul_prim2, which once used the
+/// low limb of the chunk for both halves.
+#[test]
+fn test_karatsuba_two_limb_chunk() {
+    let s = KARATSUBA_CUTOFF + 6;
+    let a = rand_nonzero_vec(3 * s + 2, 1700);
+    let b = rand_nonzero_vec(s, 1701);
+    assert_ne!(a[3 * s], a[3 * s + 1]);
+    assert_eq!(mul_vec(&a, &b), mul_ref(&a, &b));
+}
+
+/// short == ceil(long / 2) once took the recursive split with an empty upper
+/// half of short.
+#[test]
+fn test_karatsuba_short_exactly_half() {
+    let h = KARATSUBA_CUTOFF + 6;
+    for (n, m) in [(2 * h - 1, h), (2 * h, h)] {
+        let a = rand_nonzero_vec(n, 1702);
+        let b = rand_nonzero_vec(m, 1703);
+        assert_eq!(mul_vec(&a, &b), mul_ref(&a, &b), "n={n}, m={m}");
+    }
+}
+
+/// Chunked Karatsuba once dropped the last chunk when short divided long.
+#[test]
+fn test_karatsuba_chunks_divide_long_evenly() {
+    let s = KARATSUBA_CUTOFF + 6;
+    for k in 2..5 {
+        let a = rand_nonzero_vec(k * s, 1704 + k as u64);
+        let b = rand_nonzero_vec(s, 1708);
+        assert_eq!(mul_vec(&a, &b), mul_ref(&a, &b), "k={k}");
+    }
+}
+
+/// With short barely longer than half of long, the middle term of all-ones
+/// operands reaches one limb past the output, which belongs to the carry.
+#[test]
+fn test_karatsuba_middle_term_spill() {
+    for (n, m) in [
+        (26, 14),
+        (35, 19),
+        (64, 49),
+        (2 * KARATSUBA_CUTOFF + 2, KARATSUBA_CUTOFF + 2),
+    ] {
+        let (a, b) = (vec![u64::MAX; n], vec![u64::MAX; m]);
+        assert_eq!(mul_vec(&a, &b), mul_ref(&a, &b), "n={n}, m={m}");
+    }
+    for n in KARATSUBA_SQR_CUTOFF + 1..KARATSUBA_SQR_CUTOFF + 8 {
+        let a = vec![u64::MAX; n];
+        assert_eq!(sqr_vec(&a), mul_ref(&a, &a), "n={n}");
+    }
+}
+
+/// powi once sized its buffers from floor(log2) of the base, a limb short
+/// for bases with a full top limb.
+#[test]
+fn test_powi_vec_full_top_limb() {
+    for (len, pow) in [(1, 65), (1, 200), (2, 33), (3, 70)] {
+        let a = vec![u64::MAX; len];
+        let mut expected = a.clone();
+        for _ in 1..pow {
+            let (mut next, c) = mul_ref(&expected, &a);
+            next.push(c);
+            trim_lz(&mut next);
+            expected = next;
+        }
+        assert_eq!(powi_vec(&a, pow), expected, "len={len}, pow={pow}");
+    }
+}
+
+// ─── mul_into / sqr_into / powi_into ────────────────────────────────────────
+
+/// Reused buffers, shrinking and growing between calls, must give the same
+/// results as the allocating versions.
+#[test]
+fn test_mul_into_reuse_matches_mul_vec() {
+    let mut out = Vec::new();
+    let mut scratch = Vec::new();
+    for (i, &(n, m)) in [(200, 150), (3, 1), (90, 40), (7, 2), (300, 300), (30, 25)]
+        .iter()
+        .enumerate()
+    {
+        let a = rand_nonzero_vec(n, 700 + i as u64);
+        let b = rand_nonzero_vec(m, 800 + i as u64);
+        let c = mul_into(&a, &b, &mut out, &mut scratch);
+        assert_eq!((out.clone(), c), mul_vec(&a, &b), "n={n}, m={m}");
+    }
+}
+
+#[test]
+fn test_sqr_into_reuse_matches_sqr_vec() {
+    let mut out = Vec::new();
+    let mut scratch = Vec::new();
+    for (i, &n) in [150, 1, 70, 5, 257, 33].iter().enumerate() {
+        let a = rand_nonzero_vec(n, 900 + i as u64);
+        let c = sqr_into(&a, &mut out, &mut scratch);
+        assert_eq!((out.clone(), c), sqr_vec(&a), "n={n}");
+    }
+}
+
+#[test]
+fn test_powi_into_reuse_matches_powi_vec() {
+    let (mut out, mut tmp, mut scratch) = (Vec::new(), Vec::new(), Vec::new());
+    for (i, &(n, pow)) in [(4, 37), (1, 0), (2, 100), (1, 5), (9, 12)]
+        .iter()
+        .enumerate()
+    {
+        let a = rand_nonzero_vec(n, 1000 + i as u64);
+        powi_into(&a, pow, &mut out, &mut tmp, &mut scratch);
+        assert_eq!(out, powi_vec(&a, pow), "n={n}, pow={pow}");
+    }
+}
+
+#[test]
+fn test_mul_into_no_realloc_after_warm_up() {
+    let a = rand_nonzero_vec(120, 1100);
+    let b = rand_nonzero_vec(100, 1101);
+    let mut out = Vec::new();
+    let mut scratch = Vec::new();
+    mul_into(&a, &b, &mut out, &mut scratch);
+    let (out_ptr, scratch_ptr) = (out.as_ptr(), scratch.as_ptr());
+    mul_into(&b, &a, &mut out, &mut scratch);
+    mul_into(&a[..60], &b[..50], &mut out, &mut scratch);
+    assert_eq!(out.as_ptr(), out_ptr);
+    assert_eq!(scratch.as_ptr(), scratch_ptr);
+}
+
+// ─── ADX/BMI2 kernels ───────────────────────────────────────────────────────
+//
+// The adx kernels are checked against the portable baseline; on hosts without
+// ADX/BMI2 there is nothing to compare and the tests return early.
+
+#[cfg(all(target_arch = "x86_64", not(any(feature = "portable", miri))))]
+mod adx {
+    use super::super::{rand_nonzero_vec, rand_vec};
+    use crate::utils::adx::*;
+    use crate::utils::mul::*;
+    use crate::utils::utils::add_with_carry_portable;
+
+    const SIZES: [usize; 9] = [1, 2, 3, 4, 7, 8, 17, 33, 64];
+
+    #[test]
+    fn test_mul_prim_adx_matches_portable() {
+        if !has_adx_bmi2() {
+            return;
+        }
+        for (i, &n) in SIZES.iter().enumerate() {
+            for prim in [0, 1, 2, u64::MAX, rand_vec(1, i as u64)[0]] {
+                let mut a = rand_vec(n, 10 + i as u64);
+                let mut b = a.clone();
+                let ca = unsafe { mul_prim_adx(&mut a, prim) };
+                let cb = mul_prim_portable(&mut b, prim);
+                assert_eq!((a, ca), (b, cb), "n={n}, prim={prim:#x}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_buf_adx_matches_portable() {
+        if !has_adx_bmi2() {
+            return;
+        }
+        for (i, &n) in SIZES.iter().enumerate() {
+            for (j, &m) in SIZES.iter().enumerate() {
+                let a = rand_nonzero_vec(n, (i * 100 + j) as u64);
+                let b = rand_nonzero_vec(m, (j * 100 + i + 50) as u64);
+                // exact output and one with spare limbs past the product
+                for extra in [0, 3] {
+                    let len = n + m - 1 + extra;
+                    let mut out_adx = vec![u64::MAX; len];
+                    let mut out_base = vec![u64::MAX; len];
+                    let ca = unsafe { mul_buf_adx(&a, &b, &mut out_adx) };
+                    let cb = mul_buf_portable(&a, &b, &mut out_base);
+                    assert_eq!((out_adx, ca), (out_base, cb), "n={n}, m={m}, extra={extra}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_buf_adx_all_max() {
+        if !has_adx_bmi2() {
+            return;
+        }
+        let a = vec![u64::MAX; 9];
+        let b = vec![u64::MAX; 5];
+        let mut out_adx = vec![0; 13];
+        let mut out_base = vec![0; 13];
+        let ca = unsafe { mul_buf_adx(&a, &b, &mut out_adx) };
+        let cb = mul_buf_portable(&a, &b, &mut out_base);
+        assert_eq!((out_adx, ca), (out_base, cb));
+    }
+
+    #[test]
+    fn test_sqr_buf_adx_matches_portable() {
+        if !has_adx_bmi2() {
+            return;
+        }
+        for (i, &n) in SIZES.iter().enumerate() {
+            for buf in [rand_nonzero_vec(n, 200 + i as u64), vec![u64::MAX; n]] {
+                for extra in [0, 2] {
+                    let len = 2 * n - 1 + extra;
+                    let mut out_adx = vec![u64::MAX; len];
+                    let mut out_base = vec![u64::MAX; len];
+                    let ca = unsafe { sqr_buf_adx(&buf, &mut out_adx) };
+                    let cb = sqr_buf_portable(&buf, &mut out_base);
+                    assert_eq!((out_adx, ca), (out_base, cb), "n={n}, extra={extra}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_acc_adx_matches_portable() {
+        if !has_adx_bmi2() {
+            return;
+        }
+        for (i, &n) in SIZES.iter().enumerate() {
+            for comp in [false, true] {
+                for c0 in [0u8, 1] {
+                    let rhs = rand_vec(n, 300 + i as u64);
+                    let mut lhs_adx = rand_vec(n + 2, 400 + i as u64);
+                    let mut lhs_base = lhs_adx.clone();
+                    let mask = if comp { u64::MAX } else { 0 };
+
+                    let mut ca = c0;
+                    unsafe { acc_adx(&mut lhs_adx, &rhs, comp, &mut ca) };
+                    let mut cb = c0;
+                    for (l, s) in lhs_base.iter_mut().zip(&rhs) {
+                        add_with_carry_portable(l, *s ^ mask, &mut cb);
+                    }
+                    assert_eq!((lhs_adx, ca), (lhs_base, cb), "n={n}, comp={comp}, c={c0}");
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,230 @@
+use super::rand_nonzero_vec;
+use crate::bit_nums::bitint::BitInt;
+use crate::bit_nums::scratch::Scratch;
+use crate::bit_nums::traits::{DivRem, PowI, Sqr};
+use crate::bit_nums::ubitint::UBitInt;
+use crate::bit_nums::ubitint_static::UBitIntStatic;
+use crate::utils::utils::trim_lz;
+
+fn ubi(len: usize, seed: u64) -> UBitInt {
+    UBitInt::make(rand_nonzero_vec(len, seed))
+}
+
+// ─── Scratch with UBitInt ───────────────────────────────────────────────────
+
+#[test]
+fn test_scratch_ubitint_ops_match_operators() {
+    let mut ws = Scratch::new();
+    let mut out = UBitInt::zero();
+    let mut rem = UBitInt::zero();
+    for (i, &(n, m)) in [(90, 70), (2, 1), (300, 120), (1, 1), (40, 33)]
+        .iter()
+        .enumerate()
+    {
+        let a = ubi(n, 2000 + i as u64);
+        let b = ubi(m, 2100 + i as u64);
+
+        ws.mul_into(&a, &b, &mut out);
+        assert_eq!(out, &a * &b, "mul n={n}, m={m}");
+        ws.sqr_into(&a, &mut out);
+        assert_eq!(out, a.sqr(), "sqr n={n}");
+        ws.powi_into(&b, 3, &mut out);
+        assert_eq!(out, b.powi(3), "powi m={m}");
+
+        let (q, r) = (&a).div_rem(&b);
+        ws.div_rem_into(&a, &b, &mut out, &mut rem);
+        assert_eq!((&out, &rem), (&q, &r), "div_rem n={n}, m={m}");
+    }
+}
+
+#[test]
+fn test_scratch_zero_operands() {
+    let mut ws = Scratch::new();
+    let mut out = ubi(4, 2200);
+    let mut rem = UBitInt::zero();
+    ws.mul_into(&UBitInt::zero(), &ubi(3, 2201), &mut out);
+    assert!(out.is_zero());
+    ws.powi_into(&UBitInt::zero(), 0, &mut out);
+    assert_eq!(out, UBitInt::one());
+    ws.div_rem_into(&ubi(2, 2202), &ubi(5, 2203), &mut out, &mut rem);
+    assert!(out.is_zero());
+    assert_eq!(rem, ubi(2, 2202));
+}
+
+#[test]
+#[should_panic(expected = "division by zero")]
+fn test_scratch_div_by_zero_panics() {
+    let mut ws = Scratch::new();
+    let (mut q, mut r) = (UBitInt::zero(), UBitInt::zero());
+    ws.div_rem_into(&UBitInt::one(), &UBitInt::zero(), &mut q, &mut r);
+}
+
+/// Divisors past the Burnikel-Ziegler cutoff recurse through the workspace.
+#[test]
+fn test_scratch_div_rem_static_matches_operator() {
+    let mut ws = Scratch::new();
+    let stat = |len: usize, seed: u64| {
+        let mut data = [0; 200];
+        data[..len].copy_from_slice(&rand_nonzero_vec(len, seed));
+        UBitIntStatic::make(data)
+    };
+    let to_ubi = |x: UBitIntStatic<200>| {
+        let mut data = x.get_data().to_vec();
+        trim_lz(&mut data);
+        UBitInt::make(data)
+    };
+    for (i, &(n, m)) in [
+        (200, 70),
+        (160, 65),
+        (96, 40),
+        (80, 1),
+        (5, 2),
+        (3, 9),
+        (200, 200),
+    ]
+    .iter()
+    .enumerate()
+    {
+        let a = stat(n, 2230 + i as u64);
+        let b = stat(m, 2240 + i as u64);
+        let (q, r) = ws.div_rem_static(&a, &b);
+        let (q_ubi, r_ubi) = to_ubi(a).div_rem(to_ubi(b));
+        assert_eq!(to_ubi(q), q_ubi, "n={n}, m={m}");
+        assert_eq!(to_ubi(r), r_ubi, "n={n}, m={m}");
+    }
+    let cap = ws.capacity();
+    ws.div_rem_static(&stat(200, 2250), &stat(70, 2251));
+    assert_eq!(ws.capacity(), cap);
+}
+
+// ─── Scratch with BitInt ────────────────────────────────────────────────────
+
+#[test]
+fn test_scratch_bitint_signs() {
+    let mut ws = Scratch::new();
+    let mut out = BitInt::zero();
+    let mut rem = BitInt::zero();
+    for (i, &(sa, sb)) in [(false, false), (true, false), (false, true), (true, true)]
+        .iter()
+        .enumerate()
+    {
+        let a = BitInt::make(rand_nonzero_vec(50, 2300 + i as u64), sa);
+        let b = BitInt::make(rand_nonzero_vec(20, 2400 + i as u64), sb);
+
+        ws.mul_into(&a, &b, &mut out);
+        assert_eq!(out, &a * &b);
+        ws.sqr_into(&a, &mut out);
+        assert_eq!(out, a.sqr());
+        ws.powi_into(&b, 5, &mut out);
+        assert_eq!(out, b.powi(5));
+
+        let (q, r) = (&a).div_rem(&b);
+        ws.div_rem_into(&a, &b, &mut out, &mut rem);
+        assert_eq!((&out, &rem), (&q, &r), "sa={sa}, sb={sb}");
+    }
+}
+
+#[test]
+fn test_scratch_no_realloc_after_warm_up() {
+    let mut ws = Scratch::new();
+    let a = ubi(200, 2500);
+    let b = ubi(150, 2501);
+    let mut out = UBitInt::zero();
+    ws.mul_into(&a, &b, &mut out);
+    let (cap, ptr) = (ws.capacity(), out.get_data().as_ptr());
+    for _ in 0..3 {
+        ws.mul_into(&b, &a, &mut out);
+    }
+    assert_eq!(ws.capacity(), cap);
+    assert_eq!(out.get_data().as_ptr(), ptr);
+}
+
+// ─── *_into and fused methods on the integer types ──────────────────────────
+
+#[test]
+fn test_ubitint_into_methods_match_operators() {
+    let mut out = ubi(500, 2600);
+    let mut rem = UBitInt::zero();
+    for (i, &(n, m)) in [(60, 45), (3, 1), (130, 129), (1, 1)].iter().enumerate() {
+        let a = ubi(n, 2700 + i as u64);
+        let b = ubi(m, 2800 + i as u64);
+
+        a.add_into(&b, &mut out);
+        assert_eq!(out, &a + &b, "add n={n}, m={m}");
+        a.sub_into(&b, &mut out);
+        assert_eq!(out, &a - &b, "sub n={n}, m={m}");
+        a.mul_into(&b, &mut out);
+        assert_eq!(out, &a * &b, "mul n={n}, m={m}");
+        a.square_into(&mut out);
+        assert_eq!(out, a.sqr(), "square n={n}");
+        a.div_rem_into(&b, &mut out, &mut rem);
+        assert_eq!(
+            (out.clone(), rem.clone()),
+            (&a).div_rem(&b),
+            "div_rem n={n}, m={m}"
+        );
+    }
+}
+
+#[test]
+fn test_ubitint_into_reuses_capacity() {
+    let a = ubi(40, 2900);
+    let b = ubi(30, 2901);
+    let mut out = UBitInt::zero();
+    a.mul_into(&b, &mut out);
+    let ptr = out.get_data().as_ptr();
+    a.add_into(&b, &mut out);
+    a.sub_into(&b, &mut out);
+    b.square_into(&mut out);
+    a.mul_into(&b, &mut out);
+    assert_eq!(out.get_data().as_ptr(), ptr);
+}
+
+#[test]
+fn test_ubitint_add_mul_sub_mul() {
+    let a = ubi(25, 3000);
+    let b = ubi(18, 3001);
+    let base = ubi(50, 3002);
+
+    let mut x = base.clone();
+    x.add_mul(&a, &b);
+    assert_eq!(x, &base + &(&a * &b));
+    x.sub_mul(&a, &b);
+    assert_eq!(x, base);
+
+    let mut z = UBitInt::zero();
+    z.add_mul(&a, &UBitInt::zero());
+    assert!(z.is_zero());
+}
+
+#[test]
+fn test_bitint_into_and_fused_methods() {
+    let mut out = BitInt::zero();
+    let mut rem = BitInt::zero();
+    for (i, &(sa, sb)) in [(false, false), (true, false), (false, true), (true, true)]
+        .iter()
+        .enumerate()
+    {
+        let a = BitInt::make(rand_nonzero_vec(35, 3100 + i as u64), sa);
+        let b = BitInt::make(rand_nonzero_vec(35, 3200 + i as u64), sb);
+
+        a.add_into(&b, &mut out);
+        assert_eq!(out, &a + &b, "add sa={sa}, sb={sb}");
+        a.sub_into(&b, &mut out);
+        assert_eq!(out, &a - &b, "sub sa={sa}, sb={sb}");
+        a.mul_into(&b, &mut out);
+        assert_eq!(out, &a * &b, "mul sa={sa}, sb={sb}");
+        a.square_into(&mut out);
+        assert_eq!(out, a.sqr());
+        a.div_rem_into(&b, &mut out, &mut rem);
+        assert_eq!((out.clone(), rem.clone()), (&a).div_rem(&b));
+
+        // small accumulator so the product flips its sign
+        let acc = BitInt::make(rand_nonzero_vec(3, 3300 + i as u64), !sa);
+        let mut x = acc.clone();
+        x.add_mul(&a, &b);
+        assert_eq!(x, &acc + &(&a * &b), "add_mul sa={sa}, sb={sb}");
+        x.sub_mul(&a, &b);
+        assert_eq!(x, acc, "sub_mul sa={sa}, sb={sb}");
+    }
+}
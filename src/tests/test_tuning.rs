@@ -0,0 +1,68 @@
+use crate::utils::tuning::{ThresholdErr, Thresholds};
+
+// ─── threshold table text form ──────────────────────────────────────────────
+
+#[test]
+fn test_thresholds_display_roundtrip() {
+    let t = Thresholds {
+        karatsuba: 40,
+        karatsuba_sqr: 48,
+        short: 24,
+        short_sqr: 16,
+        bz: 96,
+    };
+    assert_eq!(t.to_string().parse::<Thresholds>(), Ok(t));
+    assert_eq!(
+        Thresholds::DEFAULT.to_string().parse(),
+        Ok(Thresholds::DEFAULT)
+    );
+}
+
+#[test]
+fn test_thresholds_parse_partial() {
+    let t: Thresholds = "# tuned\n\n  bz = 128  # long divisors\nkaratsuba=20\n"
+        .parse()
+        .unwrap();
+    assert_eq!(t.bz, 128);
+    assert_eq!(t.karatsuba, 20);
+    assert_eq!(t.karatsuba_sqr, Thresholds::DEFAULT.karatsuba_sqr);
+    assert_eq!(t.short, Thresholds::DEFAULT.short);
+    assert_eq!(t.short_sqr, Thresholds::DEFAULT.short_sqr);
+    assert_eq!("".parse(), Ok(Thresholds::DEFAULT));
+}
+
+#[test]
+fn test_thresholds_parse_errors() {
+    assert_eq!(
+        "bz 12".parse::<Thresholds>(),
+        Err(ThresholdErr::Malformed(1))
+    );
+    assert_eq!(
+        "bz = -3".parse::<Thresholds>(),
+        Err(ThresholdErr::Malformed(1))
+    );
+    assert_eq!(
+        "\ntoom = 90".parse::<Thresholds>(),
+        Err(ThresholdErr::UnknownKey(2))
+    );
+    assert_eq!(
+        "bz = 70\nbz = 80".parse::<Thresholds>(),
+        Err(ThresholdErr::DuplicateKey(2))
+    );
+    assert_eq!(
+        "short = 3".parse::<Thresholds>(),
+        Err(ThresholdErr::TooSmall("short"))
+    );
+}
+
+#[test]
+fn test_thresholds_install_rejects_small() {
+    // validation runs before the table is claimed, so this leaves it untouched
+    let t = Thresholds {
+        karatsuba: 2,
+        ..Thresholds::DEFAULT
+    };
+    assert_eq!(t.install(), Err(ThresholdErr::TooSmall("karatsuba")));
+    assert_eq!(Thresholds::active(), &Thresholds::DEFAULT);
+    assert_eq!(Thresholds::DEFAULT.install(), Err(ThresholdErr::AlreadySet));
+}
@@ -712,6 +712,138 @@ fn test_shl_shr_roundtrip_multi_limb() {
     }
 }
 
+// ─── portable fallback vs asm ───────────────────────────────────────────────
+//
+// The portable kernels are always compiled, so on targets with an asm path the
+// two can be checked against each other limb by limb.
+
+#[cfg(all(target_arch = "x86_64", not(miri)))]
+mod asm {
+    pub(super) use crate::utils::utils::{
+        add_with_carry_x86_64 as add_with_carry, dec_propagate_x86_64 as dec_propagate,
+        inc_propagate_x86_64 as inc_propagate, shl_carry_x86_64 as shl_carry,
+        shr_carry_x86_64 as shr_carry,
+    };
+}
+
+#[cfg(all(target_arch = "aarch64", not(miri)))]
+mod asm {
+    pub(super) use crate::utils::utils::{
+        add_with_carry_aarch64 as add_with_carry, dec_propagate_aarch64 as dec_propagate,
+        inc_propagate_aarch64 as inc_propagate, shl_carry_aarch64 as shl_carry,
+        shr_carry_aarch64 as shr_carry,
+    };
+}
+
+/// Edge-case limbs mixed into the random samples below.
+const EDGE_LIMBS: [u64; 6] = [0, 1, 2, u64::MAX - 1, u64::MAX, 1 << 63];
+
+fn sample_limbs(seed: u64) -> Vec<u64> {
+    let mut v = rand_vec(64, seed);
+    v.extend_from_slice(&EDGE_LIMBS);
+    v
+}
+
+#[test]
+fn test_add_with_carry_portable_values() {
+    let mut l = u64::MAX;
+    let mut c = 1;
+    add_with_carry_portable(&mut l, 0, &mut c);
+    assert_eq!((l, c), (0, 1));
+
+    let mut l = u64::MAX;
+    let mut c = 1;
+    add_with_carry_portable(&mut l, u64::MAX, &mut c);
+    assert_eq!((l, c), (u64::MAX, 1));
+
+    let mut l = 5;
+    let mut c = 0;
+    add_with_carry_portable(&mut l, 7, &mut c);
+    assert_eq!((l, c), (12, 0));
+}
+
+#[test]
+fn test_inc_dec_propagate_portable_values() {
+    let mut l = u64::MAX;
+    let mut c = 1;
+    inc_propagate_portable(&mut l, &mut c);
+    assert_eq!((l, c), (0, 1));
+
+    let mut l = 0;
+    let mut b = 1;
+    dec_propagate_portable(&mut l, &mut b);
+    assert_eq!((l, b), (u64::MAX, 1));
+
+    let mut l = 9;
+    let mut b = 0;
+    dec_propagate_portable(&mut l, &mut b);
+    assert_eq!((l, b), (9, 0));
+}
+
+#[cfg(all(any(target_arch = "x86_64", target_arch = "aarch64"), not(miri)))]
+#[test]
+fn test_add_with_carry_matches_asm() {
+    let ls = sample_limbs(101);
+    let ss = sample_limbs(102);
+    for &l in &ls {
+        for &s in &ss {
+            for c in [0u8, 1] {
+                let (mut la, mut ca) = (l, c);
+                let (mut lp, mut cp) = (l, c);
+                unsafe { asm::add_with_carry(&mut la, s, &mut ca) };
+                add_with_carry_portable(&mut lp, s, &mut cp);
+                assert_eq!((la, ca), (lp, cp), "l={l:#x}, s={s:#x}, c={c}");
+            }
+        }
+    }
+}
+
+#[cfg(all(any(target_arch = "x86_64", target_arch = "aarch64"), not(miri)))]
+#[test]
+fn test_inc_dec_propagate_matches_asm() {
+    for &l in &sample_limbs(103) {
+        for c in [0u8, 1] {
+            let (mut la, mut ca) = (l, c);
+            let (mut lp, mut cp) = (l, c);
+            unsafe { asm::inc_propagate(&mut la, &mut ca) };
+            inc_propagate_portable(&mut lp, &mut cp);
+            assert_eq!((la, ca), (lp, cp), "inc: l={l:#x}, c={c}");
+
+            let (mut la, mut ba) = (l, c);
+            let (mut lp, mut bp) = (l, c);
+            unsafe { asm::dec_propagate(&mut la, &mut ba) };
+            dec_propagate_portable(&mut lp, &mut bp);
+            assert_eq!((la, ba), (lp, bp), "dec: l={l:#x}, b={c}");
+        }
+    }
+}
+
+#[cfg(all(any(target_arch = "x86_64", target_arch = "aarch64"), not(miri)))]
+#[test]
+fn test_shift_carry_matches_asm() {
+    let es = sample_limbs(104);
+    let cs = sample_limbs(105);
+    for sh in 1..64u8 {
+        let mv_sz = 64 - sh;
+        for (&e, &c) in es.iter().zip(&cs) {
+            // carries only ever hold the bits shifted out of the neighbouring limb
+            let c_hi = c << mv_sz;
+            let (mut ea, mut ca) = (e, c_hi);
+            let (mut ep, mut cp) = (e, c_hi);
+            unsafe { asm::shr_carry(&mut ea, &mut ca, sh, mv_sz) };
+            shr_carry_portable(&mut ep, &mut cp, sh, mv_sz);
+            assert_eq!((ea, ca), (ep, cp), "shr: e={e:#x}, sh={sh}");
+
+            let c_lo = c >> mv_sz;
+            let (mut ea, mut ca) = (e, c_lo);
+            let (mut ep, mut cp) = (e, c_lo);
+            unsafe { asm::shl_carry(&mut ea, &mut ca, sh, mv_sz) };
+            shl_carry_portable(&mut ep, &mut cp, sh, mv_sz);
+            assert_eq!((ea, ca), (ep, cp), "shl: e={e:#x}, sh={sh}");
+        }
+    }
+}
+
 // ─── to_u128 helper (self-test) ─────────────────────────────────────────────
 
 #[test]
@@ -0,0 +1,213 @@
+#![allow(dead_code)]
+use crate::utils::utils::shl_buf;
+use std::arch::asm;
+
+// mulx/adcx/adox kernels for x86_64 chips with BMI2 and ADX. The carry chains
+// run inside a single asm block so CF/OF never have to be materialized between
+// limbs, and adcx/adox give two independent chains for the accumulating rows.
+// Loops are driven with lea/jrcxz since neither touches the flags.
+
+#[inline]
+pub(crate) fn has_adx_bmi2() -> bool {
+    std::is_x86_feature_detected!("adx") && std::is_x86_feature_detected!("bmi2")
+}
+
+// rp[..n] = up[..n] * v, returns the carry limb; rp may alias up, n > 0
+#[target_feature(enable = "adx,bmi2")]
+unsafe fn mul_1(rp: *mut u64, up: *const u64, n: usize, v: u64) -> u64 {
+    let c: u64;
+    asm!(
+        "xor {c:e}, {c:e}", // c = 0, clears CF
+        "2:",
+        "mulx {hi}, {lo}, qword ptr [{up}]", // up[i] * v -> hi:lo
+        "adcx {lo}, {c}", // lo + previous hi + CF -> lo
+        "mov qword ptr [{rp}], {lo}",
+        "mov {c}, {hi}",
+        "lea {up}, [{up} + 8]",
+        "lea {rp}, [{rp} + 8]",
+        "lea rcx, [rcx - 1]",
+        "jrcxz 3f",
+        "jmp 2b",
+        "3:",
+        "mov {lo}, 0",
+        "adcx {c}, {lo}", // fold the last CF into the carry limb
+        up = inout(reg) up => _,
+        rp = inout(reg) rp => _,
+        c = out(reg) c,
+        hi = out(reg) _,
+        lo = out(reg) _,
+        inout("rcx") n => _,
+        in("rdx") v,
+        options(nostack)
+    );
+    c
+}
+
+// rp[..n] += up[..n] * v, returns the carry limb; n > 0
+#[target_feature(enable = "adx,bmi2")]
+unsafe fn addmul_1(rp: *mut u64, up: *const u64, n: usize, v: u64) -> u64 {
+    let c: u64;
+    asm!(
+        "xor {c:e}, {c:e}", // c = 0, clears CF and OF
+        "2:",
+        "mulx {hi}, {lo}, qword ptr [{up}]", // up[i] * v -> hi:lo
+        "adcx {lo}, {c}", // lo + previous hi + CF -> lo, CF chain
+        "adox {lo}, qword ptr [{rp}]", // lo + rp[i] + OF -> lo, OF chain
+        "mov qword ptr [{rp}], {lo}",
+        "mov {c}, {hi}",
+        "lea {up}, [{up} + 8]",
+        "lea {rp}, [{rp} + 8]",
+        "lea rcx, [rcx - 1]",
+        "jrcxz 3f",
+        "jmp 2b",
+        "3:",
+        "mov {lo}, 0",
+        "adcx {c}, {lo}", // fold both chains into the carry limb,
+        "adox {c}, {lo}", // rp + up * v + c < 2^128 so this cannot overflow
+        up = inout(reg) up => _,
+        rp = inout(reg) rp => _,
+        c = out(reg) c,
+        hi = out(reg) _,
+        lo = out(reg) _,
+        inout("rcx") n => _,
+        in("rdx") v,
+        options(nostack)
+    );
+    c
+}
+
+// rp[..n] += up[..n] + c, or rp[..n] += !up[..n] + c when comp; n > 0
+#[target_feature(enable = "adx,bmi2")]
+unsafe fn add_n(rp: *mut u64, up: *const u64, n: usize, comp: bool, c: &mut u8) {
+    if comp {
+        asm!(
+            "add {c}, 0xFF", // c -> CF
+            "2:",
+            "mov {s}, qword ptr [{up}]",
+            "not {s}", // not leaves the flags alone, unlike xor
+            "mov {t}, qword ptr [{rp}]",
+            "adcx {t}, {s}", // rp[i] + !up[i] + CF -> rp[i]
+            "mov qword ptr [{rp}], {t}",
+            "lea {up}, [{up} + 8]",
+            "lea {rp}, [{rp} + 8]",
+            "lea rcx, [rcx - 1]",
+            "jrcxz 3f",
+            "jmp 2b",
+            "3:",
+            "setc {c}", // CF -> c
+            c = inout(reg_byte) *c,
+            up = inout(reg) up => _,
+            rp = inout(reg) rp => _,
+            s = out(reg) _,
+            t = out(reg) _,
+            inout("rcx") n => _,
+            options(nostack)
+        );
+    } else {
+        asm!(
+            "add {c}, 0xFF", // c -> CF
+            "2:",
+            "mov {t}, qword ptr [{rp}]",
+            "adcx {t}, qword ptr [{up}]", // rp[i] + up[i] + CF -> rp[i]
+            "mov qword ptr [{rp}], {t}",
+            "lea {up}, [{up} + 8]",
+            "lea {rp}, [{rp} + 8]",
+            "lea rcx, [rcx - 1]",
+            "jrcxz 3f",
+            "jmp 2b",
+            "3:",
+            "setc {c}", // CF -> c
+            c = inout(reg_byte) *c,
+            up = inout(reg) up => _,
+            rp = inout(reg) rp => _,
+            t = out(reg) _,
+            inout("rcx") n => _,
+            options(nostack)
+        );
+    }
+}
+
+#[target_feature(enable = "adx,bmi2")]
+pub(crate) unsafe fn acc_adx(lhs: &mut [u64], rhs: &[u64], comp: bool, c: &mut u8) {
+    let n = lhs.len().min(rhs.len());
+    if n > 0 {
+        add_n(lhs.as_mut_ptr(), rhs.as_ptr(), n, comp, c);
+    }
+}
+
+#[target_feature(enable = "adx,bmi2")]
+pub(crate) unsafe fn mul_prim_adx(buf: &mut [u64], prim: u64) -> u64 {
+    if buf.is_empty() {
+        return 0;
+    }
+    let p = buf.as_mut_ptr();
+    mul_1(p, p, buf.len(), prim)
+}
+
+// row-wise schoolbook product, out must hold at least a.len() + b.len() - 1 limbs
+#[target_feature(enable = "adx,bmi2")]
+pub(crate) unsafe fn mul_buf_adx(a: &[u64], b: &[u64], out: &mut [u64]) -> u64 {
+    if a.is_empty() || b.is_empty() {
+        return 0;
+    }
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let n = long.len();
+    let p = n + short.len() - 1;
+    debug_assert!(out.len() >= p);
+
+    let mut c = mul_1(out.as_mut_ptr(), long.as_ptr(), n, short[0]);
+    for (j, &s) in short.iter().enumerate().skip(1) {
+        out[j + n - 1] = c;
+        c = addmul_1(out[j..].as_mut_ptr(), long.as_ptr(), n, s);
+    }
+
+    if out.len() > p {
+        out[p] = c;
+        out[p + 1..].fill(0);
+        return 0;
+    }
+    c
+}
+
+// off-diagonal rows, doubled, plus the diagonal squares; out must hold at
+// least 2 * buf.len() - 1 limbs
+#[target_feature(enable = "adx,bmi2")]
+pub(crate) unsafe fn sqr_buf_adx(buf: &[u64], out: &mut [u64]) -> u64 {
+    let n = buf.len();
+    let p = 2 * n - 1;
+    debug_assert!(out.len() >= p);
+
+    out[..p].fill(0);
+    for i in 0..n - 1 {
+        let c = addmul_1(
+            out[2 * i + 1..].as_mut_ptr(),
+            buf[i + 1..].as_ptr(),
+            n - 1 - i,
+            buf[i],
+        );
+        out[i + n] = c;
+    }
+
+    let mut top = shl_buf(&mut out[..p], 1);
+    let mut c = 0;
+    for (i, &x) in buf.iter().enumerate() {
+        let sqr = (x as u128) * (x as u128);
+        let lo = (out[2 * i] as u128) + (sqr as u64 as u128) + c;
+        out[2 * i] = lo as u64;
+        let slot = if 2 * i + 1 < p {
+            &mut out[2 * i + 1]
+        } else {
+            &mut top
+        };
+        let hi = (*slot as u128) + (sqr >> 64) + (lo >> 64);
+        *slot = hi as u64;
+        c = hi >> 64;
+    }
+
+    if out.len() > p {
+        out[p] = top;
+        out[p + 1..].fill(0);
+        return 0;
+    }
+    top
+}
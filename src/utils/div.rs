@@ -1,3 +1,4 @@
+use crate::utils::tuning::Thresholds;
 use crate::utils::{mul::*, utils::*};
 
 pub fn div_prim(buf: &mut [u64], prim: u64) -> u64 {
@@ -112,11 +113,9 @@ fn div_3_2(n: &mut [u64], d: &[u64], d_lo_len: usize, q: &mut [u64], scratch: &m
     }
 }
 
-pub(crate) const BZ_CUTOFF: usize = 64;
-
 fn div_2_1(n: &mut [u64], d: &[u64], q: &mut [u64], scratch: &mut [u64]) {
     let dlen = d.len();
-    if dlen <= BZ_CUTOFF {
+    if dlen <= Thresholds::active().bz {
         div_buf_of(n, &mut 0, d, q);
         return;
     }
@@ -144,7 +143,7 @@ fn bz_div_alg(
 }
 
 fn find_bz_scratch_size(d: usize) -> usize {
-    if d <= BZ_CUTOFF {
+    if d <= Thresholds::active().bz {
         return 0;
     }
     let half = (d + 1) / 2;
@@ -174,23 +173,29 @@ fn bz_div_init(n: &mut [u64], d: &mut [u64], out: &mut [u64]) -> Option<(usize,
 }
 
 pub fn div_vec(n: &mut [u64], d: &mut [u64]) -> Vec<u64> {
+    let mut out = Vec::new();
+    div_into(n, d, &mut out, &mut Vec::new());
+    return out;
+}
+
+// quotient goes to out, the remainder is left in n
+pub fn div_into(n: &mut [u64], d: &mut [u64], out: &mut Vec<u64>, scratch: &mut Vec<u64>) {
     if d.len() == 0 {
         panic!("division by zero");
     }
+    out.clear();
     if n.len() < d.len() {
-        return vec![];
+        return;
     }
-    let mut out = vec![0_u64; n.len() + 1 - d.len()];
-    if let Some((t, sh)) = bz_div_init(n, d, &mut out) {
+    out.resize(n.len() + 1 - d.len(), 0);
+    if let Some((t, sh)) = bz_div_init(n, d, out) {
         if t > 0 {
-            let size = find_bz_scratch_size(d.len());
-            let mut scratch = vec![0_u64; size];
-            bz_div_alg(n, d, &mut out, t, |n, d, q| div_2_1(n, d, q, &mut scratch));
+            let scratch = grow_scratch(scratch, find_bz_scratch_size(d.len()));
+            bz_div_alg(n, d, out, t, |n, d, q| div_2_1(n, d, q, scratch));
         }
         shr_buf(n, sh);
         shr_buf(d, sh);
     }
-    return out;
 }
 
 fn div_3_2_static<const N: usize>(
@@ -229,7 +234,7 @@ fn div_2_1_static<const N: usize>(
     scratch: &mut [u64],
 ) {
     let dlen = d.len();
-    if dlen <= BZ_CUTOFF {
+    if dlen <= Thresholds::active().bz {
         div_buf_of(n, &mut 0, d, q);
         return;
     }
@@ -260,3 +265,22 @@ pub fn div_arr<const N: usize>(n: &mut [u64], d: &mut [u64]) -> [u64; N] {
     }
     return out;
 }
+
+// div_arr with the Burnikel-Ziegler scratch taken from the caller, so the
+// recursion never needs the stack bound split of div_2_1_static
+pub fn div_arr_into<const N: usize>(
+    n: &mut [u64],
+    d: &mut [u64],
+    out: &mut [u64; N],
+    scratch: &mut Vec<u64>,
+) {
+    out.fill(0);
+    if let Some((t, sh)) = bz_div_init(n, d, out) {
+        if t > 0 {
+            let scratch = grow_scratch(scratch, find_bz_scratch_size(d.len()));
+            bz_div_alg(n, d, out, t, |n, d, q| div_2_1(n, d, q, scratch));
+        }
+        shr_buf(n, sh);
+        shr_buf(d, sh);
+    }
+}
@@ -1,5 +1,7 @@
+#[cfg(all(target_arch = "x86_64", not(any(feature = "portable", miri))))]
+pub mod adx;
 pub mod div;
 pub mod mul;
+pub mod thresholds;
+pub mod tuning;
 pub mod utils;
-
-
@@ -1,7 +1,18 @@
 #![allow(dead_code)]
+#[cfg(all(target_arch = "x86_64", not(any(feature = "portable", miri))))]
+use crate::utils::adx::*;
+use crate::utils::tuning::Thresholds;
 use crate::utils::utils::*;
 
 pub fn mul_prim(buf: &mut [u64], prim: u64) -> u64 {
+    #[cfg(all(target_arch = "x86_64", not(any(feature = "portable", miri))))]
+    if has_adx_bmi2() {
+        return unsafe { mul_prim_adx(buf, prim) };
+    }
+    mul_prim_portable(buf, prim)
+}
+
+pub fn mul_prim_portable(buf: &mut [u64], prim: u64) -> u64 {
     let prim_u128 = prim as u128;
     let mut carry: u128 = 0;
 
@@ -48,6 +59,14 @@ pub fn mul_prim2(buf: &mut [u64], prim: u128) -> u128 {
 }
 
 pub fn mul_buf(a: &[u64], b: &[u64], out: &mut [u64]) -> u64 {
+    #[cfg(all(target_arch = "x86_64", not(any(feature = "portable", miri))))]
+    if has_adx_bmi2() && out.len() + 1 >= a.len() + b.len() {
+        return unsafe { mul_buf_adx(a, b, out) };
+    }
+    mul_buf_portable(a, b, out)
+}
+
+pub fn mul_buf_portable(a: &[u64], b: &[u64], out: &mut [u64]) -> u64 {
     if a.is_empty() || b.is_empty() {
         return 0;
     }
@@ -92,7 +111,7 @@ fn chunking_karatsuba(long: &[u64], short: &[u64], out: &mut [u64], scratch: &mu
         acc(&mut out[of..], val, 0) as u64;
     }
 
-    let of = n_chunks * s;
+    let of = len * s;
     let chunk = &long[of..];
     let (val, rest) = scratch.split_at_mut(chunk.len() + s - 1);
     overflow += karatsuba_alg(short, chunk, val, rest);
@@ -111,17 +130,17 @@ enum KDispatch {
     Recurse,
 }
 
-pub(crate) const KARATSUBA_CUTOFF: usize = 24;
-
 fn karatsuba_dispatch(long: usize, short: usize) -> KDispatch {
+    let cutoff = Thresholds::active().karatsuba;
     return if short == 1 {
         KDispatch::Prim
     } else if short == 2 {
         KDispatch::Prim2
-    } else if long <= KARATSUBA_CUTOFF {
+    } else if long <= cutoff {
         KDispatch::Base
-    } else if short < (long + 1) / 2 {
-        if short <= KARATSUBA_CUTOFF {
+    } else if short <= (long + 1) / 2 {
+        // the recursive split needs a non-empty upper half of short
+        if short <= cutoff {
             KDispatch::Base
         } else {
             KDispatch::Chunking
@@ -172,7 +191,14 @@ fn karatsuba_core(
         acc(&mut cross[z2.len()..], &[c], 1);
     }
 
-    return c + acc(&mut out[half_len..], &cross, 0) as u64;
+    // when short is barely longer than half the middle term can spill one limb
+    // past the end of out, that limb belongs to the returned carry
+    let tail = out.len() - half_len;
+    let mut c = c + acc(&mut out[half_len..], &cross[..tail.min(cross.len())], 0) as u64;
+    if let Some(&hi) = cross.get(tail) {
+        c += hi;
+    }
+    return c;
 }
 
 pub(super) fn karatsuba_alg(a: &[u64], b: &[u64], out: &mut [u64], scratch: &mut [u64]) -> u64 {
@@ -185,7 +211,7 @@ pub(super) fn karatsuba_alg(a: &[u64], b: &[u64], out: &mut [u64], scratch: &mut
         KDispatch::Prim2 => {
             out[..long.len()].copy_from_slice(long);
             out[out.len() - 1] = 0;
-            return mul_prim2(out, short[0] as u128 | (short[0] as u128) << 64) as u64;
+            return mul_prim2(out, combine_u64(short[0], short[1])) as u64;
         }
         KDispatch::Base => {
             return mul_buf(long, short, out);
@@ -204,14 +230,15 @@ pub(super) fn karatsuba_alg(a: &[u64], b: &[u64], out: &mut [u64], scratch: &mut
 }
 
 pub(super) fn find_karatsuba_scratch_size(l: usize, s: usize) -> usize {
-    if l <= KARATSUBA_CUTOFF || s <= 2 {
+    let cutoff = Thresholds::active().karatsuba;
+    if l <= cutoff || s <= 2 {
         return 0;
     }
 
     let init_half = (l + 1) / 2;
 
-    let (mut n, mut total) = if s < init_half {
-        if s <= KARATSUBA_CUTOFF {
+    let (mut n, mut total) = if s <= init_half {
+        if s <= cutoff {
             return 0;
         }
         (s, 2 * s)
@@ -219,7 +246,7 @@ pub(super) fn find_karatsuba_scratch_size(l: usize, s: usize) -> usize {
         (init_half + 1, 2 * init_half + 1)
     };
 
-    while n > KARATSUBA_CUTOFF {
+    while n > cutoff {
         let half = (n + 1) / 2;
         total += 2 * half + 1;
         n = half + 1;
@@ -228,33 +255,51 @@ pub(super) fn find_karatsuba_scratch_size(l: usize, s: usize) -> usize {
     total
 }
 
+// grows a reusable scratch vector to at least n limbs and hands back the front
+pub(crate) fn grow_scratch(scratch: &mut Vec<u64>, n: usize) -> &mut [u64] {
+    if scratch.len() < n {
+        scratch.resize(n, 0);
+    }
+    &mut scratch[..n]
+}
+
+// resizes out to exactly n limbs, only allocating when its capacity is short
+fn reset_out(out: &mut Vec<u64>, n: usize) {
+    out.clear();
+    out.resize(n, 0);
+}
+
 pub fn mul_vec(a: &[u64], b: &[u64]) -> (Vec<u64>, u64) {
-    let mut out = vec![0_u64; a.len() + b.len() - 1];
+    let mut out = Vec::new();
+    let c = mul_into(a, b, &mut out, &mut Vec::new());
+    return (out, c);
+}
+
+pub fn mul_into(a: &[u64], b: &[u64], out: &mut Vec<u64>, scratch: &mut Vec<u64>) -> u64 {
+    reset_out(out, a.len() + b.len() - 1);
     let (long, short) = if a.len() > b.len() { (a, b) } else { (b, a) };
-    let c = match karatsuba_dispatch(long.len(), short.len()) {
+    match karatsuba_dispatch(long.len(), short.len()) {
         KDispatch::Prim => {
             out[..long.len()].copy_from_slice(long);
-            mul_prim(&mut out, short[0])
+            mul_prim(out, short[0])
         }
         KDispatch::Prim2 => {
             out[..long.len()].copy_from_slice(long);
-            mul_prim2(&mut out, combine_u64(short[0], short[1])) as u64
+            mul_prim2(out, combine_u64(short[0], short[1])) as u64
         }
-        KDispatch::Base => mul_buf(long, short, &mut out),
+        KDispatch::Base => mul_buf(long, short, out),
         KDispatch::Chunking => {
-            let mut scratch = vec![0_u64; find_karatsuba_scratch_size(long.len(), short.len())];
-            chunking_karatsuba(long, short, &mut out, &mut scratch)
+            let size = find_karatsuba_scratch_size(long.len(), short.len());
+            chunking_karatsuba(long, short, out, grow_scratch(scratch, size))
         }
         KDispatch::Recurse => {
-            let mut cross_scratch =
-                vec![0_u64; find_karatsuba_scratch_size(long.len(), short.len())];
+            let size = find_karatsuba_scratch_size(long.len(), short.len());
             let half = (long.len() + 1) / 2;
-            let (cross, scratch) = cross_scratch.split_at_mut(2 * half + 1);
-            karatsuba_core(long, short, half, &mut out, cross, scratch)
+            let (cross, rest) = grow_scratch(scratch, size).split_at_mut(2 * half + 1);
+            cross.fill(0);
+            karatsuba_core(long, short, half, out, cross, rest)
         }
-    };
-
-    return (out, c);
+    }
 }
 
 pub fn mul_arr<const N: usize>(a: &[u64], b: &[u64]) -> Result<([u64; N], u64), ()> {
@@ -297,6 +342,14 @@ pub fn mul_arr<const N: usize>(a: &[u64], b: &[u64]) -> Result<([u64; N], u64),
 }
 
 pub fn sqr_buf(buf: &[u64], out: &mut [u64]) -> u64 {
+    #[cfg(all(target_arch = "x86_64", not(any(feature = "portable", miri))))]
+    if has_adx_bmi2() && out.len() + 1 >= 2 * buf.len() {
+        return unsafe { sqr_buf_adx(buf, out) };
+    }
+    sqr_buf_portable(buf, out)
+}
+
+pub fn sqr_buf_portable(buf: &[u64], out: &mut [u64]) -> u64 {
     let len = buf.len() - 1;
     let mask = u64::MAX as u128;
 
@@ -329,8 +382,6 @@ pub fn sqr_buf(buf: &[u64], out: &mut [u64]) -> u64 {
     return carry as u64;
 }
 
-pub(crate) const KARATSUBA_SQR_CUTOFF: usize = 32;
-
 fn karatsuba_sqr_core(
     buf: &[u64],
     half_len: usize,
@@ -363,15 +414,19 @@ fn karatsuba_sqr_core(
     if c > 0 {
         acc(&mut cross[z2.len()..], &[c], 1);
     }
-    if acc(&mut out[half_len..], &cross, 0) {
+    let tail = out.len() - half_len;
+    if acc(&mut out[half_len..], &cross[..tail.min(cross.len())], 0) {
         c += 1;
     }
+    if let Some(&hi) = cross.get(tail) {
+        c += hi;
+    }
 
     return c;
 }
 
 fn karatsuba_sqr_alg(buf: &[u64], out: &mut [u64], scratch: &mut [u64]) -> u64 {
-    if buf.len() <= KARATSUBA_SQR_CUTOFF {
+    if buf.len() <= Thresholds::active().karatsuba_sqr {
         return sqr_buf(buf, out);
     }
     let half = (buf.len() + 1) / 2;
@@ -380,8 +435,9 @@ fn karatsuba_sqr_alg(buf: &[u64], out: &mut [u64], scratch: &mut [u64]) -> u64 {
 }
 
 fn find_karatsuba_sqr_scratch_size(mut n: usize) -> usize {
+    let cutoff = Thresholds::active().karatsuba_sqr;
     let mut total = 0;
-    while n > KARATSUBA_SQR_CUTOFF {
+    while n > cutoff {
         let half = (n + 1) / 2;
         total += 2 * half + 1;
         n = half + 1;
@@ -390,16 +446,21 @@ fn find_karatsuba_sqr_scratch_size(mut n: usize) -> usize {
 }
 
 pub fn sqr_vec(buf: &[u64]) -> (Vec<u64>, u64) {
-    let mut out = vec![0_u64; 2 * buf.len() - 1];
-    let c = if buf.len() <= KARATSUBA_SQR_CUTOFF {
-        sqr_buf(buf, &mut out)
+    let mut out = Vec::new();
+    let c = sqr_into(buf, &mut out, &mut Vec::new());
+    return (out, c);
+}
+
+pub fn sqr_into(buf: &[u64], out: &mut Vec<u64>, scratch: &mut Vec<u64>) -> u64 {
+    reset_out(out, 2 * buf.len() - 1);
+    if buf.len() <= Thresholds::active().karatsuba_sqr {
+        sqr_buf(buf, out)
     } else {
-        let mut scratch = vec![0_u64; find_karatsuba_sqr_scratch_size(buf.len())];
+        let size = find_karatsuba_sqr_scratch_size(buf.len());
         let half_len = (buf.len() + 1) / 2;
-        let (cross, rest) = scratch.split_at_mut(2 * half_len + 1);
-        karatsuba_sqr_core(buf, half_len, &mut out, cross, rest)
-    };
-    return (out, c);
+        let (cross, rest) = grow_scratch(scratch, size).split_at_mut(2 * half_len + 1);
+        karatsuba_sqr_core(buf, half_len, out, cross, rest)
+    }
 }
 
 pub fn sqr_arr<const N: usize>(buf: &[u64]) -> Result<([u64; N], u64), ()> {
@@ -408,7 +469,7 @@ pub fn sqr_arr<const N: usize>(buf: &[u64]) -> Result<([u64; N], u64), ()> {
     }
 
     let mut out = [0_u64; N];
-    let c = if buf.len() <= KARATSUBA_SQR_CUTOFF {
+    let c = if buf.len() <= Thresholds::active().karatsuba_sqr {
         sqr_buf(buf, &mut out)
     } else {
         let mut scratch = [0_u64; N];
@@ -452,11 +513,9 @@ fn short_mul_buf(a: &[u64], b: &[u64], out: &mut [u64]) -> u64 {
     return carry as u64;
 }
 
-pub(crate) const SHORT_CUTOFF: usize = 32;
-
 pub fn short_mul_vec(a: &[u64], b: &[u64], prec: usize) -> (Vec<u64>, u64) {
     let p = prec.min(a.len() + b.len() - 1);
-    return if p <= SHORT_CUTOFF {
+    return if p <= Thresholds::active().short {
         let mut out = vec![0; p];
         let c = short_mul_buf(a, b, &mut out);
         (out, c)
@@ -510,11 +569,9 @@ fn short_sqr_buf(buf: &[u64], out: &mut [u64]) -> u64 {
     return carry as u64;
 }
 
-pub(crate) const SHORT_SQR_CUTOFF: usize = 32;
-
 pub fn short_sqr_vec(buf: &[u64], prec: usize) -> (Vec<u64>, u64) {
     let p = prec.min(2 * buf.len() - 1);
-    return if p <= SHORT_SQR_CUTOFF {
+    return if p <= Thresholds::active().short_sqr {
         let mut out = vec![0; p];
         let c = short_sqr_buf(buf, &mut out);
         (out, c)
@@ -527,20 +584,29 @@ pub fn short_sqr_vec(buf: &[u64], prec: usize) -> (Vec<u64>, u64) {
 }
 
 pub fn powi_vec(buf: &[u64], pow: usize) -> Vec<u64> {
+    let mut out = Vec::new();
+    powi_into(buf, pow, &mut out, &mut Vec::new(), &mut Vec::new());
+    return out;
+}
+
+pub fn powi_into(
+    buf: &[u64],
+    pow: usize,
+    out: &mut Vec<u64>,
+    tmp: &mut Vec<u64>,
+    scratch: &mut Vec<u64>,
+) {
     if pow == 0 {
-        return vec![1];
+        reset_out(out, 1);
+        out[0] = 1;
+        return;
     }
 
     let l = buf_len(buf);
     let log2 = (l - 1) * 64 + buf[l - 1].ilog2() as usize;
-    let tmp_sz = 1 + (log2 * pow) / 64;
-    let scratch_sz = std::cmp::max(
-        find_karatsuba_sqr_scratch_size(tmp_sz / 2),
-        find_karatsuba_scratch_size(tmp_sz, l),
-    );
-    let mut tmp = vec![0_u64; tmp_sz];
-    let mut out = vec![0_u64; tmp_sz];
-    let mut scratch = vec![0_u64; scratch_sz];
+    let tmp_sz = 1 + ((log2 + 1) * pow) / 64;
+    reset_out(out, tmp_sz);
+    let tmp = grow_scratch(tmp, tmp_sz);
 
     let mut len = buf.len();
     out[..len].copy_from_slice(buf);
@@ -550,11 +616,16 @@ pub fn powi_vec(buf: &[u64], pow: usize) -> Vec<u64> {
     for i in (0..log).rev() {
         let sqr_len = 2 * len - 1;
         let (src, dst): (&[u64], &mut [u64]) = if io {
-            (&out, &mut tmp)
+            (&out[..], &mut tmp[..])
         } else {
-            (&tmp, &mut out)
+            (&tmp[..], &mut out[..])
         };
-        let sqr_c = karatsuba_sqr_alg(&src[..len], &mut dst[..sqr_len], &mut scratch);
+        let scratch_sz = find_karatsuba_sqr_scratch_size(len);
+        let sqr_c = karatsuba_sqr_alg(
+            &src[..len],
+            &mut dst[..sqr_len],
+            grow_scratch(scratch, scratch_sz),
+        );
         len = sqr_len;
         if sqr_c > 0 {
             dst[len] = sqr_c;
@@ -565,12 +636,18 @@ pub fn powi_vec(buf: &[u64], pow: usize) -> Vec<u64> {
         if (pow >> i) & 1 == 1 {
             let mul_len = len + buf.len() - 1;
             let (src, dst): (&[u64], &mut [u64]) = if io {
-                (&out, &mut tmp)
+                (&out[..], &mut tmp[..])
             } else {
-                (&tmp, &mut out)
+                (&tmp[..], &mut out[..])
             };
 
-            let mul_c = karatsuba_alg(&src[..len], &buf, &mut dst[..mul_len], &mut scratch);
+            let scratch_sz = find_karatsuba_scratch_size(len, buf.len());
+            let mul_c = karatsuba_alg(
+                &src[..len],
+                &buf,
+                &mut dst[..mul_len],
+                grow_scratch(scratch, scratch_sz),
+            );
             len = mul_len;
             if mul_c > 0 {
                 dst[len] = mul_c;
@@ -583,8 +660,7 @@ pub fn powi_vec(buf: &[u64], pow: usize) -> Vec<u64> {
     if !io {
         out[..len].copy_from_slice(&tmp[..len]);
     }
-    trim_lz(&mut out);
-    return out;
+    trim_lz(out);
 }
 
 pub fn powi_arr<const N: usize>(buf: &[u64], pow: usize) -> Result<[u64; N], ()> {
@@ -0,0 +1,8 @@
+// Generated by `cargo run --release --features _bench_internals --bin tune -- --emit src/utils/thresholds.rs`.
+// Default algorithm cutoffs in limbs; rerun the tuner to regenerate them for the host.
+
+pub(crate) const KARATSUBA_CUTOFF: usize = 24;
+pub(crate) const KARATSUBA_SQR_CUTOFF: usize = 32;
+pub(crate) const SHORT_CUTOFF: usize = 32;
+pub(crate) const SHORT_SQR_CUTOFF: usize = 32;
+pub(crate) const BZ_CUTOFF: usize = 64;
@@ -0,0 +1,152 @@
+use crate::utils::thresholds::*;
+use core::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+// The table is fixed the first time a dispatch function reads it. Scratch sizes
+// are computed from the cutoffs before recursing, so letting them change under
+// a running multiply or divide would leave the scratch buffers too small.
+static ACTIVE: OnceLock<Thresholds> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThresholdErr {
+    Malformed(usize),
+    UnknownKey(usize),
+    DuplicateKey(usize),
+    TooSmall(&'static str),
+    AlreadySet,
+}
+
+/// Algorithm crossover points in limbs, read by the multiplication and division
+/// dispatch. The text form is one `key = value` line per cutoff, which is what
+/// the `tune` binary prints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Thresholds {
+    pub karatsuba: usize,
+    pub karatsuba_sqr: usize,
+    pub short: usize,
+    pub short_sqr: usize,
+    pub bz: usize,
+}
+
+impl Thresholds {
+    /// Smallest cutoff accepted for any entry; below this the recursive
+    /// algorithms stop shrinking their operands.
+    pub const MIN: usize = 4;
+
+    pub const DEFAULT: Thresholds = Thresholds {
+        karatsuba: KARATSUBA_CUTOFF,
+        karatsuba_sqr: KARATSUBA_SQR_CUTOFF,
+        short: SHORT_CUTOFF,
+        short_sqr: SHORT_SQR_CUTOFF,
+        bz: BZ_CUTOFF,
+    };
+
+    const KEYS: [&'static str; 5] = ["karatsuba", "karatsuba_sqr", "short", "short_sqr", "bz"];
+
+    /// The table in use, fixing it to the defaults if nothing was installed.
+    pub fn active() -> &'static Thresholds {
+        ACTIVE.get_or_init(|| Thresholds::DEFAULT)
+    }
+
+    /// Makes this the table for the rest of the process. Fails once any
+    /// arithmetic has already read the table or another table was installed.
+    pub fn install(self) -> Result<(), ThresholdErr> {
+        self.validate()?;
+        ACTIVE.set(self).map_err(|_| ThresholdErr::AlreadySet)
+    }
+
+    pub fn validate(&self) -> Result<(), ThresholdErr> {
+        for (key, val) in Self::KEYS.into_iter().zip(self.values()) {
+            if val < Self::MIN {
+                return Err(ThresholdErr::TooSmall(key));
+            }
+        }
+        Ok(())
+    }
+
+    fn values(&self) -> [usize; 5] {
+        [
+            self.karatsuba,
+            self.karatsuba_sqr,
+            self.short,
+            self.short_sqr,
+            self.bz,
+        ]
+    }
+
+    fn values_mut(&mut self) -> [&mut usize; 5] {
+        [
+            &mut self.karatsuba,
+            &mut self.karatsuba_sqr,
+            &mut self.short,
+            &mut self.short_sqr,
+            &mut self.bz,
+        ]
+    }
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Thresholds::DEFAULT
+    }
+}
+
+// missing keys keep their default, `#` starts a comment, errors carry the
+// 1-based line number
+impl FromStr for Thresholds {
+    type Err = ThresholdErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut out = Thresholds::DEFAULT;
+        let mut seen = [false; 5];
+        for (i, line) in s.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (key, val) = line.split_once('=').ok_or(ThresholdErr::Malformed(i + 1))?;
+            let key = key.trim();
+            let val: usize = val
+                .trim()
+                .parse()
+                .map_err(|_| ThresholdErr::Malformed(i + 1))?;
+            let idx = Self::KEYS
+                .iter()
+                .position(|&k| k == key)
+                .ok_or(ThresholdErr::UnknownKey(i + 1))?;
+            if seen[idx] {
+                return Err(ThresholdErr::DuplicateKey(i + 1));
+            }
+            seen[idx] = true;
+            *out.values_mut()[idx] = val;
+        }
+        out.validate()?;
+        Ok(out)
+    }
+}
+
+impl fmt::Display for Thresholds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, val) in Self::KEYS.into_iter().zip(self.values()) {
+            writeln!(f, "{key} = {val}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ThresholdErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThresholdErr::Malformed(line) => write!(f, "line {line}: expected `key = value`"),
+            ThresholdErr::UnknownKey(line) => write!(f, "line {line}: unknown threshold"),
+            ThresholdErr::DuplicateKey(line) => write!(f, "line {line}: threshold given twice"),
+            ThresholdErr::TooSmall(key) => {
+                write!(f, "{key} must be at least {}", Thresholds::MIN)
+            }
+            ThresholdErr::AlreadySet => write!(f, "thresholds are already in use"),
+        }
+    }
+}
+
+impl std::error::Error for ThresholdErr {}
@@ -1,4 +1,6 @@
 #![allow(dead_code)]
+#[cfg(all(target_arch = "x86_64", not(any(feature = "portable", miri))))]
+use crate::utils::adx::{acc_adx, has_adx_bmi2};
 use std::arch::asm;
 
 #[inline(always)]
@@ -122,7 +124,7 @@ pub fn lsb(val: u64, sh: i32) -> u64 {
 // adds values with carry and propagates carry on ARM
 #[cfg(target_arch = "aarch64")]
 #[inline(always)]
-unsafe fn add_with_carry_aarch64(l: &mut u64, s: u64, c: &mut u8) {
+pub(crate) unsafe fn add_with_carry_aarch64(l: &mut u64, s: u64, c: &mut u8) {
     asm!(
         "subs wzr, {c:w}, #1", // c -> cf
         "adcs {l}, {l}, {s}", // l+s+cf -> l , updates cf
@@ -137,7 +139,7 @@ unsafe fn add_with_carry_aarch64(l: &mut u64, s: u64, c: &mut u8) {
 // adds values with carry and propagates carry on x86
 #[cfg(target_arch = "x86_64")]
 #[inline(always)]
-unsafe fn add_with_carry_x86_64(l: &mut u64, s: u64, c: &mut u8) {
+pub(crate) unsafe fn add_with_carry_x86_64(l: &mut u64, s: u64, c: &mut u8) {
     asm!(
         "add {c} , 0xFF", // c -> cc
         "adc {l}, {s}", // l+s+cf -> l , updates cf
@@ -149,14 +151,43 @@ unsafe fn add_with_carry_x86_64(l: &mut u64, s: u64, c: &mut u8) {
     );
 }
 
+// adds values with carry and propagates carry without inline assembly
+#[inline(always)]
+pub(crate) fn add_with_carry_portable(l: &mut u64, s: u64, c: &mut u8) {
+    let sum = (*l as u128) + (s as u128) + ((*c != 0) as u128);
+    *l = sum as u64;
+    *c = (sum >> 64) as u8;
+}
+
 //architecture wrapper
 #[inline(always)]
 pub(super) unsafe fn add_with_carry(l: &mut u64, s: u64, c: &mut u8) {
-    #[cfg(target_arch = "aarch64")]
+    #[cfg(all(target_arch = "aarch64", not(any(feature = "portable", miri))))]
     add_with_carry_aarch64(l, s, c);
 
-    #[cfg(target_arch = "x86_64")]
+    #[cfg(all(target_arch = "x86_64", not(any(feature = "portable", miri))))]
     add_with_carry_x86_64(l, s, c);
+
+    #[cfg(any(
+        feature = "portable",
+        miri,
+        not(any(target_arch = "aarch64", target_arch = "x86_64"))
+    ))]
+    add_with_carry_portable(l, s, c);
+}
+
+// adds (or with mask set, subtracts) rhs into the overlapping limbs of lhs
+#[inline(always)]
+unsafe fn add_n(lhs: &mut [u64], rhs: &[u64], mask: u64, c: &mut u8) {
+    #[cfg(all(target_arch = "x86_64", not(any(feature = "portable", miri))))]
+    if has_adx_bmi2() {
+        acc_adx(lhs, rhs, mask != 0, c);
+        return;
+    }
+
+    for (l, s) in lhs.iter_mut().zip(rhs) {
+        add_with_carry(l, *s ^ mask, c);
+    }
 }
 
 pub fn acc(lhs: &mut [u64], rhs: &[u64], comp: u8) -> bool {
@@ -164,9 +195,7 @@ pub fn acc(lhs: &mut [u64], rhs: &[u64], comp: u8) -> bool {
     let cf = comp;
     unsafe {
         let mut c = comp;
-        for (l, s) in lhs.iter_mut().zip(rhs) {
-            add_with_carry(l, *s ^ mask, &mut c);
-        }
+        add_n(lhs, rhs, mask, &mut c);
         if c != cf {
             for l in &mut lhs[rhs.len()..] {
                 add_with_carry(l, mask, &mut c);
@@ -182,7 +211,7 @@ pub fn acc(lhs: &mut [u64], rhs: &[u64], comp: u8) -> bool {
 
 #[cfg(target_arch = "aarch64")]
 #[inline(always)]
-unsafe fn inc_propagate_aarch64(l: &mut u64, c: &mut u8) {
+pub(crate) unsafe fn inc_propagate_aarch64(l: &mut u64, c: &mut u8) {
     asm!(
         "adds {l}, {l}, {c}", // l + c -> l, sets CF
         "cset {c:w}, cs",     // CF -> c
@@ -194,7 +223,7 @@ unsafe fn inc_propagate_aarch64(l: &mut u64, c: &mut u8) {
 
 #[cfg(target_arch = "x86_64")]
 #[inline(always)]
-unsafe fn inc_propagate_x86_64(l: &mut u64, c: &mut u8) {
+pub(crate) unsafe fn inc_propagate_x86_64(l: &mut u64, c: &mut u8) {
     asm!(
         "add {c}, 0xFF", // c -> CF (0xFF + 1 overflows, 0xFF + 0 doesn't)
         "adc {l}, 0",    // l + 0 + CF -> l
@@ -205,12 +234,25 @@ unsafe fn inc_propagate_x86_64(l: &mut u64, c: &mut u8) {
     );
 }
 
+#[inline(always)]
+pub(crate) fn inc_propagate_portable(l: &mut u64, c: &mut u8) {
+    let (sum, of) = l.overflowing_add((*c != 0) as u64);
+    *l = sum;
+    *c = of as u8;
+}
+
 #[inline(always)]
 unsafe fn inc_propagate(l: &mut u64, c: &mut u8) {
-    #[cfg(target_arch = "aarch64")]
+    #[cfg(all(target_arch = "aarch64", not(any(feature = "portable", miri))))]
     inc_propagate_aarch64(l, c);
-    #[cfg(target_arch = "x86_64")]
+    #[cfg(all(target_arch = "x86_64", not(any(feature = "portable", miri))))]
     inc_propagate_x86_64(l, c);
+    #[cfg(any(
+        feature = "portable",
+        miri,
+        not(any(target_arch = "aarch64", target_arch = "x86_64"))
+    ))]
+    inc_propagate_portable(l, c);
 }
 
 pub fn inc(lhs: &mut [u64]) -> bool {
@@ -228,7 +270,7 @@ pub fn inc(lhs: &mut [u64]) -> bool {
 
 #[cfg(target_arch = "aarch64")]
 #[inline(always)]
-unsafe fn dec_propagate_aarch64(l: &mut u64, b: &mut u8) {
+pub(crate) unsafe fn dec_propagate_aarch64(l: &mut u64, b: &mut u8) {
     asm!(
         "subs {l}, {l}, {b}", // l - b -> l, sets C = NOT borrow
         "cset {b:w}, cc",     // cc (carry clear) = borrow occurred -> b
@@ -240,7 +282,7 @@ unsafe fn dec_propagate_aarch64(l: &mut u64, b: &mut u8) {
 
 #[cfg(target_arch = "x86_64")]
 #[inline(always)]
-unsafe fn dec_propagate_x86_64(l: &mut u64, b: &mut u8) {
+pub(crate) unsafe fn dec_propagate_x86_64(l: &mut u64, b: &mut u8) {
     asm!(
         "add {b}, 0xFF", // b -> CF
         "sbb {l}, 0",    // l - 0 - CF -> l
@@ -251,12 +293,25 @@ unsafe fn dec_propagate_x86_64(l: &mut u64, b: &mut u8) {
     );
 }
 
+#[inline(always)]
+pub(crate) fn dec_propagate_portable(l: &mut u64, b: &mut u8) {
+    let (diff, of) = l.overflowing_sub((*b != 0) as u64);
+    *l = diff;
+    *b = of as u8;
+}
+
 #[inline(always)]
 unsafe fn dec_propagate(l: &mut u64, b: &mut u8) {
-    #[cfg(target_arch = "aarch64")]
+    #[cfg(all(target_arch = "aarch64", not(any(feature = "portable", miri))))]
     dec_propagate_aarch64(l, b);
-    #[cfg(target_arch = "x86_64")]
+    #[cfg(all(target_arch = "x86_64", not(any(feature = "portable", miri))))]
     dec_propagate_x86_64(l, b);
+    #[cfg(any(
+        feature = "portable",
+        miri,
+        not(any(target_arch = "aarch64", target_arch = "x86_64"))
+    ))]
+    dec_propagate_portable(l, b);
 }
 
 pub fn dec(lhs: &mut [u64]) -> bool {
@@ -281,7 +336,7 @@ pub fn twos_comp(buf: &mut [u64]) {
 
 #[cfg(target_arch = "aarch64")]
 #[inline(always)]
-unsafe fn shr_carry_aarch64(e: &mut u64, c: &mut u64, sh: u8, mv_sz: u8) {
+pub(crate) unsafe fn shr_carry_aarch64(e: &mut u64, c: &mut u64, sh: u8, mv_sz: u8) {
     asm!(
         "lsl {tmp}, {e}, {ms:x}", // put the last bits of the e into tmp
         "lsr {e}, {e}, {r:x}", // shift e by rem
@@ -298,7 +353,7 @@ unsafe fn shr_carry_aarch64(e: &mut u64, c: &mut u64, sh: u8, mv_sz: u8) {
 
 #[cfg(target_arch = "x86_64")]
 #[inline(always)]
-unsafe fn shr_carry_x86_64(e: &mut u64, c: &mut u64, sh: u8, mv_sz: u8) {
+pub(crate) unsafe fn shr_carry_x86_64(e: &mut u64, c: &mut u64, sh: u8, mv_sz: u8) {
     asm!(
         "mov {tmp}, {e}", // put e into tmp
         "mov cl , {ms}", // move into cl reg
@@ -317,12 +372,26 @@ unsafe fn shr_carry_x86_64(e: &mut u64, c: &mut u64, sh: u8, mv_sz: u8) {
     );
 }
 
+#[inline(always)]
+pub(crate) fn shr_carry_portable(e: &mut u64, c: &mut u64, sh: u8, mv_sz: u8) {
+    let tmp = *e << mv_sz;
+    *e = (*e >> sh) | *c;
+    *c = tmp;
+}
+
 unsafe fn shr_carry(e: &mut u64, c: &mut u64, sh: u8, mv_sz: u8) {
-    #[cfg(target_arch = "aarch64")]
+    #[cfg(all(target_arch = "aarch64", not(any(feature = "portable", miri))))]
     shr_carry_aarch64(e, c, sh, mv_sz);
 
-    #[cfg(target_arch = "x86_64")]
+    #[cfg(all(target_arch = "x86_64", not(any(feature = "portable", miri))))]
     shr_carry_x86_64(e, c, sh, mv_sz);
+
+    #[cfg(any(
+        feature = "portable",
+        miri,
+        not(any(target_arch = "aarch64", target_arch = "x86_64"))
+    ))]
+    shr_carry_portable(e, c, sh, mv_sz);
 }
 
 pub fn shr_buf(buf: &mut [u64], sh: u8) -> u64 {
@@ -343,7 +412,7 @@ pub fn shr_buf(buf: &mut [u64], sh: u8) -> u64 {
 
 #[cfg(target_arch = "aarch64")]
 #[inline(always)]
-unsafe fn shl_carry_aarch64(e: &mut u64, c: &mut u64, sh: u8, mv_sz: u8) {
+pub(crate) unsafe fn shl_carry_aarch64(e: &mut u64, c: &mut u64, sh: u8, mv_sz: u8) {
     asm!(
         "lsr {tmp}, {e}, {ms:x}", // put the last bits of the e into tmp
         "lsl {e}, {e}, {r:x}", // shift e by rem
@@ -360,7 +429,7 @@ unsafe fn shl_carry_aarch64(e: &mut u64, c: &mut u64, sh: u8, mv_sz: u8) {
 
 #[cfg(target_arch = "x86_64")]
 #[inline(always)]
-unsafe fn shl_carry_x86_64(e: &mut u64, c: &mut u64, sh: u8, mv_sz: u8) {
+pub(crate) unsafe fn shl_carry_x86_64(e: &mut u64, c: &mut u64, sh: u8, mv_sz: u8) {
     asm!(
         "mov {tmp}, {e}", // put e into tmp
         "mov cl , {ms}", // move into cl reg
@@ -379,12 +448,26 @@ unsafe fn shl_carry_x86_64(e: &mut u64, c: &mut u64, sh: u8, mv_sz: u8) {
     );
 }
 
+#[inline(always)]
+pub(crate) fn shl_carry_portable(e: &mut u64, c: &mut u64, sh: u8, mv_sz: u8) {
+    let tmp = *e >> mv_sz;
+    *e = (*e << sh) | *c;
+    *c = tmp;
+}
+
 unsafe fn shl_carry(e: &mut u64, c: &mut u64, sh: u8, mv_sz: u8) {
-    #[cfg(target_arch = "aarch64")]
+    #[cfg(all(target_arch = "aarch64", not(any(feature = "portable", miri))))]
     shl_carry_aarch64(e, c, sh, mv_sz);
 
-    #[cfg(target_arch = "x86_64")]
+    #[cfg(all(target_arch = "x86_64", not(any(feature = "portable", miri))))]
     shl_carry_x86_64(e, c, sh, mv_sz);
+
+    #[cfg(any(
+        feature = "portable",
+        miri,
+        not(any(target_arch = "aarch64", target_arch = "x86_64"))
+    ))]
+    shl_carry_portable(e, c, sh, mv_sz);
 }
 
 pub fn shl_buf(buf: &mut [u64], sh: u8) -> u64 {